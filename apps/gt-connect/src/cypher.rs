@@ -0,0 +1,82 @@
+use graphs::graph::{Graph, NodeId};
+use graphs::mst::Mst;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Articulation points and bridges from `Graph::critical_components`.
+pub(crate) type Critical<'a> = (&'a [NodeId], &'a [(NodeId, NodeId)]);
+
+/// Renders the graph as a single Cypher `CREATE` statement for Neo4j.
+///
+/// Every node becomes a `:Node {id, degree_centrality}` and every edge a
+/// `:LINK {weight}` relationship. Analysis results that were computed are
+/// attached as extra properties: `in_mst` on relationships when `mst` is
+/// given, and `bridge` / `articulation_point` when `critical` is given.
+///
+/// # Example output
+///
+/// ```text
+/// CREATE
+///   (n0:Node {id: 0, degree_centrality: 0.5, articulation_point: false}),
+///   (n0)-[:LINK {weight: 1.5, in_mst: true, bridge: false}]->(n1);
+/// ```
+pub(crate) fn render(graph: &Graph, mst: Option<&Mst>, critical: Option<Critical>) -> String {
+    let centrality = graph.degree_centrality();
+    let points: Option<HashSet<u32>> =
+        critical.map(|(points, _)| points.iter().map(|n| n.0).collect());
+    let bridges: Option<HashSet<(u32, u32)>> = critical.map(|(_, bridges)| {
+        bridges
+            .iter()
+            .map(|(u, v)| (u.0.min(v.0), u.0.max(v.0)))
+            .collect()
+    });
+
+    // MST edges are copies, so match them back by endpoints and weight
+    let mut mst_edges: Option<HashMap<(u32, u32, u32), usize>> = mst.map(|mst| {
+        let mut counts = HashMap::new();
+        for e in &mst.edges {
+            *counts
+                .entry((e.u.0, e.v.0, e.weight.to_bits()))
+                .or_insert(0) += 1;
+        }
+        counts
+    });
+
+    let mut items = Vec::new();
+    for (id, score) in centrality.iter().enumerate() {
+        let mut props = format!("id: {}, degree_centrality: {}", id, score);
+        if let Some(points) = &points {
+            let _ = write!(
+                props,
+                ", articulation_point: {}",
+                points.contains(&(id as u32))
+            );
+        }
+        items.push(format!("(n{}:Node {{{}}})", id, props));
+    }
+
+    for e in graph.edges() {
+        let mut props = format!("weight: {}", e.weight);
+        if let Some(counts) = &mut mst_edges {
+            let in_mst = match counts.get_mut(&(e.u.0, e.v.0, e.weight.to_bits())) {
+                Some(n) if *n > 0 => {
+                    *n -= 1;
+                    true
+                }
+                _ => false,
+            };
+            let _ = write!(props, ", in_mst: {}", in_mst);
+        }
+        if let Some(bridges) = &bridges {
+            let key = (e.u.0.min(e.v.0), e.u.0.max(e.v.0));
+            let _ = write!(props, ", bridge: {}", bridges.contains(&key));
+        }
+        items.push(format!("(n{})-[:LINK {{{}}}]->(n{})", e.u.0, props, e.v.0));
+    }
+
+    if items.is_empty() {
+        return String::new();
+    }
+
+    format!("CREATE\n  {};", items.join(",\n  "))
+}
@@ -0,0 +1,64 @@
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Rows shown per list when stdout is a terminal and `--limit` wasn't given.
+const TERMINAL_LIMIT: usize = 50;
+
+/// (row limit, summary only), set once from the global flags.
+static LISTING: OnceLock<(Option<usize>, bool)> = OnceLock::new();
+
+/// Sets the row limit for text output from `--limit` (0 for no limit) and
+/// `--summary-only`. Without `--limit`, lists are cut to `TERMINAL_LIMIT`
+/// rows when stdout is a terminal and printed in full otherwise.
+pub(crate) fn init(limit: Option<usize>, summary_only: bool) {
+    let limit = match limit {
+        Some(0) => None,
+        Some(n) => Some(n),
+        None if std::io::stdout().is_terminal() => Some(TERMINAL_LIMIT),
+        None => None,
+    };
+    let _ = LISTING.set((limit, summary_only));
+}
+
+/// Returns true if `--summary-only` was given: text reports print their
+/// counts and totals but no lists.
+pub(crate) fn summary_only() -> bool {
+    LISTING.get().is_some_and(|&(_, summary)| summary)
+}
+
+fn limit() -> Option<usize> {
+    LISTING.get().and_then(|&(limit, _)| limit)
+}
+
+/// Prints a blank line, `title`, and one indented row per item, stopping
+/// at the row limit with a "… and N more" footer. Prints nothing with
+/// `--summary-only`.
+pub(crate) fn section<T>(
+    title: &str,
+    items: impl ExactSizeIterator<Item = T>,
+    row: impl Fn(T) -> String,
+) {
+    if summary_only() {
+        return;
+    }
+    println!("\n{}", title);
+    let total = items.len();
+    let shown = limit().unwrap_or(total).min(total);
+    for item in items.take(shown) {
+        println!("  {}", row(item));
+    }
+    if shown < total {
+        println!("  … and {} more", total - shown);
+    }
+}
+
+/// Joins `items` with commas for a single row, cut at the row limit with
+/// "… and N more".
+pub(crate) fn join<T: ToString>(items: &[T]) -> String {
+    let shown = limit().unwrap_or(items.len()).min(items.len());
+    let mut parts: Vec<String> = items[..shown].iter().map(|i| i.to_string()).collect();
+    if shown < items.len() {
+        parts.push(format!("… and {} more", items.len() - shown));
+    }
+    parts.join(", ")
+}
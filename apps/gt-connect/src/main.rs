@@ -1,9 +1,33 @@
+mod cypher;
+mod listing;
+mod openmetrics;
+mod progress;
+mod sarif;
+
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
-use graphs::io::load_csv;
-use graphs::mst::kruskal;
-use serde::Serialize;
+use graphs::arborescence::min_arborescence;
+use graphs::bridges::bridge_splits;
+use graphs::centrality::edge_betweenness;
+use graphs::coloring::{dsatur, greedy_coloring};
+use graphs::cut::{edge_connectivity, min_vertex_cut, vertex_connectivity};
+use graphs::density::densest_subgraph;
+use graphs::directed::{condensation, strong_critical_components};
+use graphs::graph::{Edge, Graph, NodeId};
+use graphs::io::{
+    IoError, LoadReport, load_csv_from_reader_with_report, load_csv_parallel_with_report,
+    load_labeled_csv_from_reader_with_report,
+};
+use graphs::matching::hopcroft_karp;
+use graphs::mst::{MstViolation, degree_constrained_kruskal, kruskal, mst_clusters, verify_mst};
+use graphs::robustness::fragmentation;
+use graphs::spectral::laplacian_spectrum;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
 #[derive(Parser)]
 #[command(name = "gt-connect")]
@@ -11,165 +35,2602 @@ use std::process;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Suppress progress bars and text reports; JSON is printed compactly
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Read the whole graph CSV into memory and parse it on all cores;
+    /// fields must not be quoted
+    #[arg(long, global = true)]
+    fast_load: bool,
+
+    /// Drop edges lighter than this when loading the graph
+    #[arg(long, global = true, value_name = "WEIGHT")]
+    min_weight: Option<f32>,
+
+    /// Drop edges heavier than this when loading the graph
+    #[arg(long, global = true, value_name = "WEIGHT")]
+    max_weight: Option<f32>,
+
+    /// Show at most this many rows of each list in text output (0 for
+    /// all); the default is 50 on a terminal and all otherwise
+    #[arg(long, global = true, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Print only counts and totals in text output, without lists
+    #[arg(long, global = true)]
+    summary_only: bool,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Compute minimum spanning tree
+    Mst {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Algorithm to use
+        #[arg(long, value_enum, default_value = "kruskal")]
+        algo: MstAlgorithm,
+
+        /// Limit the number of edges at any node (heuristic; may not span)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        max_degree: Option<u32>,
+
+        /// List the tree edges by this key instead of in the order they
+        /// were added
+        #[arg(long, value_enum)]
+        sort_by: Option<MstOrder>,
+
+        /// Sort in descending order
+        #[arg(long, requires = "sort_by")]
+        desc: bool,
+
+        /// Exit with code 3, listing the components, if the graph is disconnected
+        #[arg(long)]
+        require_connected: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Check that an edge set is a minimum spanning tree of a graph
+    VerifyMst {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Path to the tree's edges as CSV (format: u,v,weight)
+        #[arg(short, long)]
+        tree: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Find critical components (bridges and articulation points)
+    Critical {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Read each row as an arc u → v and find strong bridges and strong
+        /// articulation points instead
+        #[arg(long)]
+        directed: bool,
+
+        /// Fail (exit code 4) if there are more bridges than this
+        #[arg(long, value_name = "N")]
+        max_bridges: Option<usize>,
+
+        /// Fail (exit code 4) if there are more articulation points than this
+        #[arg(long, value_name = "N")]
+        max_articulation_points: Option<usize>,
+
+        /// Fail (exit code 5) on regressions against this earlier JSON output
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<String>,
+
+        /// Write the results to the --baseline file instead of comparing
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Find the fewest nodes whose failure disconnects two nodes
+    Cut {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Source node ID
+        #[arg(short, long)]
+        from: u32,
+
+        /// Destination node ID
+        #[arg(short, long)]
+        to: u32,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Report edge and vertex connectivity with a witnessing cut
+    Connectivity {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Maximum bipartite matching (e.g., jobs to workers) on a labeled edge list
+    Match {
+        /// Path to graph CSV file with labeled nodes (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Label prefix of nodes on the left side (e.g., "job-")
+        #[arg(long)]
+        left_prefix: String,
+
+        /// Label prefix of nodes on the right side (e.g., "worker-")
+        #[arg(long)]
+        right_prefix: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Color nodes so no edge joins two nodes of the same color
+    Color {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Algorithm to use
+        #[arg(long, value_enum, default_value = "dsatur")]
+        algo: ColorAlgorithm,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Split nodes into k clusters by cutting the heaviest MST edges
+    Cluster {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Number of clusters
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        k: u32,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Smallest Laplacian eigenvalues, including algebraic connectivity
+    Spectral {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Number of eigenvalues to compute
+        #[arg(long, default_value = "3", value_parser = clap::value_parser!(u32).range(2..))]
+        k: u32,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Find a densely connected group of nodes (Charikar's peeling)
+    Densest {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Rank edges by hardening priority (betweenness × bridge × weight)
+    Risk {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Number of edges to list
+        #[arg(short = 'n', long, default_value_t = 10)]
+        top: usize,
+
+        /// Fail (exit code 5) on regressions against this earlier JSON output
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<String>,
+
+        /// Write the results to the --baseline file instead of comparing
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Fail random edges or nodes and measure how the graph falls apart
+    Robustness {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Random failures to simulate at each point of the curve
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u32).range(1..))]
+        trials: u32,
+
+        /// Largest fraction of edges (or nodes) to remove; the curve climbs
+        /// to it in --steps equal steps
+        #[arg(long, default_value_t = 0.05)]
+        remove_fraction: f64,
+
+        /// Number of points on the curve after the intact graph
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+        steps: u32,
+
+        /// What fails
+        #[arg(long, value_enum, default_value = "edges")]
+        remove: FailureTarget,
+
+        /// Fail heavier edges, or nodes with heavier edges, more often
+        #[arg(long)]
+        weighted: bool,
+
+        /// Seed for choosing failures
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Cheapest directed tree reaching every node from a root (rows read as arcs u → v)
+    Arborescence {
+        /// Path to graph CSV file (format: u,v,weight; nodes may be labels)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Node every arc points away from
+        #[arg(short, long)]
+        root: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Collapse strongly connected components into a DAG (rows read as arcs u → v)
+    Condense {
+        /// Path to graph CSV file (format: u,v,weight)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: CondenseFormat,
+    },
+
+    /// Check a graph for likely mistakes (the load warnings) without analyzing it
+    Validate {
+        /// Path to graph CSV file (format: u,v,weight; nodes may be labels)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Summarize a graph: size, degrees, weights, and connectivity
+    Describe {
+        /// Path to graph CSV file (format: u,v,weight; nodes may be labels)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// List the heaviest and lightest edges, e.g. to spot weight typos
+    Edges {
+        /// Path to graph CSV file (format: u,v,weight; nodes may be labels)
+        #[arg(short, long)]
+        graph: String,
+
+        /// Number of edges to list at each end
+        #[arg(short = 'n', long, default_value_t = 20)]
+        top: usize,
+
+        /// What to rank edges by
+        #[arg(long, value_enum, default_value = "weight")]
+        by: EdgeOrder,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Full connectivity analysis (MST + critical components)
+    Analyze {
+        /// Path to graph CSV file (format: u,v,weight), or to a gt-path
+        /// JSON graph (.json) analyzed with edge direction ignored
+        #[arg(short, long)]
+        graph: String,
+
+        /// Report the bridges and articulation points that would cut off
+        /// the shortest path from FROM to TO, by node name (repeatable;
+        /// needs a gt-path JSON graph)
+        #[arg(long = "route", value_name = "FROM:TO")]
+        routes: Vec<String>,
+
+        /// Latency SLO in milliseconds to check each --route against
+        #[arg(long, value_name = "MS", requires = "routes")]
+        max_latency: Option<u32>,
+
+        /// Exit with code 3, listing the components, if the graph is disconnected
+        #[arg(long)]
+        require_connected: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Clone, ValueEnum)]
+enum MstAlgorithm {
+    Kruskal,
+}
+
+#[derive(Clone, ValueEnum)]
+enum ColorAlgorithm {
+    /// Smallest free color in node ID order
+    Greedy,
+    /// Most-constrained node first (usually fewer colors)
+    Dsatur,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FailureTarget {
+    Edges,
+    Nodes,
+}
+
+#[derive(Clone, ValueEnum)]
+enum EdgeOrder {
+    Weight,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MstOrder {
+    Weight,
+    U,
+    V,
+}
+
+#[derive(Clone, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    /// Cypher CREATE statements for Neo4j
+    Cypher,
+    /// SARIF 2.1.0 for code-scanning dashboards
+    Sarif,
+    /// OpenMetrics text for node_exporter's textfile collector
+    #[value(name = "openmetrics")]
+    OpenMetrics,
+}
+
+#[derive(Clone, ValueEnum)]
+enum CondenseFormat {
+    Text,
+    Json,
+    /// Graphviz DOT
+    Dot,
+}
+
+/// Something in the input that is valid but likely a mistake.
+#[derive(Serialize)]
+struct WarningOutput {
+    /// Machine-readable category, e.g. `isolated_nodes`
+    kind: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct MstOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    graph_hash: Option<String>,
+    algorithm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_degree: Option<u32>,
+    /// False if the degree limit left the tree in more pieces than the graph
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spanning: Option<bool>,
+    total_weight: f32,
+    num_edges: usize,
+    edges: Vec<EdgeOutput>,
+}
+
+#[derive(Serialize)]
+struct EdgeOutput {
+    u: u32,
+    v: u32,
+    weight: f32,
+}
+
+#[derive(Serialize)]
+struct VerifyMstOutput {
+    graph_hash: String,
+    valid: bool,
+    tree_edges: usize,
+    tree_weight: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violation: Option<ViolationOutput>,
+}
+
+#[derive(Serialize)]
+struct ViolationOutput {
+    /// membership, connectivity, acyclicity, or cut_optimality
+    property: String,
+    message: String,
+    edge: EdgeOutput,
+    /// Tree edge that `edge` should replace (cut_optimality only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    heaviest: Option<EdgeOutput>,
+}
+
+#[derive(Serialize)]
+struct CriticalOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    graph_hash: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    directed: bool,
+    num_bridges: usize,
+    num_articulation_points: usize,
+    bridges: Vec<(u32, u32)>,
+    articulation_points: Vec<u32>,
+    /// Same order as `bridges`; not computed for directed analysis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bridge_splits: Option<Vec<BridgeSplitOutput>>,
+    /// Only with --max-bridges or --max-articulation-points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget: Option<CriticalBudgetOutput>,
+}
+
+#[derive(Serialize)]
+struct CriticalBudgetOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_bridges: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_articulation_points: Option<usize>,
+    /// False if either count is over its limit
+    passed: bool,
+}
+
+#[derive(Serialize)]
+struct BridgeSplitOutput {
+    u: u32,
+    v: u32,
+    components: (usize, usize),
+    component_sizes: (usize, usize),
+    side_sizes: (usize, usize),
+}
+
+#[derive(Serialize)]
+struct CutOutput {
+    graph_hash: String,
+    from: u32,
+    to: u32,
+    size: usize,
+    nodes: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct ConnectivityOutput {
+    graph_hash: String,
+    edge_connectivity: usize,
+    vertex_connectivity: usize,
+    edge_cut: Vec<(u32, u32)>,
+    vertex_cut: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct MatchOutput {
+    graph_hash: String,
+    size: usize,
+    pairs: Vec<MatchPairOutput>,
+    unmatched_left: Vec<String>,
+    unmatched_right: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct MatchPairOutput {
+    left: String,
+    right: String,
+}
+
+#[derive(Serialize)]
+struct ColoringOutput {
+    graph_hash: String,
+    algorithm: String,
+    num_colors: usize,
+    /// Color of each node, indexed by node ID
+    colors: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct ClusteringOutput {
+    graph_hash: String,
+    k: u32,
+    /// More than `k` if the graph has more than `k` connected components
+    num_clusters: usize,
+    /// Total weight of edges between different clusters
+    inter_cluster_weight: f32,
+    clusters: Vec<ClusterOutput>,
+}
+
+#[derive(Serialize)]
+struct ClusterOutput {
+    id: usize,
+    nodes: Vec<u32>,
+    /// Total weight of edges inside the cluster
+    intra_cluster_weight: f32,
+}
+
+#[derive(Serialize)]
+struct SpectralOutput {
+    graph_hash: String,
+    /// Second-smallest Laplacian eigenvalue (Fiedler value); 0 if disconnected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    algebraic_connectivity: Option<f64>,
+    /// Smallest Laplacian eigenvalues, ascending
+    eigenvalues: Vec<f64>,
+    /// False if the graph was too large for the values to be exact
+    exact: bool,
+}
+
+#[derive(Serialize)]
+struct DensestOutput {
+    graph_hash: String,
+    /// Edges per node of the subgraph
+    density: f64,
+    num_nodes: usize,
+    num_edges: usize,
+    nodes: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct RiskOutput {
+    graph_hash: String,
+    num_edges: usize,
+    num_bridges: usize,
+    /// Highest-risk edges first
+    edges: Vec<EdgeRiskOutput>,
+}
+
+#[derive(Serialize)]
+struct EdgeRiskOutput {
+    u: u32,
+    v: u32,
+    weight: f32,
+    /// Shortest paths (in hops) through the edge, summed over node pairs
+    betweenness: f64,
+    is_bridge: bool,
+    /// betweenness × weight for bridges, 0 otherwise
+    score: f64,
+}
+
+#[derive(Serialize)]
+struct RobustnessOutput {
+    graph_hash: String,
+    num_nodes: usize,
+    num_edges: usize,
+    /// `edges` or `nodes`
+    removed: &'static str,
+    weighted: bool,
+    trials: u32,
+    seed: u64,
+    /// Mean largest component over the curve's points: 1 if no failure
+    /// tried splits the graph
+    score: f64,
+    /// The intact graph first, then each step of removals
+    curve: Vec<RobustnessPointOutput>,
+}
+
+#[derive(Serialize)]
+struct RobustnessPointOutput {
+    /// Fraction of edges or nodes removed
+    fraction: f64,
+    /// Number of edges or nodes removed in each trial
+    removed: usize,
+    /// Mean size of the largest remaining component, as a fraction of all
+    /// nodes
+    mean_largest_component: f64,
+    /// Smallest largest component of any trial, as a fraction of all nodes
+    min_largest_component: f64,
+    /// Mean number of pairs of surviving nodes with no path between them
+    mean_disconnected_pairs: f64,
+    /// Fraction of trials that left the surviving nodes in more than one
+    /// component
+    split_probability: f64,
+}
+
+#[derive(Serialize)]
+struct ArborescenceOutput {
+    graph_hash: String,
+    root: String,
+    total_weight: f32,
+    num_arcs: usize,
+    arcs: Vec<ArcOutput>,
+}
+
+#[derive(Serialize)]
+struct ArcOutput {
+    from: String,
+    to: String,
+    weight: f32,
+}
+
+#[derive(Serialize)]
+struct CondensationOutput {
+    graph_hash: String,
+    num_components: usize,
+    num_cyclic: usize,
+    components: Vec<ComponentOutput>,
+    edges: Vec<CondensedEdgeOutput>,
+}
+
+#[derive(Serialize)]
+struct ComponentOutput {
+    id: usize,
+    nodes: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct CondensedEdgeOutput {
+    from: usize,
+    to: usize,
+    /// Number of original arcs collapsed into this edge
+    arcs: usize,
+}
+
+#[derive(Serialize)]
+struct ValidateOutput {
+    graph_hash: String,
+    num_nodes: usize,
+    num_edges: usize,
+}
+
+#[derive(Serialize)]
+struct DescribeOutput {
+    graph_hash: String,
+    /// How the file was read: `csv` (integer node IDs) or `labeled_csv`
+    format: String,
+    /// Whether the first row was column names
+    header: bool,
+    num_nodes: usize,
+    num_edges: usize,
+    degree: DegreeSummary,
+    /// Absent when there are no edges
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<WeightSummary>,
+    /// Connected components, counting isolated nodes
+    components: usize,
+    connected: bool,
+}
+
+#[derive(Serialize)]
+struct DegreeSummary {
+    min: usize,
+    mean: f64,
+    max: usize,
+}
+
+#[derive(Serialize)]
+struct WeightSummary {
+    min: f32,
+    mean: f64,
+    median: f32,
+    p90: f32,
+    max: f32,
+}
+
+#[derive(Serialize)]
+struct EdgesOutput {
+    graph_hash: String,
+    num_edges: usize,
+    /// Heaviest edges first
+    heaviest: Vec<ArcOutput>,
+    /// Lightest edges first
+    lightest: Vec<ArcOutput>,
+}
+
+#[derive(Serialize)]
+struct AnalysisOutput {
+    graph_hash: String,
+    /// Name of each node ID, for gt-path JSON graphs
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    nodes: Vec<String>,
+    mst: MstOutput,
+    critical: CriticalOutput,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    routes: Vec<RouteOutput>,
+}
+
+#[derive(Serialize)]
+struct RouteOutput {
+    from: String,
+    to: String,
+    /// Node names along the shortest path
+    path: Vec<String>,
+    latency_ms: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_latency_ms: Option<u32>,
+    /// False if the path is slower than `max_latency_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    met: Option<bool>,
+    /// Nodes on the path whose failure leaves no way from `from` to `to`
+    articulation_points: Vec<String>,
+    /// Edges on the path whose failure leaves no way from `from` to `to`,
+    /// in path order
+    bridges: Vec<(String, String)>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    progress::set_quiet(cli.quiet);
+    listing::init(cli.limit, cli.summary_only);
+    FAST_LOAD.store(cli.fast_load, Ordering::Relaxed);
+    if cli.min_weight.is_some() || cli.max_weight.is_some() {
+        let _ = WEIGHT_RANGE.set((cli.min_weight, cli.max_weight));
+    }
+
+    let result = match cli.command {
+        Commands::Mst {
+            graph,
+            algo,
+            max_degree,
+            sort_by,
+            desc,
+            require_connected,
+            format,
+        } => {
+            let order = sort_by.map(|key| (key, desc));
+            run_mst(&graph, algo, max_degree, order, require_connected, format)
+        }
+        Commands::VerifyMst {
+            graph,
+            tree,
+            format,
+        } => run_verify_mst(&graph, &tree, format),
+        Commands::Critical {
+            graph,
+            directed,
+            max_bridges,
+            max_articulation_points,
+            baseline,
+            update_baseline,
+            format,
+        } => {
+            let limits = CriticalLimits {
+                max_bridges,
+                max_articulation_points,
+            };
+            let baseline = baseline.map(|path| Baseline {
+                path,
+                update: update_baseline,
+            });
+            run_critical(&graph, directed, &limits, baseline.as_ref(), format)
+        }
+        Commands::Cut {
+            graph,
+            from,
+            to,
+            format,
+        } => run_cut(&graph, from, to, format),
+        Commands::Connectivity { graph, format } => run_connectivity(&graph, format),
+        Commands::Match {
+            graph,
+            left_prefix,
+            right_prefix,
+            format,
+        } => run_match(&graph, &left_prefix, &right_prefix, format),
+        Commands::Color {
+            graph,
+            algo,
+            format,
+        } => run_color(&graph, algo, format),
+        Commands::Cluster { graph, k, format } => run_cluster(&graph, k, format),
+        Commands::Spectral { graph, k, format } => run_spectral(&graph, k, format),
+        Commands::Densest { graph, format } => run_densest(&graph, format),
+        Commands::Risk {
+            graph,
+            top,
+            baseline,
+            update_baseline,
+            format,
+        } => {
+            let baseline = baseline.map(|path| Baseline {
+                path,
+                update: update_baseline,
+            });
+            run_risk(&graph, top, baseline.as_ref(), format)
+        }
+        Commands::Robustness {
+            graph,
+            trials,
+            remove_fraction,
+            steps,
+            remove,
+            weighted,
+            seed,
+            format,
+        } => {
+            let failures = Failures {
+                trials,
+                remove_fraction,
+                steps,
+                target: remove,
+                weighted,
+                seed,
+            };
+            run_robustness(&graph, &failures, format)
+        }
+        Commands::Arborescence {
+            graph,
+            root,
+            format,
+        } => run_arborescence(&graph, &root, format),
+        Commands::Condense { graph, format } => run_condense(&graph, format),
+        Commands::Describe { graph, format } => run_describe(&graph, format),
+        Commands::Validate { graph, format } => run_validate(&graph, format),
+        Commands::Edges {
+            graph,
+            top,
+            by,
+            format,
+        } => run_edges(&graph, top, by, format),
+        Commands::Analyze {
+            graph,
+            routes,
+            max_latency,
+            require_connected,
+            format,
+        } => run_analyze(&graph, &routes, max_latency, require_connected, format),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+}
+
+/// Set from the global `--fast-load` flag.
+static FAST_LOAD: AtomicBool = AtomicBool::new(false);
+
+/// Loads a graph CSV file, with a progress bar for large files. With
+/// `--fast-load` the file is read into memory first and parsed in parallel.
+fn load_graph(path: &str) -> Result<Graph> {
+    load_graph_reported(path).map(|(graph, _)| graph)
+}
+
+/// Like `load_graph`, also returning details of how the file was read.
+fn load_graph_reported(path: &str) -> Result<(Graph, LoadReport)> {
+    let file = std::fs::File::open(path).context(format!("Failed to read file: {}", path))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let bar = progress::bytes(len, "Loading graph");
+    let graph = if FAST_LOAD.load(Ordering::Relaxed) {
+        let mut data = Vec::with_capacity(len as usize);
+        bar.wrap_read(file)
+            .read_to_end(&mut data)
+            .context(format!("Failed to read file: {}", path))?;
+        bar.finish_and_clear();
+        load_csv_parallel_with_report(&data)
+    } else {
+        let graph = load_csv_from_reader_with_report(bar.wrap_read(file));
+        bar.finish_and_clear();
+        graph
+    };
+
+    let (mut graph, report) = graph?;
+    filter_weights(&mut graph);
+    lint(&graph, &report, |v| v.0.to_string());
+    Ok((graph, report))
+}
+
+/// Loads a gt-path JSON graph, returning it for shortest path searches and
+/// as an undirected graph for connectivity analysis. The undirected graph
+/// keeps gt-path's node IDs and joins each linked pair once, weighted by
+/// the lower latency of its links.
+fn load_latency_graph(path: &str) -> Result<(Graph, gt_path_core::graph::Graph)> {
+    if WEIGHT_RANGE.get().is_some() {
+        anyhow::bail!("--min-weight and --max-weight only apply to CSV graphs");
+    }
+    let latency = gt_path_core::graph::Graph::load_json(path)?;
+    let mut links = std::collections::BTreeMap::new();
+    for (u, adj) in latency.adj.iter().enumerate() {
+        for link in adj {
+            let (u, v) = (u as u32, link.to.0);
+            if u != v {
+                let weight = links.entry((u.min(v), u.max(v))).or_insert(u32::MAX);
+                *weight = (*weight).min(link.latency_ms);
+            }
+        }
+    }
+    let mut graph = Graph::new(latency.to_name.len());
+    for ((u, v), weight) in links {
+        graph.add_edge(Edge {
+            u: NodeId(u),
+            v: NodeId(v),
+            weight: weight as f32,
+        });
+    }
+    Ok((graph, latency))
+}
+
+/// Finds the shortest path of a FROM:TO route and the articulation points
+/// and bridges on it that would disconnect its ends.
+fn route_output(
+    graph: &Graph,
+    latency: &gt_path_core::graph::Graph,
+    route: &str,
+    (articulation_points, bridges): (&[NodeId], &[(NodeId, NodeId)]),
+    max_latency: Option<u32>,
+) -> Result<RouteOutput> {
+    let (from, to) = route
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid --route '{}': expected FROM:TO", route))?;
+    let path = latency
+        .shortest_path(from, to)
+        .map_err(anyhow::Error::from)
+        .context(format!("No shortest path for route {}", route))?;
+    let name = |n: NodeId| latency.to_name[n.0 as usize].to_string();
+    let nodes: Vec<NodeId> = path.path.iter().map(|n| NodeId(n.0)).collect();
+
+    let mut neighbors = vec![Vec::new(); graph.size()];
+    for e in graph.edges() {
+        neighbors[e.u.0 as usize].push(e.v);
+        neighbors[e.v.0 as usize].push(e.u);
+    }
+    // an articulation point may only split off nodes off the route
+    let cuts_route = |failed: NodeId| {
+        let mut seen = vec![false; graph.size()];
+        seen[failed.0 as usize] = true;
+        let mut stack = vec![nodes[0]];
+        while let Some(n) = stack.pop() {
+            if std::mem::replace(&mut seen[n.0 as usize], true) {
+                continue;
+            }
+            stack.extend(neighbors[n.0 as usize].iter().copied());
+        }
+        !seen[path.to.0 as usize]
+    };
+    let interior = match nodes.as_slice() {
+        [_, interior @ .., _] => interior,
+        _ => &[],
+    };
+    let cut_points = interior
+        .iter()
+        .filter(|&&n| articulation_points.contains(&n) && cuts_route(n))
+        .map(|&n| name(n))
+        .collect();
+    // a simple path crosses a bridge once, so every bridge on it splits the
+    // route's ends apart
+    let cut_bridges = nodes
+        .windows(2)
+        .filter(|w| bridges.contains(&(w[0].min(w[1]), w[0].max(w[1]))))
+        .map(|w| (name(w[0]), name(w[1])))
+        .collect();
+
+    Ok(RouteOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        path: nodes.iter().map(|&n| name(n)).collect(),
+        latency_ms: path.cost,
+        max_latency_ms: max_latency,
+        met: max_latency.map(|max| path.cost <= max),
+        articulation_points: cut_points,
+        bridges: cut_bridges,
+    })
+}
+
+/// Loads a CSV file whose nodes are labels rather than integer IDs.
+fn load_labeled_graph(path: &str) -> Result<(Graph, Vec<String>)> {
+    load_labeled_graph_reported(path).map(|(graph, labels, _)| (graph, labels))
+}
+
+/// Like `load_labeled_graph`, also returning details of how the file was read.
+fn load_labeled_graph_reported(path: &str) -> Result<(Graph, Vec<String>, LoadReport)> {
+    let file = std::fs::File::open(path).context(format!("Failed to read file: {}", path))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let bar = progress::bytes(len, "Loading graph");
+    let graph = load_labeled_csv_from_reader_with_report(bar.wrap_read(file));
+    bar.finish_and_clear();
+
+    let (mut graph, labels, report) = graph?;
+    filter_weights(&mut graph);
+    lint(&graph, &report, |v| labels[v.0 as usize].clone());
+    Ok((graph, labels, report))
+}
+
+/// Set from the global `--min-weight` and `--max-weight` flags.
+static WEIGHT_RANGE: OnceLock<(Option<f32>, Option<f32>)> = OnceLock::new();
+
+/// Drops edges outside `--min-weight`/`--max-weight`, if given, with a
+/// warning counting them.
+fn filter_weights(graph: &mut Graph) {
+    let Some(&(min, max)) = WEIGHT_RANGE.get() else {
+        return;
+    };
+    let dropped = graph.retain_edges(|e| {
+        min.is_none_or(|min| e.weight >= min) && max.is_none_or(|max| e.weight <= max)
+    });
+    if dropped > 0 {
+        let message = format!("{} edge(s) outside the weight range were dropped", dropped);
+        if !progress::quiet() {
+            eprintln!("Note: {}", message);
+        }
+        WARNINGS.lock().unwrap().push(WarningOutput {
+            kind: "edges_filtered".to_string(),
+            message,
+        });
+    }
+}
+
+/// Version of the JSON output format, reported as `schema_version` in
+/// every output object. Bumped whenever a field is renamed, removed, or
+/// changes meaning; adding a field doesn't bump it.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Warnings for the JSON `warnings` array, collected while loading.
+static WARNINGS: Mutex<Vec<WarningOutput>> = Mutex::new(Vec::new());
+
+/// Records warnings for input that loads fine but is likely a mistake:
+/// header rows after the first, nodes without edges, and node pairs joined
+/// by several edges. `name` renders a node for the messages.
+fn lint(graph: &Graph, report: &LoadReport, name: impl Fn(NodeId) -> String) {
+    let mut warnings = WARNINGS.lock().unwrap();
+    let mut warn = |kind: &str, message: String| {
+        warnings.push(WarningOutput {
+            kind: kind.to_string(),
+            message,
+        })
+    };
+
+    if report.stray_headers > 0 {
+        warn(
+            "header_rows_skipped",
+            format!(
+                "{} header row(s) after the first line were skipped",
+                report.stray_headers
+            ),
+        );
+    }
+
+    let isolated = graph.isolated_nodes();
+    if !isolated.is_empty() {
+        // sparse node IDs leave many gaps, so only the first few are named
+        let mut names: Vec<String> = isolated.iter().take(10).map(|&v| name(v)).collect();
+        if isolated.len() > names.len() {
+            names.push("...".to_string());
+        }
+        warn(
+            "isolated_nodes",
+            format!(
+                "{} node(s) have no edges: {}",
+                isolated.len(),
+                names.join(", ")
+            ),
+        );
+    }
+
+    for (u, v, count) in graph.duplicate_edges() {
+        warn(
+            "duplicate_edge",
+            format!("edge {} - {} is declared {} times", name(u), name(v), count),
+        );
+    }
+}
+
+fn run_mst(
+    graph_file: &str,
+    algo: MstAlgorithm,
+    max_degree: Option<u32>,
+    order: Option<(MstOrder, bool)>,
+    require_connected: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+
+    let mst = match (algo, max_degree) {
+        (MstAlgorithm::Kruskal, None) => kruskal(&graph),
+        (MstAlgorithm::Kruskal, Some(limit)) => degree_constrained_kruskal(&graph, limit as usize),
+    };
+
+    // the unconstrained MST has one edge per merge, so fewer edges here
+    // means the degree limit left pieces unconnected
+    let spanning = max_degree.map(|_| mst.edges.len() == kruskal(&graph).edges.len());
+    if spanning == Some(false) && !progress::quiet() {
+        eprintln!(
+            "Warning: --max-degree {} left the tree disconnected; no spanning tree was found within the limit",
+            max_degree.unwrap_or_default()
+        );
+    }
+
+    let mut edges: Vec<EdgeOutput> = mst
+        .edges
+        .iter()
+        .map(|e| EdgeOutput {
+            u: e.u.0,
+            v: e.v.0,
+            weight: e.weight,
+        })
+        .collect();
+    if let Some((key, desc)) = order {
+        sort_edges(&mut edges, key, desc);
+    }
+
+    let output = MstOutput {
+        graph_hash: Some(graph.content_hash()),
+        algorithm: "kruskal".to_string(),
+        max_degree,
+        spanning,
+        total_weight: mst.total_weight,
+        num_edges: mst.edges.len(),
+        edges,
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_mst_text(&output, &|n| n.to_string()),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => println!("{}", cypher::render(&graph, Some(&mst), None)),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by mst"),
+        OutputFormat::OpenMetrics => print!("{}", openmetrics::render(&mst_gauges(&output))),
+    }
+
+    if require_connected {
+        require_connected_graph(&graph);
+    }
+    Ok(())
+}
+
+/// Exit code for `--require-connected` on a disconnected graph.
+const EXIT_DISCONNECTED: i32 = 3;
+
+/// Lists the components and exits with `EXIT_DISCONNECTED` if `graph` is
+/// split, so CI notices a spanning forest instead of a tree.
+fn require_connected_graph(graph: &Graph) {
+    let components = graph.components();
+    if components.len() <= 1 {
+        return;
+    }
+    eprintln!(
+        "Error: graph is disconnected ({} components)",
+        components.len()
+    );
+    for (i, nodes) in components.iter().enumerate() {
+        // a component can hold most of the graph, so only the first few are named
+        let mut names: Vec<String> = nodes.iter().take(10).map(|n| n.0.to_string()).collect();
+        if nodes.len() > names.len() {
+            names.push("...".to_string());
+        }
+        eprintln!("  {}: {} node(s): {}", i, nodes.len(), names.join(", "));
+    }
+    process::exit(EXIT_DISCONNECTED);
+}
+
+/// Sorts tree edges by `key`, breaking ties by the other endpoints so the
+/// order doesn't depend on the algorithm.
+fn sort_edges(edges: &mut [EdgeOutput], key: MstOrder, desc: bool) {
+    edges.sort_by(|a, b| {
+        let ends = (a.u, a.v).cmp(&(b.u, b.v));
+        let ordering = match key {
+            MstOrder::Weight => a.weight.total_cmp(&b.weight).then(ends),
+            MstOrder::U => ends,
+            MstOrder::V => (a.v, a.u).cmp(&(b.v, b.u)),
+        };
+        if desc { ordering.reverse() } else { ordering }
+    });
+}
+
+fn run_verify_mst(graph_file: &str, tree_file: &str, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let tree = load_graph(tree_file).context("Failed to load tree")?;
+    let tree_edges = tree.edges();
+
+    let edge_output = |e: &graphs::graph::Edge| EdgeOutput {
+        u: e.u.0,
+        v: e.v.0,
+        weight: e.weight,
+    };
+    let violation = verify_mst(&graph, &tree_edges).err().map(|v| match v {
+        MstViolation::UnknownEdge(e) => ViolationOutput {
+            property: "membership".to_string(),
+            message: format!(
+                "tree edge {} -- {} (weight {:.2}) is not in the graph",
+                e.u.0, e.v.0, e.weight
+            ),
+            edge: edge_output(&e),
+            heaviest: None,
+        },
+        MstViolation::Disconnected(e) => ViolationOutput {
+            property: "connectivity".to_string(),
+            message: format!("tree does not connect {} and {}", e.u.0, e.v.0),
+            edge: edge_output(&e),
+            heaviest: None,
+        },
+        MstViolation::Cycle(e) => ViolationOutput {
+            property: "acyclicity".to_string(),
+            message: format!("tree edge {} -- {} closes a cycle", e.u.0, e.v.0),
+            edge: edge_output(&e),
+            heaviest: None,
+        },
+        MstViolation::NotMinimal { edge, heaviest } => ViolationOutput {
+            property: "cut_optimality".to_string(),
+            message: format!(
+                "edge {} -- {} (weight {:.2}) is lighter than tree edge {} -- {} (weight {:.2}) on its cycle",
+                edge.u.0, edge.v.0, edge.weight, heaviest.u.0, heaviest.v.0, heaviest.weight
+            ),
+            edge: edge_output(&edge),
+            heaviest: Some(edge_output(&heaviest)),
+        },
+    });
+
+    let output = VerifyMstOutput {
+        graph_hash: graph.content_hash(),
+        valid: violation.is_none(),
+        tree_edges: tree_edges.len(),
+        tree_weight: tree_edges.iter().map(|e| e.weight).sum(),
+        violation,
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_verify_mst_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by verify-mst"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by verify-mst"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by verify-mst")
+        }
+    }
+
+    if !output.valid {
+        process::exit(2);
+    }
+    Ok(())
+}
+
+fn print_verify_mst_text(output: &VerifyMstOutput) {
+    println!("MST Verification");
+    println!("  Tree Edges: {}", output.tree_edges);
+    println!("  Tree Weight: {:.2}", output.tree_weight);
+    match &output.violation {
+        None => println!("  Result: valid minimum spanning tree"),
+        Some(v) => {
+            println!("  Result: invalid ({})", v.property);
+            println!("\n{}", v.message);
+        }
+    }
+}
+
+/// Thresholds from `critical --max-bridges` / `--max-articulation-points`.
+struct CriticalLimits {
+    max_bridges: Option<usize>,
+    max_articulation_points: Option<usize>,
+}
+
+impl CriticalLimits {
+    /// The budget check for `output`, None without any limit set.
+    fn check(&self, output: &CriticalOutput) -> Option<CriticalBudgetOutput> {
+        if self.max_bridges.is_none() && self.max_articulation_points.is_none() {
+            return None;
+        }
+        let within = |count: usize, max: Option<usize>| max.is_none_or(|max| count <= max);
+        Some(CriticalBudgetOutput {
+            max_bridges: self.max_bridges,
+            max_articulation_points: self.max_articulation_points,
+            passed: within(output.num_bridges, self.max_bridges)
+                && within(output.num_articulation_points, self.max_articulation_points),
+        })
+    }
+}
+
+/// Exit code for `critical` over its bridge or articulation point budget.
+const EXIT_OVER_BUDGET: i32 = 4;
+
+fn run_critical(
+    graph_file: &str,
+    directed: bool,
+    limits: &CriticalLimits,
+    baseline: Option<&Baseline>,
+    format: OutputFormat,
+) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+
+    if directed {
+        return run_critical_directed(graph_file, &graph, limits, baseline, format);
+    }
+
+    let (articulation_points, bridges) = graph.critical_components();
+
+    let mut output = critical_output(&graph, &articulation_points, &bridges);
+    output.graph_hash = Some(graph.content_hash());
+    output.budget = limits.check(&output);
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_critical_text(&output, &|n| n.to_string()),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => println!(
+            "{}",
+            cypher::render(&graph, None, Some((&articulation_points, &bridges)))
+        ),
+        OutputFormat::Sarif => print_sarif(graph_file, critical_findings(&output))?,
+        OutputFormat::OpenMetrics => print!("{}", openmetrics::render(&critical_gauges(&output))),
+    }
+
+    if let Some(baseline) = baseline {
+        baseline.check(&output, critical_regressions)?;
+    }
+    exit_if_over_budget(&output);
+    Ok(())
+}
+
+/// Exit code for `--baseline` when the results regressed.
+const EXIT_REGRESSED: i32 = 5;
+
+/// `--baseline FILE`, with `--update-baseline`.
+struct Baseline {
+    path: String,
+    update: bool,
+}
+
+impl Baseline {
+    /// With `update`, writes `output` as the new baseline. Otherwise reads
+    /// the baseline, which is earlier JSON output of the same command, and
+    /// exits with `EXIT_REGRESSED` after listing any regressions
+    /// `regressions` finds.
+    fn check<T: Serialize, B: DeserializeOwned>(
+        &self,
+        output: &T,
+        regressions: impl Fn(&T, B) -> Vec<String>,
+    ) -> Result<()> {
+        if self.update {
+            let json = serde_json::to_string_pretty(output)?;
+            std::fs::write(&self.path, json + "\n")
+                .context(format!("Failed to write baseline: {}", self.path))?;
+            if !progress::quiet() {
+                eprintln!("Note: baseline written to {}", self.path);
+            }
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&self.path)
+            .context(format!("Failed to read baseline: {}", self.path))?;
+        let stored: B = serde_json::from_str(&content)
+            .context(format!("Failed to parse baseline: {}", self.path))?;
+        let found = regressions(output, stored);
+        if found.is_empty() {
+            return Ok(());
+        }
+        eprintln!("Error: {} regression(s) against {}", found.len(), self.path);
+        for r in &found {
+            eprintln!("  {}", r);
+        }
+        process::exit(EXIT_REGRESSED);
+    }
+}
+
+/// The parts of stored `critical` output a baseline compares.
+#[derive(Deserialize)]
+struct CriticalBaseline {
+    bridges: Vec<(u32, u32)>,
+    articulation_points: Vec<u32>,
+}
+
+/// Bridges and articulation points the baseline doesn't have.
+fn critical_regressions(output: &CriticalOutput, baseline: CriticalBaseline) -> Vec<String> {
+    let arrow = if output.directed { "->" } else { "--" };
+    let bridges = output
+        .bridges
+        .iter()
+        .filter(|b| !baseline.bridges.contains(b))
+        .map(|(u, v)| format!("new bridge {} {} {}", u, arrow, v));
+    let points = output
+        .articulation_points
+        .iter()
+        .filter(|n| !baseline.articulation_points.contains(n))
+        .map(|n| format!("new articulation point {}", n));
+    bridges.chain(points).collect()
+}
+
+/// The parts of stored `risk` output a baseline compares.
+#[derive(Deserialize)]
+struct RiskBaseline {
+    edges: Vec<EdgeScoreBaseline>,
+}
+
+#[derive(Deserialize)]
+struct EdgeScoreBaseline {
+    u: u32,
+    v: u32,
+    score: f64,
+}
+
+/// Listed edges scoring higher than in the baseline. An edge the baseline
+/// doesn't list counts as having scored 0 there.
+fn risk_regressions(output: &RiskOutput, baseline: RiskBaseline) -> Vec<String> {
+    output
+        .edges
+        .iter()
+        .filter_map(|e| {
+            let before = baseline
+                .edges
+                .iter()
+                .find(|b| (b.u, b.v) == (e.u, e.v))
+                .map_or(0.0, |b| b.score);
+            // scores are recomputed from the same floats, so allow for rounding
+            (e.score > before + 1e-9).then(|| {
+                format!(
+                    "edge {} - {} risk score rose from {:.2} to {:.2}",
+                    e.u, e.v, before, e.score
+                )
+            })
+        })
+        .collect()
+}
+
+/// Exits with `EXIT_OVER_BUDGET` if `output` failed its budget check.
+fn exit_if_over_budget(output: &CriticalOutput) {
+    if output.budget.as_ref().is_some_and(|b| !b.passed) {
+        process::exit(EXIT_OVER_BUDGET);
+    }
+}
+
+fn run_critical_directed(
+    graph_file: &str,
+    graph: &Graph,
+    limits: &CriticalLimits,
+    baseline: Option<&Baseline>,
+    format: OutputFormat,
+) -> Result<()> {
+    if matches!(format, OutputFormat::Cypher) {
+        anyhow::bail!("--format cypher is not supported with --directed");
+    }
+
+    let critical = strong_critical_components(graph);
+    let mut output = CriticalOutput {
+        graph_hash: Some(graph.content_hash()),
+        directed: true,
+        num_bridges: critical.bridges.len(),
+        num_articulation_points: critical.articulation_points.len(),
+        bridges: critical.bridges.iter().map(|(u, v)| (u.0, v.0)).collect(),
+        articulation_points: critical.articulation_points.iter().map(|n| n.0).collect(),
+        bridge_splits: None,
+        budget: None,
+    };
+    output.budget = limits.check(&output);
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_critical_text(&output, &|n| n.to_string()),
+        OutputFormat::Sarif => print_sarif(graph_file, critical_findings(&output))?,
+        OutputFormat::OpenMetrics => print!("{}", openmetrics::render(&critical_gauges(&output))),
+        _ => print_json(&output)?,
+    }
+
+    if let Some(baseline) = baseline {
+        baseline.check(&output, critical_regressions)?;
+    }
+    exit_if_over_budget(&output);
+    Ok(())
+}
+
+fn run_cut(graph_file: &str, from: u32, to: u32, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    for id in [from, to] {
+        if id as usize >= graph.size() {
+            anyhow::bail!("node {} not in graph ({} nodes)", id, graph.size());
+        }
+    }
+
+    let Some(cut) = min_vertex_cut(&graph, NodeId(from), NodeId(to)) else {
+        anyhow::bail!(
+            "nodes {} and {} are the same or adjacent; no set of other nodes separates them",
+            from,
+            to
+        );
+    };
+
+    let output = CutOutput {
+        graph_hash: graph.content_hash(),
+        from,
+        to,
+        size: cut.len(),
+        nodes: cut.iter().map(|n| n.0).collect(),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_cut_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by cut"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by cut"),
+        OutputFormat::OpenMetrics => anyhow::bail!("--format openmetrics is not supported by cut"),
+    }
+
+    Ok(())
+}
+
+fn print_cut_text(output: &CutOutput) {
+    println!("Minimum Vertex Cut: {} -- {}", output.from, output.to);
+    println!("  Size: {}", output.size);
+    if output.size == 0 {
+        println!("  Already disconnected");
+        return;
+    }
+    println!(
+        "  Tolerates: {} simultaneous node failures",
+        output.size - 1
+    );
+
+    listing::section("Cut nodes:", output.nodes.iter(), |n| n.to_string());
+}
+
+fn run_connectivity(graph_file: &str, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let edge = edge_connectivity(&graph);
+    let vertex = vertex_connectivity(&graph);
+
+    let output = ConnectivityOutput {
+        graph_hash: graph.content_hash(),
+        edge_connectivity: edge.value,
+        vertex_connectivity: vertex.value,
+        edge_cut: edge.cut.iter().map(|(u, v)| (u.0, v.0)).collect(),
+        vertex_cut: vertex.cut.iter().map(|n| n.0).collect(),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_connectivity_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => {
+            anyhow::bail!("--format cypher is not supported by connectivity")
+        }
+        OutputFormat::Sarif => {
+            anyhow::bail!("--format sarif is not supported by connectivity")
+        }
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by connectivity")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_connectivity_text(output: &ConnectivityOutput) {
+    println!("Connectivity");
+    println!("  Edge Connectivity: {}", output.edge_connectivity);
+    println!("  Vertex Connectivity: {}", output.vertex_connectivity);
+
+    if !output.edge_cut.is_empty() {
+        listing::section("Minimum edge cut:", output.edge_cut.iter(), |(u, v)| {
+            format!("{} -- {}", u, v)
+        });
+    }
+
+    if !output.vertex_cut.is_empty() {
+        listing::section("Minimum vertex cut:", output.vertex_cut.iter(), |n| {
+            n.to_string()
+        });
+    }
+}
+
+fn run_match(
+    graph_file: &str,
+    left_prefix: &str,
+    right_prefix: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let (graph, labels) = load_labeled_graph(graph_file).context("Failed to load graph")?;
+
+    let mut left = Vec::with_capacity(labels.len());
+    for label in &labels {
+        match (
+            label.starts_with(left_prefix),
+            label.starts_with(right_prefix),
+        ) {
+            (true, false) => left.push(true),
+            (false, true) => left.push(false),
+            _ => anyhow::bail!(
+                "node '{}' must match exactly one of --left-prefix '{}' and --right-prefix '{}'",
+                label,
+                left_prefix,
+                right_prefix
+            ),
+        }
+    }
+    for e in graph.edges() {
+        if left[e.u.0 as usize] == left[e.v.0 as usize] {
+            anyhow::bail!(
+                "edge {} -- {} joins two nodes on the same side; the graph must be bipartite",
+                labels[e.u.0 as usize],
+                labels[e.v.0 as usize]
+            );
+        }
+    }
+
+    let pairs = hopcroft_karp(&graph, &left);
+    let mut matched = vec![false; labels.len()];
+    for (u, v) in &pairs {
+        matched[u.0 as usize] = true;
+        matched[v.0 as usize] = true;
+    }
+    let unmatched = |side: bool| -> Vec<String> {
+        (0..labels.len())
+            .filter(|&n| left[n] == side && !matched[n])
+            .map(|n| labels[n].clone())
+            .collect()
+    };
+
+    let output = MatchOutput {
+        graph_hash: graph.content_hash(),
+        size: pairs.len(),
+        pairs: pairs
+            .iter()
+            .map(|(u, v)| MatchPairOutput {
+                left: labels[u.0 as usize].clone(),
+                right: labels[v.0 as usize].clone(),
+            })
+            .collect(),
+        unmatched_left: unmatched(true),
+        unmatched_right: unmatched(false),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_match_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by match"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by match"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by match")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_match_text(output: &MatchOutput) {
+    println!("Maximum Matching");
+    println!("  Matched Pairs: {}", output.size);
+    println!("  Unmatched Left: {}", output.unmatched_left.len());
+    println!("  Unmatched Right: {}", output.unmatched_right.len());
+
+    if !output.pairs.is_empty() {
+        listing::section("Pairs:", output.pairs.iter(), |p| {
+            format!("{} -- {}", p.left, p.right)
+        });
+    }
+
+    if !output.unmatched_left.is_empty() {
+        listing::section("Unmatched left nodes:", output.unmatched_left.iter(), |l| {
+            l.clone()
+        });
+    }
+
+    if !output.unmatched_right.is_empty() {
+        listing::section(
+            "Unmatched right nodes:",
+            output.unmatched_right.iter(),
+            |l| l.clone(),
+        );
+    }
+}
+
+fn run_color(graph_file: &str, algo: ColorAlgorithm, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+
+    let (coloring, algorithm) = match algo {
+        ColorAlgorithm::Greedy => (greedy_coloring(&graph), "greedy"),
+        ColorAlgorithm::Dsatur => (dsatur(&graph), "dsatur"),
+    };
+
+    let output = ColoringOutput {
+        graph_hash: graph.content_hash(),
+        algorithm: algorithm.to_string(),
+        num_colors: coloring.num_colors,
+        colors: coloring.colors,
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_coloring_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by color"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by color"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by color")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_coloring_text(output: &ColoringOutput) {
+    println!("Graph Coloring ({})", output.algorithm);
+    println!("  Colors Used: {}", output.num_colors);
+
+    if output.num_colors > 0 {
+        listing::section("Nodes by color:", 0..output.num_colors, |color| {
+            let nodes: Vec<usize> = output
+                .colors
+                .iter()
+                .enumerate()
+                .filter(|&(_, &c)| c == color)
+                .map(|(n, _)| n)
+                .collect();
+            format!("{}: {}", color, listing::join(&nodes))
+        });
+    }
+}
+
+fn run_cluster(graph_file: &str, k: u32, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    if k as usize > graph.size() {
+        anyhow::bail!("--k {} exceeds the number of nodes ({})", k, graph.size());
+    }
+
+    let clustering = mst_clusters(&graph, k as usize);
+
+    let output = ClusteringOutput {
+        graph_hash: graph.content_hash(),
+        k,
+        num_clusters: clustering.members.len(),
+        inter_cluster_weight: clustering.inter_weight,
+        clusters: clustering
+            .members
+            .iter()
+            .zip(&clustering.intra_weight)
+            .enumerate()
+            .map(|(id, (nodes, &weight))| ClusterOutput {
+                id,
+                nodes: nodes.iter().map(|n| n.0).collect(),
+                intra_cluster_weight: weight,
+            })
+            .collect(),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_clustering_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by cluster"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by cluster"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by cluster")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_clustering_text(output: &ClusteringOutput) {
+    println!("MST Clustering (k = {})", output.k);
+    println!("  Clusters: {}", output.num_clusters);
+    println!("  Inter-cluster Weight: {:.2}", output.inter_cluster_weight);
+
+    listing::section("Clusters:", output.clusters.iter(), |cluster| {
+        format!(
+            "c{}: {} nodes, intra weight {:.2} [{}]",
+            cluster.id,
+            cluster.nodes.len(),
+            cluster.intra_cluster_weight,
+            listing::join(&cluster.nodes)
+        )
+    });
+}
+
+fn run_spectral(graph_file: &str, k: u32, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let spectrum = laplacian_spectrum(&graph, k as usize);
+
+    let output = SpectralOutput {
+        graph_hash: graph.content_hash(),
+        algebraic_connectivity: spectrum.algebraic_connectivity(),
+        eigenvalues: spectrum.eigenvalues,
+        exact: spectrum.exact,
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_spectral_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by spectral"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by spectral"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by spectral")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_spectral_text(output: &SpectralOutput) {
+    if output.exact {
+        println!("Laplacian Spectrum");
+    } else {
+        println!("Laplacian Spectrum (Lanczos approximation)");
+    }
+    match output.algebraic_connectivity {
+        Some(value) => println!("  Algebraic Connectivity: {:.4}", value),
+        None => println!("  Algebraic Connectivity: n/a (fewer than two nodes)"),
+    }
+
+    listing::section(
+        "Smallest eigenvalues:",
+        output.eigenvalues.iter().enumerate(),
+        |(i, value)| format!("λ{}: {:.4}", i + 1, value),
+    );
+}
+
+fn run_densest(graph_file: &str, format: OutputFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let dense = densest_subgraph(&graph);
+
+    let output = DensestOutput {
+        graph_hash: graph.content_hash(),
+        density: dense.density,
+        num_nodes: dense.nodes.len(),
+        num_edges: dense.edges,
+        nodes: dense.nodes.iter().map(|n| n.0).collect(),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_densest_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by densest"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by densest"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by densest")
+        }
+    }
+
+    Ok(())
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Compute minimum spanning tree
-    Mst {
-        /// Path to graph CSV file (format: u,v,weight)
-        #[arg(short, long)]
-        graph: String,
+fn print_densest_text(output: &DensestOutput) {
+    println!("Densest Subgraph (peeling approximation)");
+    println!("  Density: {:.2} edges per node", output.density);
+    println!("  Nodes: {}", output.num_nodes);
+    println!("  Edges: {}", output.num_edges);
 
-        /// Algorithm to use
-        #[arg(long, value_enum, default_value = "kruskal")]
-        algo: MstAlgorithm,
+    if !output.nodes.is_empty() && !listing::summary_only() {
+        println!("\nMembers: {}", listing::join(&output.nodes));
+    }
+}
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+fn run_risk(
+    graph_file: &str,
+    top: usize,
+    baseline: Option<&Baseline>,
+    format: OutputFormat,
+) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let (_, bridges) = graph.critical_components();
+    let betweenness = edge_betweenness(&graph);
 
-    /// Find critical components (bridges and articulation points)
-    Critical {
-        /// Path to graph CSV file (format: u,v,weight)
-        #[arg(short, long)]
-        graph: String,
+    let mut edges: Vec<EdgeRiskOutput> = graph
+        .edges()
+        .iter()
+        .zip(betweenness)
+        .map(|(e, betweenness)| {
+            let (u, v) = (e.u.min(e.v), e.u.max(e.v));
+            let is_bridge = bridges.binary_search(&(u, v)).is_ok();
+            EdgeRiskOutput {
+                u: u.0,
+                v: v.0,
+                weight: e.weight,
+                betweenness,
+                is_bridge,
+                score: if is_bridge {
+                    betweenness * e.weight as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    // non-bridges all score 0; among them, busier and heavier edges go first
+    edges.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then((b.betweenness * b.weight as f64).total_cmp(&(a.betweenness * a.weight as f64)))
+            .then((a.u, a.v).cmp(&(b.u, b.v)))
+    });
+    let num_edges = edges.len();
+    edges.truncate(top);
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+    let output = RiskOutput {
+        graph_hash: graph.content_hash(),
+        num_edges,
+        num_bridges: bridges.len(),
+        edges,
+    };
 
-    /// Full connectivity analysis (MST + critical components)
-    Analyze {
-        /// Path to graph CSV file (format: u,v,weight)
-        #[arg(short, long)]
-        graph: String,
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_risk_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by risk"),
+        OutputFormat::Sarif => print_sarif(graph_file, risk_findings(&output))?,
+        OutputFormat::OpenMetrics => anyhow::bail!("--format openmetrics is not supported by risk"),
+    }
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+    if let Some(baseline) = baseline {
+        baseline.check(&output, risk_regressions)?;
+    }
+    Ok(())
 }
 
-#[derive(Clone, ValueEnum)]
-enum MstAlgorithm {
-    Kruskal,
-}
+fn print_risk_text(output: &RiskOutput) {
+    println!("Edge Risk Report");
+    println!("  Edges: {}", output.num_edges);
+    println!("  Bridges: {}", output.num_bridges);
 
-#[derive(Clone, ValueEnum)]
-enum OutputFormat {
-    Text,
-    Json,
+    if output.edges.is_empty() {
+        return;
+    }
+    listing::section(
+        &format!("Top {} edges:", output.edges.len()),
+        output.edges.iter().enumerate(),
+        |(rank, e)| {
+            format!(
+                "{}. {} - {}: score {:.2} (betweenness {:.2}, weight {:.2}{})",
+                rank + 1,
+                e.u,
+                e.v,
+                e.score,
+                e.betweenness,
+                e.weight,
+                if e.is_bridge { ", bridge" } else { "" }
+            )
+        },
+    );
 }
 
-#[derive(Serialize)]
-struct MstOutput {
-    algorithm: String,
-    total_weight: f32,
-    num_edges: usize,
-    edges: Vec<EdgeOutput>,
+/// Settings for `robustness`.
+struct Failures {
+    trials: u32,
+    remove_fraction: f64,
+    steps: u32,
+    target: FailureTarget,
+    weighted: bool,
+    seed: u64,
 }
 
-#[derive(Serialize)]
-struct EdgeOutput {
-    u: u32,
-    v: u32,
-    weight: f32,
-}
+fn run_robustness(graph_file: &str, failures: &Failures, format: OutputFormat) -> Result<()> {
+    use rand::seq::index;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
 
-#[derive(Serialize)]
-struct CriticalOutput {
-    num_bridges: usize,
-    num_articulation_points: usize,
-    bridges: Vec<(u32, u32)>,
-    articulation_points: Vec<u32>,
-}
+    if !(0.0..=1.0).contains(&failures.remove_fraction) {
+        anyhow::bail!(
+            "--remove-fraction must be between 0 and 1, got {}",
+            failures.remove_fraction
+        );
+    }
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let edges = graph.edges();
+    let n = graph.size();
 
-#[derive(Serialize)]
-struct AnalysisOutput {
-    mst: MstOutput,
-    critical: CriticalOutput,
-}
+    // what can fail, with the weight --weighted picks it by
+    let weights: Vec<f64> = match failures.target {
+        FailureTarget::Edges => edges.iter().map(|e| e.weight.max(0.0) as f64).collect(),
+        FailureTarget::Nodes => {
+            let mut weights = vec![0.0; n];
+            for e in &edges {
+                weights[e.u.0 as usize] += e.weight.max(0.0) as f64;
+                weights[e.v.0 as usize] += e.weight.max(0.0) as f64;
+            }
+            weights
+        }
+    };
+    let mut rng = StdRng::seed_from_u64(failures.seed);
+    // weighted sampling without replacement (Efraimidis-Spirakis): keep
+    // the k largest of u^(1/w)
+    let mut pick = |k: usize| -> Vec<bool> {
+        let mut failed = vec![false; weights.len()];
+        if failures.weighted {
+            let mut keys: Vec<(f64, usize)> = weights
+                .iter()
+                .enumerate()
+                .map(|(i, &w)| (rng.r#gen::<f64>().ln() / w, i))
+                .collect();
+            keys.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+            for &(_, i) in keys.iter().take(k) {
+                failed[i] = true;
+            }
+        } else {
+            for i in index::sample(&mut rng, weights.len(), k) {
+                failed[i] = true;
+            }
+        }
+        failed
+    };
 
-fn main() {
-    let cli = Cli::parse();
+    let fraction_of_nodes = |size: usize| if n == 0 { 1.0 } else { size as f64 / n as f64 };
+    let intact = fragmentation(&graph, &[], &[]);
+    let mut curve = vec![RobustnessPointOutput {
+        fraction: 0.0,
+        removed: 0,
+        mean_largest_component: fraction_of_nodes(intact.largest_component),
+        min_largest_component: fraction_of_nodes(intact.largest_component),
+        mean_disconnected_pairs: intact.disconnected_pairs as f64,
+        split_probability: (intact.largest_component < intact.surviving) as u8 as f64,
+    }];
 
-    let result = match cli.command {
-        Commands::Mst {
-            graph,
-            algo,
-            format,
-        } => run_mst(&graph, algo, format),
-        Commands::Critical { graph, format } => run_critical(&graph, format),
-        Commands::Analyze { graph, format } => run_analyze(&graph, format),
+    let bar = progress::items(
+        failures.steps as u64 * failures.trials as u64,
+        "Failing at random",
+    );
+    for step in 1..=failures.steps {
+        let fraction = failures.remove_fraction * step as f64 / failures.steps as f64;
+        let removed = (fraction * weights.len() as f64).round() as usize;
+        let (mut largest, mut smallest, mut pairs, mut splits) = (0.0, 1.0f64, 0.0, 0);
+        for _ in 0..failures.trials {
+            let failed = pick(removed);
+            let f = match failures.target {
+                FailureTarget::Edges => fragmentation(&graph, &[], &failed),
+                FailureTarget::Nodes => fragmentation(&graph, &failed, &[]),
+            };
+            largest += fraction_of_nodes(f.largest_component);
+            smallest = smallest.min(fraction_of_nodes(f.largest_component));
+            pairs += f.disconnected_pairs as f64;
+            splits += (f.largest_component < f.surviving) as u32;
+            bar.inc(1);
+        }
+        let trials = failures.trials as f64;
+        curve.push(RobustnessPointOutput {
+            fraction,
+            removed,
+            mean_largest_component: largest / trials,
+            min_largest_component: smallest,
+            mean_disconnected_pairs: pairs / trials,
+            split_probability: splits as f64 / trials,
+        });
+    }
+    bar.finish_and_clear();
+
+    let output = RobustnessOutput {
+        graph_hash: graph.content_hash(),
+        num_nodes: n,
+        num_edges: edges.len(),
+        removed: match failures.target {
+            FailureTarget::Edges => "edges",
+            FailureTarget::Nodes => "nodes",
+        },
+        weighted: failures.weighted,
+        trials: failures.trials,
+        seed: failures.seed,
+        score: curve.iter().map(|p| p.mean_largest_component).sum::<f64>() / curve.len() as f64,
+        curve,
     };
 
-    if let Err(e) = result {
-        eprintln!("Error: {:#}", e);
-        process::exit(1);
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_robustness_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by robustness"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by robustness"),
+        OutputFormat::OpenMetrics => {
+            print!("{}", openmetrics::render(&robustness_gauges(&output)))
+        }
+    }
+
+    Ok(())
+}
+
+fn print_robustness_text(output: &RobustnessOutput) {
+    println!(
+        "Robustness ({}random {} failures, {} trials per step)",
+        if output.weighted { "weighted " } else { "" },
+        &output.removed[..output.removed.len() - 1],
+        output.trials
+    );
+    println!("  Nodes: {}", output.num_nodes);
+    println!("  Edges: {}", output.num_edges);
+    println!("  Score: {:.3} (mean largest component)", output.score);
+
+    println!(
+        "
+Curve:"
+    );
+    for p in &output.curve {
+        println!(
+            "  {:.1}% removed ({}): largest component {:.1}% (min {:.1}%), \
+             {:.1} disconnected pairs, split in {:.0}% of trials",
+            p.fraction * 100.0,
+            p.removed,
+            p.mean_largest_component * 100.0,
+            p.min_largest_component * 100.0,
+            p.mean_disconnected_pairs,
+            p.split_probability * 100.0
+        );
     }
 }
 
-fn run_mst(graph_file: &str, algo: MstAlgorithm, format: OutputFormat) -> Result<()> {
-    let graph = load_csv(graph_file).context("Failed to load graph")?;
+fn robustness_gauges(output: &RobustnessOutput) -> Vec<openmetrics::Gauge> {
+    let mut largest = openmetrics::Gauge::new(
+        "gt_connect_robustness_largest_component",
+        "Mean largest component after random failures, as a fraction of all nodes",
+    );
+    let mut split = openmetrics::Gauge::new(
+        "gt_connect_robustness_split_probability",
+        "Fraction of random failure trials that split the graph",
+    );
+    for p in &output.curve {
+        let labels = || {
+            vec![
+                ("removed", output.removed.to_string()),
+                ("fraction", p.fraction.to_string()),
+            ]
+        };
+        largest = largest.sample(labels(), p.mean_largest_component);
+        split = split.sample(labels(), p.split_probability);
+    }
+    vec![
+        openmetrics::Gauge::new(
+            "gt_connect_robustness_score",
+            "Mean largest component over the robustness curve",
+        )
+        .sample(vec![], output.score),
+        largest,
+        split,
+    ]
+}
 
-    let mst = match algo {
-        MstAlgorithm::Kruskal => kruskal(&graph),
+fn run_arborescence(graph_file: &str, root: &str, format: OutputFormat) -> Result<()> {
+    let (graph, labels) = load_labeled_graph(graph_file).context("Failed to load graph")?;
+    let root_id = labels
+        .iter()
+        .position(|l| l == root)
+        .context(format!("Node not found: {}", root))?;
+
+    let tree = match min_arborescence(&graph, NodeId(root_id as u32)) {
+        Ok(tree) => tree,
+        Err(unreachable) => {
+            let names: Vec<&str> = unreachable
+                .iter()
+                .map(|n| labels[n.0 as usize].as_str())
+                .collect();
+            anyhow::bail!(
+                "{} node(s) unreachable from {}: {}",
+                names.len(),
+                root,
+                names.join(", ")
+            );
+        }
     };
 
-    let output = MstOutput {
-        algorithm: "kruskal".to_string(),
-        total_weight: mst.total_weight,
-        num_edges: mst.edges.len(),
-        edges: mst
-            .edges
+    let output = ArborescenceOutput {
+        graph_hash: graph.content_hash(),
+        root: root.to_string(),
+        total_weight: tree.total_weight,
+        num_arcs: tree.arcs.len(),
+        arcs: tree
+            .arcs
             .iter()
-            .map(|e| EdgeOutput {
-                u: e.u.0,
-                v: e.v.0,
+            .map(|e| ArcOutput {
+                from: labels[e.u.0 as usize].clone(),
+                to: labels[e.v.0 as usize].clone(),
                 weight: e.weight,
             })
             .collect(),
     };
 
     match format {
-        OutputFormat::Text => print_mst_text(&output),
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_arborescence_text(&output),
         OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by arborescence"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by arborescence"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by arborescence")
+        }
     }
 
     Ok(())
 }
 
-fn run_critical(graph_file: &str, format: OutputFormat) -> Result<()> {
-    let graph = load_csv(graph_file).context("Failed to load graph")?;
+fn print_arborescence_text(output: &ArborescenceOutput) {
+    println!("Minimum Arborescence (root: {})", output.root);
+    println!("  Total Weight: {:.2}", output.total_weight);
+    println!("  Arcs: {}", output.num_arcs);
+    listing::section("Arcs:", output.arcs.iter(), |arc| {
+        format!("{} -> {} (weight: {:.2})", arc.from, arc.to, arc.weight)
+    });
+}
+
+fn run_condense(graph_file: &str, format: CondenseFormat) -> Result<()> {
+    let graph = load_graph(graph_file).context("Failed to load graph")?;
+    let condensed = condensation(&graph);
 
-    let (articulation_points, bridges) = graph.critical_components();
+    let output = CondensationOutput {
+        graph_hash: graph.content_hash(),
+        num_components: condensed.members.len(),
+        num_cyclic: condensed.members.iter().filter(|m| m.len() > 1).count(),
+        components: condensed
+            .members
+            .iter()
+            .enumerate()
+            .map(|(id, nodes)| ComponentOutput {
+                id,
+                nodes: nodes.iter().map(|n| n.0).collect(),
+            })
+            .collect(),
+        edges: condensed
+            .arcs
+            .iter()
+            .map(|a| CondensedEdgeOutput {
+                from: a.from,
+                to: a.to,
+                arcs: a.count,
+            })
+            .collect(),
+    };
+
+    match format {
+        CondenseFormat::Text if progress::quiet() => {}
+        CondenseFormat::Text => print_condensation_text(&output),
+        CondenseFormat::Json => print_json(&output)?,
+        CondenseFormat::Dot => print!("{}", condensation_dot(&output)),
+    }
+
+    Ok(())
+}
+
+fn print_condensation_text(output: &CondensationOutput) {
+    println!("Condensation");
+    println!("  Components: {}", output.num_components);
+    println!("  Cyclic Components: {}", output.num_cyclic);
+    println!("  Edges: {}", output.edges.len());
+
+    let cyclic: Vec<_> = output
+        .components
+        .iter()
+        .filter(|c| c.nodes.len() > 1)
+        .collect();
+    if !cyclic.is_empty() {
+        listing::section("Cyclic components:", cyclic.into_iter(), |c| {
+            format!(
+                "c{}: {} nodes [{}]",
+                c.id,
+                c.nodes.len(),
+                listing::join(&c.nodes)
+            )
+        });
+    }
+
+    if !output.edges.is_empty() {
+        listing::section("Edges:", output.edges.iter(), |e| {
+            format!("c{} -> c{} ({} arcs)", e.from, e.to, e.arcs)
+        });
+    }
+}
+
+/// Renders the condensation as a Graphviz digraph. Components are labeled
+/// with their member node IDs; cyclic ones are boxed so they stand out.
+fn condensation_dot(output: &CondensationOutput) -> String {
+    let mut dot = String::from("digraph condensation {\n");
+    for c in &output.components {
+        let nodes: Vec<String> = c.nodes.iter().map(|n| n.to_string()).collect();
+        let shape = if c.nodes.len() > 1 { "box" } else { "ellipse" };
+        dot.push_str(&format!(
+            "  c{} [label=\"{}\", shape={}];\n",
+            c.id,
+            nodes.join(", "),
+            shape
+        ));
+    }
+    for e in &output.edges {
+        dot.push_str(&format!(
+            "  c{} -> c{} [label=\"{}\"];\n",
+            e.from, e.to, e.arcs
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
 
-    let output = CriticalOutput {
+fn critical_output(
+    graph: &Graph,
+    articulation_points: &[NodeId],
+    bridges: &[(NodeId, NodeId)],
+) -> CriticalOutput {
+    CriticalOutput {
+        graph_hash: None,
+        directed: false,
         num_bridges: bridges.len(),
         num_articulation_points: articulation_points.len(),
         bridges: bridges.iter().map(|(u, v)| (u.0, v.0)).collect(),
         articulation_points: articulation_points.iter().map(|n| n.0).collect(),
+        bridge_splits: Some(
+            bridge_splits(graph)
+                .into_iter()
+                .map(|s| BridgeSplitOutput {
+                    u: s.u.0,
+                    v: s.v.0,
+                    components: s.components,
+                    component_sizes: s.component_sizes,
+                    side_sizes: s.side_sizes,
+                })
+                .collect(),
+        ),
+        budget: None,
+    }
+}
+
+fn run_describe(graph_file: &str, format: OutputFormat) -> Result<()> {
+    // integer IDs if every row has them, labels otherwise
+    let (graph, format_name, report) = match load_graph_reported(graph_file) {
+        Ok((graph, report)) => (graph, "csv", report),
+        Err(e) if matches!(e.downcast_ref(), Some(IoError::InvalidNodeId(_))) => {
+            let (graph, _, report) =
+                load_labeled_graph_reported(graph_file).context("Failed to load graph")?;
+            (graph, "labeled_csv", report)
+        }
+        Err(e) => return Err(e.context("Failed to load graph")),
+    };
+
+    let edges = graph.edges();
+    let mut degree = vec![0usize; graph.size()];
+    for e in &edges {
+        degree[e.u.0 as usize] += 1;
+        degree[e.v.0 as usize] += 1;
+    }
+    let mut weights: Vec<f32> = edges.iter().map(|e| e.weight).collect();
+    weights.sort_by(f32::total_cmp);
+    // nearest rank
+    let percentile = |p: f64| {
+        let rank = (p / 100.0 * weights.len() as f64).ceil() as usize;
+        weights[rank.clamp(1, weights.len()) - 1]
+    };
+    let components = graph.component_count();
+
+    let output = DescribeOutput {
+        graph_hash: graph.content_hash(),
+        format: format_name.to_string(),
+        header: report.header,
+        num_nodes: graph.size(),
+        num_edges: edges.len(),
+        degree: DegreeSummary {
+            min: degree.iter().copied().min().unwrap_or(0),
+            mean: degree.iter().sum::<usize>() as f64 / graph.size().max(1) as f64,
+            max: degree.iter().copied().max().unwrap_or(0),
+        },
+        weight: (!weights.is_empty()).then(|| WeightSummary {
+            min: weights[0],
+            mean: weights.iter().map(|&w| w as f64).sum::<f64>() / weights.len() as f64,
+            median: percentile(50.0),
+            p90: percentile(90.0),
+            max: weights[weights.len() - 1],
+        }),
+        components,
+        connected: components <= 1,
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_describe_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by describe"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by describe"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by describe")
+        }
+    }
+
+    Ok(())
+}
+
+fn run_validate(graph_file: &str, format: OutputFormat) -> Result<()> {
+    let graph = match load_graph(graph_file) {
+        Ok(graph) => graph,
+        Err(e) if matches!(e.downcast_ref(), Some(IoError::InvalidNodeId(_))) => {
+            load_labeled_graph(graph_file)
+                .context("Failed to load graph")?
+                .0
+        }
+        Err(e) => return Err(e.context("Failed to load graph")),
+    };
+
+    let output = ValidateOutput {
+        graph_hash: graph.content_hash(),
+        num_nodes: graph.size(),
+        num_edges: graph.edges().len(),
+    };
+
+    match format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => {
+            let warnings = std::mem::take(&mut *WARNINGS.lock().unwrap());
+            println!(
+                "{}: {} nodes, {} edges, {} warning(s)",
+                graph_file,
+                output.num_nodes,
+                output.num_edges,
+                warnings.len()
+            );
+            for w in warnings {
+                println!("  {}: {}", w.kind, w.message);
+            }
+        }
+        OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Sarif => print_sarif(graph_file, Vec::new())?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by validate"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by validate")
+        }
+    }
+
+    Ok(())
+}
+
+fn print_describe_text(output: &DescribeOutput) {
+    let format = match output.format.as_str() {
+        "csv" => "CSV, integer node IDs",
+        _ => "CSV, labeled nodes",
+    };
+    let header = if output.header {
+        "with header"
+    } else {
+        "no header"
+    };
+    println!("Graph Summary");
+    println!("  Format: {} ({})", format, header);
+    println!("  Nodes: {}", output.num_nodes);
+    println!("  Edges: {}", output.num_edges);
+    println!(
+        "  Degree: min {}, mean {:.2}, max {}",
+        output.degree.min, output.degree.mean, output.degree.max
+    );
+    if let Some(w) = &output.weight {
+        println!(
+            "  Weight: min {}, mean {:.2}, median {}, p90 {}, max {}",
+            w.min, w.mean, w.median, w.p90, w.max
+        );
+    }
+    if output.connected {
+        println!("  Connectivity: connected");
+    } else {
+        println!(
+            "  Connectivity: disconnected ({} components)",
+            output.components
+        );
+    }
+}
+
+fn run_edges(graph_file: &str, top: usize, by: EdgeOrder, format: OutputFormat) -> Result<()> {
+    let (graph, labels) = load_labeled_graph(graph_file).context("Failed to load graph")?;
+
+    let mut edges = graph.edges();
+    match by {
+        EdgeOrder::Weight => edges.sort_by(|a, b| a.weight.total_cmp(&b.weight)),
+    }
+    let arc = |e: &Edge| ArcOutput {
+        from: labels[e.u.0 as usize].clone(),
+        to: labels[e.v.0 as usize].clone(),
+        weight: e.weight,
+    };
+
+    let output = EdgesOutput {
+        graph_hash: graph.content_hash(),
+        num_edges: edges.len(),
+        heaviest: edges.iter().rev().take(top).map(arc).collect(),
+        lightest: edges.iter().take(top).map(arc).collect(),
     };
 
     match format {
-        OutputFormat::Text => print_critical_text(&output),
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_edges_text(&output),
         OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => anyhow::bail!("--format cypher is not supported by edges"),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by edges"),
+        OutputFormat::OpenMetrics => {
+            anyhow::bail!("--format openmetrics is not supported by edges")
+        }
     }
 
     Ok(())
 }
 
-fn run_analyze(graph_file: &str, format: OutputFormat) -> Result<()> {
-    let graph = load_csv(graph_file).context("Failed to load graph")?;
+fn print_edges_text(output: &EdgesOutput) {
+    println!("Edge Weights");
+    println!("  Edges: {}", output.num_edges);
+
+    for (title, edges) in [
+        ("Heaviest", &output.heaviest),
+        ("Lightest", &output.lightest),
+    ] {
+        if edges.is_empty() {
+            continue;
+        }
+        listing::section(
+            &format!("{} {} edges:", title, edges.len()),
+            edges.iter().enumerate(),
+            |(rank, e)| format!("{}. {} - {}: {}", rank + 1, e.from, e.to, e.weight),
+        );
+    }
+}
+
+fn run_analyze(
+    graph_file: &str,
+    routes: &[String],
+    max_latency: Option<u32>,
+    require_connected: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let (graph, latency) = if graph_file.ends_with(".json") {
+        let (graph, latency) = load_latency_graph(graph_file).context("Failed to load graph")?;
+        (graph, Some(latency))
+    } else if routes.is_empty() {
+        (
+            load_graph(graph_file).context("Failed to load graph")?,
+            None,
+        )
+    } else {
+        anyhow::bail!("--route needs a gt-path JSON graph (.json)");
+    };
 
     let mst = kruskal(&graph);
     let (articulation_points, bridges) = graph.critical_components();
+    let routes = match &latency {
+        Some(latency) => routes
+            .iter()
+            .map(|route| {
+                let critical = (articulation_points.as_slice(), bridges.as_slice());
+                route_output(&graph, latency, route, critical, max_latency)
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
 
     let mst_output = MstOutput {
+        graph_hash: None,
         algorithm: "kruskal".to_string(),
+        max_degree: None,
+        spanning: None,
         total_weight: mst.total_weight,
         num_edges: mst.edges.len(),
         edges: mst
@@ -183,65 +2644,313 @@ fn run_analyze(graph_file: &str, format: OutputFormat) -> Result<()> {
             .collect(),
     };
 
-    let critical_output = CriticalOutput {
-        num_bridges: bridges.len(),
-        num_articulation_points: articulation_points.len(),
-        bridges: bridges.iter().map(|(u, v)| (u.0, v.0)).collect(),
-        articulation_points: articulation_points.iter().map(|n| n.0).collect(),
-    };
-
     let output = AnalysisOutput {
+        graph_hash: graph.content_hash(),
+        nodes: latency
+            .iter()
+            .flat_map(|l| l.to_name.iter().map(|n| n.to_string()))
+            .collect(),
         mst: mst_output,
-        critical: critical_output,
+        critical: critical_output(&graph, &articulation_points, &bridges),
+        routes,
     };
 
     match format {
+        OutputFormat::Text if progress::quiet() => {}
         OutputFormat::Text => print_analysis_text(&output),
         OutputFormat::Json => print_json(&output)?,
+        OutputFormat::Cypher => println!(
+            "{}",
+            cypher::render(&graph, Some(&mst), Some((&articulation_points, &bridges)))
+        ),
+        OutputFormat::Sarif => anyhow::bail!("--format sarif is not supported by analyze"),
+        OutputFormat::OpenMetrics => {
+            let mut gauges = mst_gauges(&output.mst);
+            gauges.extend(critical_gauges(&output.critical));
+            gauges.extend(route_gauges(&output.routes));
+            print!("{}", openmetrics::render(&gauges));
+        }
     }
 
+    if require_connected {
+        require_connected_graph(&graph);
+    }
     Ok(())
 }
 
-fn print_mst_text(output: &MstOutput) {
-    println!("Minimum Spanning Tree ({})", output.algorithm);
+fn print_mst_text(output: &MstOutput, name: &dyn Fn(u32) -> String) {
+    match output.max_degree {
+        Some(limit) => println!("Spanning Tree ({}, max degree {})", output.algorithm, limit),
+        None => println!("Minimum Spanning Tree ({})", output.algorithm),
+    }
+    if output.spanning == Some(false) {
+        println!("  Spanning: no (degree limit left the tree disconnected)");
+    }
     println!("  Total Weight: {:.2}", output.total_weight);
     println!("  Edges: {}", output.num_edges);
-    println!("\nEdges:");
-    for edge in &output.edges {
-        println!("  {} -- {} (weight: {:.2})", edge.u, edge.v, edge.weight);
-    }
+    listing::section("Edges:", output.edges.iter(), |edge| {
+        format!(
+            "{} -- {} (weight: {:.2})",
+            name(edge.u),
+            name(edge.v),
+            edge.weight
+        )
+    });
 }
 
-fn print_critical_text(output: &CriticalOutput) {
-    println!("Critical Components Analysis");
-    println!("  Bridges: {}", output.num_bridges);
-    println!("  Articulation Points: {}", output.num_articulation_points);
+fn print_critical_text(output: &CriticalOutput, name: &dyn Fn(u32) -> String) {
+    let strong = if output.directed { "Strong " } else { "" };
+    if output.directed {
+        println!("Critical Components Analysis (directed)");
+    } else {
+        println!("Critical Components Analysis");
+    }
+    println!("  {}Bridges: {}", strong, output.num_bridges);
+    println!(
+        "  {}Articulation Points: {}",
+        strong, output.num_articulation_points
+    );
 
-    if !output.bridges.is_empty() {
-        println!("\nBridges (critical edges):");
-        for (u, v) in &output.bridges {
-            println!("  {} -- {}", u, v);
-        }
+    if output.directed && !output.bridges.is_empty() {
+        listing::section(
+            "Strong Bridges (critical arcs):",
+            output.bridges.iter(),
+            |&(u, v)| format!("{} -> {}", name(u), name(v)),
+        );
+    }
+
+    if let Some(splits) = output.bridge_splits.as_ref().filter(|s| !s.is_empty()) {
+        listing::section("Bridges (critical edges):", splits.iter(), |split| {
+            format!(
+                "{} -- {} (splits {} | {} nodes; components {} [{}] and {} [{}])",
+                name(split.u),
+                name(split.v),
+                split.side_sizes.0,
+                split.side_sizes.1,
+                split.components.0,
+                split.component_sizes.0,
+                split.components.1,
+                split.component_sizes.1
+            )
+        });
     }
 
     if !output.articulation_points.is_empty() {
-        println!("\nArticulation Points (critical nodes):");
-        for node in &output.articulation_points {
-            println!("  {}", node);
-        }
+        listing::section(
+            &format!("{}Articulation Points (critical nodes):", strong),
+            output.articulation_points.iter(),
+            |&n| name(n),
+        );
+    }
+
+    if let Some(budget) = &output.budget {
+        let status = if budget.passed {
+            "✓ PASS"
+        } else {
+            "✗ FAIL"
+        };
+        println!("\nBudget: {}", status);
+        let line = |name: &str, count: usize, max: Option<usize>| {
+            if let Some(max) = max {
+                let mark = if count <= max { "✓" } else { "✗" };
+                println!("  {}{}: {} (max {}) {}", strong, name, count, max, mark);
+            }
+        };
+        line("Bridges", output.num_bridges, budget.max_bridges);
+        line(
+            "Articulation Points",
+            output.num_articulation_points,
+            budget.max_articulation_points,
+        );
     }
 }
 
 fn print_analysis_text(output: &AnalysisOutput) {
+    let name = |n: u32| match output.nodes.get(n as usize) {
+        Some(name) => name.clone(),
+        None => n.to_string(),
+    };
     println!("=== Full Connectivity Analysis ===\n");
-    print_mst_text(&output.mst);
+    print_mst_text(&output.mst, &name);
     println!();
-    print_critical_text(&output.critical);
+    print_critical_text(&output.critical, &name);
+    if !output.routes.is_empty() {
+        println!();
+        print_routes_text(&output.routes);
+    }
+}
+
+fn print_routes_text(routes: &[RouteOutput]) {
+    println!("Critical Routes");
+    for route in routes {
+        let slo = match (route.max_latency_ms, route.met) {
+            (Some(max), Some(true)) => format!(", max {}ms ✓", max),
+            (Some(max), _) => format!(", max {}ms ✗", max),
+            _ => String::new(),
+        };
+        println!(
+            "  {} -> {} ({}ms{}): {}",
+            route.from,
+            route.to,
+            route.latency_ms,
+            slo,
+            route.path.join(" -> ")
+        );
+        if route.articulation_points.is_empty() && route.bridges.is_empty() {
+            println!("    No single point of failure");
+        }
+        for node in &route.articulation_points {
+            println!("    Articulation point: {}", node);
+        }
+        for (u, v) in &route.bridges {
+            println!("    Bridge: {} -- {}", u, v);
+        }
+    }
+}
+
+/// Single points of failure on each route as OpenMetrics gauges.
+fn route_gauges(routes: &[RouteOutput]) -> Vec<openmetrics::Gauge> {
+    if routes.is_empty() {
+        return Vec::new();
+    }
+    let mut articulation_points = openmetrics::Gauge::new(
+        "gt_connect_route_articulation_points",
+        "Nodes on the route's shortest path whose failure disconnects it",
+    );
+    let mut bridges = openmetrics::Gauge::new(
+        "gt_connect_route_bridges",
+        "Edges on the route's shortest path whose failure disconnects it",
+    );
+    for route in routes {
+        let labels = vec![("from", route.from.clone()), ("to", route.to.clone())];
+        articulation_points =
+            articulation_points.sample(labels.clone(), route.articulation_points.len() as f64);
+        bridges = bridges.sample(labels, route.bridges.len() as f64);
+    }
+    vec![articulation_points, bridges]
+}
+
+/// MST weight and size as OpenMetrics gauges.
+fn mst_gauges(output: &MstOutput) -> Vec<openmetrics::Gauge> {
+    vec![
+        openmetrics::Gauge::new("gt_connect_mst_weight", "Total weight of the spanning tree")
+            .sample(vec![], output.total_weight as f64),
+        openmetrics::Gauge::new("gt_connect_mst_edges", "Edges in the spanning tree")
+            .sample(vec![], output.num_edges as f64),
+    ]
+}
+
+/// Bridge and articulation point counts as OpenMetrics gauges.
+fn critical_gauges(output: &CriticalOutput) -> Vec<openmetrics::Gauge> {
+    vec![
+        openmetrics::Gauge::new(
+            "gt_connect_bridges",
+            "Edges whose removal disconnects the graph",
+        )
+        .sample(vec![], output.num_bridges as f64),
+        openmetrics::Gauge::new(
+            "gt_connect_articulation_points",
+            "Nodes whose removal disconnects the graph",
+        )
+        .sample(vec![], output.num_articulation_points as f64),
+    ]
+}
+
+/// Articulation points and bridges as SARIF findings.
+fn critical_findings(output: &CriticalOutput) -> Vec<sarif::Finding> {
+    let effect = if output.directed {
+        "breaks strong connectivity"
+    } else {
+        "disconnects the graph"
+    };
+    let points = output.articulation_points.iter().map(|n| sarif::Finding {
+        rule: "articulation_point".to_string(),
+        level: sarif::Level::Warning,
+        message: format!("Removing node {} {}", n, effect),
+    });
+    let bridges = output.bridges.iter().map(|(u, v)| sarif::Finding {
+        rule: "bridge".to_string(),
+        level: sarif::Level::Warning,
+        message: format!("Removing edge {} - {} {}", u, v, effect),
+    });
+    points.chain(bridges).collect()
+}
+
+/// The ranked edges as SARIF findings: warnings for bridges, notes for the
+/// rest.
+fn risk_findings(output: &RiskOutput) -> Vec<sarif::Finding> {
+    output
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(rank, e)| sarif::Finding {
+            rule: if e.is_bridge { "bridge" } else { "risky_edge" }.to_string(),
+            level: if e.is_bridge {
+                sarif::Level::Warning
+            } else {
+                sarif::Level::Note
+            },
+            message: format!(
+                "Edge {} - {} ranks #{} by risk: score {:.2} (betweenness {:.2}, weight {:.2})",
+                e.u,
+                e.v,
+                rank + 1,
+                e.score,
+                e.betweenness,
+                e.weight
+            ),
+        })
+        .collect()
+}
+
+/// Prints `findings` and the load warnings as a SARIF log, pretty by
+/// default and compact with `--quiet`.
+fn print_sarif(graph_file: &str, mut findings: Vec<sarif::Finding>) -> Result<()> {
+    for w in std::mem::take(&mut *WARNINGS.lock().unwrap()) {
+        findings.push(sarif::Finding {
+            // filtering was asked for, so it's only worth a note
+            level: if w.kind == "edges_filtered" {
+                sarif::Level::Note
+            } else {
+                sarif::Level::Warning
+            },
+            rule: w.kind,
+            message: w.message,
+        });
+    }
+    let log = sarif::render(graph_file, &findings);
+    let json = if progress::quiet() {
+        serde_json::to_string(&log)?
+    } else {
+        serde_json::to_string_pretty(&log)?
+    };
+    println!("{}", json);
+    Ok(())
 }
 
+/// Prints JSON output: pretty by default, compact with `--quiet`. Objects
+/// get a `warnings` array of the load warnings.
 fn print_json<T: Serialize>(output: &T) -> Result<()> {
-    let json = serde_json::to_string_pretty(output)?;
+    #[derive(Serialize)]
+    struct Report<'a, T> {
+        schema_version: u32,
+        tool_version: &'static str,
+        #[serde(flatten)]
+        output: &'a T,
+        warnings: Vec<WarningOutput>,
+    }
+
+    let report = Report {
+        schema_version: SCHEMA_VERSION,
+        tool_version: env!("CARGO_PKG_VERSION"),
+        output,
+        warnings: std::mem::take(&mut *WARNINGS.lock().unwrap()),
+    };
+    let json = if progress::quiet() {
+        serde_json::to_string(&report)?
+    } else {
+        serde_json::to_string_pretty(&report)?
+    };
     println!("{}", json);
     Ok(())
 }
@@ -0,0 +1,52 @@
+use std::fmt::Write;
+
+/// A gauge and its samples, one per label set.
+pub(crate) struct Gauge {
+    pub(crate) name: &'static str,
+    pub(crate) help: &'static str,
+    pub(crate) samples: Vec<(Vec<(&'static str, String)>, f64)>,
+}
+
+impl Gauge {
+    pub(crate) fn new(name: &'static str, help: &'static str) -> Gauge {
+        Gauge {
+            name,
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    pub(crate) fn sample(mut self, labels: Vec<(&'static str, String)>, value: f64) -> Gauge {
+        self.samples.push((labels, value));
+        self
+    }
+}
+
+/// Renders the gauges in the OpenMetrics text format, ending with the
+/// `# EOF` marker. node_exporter's textfile collector reads this as well.
+pub(crate) fn render(gauges: &[Gauge]) -> String {
+    let mut out = String::new();
+    for g in gauges {
+        let _ = writeln!(out, "# TYPE {} gauge", g.name);
+        let _ = writeln!(out, "# HELP {} {}", g.name, g.help);
+        for (labels, value) in &g.samples {
+            out += g.name;
+            if !labels.is_empty() {
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+                    .collect();
+                let _ = write!(out, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(out, " {}", value);
+        }
+    }
+    out + "# EOF\n"
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
@@ -0,0 +1,141 @@
+use serde_json::{Value, json};
+
+/// SARIF `level` of a finding.
+#[derive(Clone, Copy)]
+pub(crate) enum Level {
+    Warning,
+    Note,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// One result: a rule from `RULES` and what triggered it.
+pub(crate) struct Finding {
+    pub(crate) rule: String,
+    pub(crate) level: Level,
+    pub(crate) message: String,
+}
+
+/// Every rule a finding can name, as (id, description). Load warnings use
+/// their `kind` as the rule ID.
+const RULES: &[(&str, &str)] = &[
+    (
+        "articulation_point",
+        "Node whose removal disconnects the graph",
+    ),
+    ("bridge", "Edge whose removal disconnects the graph"),
+    (
+        "risky_edge",
+        "Edge carrying many shortest paths, ranked by risk score",
+    ),
+    (
+        "header_rows_skipped",
+        "Header row after the first line of the input",
+    ),
+    ("isolated_nodes", "Nodes without edges"),
+    ("duplicate_edge", "Node pair joined by several edges"),
+    (
+        "edges_filtered",
+        "Edges dropped by --min-weight / --max-weight",
+    ),
+];
+
+/// Renders `findings` as a SARIF 2.1.0 log with one run, every result
+/// located in `graph_file`.
+pub(crate) fn render(graph_file: &str, findings: &[Finding]) -> Value {
+    let uri = if graph_file == "-" {
+        "stdin"
+    } else {
+        graph_file
+    };
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let mut result = json!({
+                "ruleId": f.rule,
+                "level": f.level.as_str(),
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": uri } }
+                }],
+            });
+            if let Some(index) = RULES.iter().position(|(id, _)| *id == f.rule) {
+                result["ruleIndex"] = json!(index);
+            }
+            result
+        })
+        .collect();
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "gt-connect",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "artifacts": [{ "location": { "uri": uri } }],
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule: &str, level: Level) -> Finding {
+        Finding {
+            rule: rule.to_string(),
+            level,
+            message: format!("{} found", rule),
+        }
+    }
+
+    #[test]
+    fn test_render_rule_index_and_level() {
+        let findings = [
+            finding("articulation_point", Level::Warning),
+            finding("bridge", Level::Warning),
+            finding("risky_edge", Level::Note),
+            finding("edges_filtered", Level::Note),
+            finding("not_a_rule", Level::Warning),
+        ];
+        let log = render("-", &findings);
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 5);
+
+        for result in &results[..4] {
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+        }
+        assert_eq!(results[0]["ruleIndex"], 0);
+        assert_eq!(results[2]["ruleIndex"], 2);
+        assert!(results[4].get("ruleIndex").is_none());
+
+        let levels: Vec<&str> = results
+            .iter()
+            .map(|r| r["level"].as_str().unwrap())
+            .collect();
+        assert_eq!(levels, ["warning", "warning", "note", "note", "warning"]);
+
+        let uri = &results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"];
+        assert_eq!(uri, "stdin");
+    }
+}
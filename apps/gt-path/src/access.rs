@@ -0,0 +1,107 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// One line of the daemon's `--access-log`: who sent what, and how it was
+/// answered.
+#[derive(Serialize, Default)]
+pub(crate) struct Entry {
+    /// When the answer was sent, in UTC
+    pub(crate) ts: String,
+    /// Connection the message arrived on, numbered from 1
+    pub(crate) connection: u64,
+    /// Fingerprint of the client's token, without `--token-file` absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) client: Option<String>,
+    /// `connect`, `query`, `batch`, `upload`, `subscribe`, `stats`,
+    /// `schema`, or `health`
+    pub(crate) kind: &'static str,
+    /// Graph file or name the message is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) graph: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) at: Option<String>,
+    /// `ok`, `path`, `query_failed`, `invalid_input`, `rejected`,
+    /// `uploaded`, or `invalid_graph`
+    pub(crate) outcome: &'static str,
+    /// Total latency of the path found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) latency_ms: Option<u32>,
+    /// Whether the answer came from the cache, for queries to a daemon
+    /// with one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) cache_hit: Option<bool>,
+    /// Time taken to answer, in microseconds
+    pub(crate) duration_us: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+impl Entry {
+    /// An entry for a control request, which always succeeds.
+    pub(crate) fn control(kind: &'static str) -> Entry {
+        Entry {
+            kind,
+            outcome: "ok",
+            ..Entry::default()
+        }
+    }
+}
+
+/// Appends entries as JSON lines to a file, or to stdout.
+pub(crate) struct AccessLog {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl AccessLog {
+    /// Opens `target` for appending; `-` is stdout.
+    pub(crate) fn open(target: &Path) -> Result<AccessLog> {
+        let out: Box<dyn Write + Send> = if target == Path::new("-") {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(target)
+                    .context(format!("Failed to open access log: {}", target.display()))?,
+            )
+        };
+        Ok(AccessLog {
+            out: Mutex::new(out),
+        })
+    }
+
+    /// Writes `entry`, stamped with the current time and the time taken
+    /// since `started`.
+    pub(crate) fn write(&self, started: Instant, mut entry: Entry) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        entry.ts = gt_path_core::time::format_timestamp(now.as_secs() as i64);
+        entry.duration_us = started.elapsed().as_micros() as u64;
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        let mut out = self.out.lock().unwrap();
+        out.write_all(&line)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Identifies a client by token without revealing it: the first 8 hex
+/// digits of its SHA-256.
+pub(crate) fn fingerprint(token: &str) -> String {
+    Sha256::digest(token.as_bytes())[..4]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
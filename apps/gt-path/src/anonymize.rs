@@ -0,0 +1,159 @@
+use gt_path_core::io::GraphInput;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+
+/// Strategy used to replace node labels.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum LabelMode {
+    /// Replace each label with a truncated salted SHA-256 digest
+    Hash,
+    /// Replace each label with `node-<index>` in declaration order
+    Sequential,
+}
+
+/// Options controlling how a graph is anonymized.
+pub(crate) struct AnonymizeOptions {
+    /// How node labels are rewritten
+    pub(crate) mode: LabelMode,
+    /// Salt mixed into hashed labels so they can't be reversed by a dictionary lookup
+    pub(crate) salt: String,
+    /// Maximum relative latency perturbation (0.1 = ±10%), if any
+    pub(crate) perturb: Option<f32>,
+    /// Seed for the latency perturbation
+    pub(crate) seed: u64,
+}
+
+/// Result of anonymizing a graph.
+pub(crate) struct Anonymized {
+    /// The graph with rewritten labels (and possibly perturbed latencies)
+    pub(crate) graph: GraphInput,
+    /// Mapping from original label to anonymized label
+    pub(crate) mapping: BTreeMap<String, String>,
+}
+
+/// Rewrites node labels and optionally perturbs edge latencies.
+///
+/// The output is fully determined by the input and the options: the same
+/// graph, salt, and seed always produce the same anonymized graph.
+///
+/// # Returns
+///
+/// * `Ok(Anonymized)` - The rewritten graph and the label mapping
+/// * `Err` - If two labels hash to the same anonymized label
+pub(crate) fn anonymize(input: &GraphInput, opts: &AnonymizeOptions) -> anyhow::Result<Anonymized> {
+    let mut mapping = BTreeMap::new();
+    let mut used = HashSet::new();
+
+    for (i, node) in input.nodes.iter().enumerate() {
+        let name = node.name();
+        let label = match opts.mode {
+            LabelMode::Hash => hash_label(name, &opts.salt),
+            LabelMode::Sequential => format!("node-{}", i),
+        };
+
+        if !used.insert(label.clone()) {
+            anyhow::bail!(
+                "anonymized label collision on '{}'; try a different salt",
+                label
+            );
+        }
+        mapping.insert(name.to_string(), label);
+    }
+
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let mut graph = input.clone();
+    for node in graph.nodes.iter_mut() {
+        let name = node.name_mut();
+        *name = mapping[name.as_str()].clone();
+    }
+    for edge in graph.edges.iter_mut() {
+        // unknown endpoints are left as-is; graph validation reports them
+        if let Some(from) = mapping.get(&edge.from) {
+            edge.from = from.clone();
+        }
+        if let Some(to) = mapping.get(&edge.to) {
+            edge.to = to.clone();
+        }
+
+        if let Some(p) = opts.perturb {
+            let factor: f32 = rng.gen_range(1.0 - p..=1.0 + p);
+            if let Some(latency_ms) = &mut edge.latency_ms {
+                *latency_ms = (*latency_ms * factor).max(0.0);
+            }
+        }
+    }
+
+    Ok(Anonymized { graph, mapping })
+}
+
+/// Hashes a label with the salt, keeping the first 12 hex digits.
+fn hash_label(name: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(name.as_bytes());
+    let digest = hasher.finalize();
+
+    let hex: String = digest
+        .iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("n{}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GraphInput {
+        let json = include_str!("testdata/sample_graph.json");
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn opts(mode: LabelMode, perturb: Option<f32>, seed: u64) -> AnonymizeOptions {
+        AnonymizeOptions {
+            mode,
+            salt: "s3cret".to_string(),
+            perturb,
+            seed,
+        }
+    }
+
+    #[test]
+    fn test_hash_is_deterministic() {
+        let a = anonymize(&sample(), &opts(LabelMode::Hash, None, 0)).unwrap();
+        let b = anonymize(&sample(), &opts(LabelMode::Hash, None, 0)).unwrap();
+
+        assert_eq!(a.mapping, b.mapping);
+        assert!(!a.graph.nodes.contains(&"api".into()));
+        assert_eq!(a.graph.edges[0].from, a.mapping["api"]);
+        assert_eq!(a.graph.edges[0].to, a.mapping["auth"]);
+    }
+
+    #[test]
+    fn test_sequential_labels() {
+        let a = anonymize(&sample(), &opts(LabelMode::Sequential, None, 0)).unwrap();
+
+        let names: Vec<&str> = a.graph.nodes.iter().map(|n| n.name()).collect();
+        assert_eq!(names, vec!["node-0", "node-1", "node-2", "node-3"]);
+        assert_eq!(a.mapping["cache"], "node-3");
+    }
+
+    #[test]
+    fn test_perturb_within_bounds_and_seeded() {
+        let input = sample();
+        let a = anonymize(&input, &opts(LabelMode::Hash, Some(0.1), 7)).unwrap();
+        let b = anonymize(&input, &opts(LabelMode::Hash, Some(0.1), 7)).unwrap();
+
+        for (orig, new) in input.edges.iter().zip(a.graph.edges.iter()) {
+            let (new, orig) = (new.latency_ms.unwrap(), orig.latency_ms.unwrap());
+            assert!(new >= orig * 0.9 - 1e-4);
+            assert!(new <= orig * 1.1 + 1e-4);
+        }
+        for (x, y) in a.graph.edges.iter().zip(b.graph.edges.iter()) {
+            assert_eq!(x.latency_ms, y.latency_ms);
+        }
+    }
+}
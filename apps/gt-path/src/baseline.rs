@@ -0,0 +1,113 @@
+use crate::{RouteSlo, units};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+/// A stored `slo` baseline. `--update-baseline` writes the same shape as
+/// the JSON output of a `--route` batch, so that output works as a baseline
+/// too.
+#[derive(Deserialize)]
+struct SloBaseline {
+    routes: Vec<RouteBaseline>,
+}
+
+#[derive(Deserialize)]
+struct RouteBaseline {
+    from: String,
+    to: String,
+    /// Missing for a route that had no path
+    actual_latency_ms: Option<u32>,
+}
+
+/// Writes `routes` to `path` as the new baseline.
+pub(crate) fn write(path: &str, routes: &[RouteSlo], max_latency: u32) -> Result<()> {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|r| {
+            json!({
+                "from": r.from,
+                "to": r.to,
+                "slo_met": r.slo_met,
+                "actual_latency_ms": r.latency_ms,
+            })
+        })
+        .collect();
+    let baseline = json!({ "max_latency_ms": max_latency, "routes": routes });
+    std::fs::write(path, serde_json::to_string_pretty(&baseline)? + "\n")
+        .context(format!("Failed to write baseline: {}", path))
+}
+
+/// Reads the baseline at `path` and describes every route in `routes` that
+/// got slower, or lost its path. Routes the baseline doesn't list aren't
+/// regressions.
+pub(crate) fn regressions(path: &str, routes: &[RouteSlo]) -> Result<Vec<String>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read baseline: {}", path))?;
+    let baseline: SloBaseline =
+        serde_json::from_str(&content).context(format!("Failed to parse baseline: {}", path))?;
+    Ok(compare(&baseline, routes))
+}
+
+fn compare(baseline: &SloBaseline, routes: &[RouteSlo]) -> Vec<String> {
+    let latency = |l: Option<u32>| l.map_or("no path".to_string(), units::ms);
+    routes
+        .iter()
+        .filter_map(|r| {
+            let before = baseline
+                .routes
+                .iter()
+                .find(|b| b.from == r.from && b.to == r.to)?;
+            let slower = match (before.actual_latency_ms, r.latency_ms) {
+                (Some(before), Some(now)) => now > before,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            slower.then(|| {
+                format!(
+                    "{} → {}: {} → {}",
+                    r.from,
+                    r.to,
+                    latency(before.actual_latency_ms),
+                    latency(r.latency_ms)
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(to: &str, latency_ms: Option<u32>) -> RouteSlo {
+        RouteSlo {
+            from: "api".to_string(),
+            to: to.to_string(),
+            latency_ms,
+            slo_met: true,
+        }
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline: SloBaseline = serde_json::from_str(
+            r#"{"routes": [
+                {"from": "api", "to": "db", "actual_latency_ms": 8},
+                {"from": "api", "to": "auth", "actual_latency_ms": 5},
+                {"from": "api", "to": "cache", "error": "no path"}
+            ]}"#,
+        )
+        .unwrap();
+        let routes = [
+            route("db", Some(9)),
+            route("auth", Some(4)),
+            route("cache", Some(50)),
+            route("queue", Some(50)),
+        ];
+        assert_eq!(compare(&baseline, &routes), vec!["api → db: 8ms → 9ms"]);
+        assert_eq!(
+            compare(&baseline, &[route("auth", None)]),
+            vec!["api → auth: 5ms → no path"]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use gt_path_core::path::{Edge, Path};
+
+/// How an end-to-end latency budget is split across the hops of a path.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum BudgetPolicy {
+    /// Each hop gets a share proportional to its current latency
+    Proportional,
+    /// Each hop gets the same share
+    Equal,
+}
+
+/// Latency budget assigned to a single hop.
+pub(crate) struct HopBudget {
+    /// The hop's edge
+    pub(crate) edge: Edge,
+    /// Allocated budget in milliseconds
+    pub(crate) budget_ms: f64,
+}
+
+impl HopBudget {
+    /// Budget left over after the hop's current latency (negative if over budget).
+    pub(crate) fn headroom_ms(&self) -> f64 {
+        self.budget_ms - self.edge.latency_ms as f64
+    }
+}
+
+/// Splits `max_latency` across the hops of `path` according to `policy`.
+///
+/// The hop budgets always sum to `max_latency`. A proportional split over a
+/// path whose hops all have zero latency falls back to an equal split.
+///
+/// # Example
+///
+/// ```ignore
+/// // api → auth (5ms) → db (3ms) with a 16ms SLO
+/// // Proportional: api → auth gets 10ms, auth → db gets 6ms
+/// let hops = allocate(&path, 16, BudgetPolicy::Proportional);
+/// ```
+pub(crate) fn allocate(path: &Path, max_latency: u32, policy: BudgetPolicy) -> Vec<HopBudget> {
+    let total = max_latency as f64;
+    let hops = path.edges.len() as f64;
+    let proportional = matches!(policy, BudgetPolicy::Proportional) && path.cost > 0;
+
+    path.edges
+        .iter()
+        .map(|edge| HopBudget {
+            edge: edge.clone(),
+            budget_ms: if proportional {
+                total * edge.latency_ms as f64 / path.cost as f64
+            } else {
+                total / hops
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::graph::Graph;
+
+    #[test]
+    fn test_proportional() {
+        let graph = Graph::load_json("src/testdata/simple_graph.json").unwrap();
+        let path = graph.shortest_path("a", "c").unwrap();
+
+        let hops = allocate(&path, 60, BudgetPolicy::Proportional);
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].budget_ms, 20.0);
+        assert_eq!(hops[1].budget_ms, 40.0);
+        assert_eq!(hops[0].headroom_ms(), 10.0);
+    }
+
+    #[test]
+    fn test_equal() {
+        let graph = Graph::load_json("src/testdata/simple_graph.json").unwrap();
+        let path = graph.shortest_path("a", "c").unwrap();
+
+        let hops = allocate(&path, 24, BudgetPolicy::Equal);
+        assert_eq!(hops[0].budget_ms, 12.0);
+        assert_eq!(hops[1].budget_ms, 12.0);
+        assert_eq!(hops[1].headroom_ms(), -8.0);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::progress;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::error::PathError;
+use gt_path_core::graph::Graph;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Settings for a Monte Carlo failure simulation.
+pub(crate) struct ChaosOptions {
+    /// Number of simulated periods
+    pub(crate) trials: u32,
+    /// Seed for the random failure draws
+    pub(crate) seed: u64,
+}
+
+/// Outcome of a Monte Carlo failure simulation for one route.
+#[derive(PartialEq)]
+pub(crate) struct ChaosReport {
+    /// Latency of the route with nothing failed
+    pub(crate) baseline_ms: u32,
+    /// Trials in which the route had no path or missed the SLO
+    pub(crate) violations: u32,
+    /// Trials in which the route had no path at all
+    pub(crate) disconnected: u32,
+}
+
+/// Reads each edge's `failure_rate` attribute: the probability that the
+/// edge fails at some point during one simulated period (e.g. a month).
+/// Edges without one use `default_rate`.
+///
+/// Returns rates indexed by edge, or an error naming the first edge whose
+/// rate isn't a number in `[0, 1]`.
+pub(crate) fn failure_rates(graph: &Graph, default_rate: f64) -> anyhow::Result<Vec<f64>> {
+    let mut rates = vec![default_rate; graph.edge_attrs.len()];
+    for (u, links) in graph.adj.iter().enumerate() {
+        for link in links {
+            let Some(value) = graph.edge_attrs[link.edge].get("failure_rate") else {
+                continue;
+            };
+            match value.as_f64() {
+                Some(rate) if (0.0..=1.0).contains(&rate) => rates[link.edge] = rate,
+                _ => anyhow::bail!(
+                    "invalid failure_rate on edge {}->{}: {} (expected a number between 0 and 1)",
+                    graph.to_name[u],
+                    graph.to_name[link.to.0 as usize],
+                    value
+                ),
+            }
+        }
+    }
+    Ok(rates)
+}
+
+/// Estimates how often a route misses its latency SLO when edges fail
+/// independently, each with its own probability per trial.
+///
+/// Each trial draws a failed or working state for every edge and, if any
+/// edge on the baseline shortest path failed, reroutes around the failed
+/// edges. A trial violates the SLO if no path remains or the new path is
+/// slower than `max_latency_ms`.
+///
+/// # Returns
+///
+/// * `Ok(ChaosReport)` - Violation counts over all trials
+/// * `Err(PathError)` - If either node doesn't exist or there is no path
+///   even with every edge working
+pub(crate) fn simulate(
+    graph: &Graph,
+    from: &str,
+    to: &str,
+    max_latency_ms: u32,
+    constraints: &Constraints,
+    rates: &[f64],
+    opts: &ChaosOptions,
+) -> Result<ChaosReport, PathError> {
+    let baseline = graph.shortest_path_with(from, to, constraints)?;
+    let baseline_violates = baseline.cost > max_latency_ms;
+
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let mut down = vec![false; rates.len()];
+    let mut violations = 0;
+    let mut disconnected = 0;
+
+    let bar = progress::items(opts.trials as u64, "Simulating failures");
+    for _ in bar.wrap_iter(0..opts.trials) {
+        for (state, &rate) in down.iter_mut().zip(rates) {
+            *state = rate > 0.0 && rng.gen_bool(rate);
+        }
+
+        // failures off the shortest path can't make it slower
+        if !baseline.edges.iter().any(|e| down[e.index]) {
+            violations += baseline_violates as u32;
+            continue;
+        }
+        match graph.shortest_path_without(from, to, constraints, &down) {
+            Ok(path) => violations += (path.cost > max_latency_ms) as u32,
+            Err(_) => {
+                violations += 1;
+                disconnected += 1;
+            }
+        }
+    }
+    bar.finish_and_clear();
+
+    Ok(ChaosReport {
+        baseline_ms: baseline.cost,
+        violations,
+        disconnected,
+    })
+}
+
+/// 95% Wilson score interval for `successes` out of `trials`.
+pub(crate) fn wilson_interval(successes: u32, trials: u32) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let (n, z) = (trials as f64, 1.96);
+    let p = successes as f64 / n;
+    let denom = 1.0 + z * z / n;
+    let center = (p + z * z / (2.0 * n)) / denom;
+    let half = z * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt() / denom;
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::io::GraphInput;
+
+    fn graph(auth_db_rate: f64) -> Graph {
+        let input: GraphInput = serde_json::from_str(&format!(
+            r#"{{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {{"from": "api", "to": "auth", "latency_ms": 5}},
+                {{"from": "auth", "to": "db", "latency_ms": 3, "failure_rate": {}}},
+                {{"from": "api", "to": "cache", "latency_ms": 7}},
+                {{"from": "cache", "to": "db", "latency_ms": 20}}
+            ]}}"#,
+            auth_db_rate
+        ))
+        .unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    fn opts(trials: u32) -> ChaosOptions {
+        ChaosOptions { trials, seed: 7 }
+    }
+
+    #[test]
+    fn test_weighted_failures() {
+        // only auth → db can fail; the detour via cache takes 27ms
+        let g = graph(0.25);
+        let rates = failure_rates(&g, 0.0).unwrap();
+        let c = Constraints::default();
+
+        let report = simulate(&g, "api", "db", 10, &c, &rates, &opts(4000)).unwrap();
+        assert_eq!(report.baseline_ms, 8);
+        assert_eq!(report.disconnected, 0);
+        let p = report.violations as f64 / 4000.0;
+        assert!((p - 0.25).abs() < 0.03, "violation rate {}", p);
+
+        // the detour meets a looser SLO
+        let report = simulate(&g, "api", "db", 30, &c, &rates, &opts(500)).unwrap();
+        assert_eq!(report.violations, 0);
+    }
+
+    #[test]
+    fn test_rejects_invalid_rate() {
+        assert!(failure_rates(&graph(1.5), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let (lo, hi) = wilson_interval(50, 1000);
+        assert!(lo < 0.05 && 0.05 < hi);
+        assert_eq!(wilson_interval(0, 100).0, 0.0);
+    }
+}
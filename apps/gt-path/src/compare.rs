@@ -0,0 +1,165 @@
+use gt_path_core::io::GraphInput;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Jaccard similarity of the two graphs' node names: shared nodes over all
+/// nodes. Two empty graphs are identical (1.0).
+pub(crate) fn node_jaccard(a: &GraphInput, b: &GraphInput) -> f64 {
+    let a: HashSet<&str> = a.nodes.iter().map(|n| n.name()).collect();
+    let b: HashSet<&str> = b.nodes.iter().map(|n| n.name()).collect();
+    jaccard(&a, &b)
+}
+
+/// Jaccard similarity of the two graphs' edges, compared as `(from, to)`
+/// pairs regardless of latency.
+pub(crate) fn edge_jaccard(a: &GraphInput, b: &GraphInput) -> f64 {
+    jaccard(&edge_set(a), &edge_set(b))
+}
+
+/// Total variation distance between the two degree distributions, from 0
+/// (identical) to 1 (disjoint). A node's degree is its number of distinct
+/// in- and out-neighbors. Unlike the Jaccard scores this ignores names, so
+/// it also compares graphs with unrelated labels.
+pub(crate) fn degree_distance(a: &GraphInput, b: &GraphInput) -> f64 {
+    let (pa, pb) = (degree_distribution(a), degree_distribution(b));
+    let degrees: HashSet<usize> = pa.keys().chain(pb.keys()).copied().collect();
+    degrees
+        .iter()
+        .map(|d| (pa.get(d).unwrap_or(&0.0) - pb.get(d).unwrap_or(&0.0)).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Edit operations turning graph `a` into graph `b`, matching nodes by name.
+pub(crate) struct EditDistance {
+    pub(crate) nodes_added: usize,
+    pub(crate) nodes_removed: usize,
+    pub(crate) edges_added: usize,
+    pub(crate) edges_removed: usize,
+    /// Edges in both graphs whose latency differs
+    pub(crate) latency_changed: usize,
+}
+
+impl EditDistance {
+    /// Total number of edit operations.
+    pub(crate) fn total(&self) -> usize {
+        self.nodes_added
+            + self.nodes_removed
+            + self.edges_added
+            + self.edges_removed
+            + self.latency_changed
+    }
+}
+
+/// Counts the node and edge insertions, deletions, and latency changes that
+/// turn `a` into `b` when nodes are matched by name.
+///
+/// This is the exact edit distance under that matching and an upper bound
+/// on the true graph edit distance, which would search all matchings and is
+/// NP-hard. Edges are compared as `(from, to)` pairs; for duplicates the
+/// first latency is used.
+pub(crate) fn edit_distance(a: &GraphInput, b: &GraphInput) -> EditDistance {
+    let na: HashSet<&str> = a.nodes.iter().map(|n| n.name()).collect();
+    let nb: HashSet<&str> = b.nodes.iter().map(|n| n.name()).collect();
+    let (ea, eb) = (edge_latencies(a), edge_latencies(b));
+
+    EditDistance {
+        nodes_added: nb.difference(&na).count(),
+        nodes_removed: na.difference(&nb).count(),
+        edges_added: eb.keys().filter(|k| !ea.contains_key(*k)).count(),
+        edges_removed: ea.keys().filter(|k| !eb.contains_key(*k)).count(),
+        latency_changed: ea
+            .iter()
+            .filter(|(k, latency)| eb.get(*k).is_some_and(|other| other != *latency))
+            .count(),
+    }
+}
+
+fn jaccard<T: Eq + std::hash::Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn edge_set(g: &GraphInput) -> HashSet<(&str, &str)> {
+    g.edges
+        .iter()
+        .map(|e| (e.from.as_str(), e.to.as_str()))
+        .collect()
+}
+
+fn edge_latencies(g: &GraphInput) -> HashMap<(&str, &str), f32> {
+    let mut latencies = HashMap::new();
+    // normalized input always has latencies
+    for e in &g.edges {
+        if let Some(latency_ms) = e.latency_ms {
+            latencies
+                .entry((e.from.as_str(), e.to.as_str()))
+                .or_insert(latency_ms);
+        }
+    }
+    latencies
+}
+
+/// Fraction of nodes with each degree.
+fn degree_distribution(g: &GraphInput) -> BTreeMap<usize, f64> {
+    let mut degree: HashMap<&str, usize> = g.nodes.iter().map(|n| (n.name(), 0)).collect();
+    for (from, to) in edge_set(g) {
+        *degree.entry(from).or_default() += 1;
+        *degree.entry(to).or_default() += 1;
+    }
+
+    let mut distribution = BTreeMap::new();
+    for &d in degree.values() {
+        *distribution.entry(d).or_default() += 1.0;
+    }
+    let total = degree.len() as f64;
+    for share in distribution.values_mut() {
+        *share /= total;
+    }
+    distribution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(json: &str) -> GraphInput {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_scores() {
+        let a = graph(
+            r#"{"nodes": ["api", "auth", "db"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5},
+                {"from": "auth", "to": "db", "latency_ms": 3}
+            ]}"#,
+        );
+        let b = graph(
+            r#"{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5},
+                {"from": "auth", "to": "db", "latency_ms": 4},
+                {"from": "api", "to": "cache", "latency_ms": 1}
+            ]}"#,
+        );
+
+        assert_eq!(node_jaccard(&a, &b), 0.75);
+        assert!((edge_jaccard(&a, &b) - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(degree_distance(&a, &a), 0.0);
+
+        let edits = edit_distance(&a, &b);
+        assert_eq!(
+            (
+                edits.nodes_added,
+                edits.nodes_removed,
+                edits.edges_added,
+                edits.edges_removed,
+                edits.latency_changed
+            ),
+            (1, 0, 1, 0, 1)
+        );
+        assert_eq!(edits.total(), 3);
+    }
+}
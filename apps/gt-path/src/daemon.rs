@@ -0,0 +1,1637 @@
+use crate::ConstraintArgs;
+use crate::access::{self, AccessLog, Entry};
+use anyhow::{Context, Result};
+use gt_path_core::ArcGraph;
+use gt_path_core::cache::{CacheKey, PathCache};
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{
+    DaemonHealthOutput, DaemonStatsOutput, DaemonUploadOutput, HistogramOutput, PathOutput,
+    WarningOutput,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Socket used when `daemon --socket` or `--use-daemon` is given no path:
+/// `gt-path.sock` in `$XDG_RUNTIME_DIR`, or in the temp directory.
+pub(crate) fn default_socket() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("gt-path.sock")
+}
+
+/// A query sent to the daemon, one JSON object per line.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct Request {
+    /// Absolute path of the graph file, or the name of a graph in the
+    /// daemon's `--graph-dir`
+    pub(crate) graph: PathBuf,
+    /// Source node name
+    pub(crate) from: String,
+    /// Destination node name
+    pub(crate) to: String,
+    /// Point in time to resolve latencies at, as given on the command line
+    pub(crate) at: Option<String>,
+    /// Attribute filters, as given on the command line
+    pub(crate) constraints: ConstraintArgs,
+}
+
+/// Several queries sent on one line, answered in order: as a whole, by one
+/// JSON array of responses, or streamed, by one response per line as each
+/// is found.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub(crate) enum Batch {
+    Whole(Vec<Request>),
+    Streamed { stream: Vec<Request> },
+}
+
+/// The daemon's answer to a `Request`, one JSON object per line.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Response {
+    /// The shortest path, with warnings about the graph
+    Path {
+        path: Box<PathOutput>,
+        #[serde(default)]
+        warnings: Vec<WarningOutput>,
+    },
+    /// The graph couldn't be loaded or the request was malformed
+    InvalidInput(String),
+    /// A node doesn't exist or no path connects them
+    QueryFailed(String),
+}
+
+/// A graph to validate and serve under a name in the daemon's
+/// `--graph-dir`, replacing the graph of that name.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct Upload {
+    /// Name to serve the graph under
+    pub(crate) upload: String,
+    /// The graph, in the same JSON format as graph files
+    pub(crate) graph: serde_json::Value,
+}
+
+/// Routes to be told about whenever their shortest path or SLO status
+/// changes, answered with an `Update` per route and then one per change
+/// for as long as the connection stays open.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct Subscribe {
+    pub(crate) subscribe: Vec<Watch>,
+}
+
+/// One subscribed route.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct Watch {
+    #[serde(flatten)]
+    pub(crate) request: Request,
+    /// Latency the route must stay within, to be told when it stops or
+    /// starts meeting it
+    #[serde(default)]
+    pub(crate) max_latency_ms: Option<u32>,
+}
+
+/// A subscribed route's answer, sent when it's first found and again when
+/// the route's path, whether it has one, or its SLO status changes.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct Update {
+    /// Position of the route in the subscription, from 0
+    pub(crate) route: usize,
+    /// Whether the path is within `max_latency_ms`, for routes with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) met: Option<bool>,
+    pub(crate) response: Response,
+}
+
+/// The daemon's answer to an `Upload`.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UploadResponse {
+    /// The graph is valid and is now served under its name
+    Uploaded {
+        graph: DaemonUploadOutput,
+        /// Input that was accepted but is likely a mistake
+        #[serde(default)]
+        warnings: Vec<WarningOutput>,
+    },
+    /// The graph failed validation, and the graph served before is
+    /// unchanged. Each entry is one level of the error, outermost first.
+    InvalidGraph(Vec<String>),
+    /// Uploads aren't enabled, or the name isn't valid
+    InvalidInput(String),
+}
+
+/// Sent instead of an answer when the client isn't authenticated or is over
+/// its rate limit.
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Rejected {
+    rejected: String,
+}
+
+/// First line of a connection to a daemon started with `--token-file`.
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Hello {
+    token: String,
+}
+
+/// Environment variable clients read their daemon token from.
+const TOKEN_VAR: &str = "GT_PATH_DAEMON_TOKEN";
+
+/// A request for the daemon itself rather than a query, sent as a bare
+/// JSON string such as `"stats"`.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Control {
+    /// Answered with a `DaemonStatsOutput`
+    Stats,
+    /// Answered with the protocol's JSON Schema, as printed by
+    /// `daemon --schema`
+    Schema,
+    /// Answered with a `DaemonHealthOutput`
+    Health,
+}
+
+/// One line of a `daemon --record` session file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Recorded {
+    pub(crate) request: Request,
+    pub(crate) response: Response,
+}
+
+/// Graphs in memory by file, shared by connections.
+type Graphs = Mutex<HashMap<PathBuf, Resident>>;
+
+/// How `serve` answers and guards queries.
+#[derive(Default)]
+pub(crate) struct Options {
+    /// Session file to append every request and response to
+    pub(crate) record: Option<PathBuf>,
+    /// Cache for answers, keyed by graph version, time, route, and filters
+    pub(crate) cache: Option<PathCache>,
+    /// Tokens clients must present; empty to accept any client
+    pub(crate) tokens: Vec<String>,
+    /// Queries per second allowed per client: per token, or per connection
+    /// without tokens
+    pub(crate) rate_limit: Option<u32>,
+    /// Directory of `NAME.json` files that requests may name a graph from
+    pub(crate) graph_dir: Option<PathBuf>,
+    /// Whether clients may upload graphs into `graph_dir`
+    pub(crate) allow_upload: bool,
+    /// File to log every message and its outcome to, `-` for stdout
+    pub(crate) access_log: Option<PathBuf>,
+    /// Graphs to load at startup, by path or name; the daemon is ready once
+    /// they have all loaded
+    pub(crate) preload: Vec<PathBuf>,
+    /// How long to wait for open connections when stopping
+    pub(crate) shutdown_timeout: Duration,
+    /// How often subscribed routes are checked for changes
+    pub(crate) poll_interval: Duration,
+}
+
+/// State shared by every connection.
+struct Daemon {
+    graphs: Graphs,
+    recorder: Option<Mutex<std::fs::File>>,
+    cache: Option<PathCache>,
+    tokens: Vec<String>,
+    limiter: Option<RateLimiter>,
+    graph_dir: Option<PathBuf>,
+    allow_upload: bool,
+    access_log: Option<AccessLog>,
+    preload: Vec<PathBuf>,
+    /// Preloads not yet finished
+    loading: AtomicUsize,
+    /// Preloads that failed, with why
+    failed: Mutex<Vec<String>>,
+    stopping: AtomicBool,
+    shutdown_timeout: Duration,
+    poll_interval: Duration,
+    /// Open connections by number, to stop reading from when stopping
+    open: Mutex<HashMap<u64, UnixStream>>,
+    metrics: Metrics,
+}
+
+/// Upper bounds of the daemon's timing histograms, in seconds.
+const BUCKETS: [f64; 10] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// What the daemon has spent its time on, for `--status`.
+struct Metrics {
+    reloads: AtomicU64,
+    loads: Mutex<HistogramOutput>,
+    queries: Mutex<HistogramOutput>,
+}
+
+impl Default for Metrics {
+    fn default() -> Metrics {
+        Metrics {
+            reloads: AtomicU64::new(0),
+            loads: Mutex::new(HistogramOutput::new(&BUCKETS)),
+            queries: Mutex::new(HistogramOutput::new(&BUCKETS)),
+        }
+    }
+}
+
+impl Metrics {
+    /// Counts a graph loaded in `elapsed`, which replaced one held before
+    /// if `replaced`.
+    fn loaded(&self, elapsed: Duration, replaced: bool) {
+        self.loads.lock().unwrap().observe(elapsed.as_secs_f64());
+        if replaced {
+            self.reloads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Uploads staged so far, so concurrent uploads stage to distinct files.
+static UPLOADS: AtomicU64 = AtomicU64::new(0);
+
+impl Daemon {
+    fn new(options: Options) -> Result<Daemon> {
+        let recorder = options
+            .record
+            .map(|path| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .context(format!("Failed to open session file: {}", path.display()))
+            })
+            .transpose()?
+            .map(Mutex::new);
+        Ok(Daemon {
+            graphs: Graphs::default(),
+            recorder,
+            cache: options.cache,
+            tokens: options.tokens,
+            limiter: options.rate_limit.map(RateLimiter::new),
+            graph_dir: options.graph_dir,
+            allow_upload: options.allow_upload,
+            access_log: options
+                .access_log
+                .as_deref()
+                .map(AccessLog::open)
+                .transpose()?,
+            loading: AtomicUsize::new(options.preload.len()),
+            preload: options.preload,
+            failed: Mutex::default(),
+            stopping: AtomicBool::new(false),
+            shutdown_timeout: options.shutdown_timeout,
+            poll_interval: options.poll_interval,
+            open: Mutex::default(),
+            metrics: Metrics::default(),
+        })
+    }
+
+    fn health(&self) -> DaemonHealthOutput {
+        let loading = self.loading.load(Ordering::SeqCst);
+        let errors = self.failed.lock().unwrap().clone();
+        let status = if self.stopping.load(Ordering::SeqCst) {
+            "stopping"
+        } else if !errors.is_empty() {
+            "failed"
+        } else if loading > 0 {
+            "loading"
+        } else {
+            "ready"
+        };
+        DaemonHealthOutput {
+            ready: status == "ready",
+            status: status.to_string(),
+            loading,
+            errors,
+        }
+    }
+
+    /// Makes `run` stop accepting connections and return once open ones
+    /// finish.
+    fn stop(&self, socket: &Path) {
+        self.stopping.store(true, Ordering::SeqCst);
+        // wakes the accept loop so it sees the flag
+        let _ = UnixStream::connect(socket);
+    }
+
+    /// Writes `entry` for `client` to the access log, if there is one.
+    fn audit(&self, client: &Client, started: Instant, entry: Entry) -> Result<()> {
+        let Some(log) = &self.access_log else {
+            return Ok(());
+        };
+        log.write(
+            started,
+            Entry {
+                connection: client.connection,
+                client: client.id.clone(),
+                ..entry
+            },
+        )
+    }
+
+    /// Finds the file a request's graph refers to: an absolute path as is,
+    /// or a name as `NAME.json` in the graph directory.
+    fn locate(&self, graph: &Path) -> Result<PathBuf> {
+        if graph.is_absolute() {
+            return Ok(graph.to_path_buf());
+        }
+        let name = graph.to_string_lossy();
+        let Some(dir) = &self.graph_dir else {
+            anyhow::bail!(
+                "Graph '{}' isn't an absolute path, and the daemon has no --graph-dir",
+                name
+            );
+        };
+        let name = graph_name(&name)?;
+        let file = dir.join(format!("{}.json", name));
+        if !file.is_file() {
+            anyhow::bail!("No graph named '{}' in {}", name, dir.display());
+        }
+        Ok(file)
+    }
+
+    /// Validates an uploaded graph the way graph files are loaded and, if
+    /// it passes, atomically replaces the file of that name and serves it.
+    fn upload(&self, upload: Upload) -> UploadResponse {
+        let Some(dir) = self.graph_dir.as_ref().filter(|_| self.allow_upload) else {
+            return UploadResponse::InvalidInput(
+                "uploads are disabled; start the daemon with --graph-dir and --allow-upload"
+                    .to_string(),
+            );
+        };
+        let name = match graph_name(&upload.upload) {
+            Ok(name) => name.to_string(),
+            Err(e) => return UploadResponse::InvalidInput(e.to_string()),
+        };
+
+        // staged beside the target, so includes resolve the same and the
+        // rename stays on one filesystem
+        let file = dir.join(format!("{}.json", name));
+        let staged = dir.join(format!(
+            ".{}.{}.upload",
+            name,
+            UPLOADS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let started = Instant::now();
+        let replaced = stage(&staged, &upload.graph).and_then(|graph| {
+            std::fs::rename(&staged, &file).map_err(|e| {
+                UploadResponse::InvalidInput(format!("Failed to replace {}: {}", file.display(), e))
+            })?;
+            Ok(graph)
+        });
+        let elapsed = started.elapsed();
+        let (graph, warnings) = match replaced {
+            Ok(loaded) => loaded,
+            Err(response) => {
+                let _ = std::fs::remove_file(&staged);
+                return response;
+            }
+        };
+
+        let output = DaemonUploadOutput {
+            name,
+            graph_hash: graph.content_hash().to_string(),
+            nodes: graph.to_name.len(),
+            edges: graph.edge_attrs.len(),
+        };
+        let modified = modified(&file).ok().flatten();
+        let replaced = hold(&self.graphs, &file, modified, graph, warnings.clone());
+        self.metrics.loaded(elapsed, replaced);
+        UploadResponse::Uploaded {
+            graph: output,
+            warnings,
+        }
+    }
+}
+
+/// Writes an uploaded graph to `staged` and loads it from there, with the
+/// warnings loading it raised.
+fn stage(
+    staged: &Path,
+    graph: &serde_json::Value,
+) -> Result<(Graph, Vec<WarningOutput>), UploadResponse> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        UploadResponse::InvalidInput(format!("Failed to stage upload: {}", e))
+    };
+    let data = serde_json::to_vec_pretty(graph).map_err(|e| invalid(&e))?;
+    std::fs::write(staged, data).map_err(|e| invalid(&e))?;
+    let (loaded, warnings) =
+        crate::collect_warnings(|| crate::load_graph(&staged.display().to_string()));
+    match loaded {
+        Ok(graph) => Ok((graph, warnings)),
+        Err(e) => Err(UploadResponse::InvalidGraph(
+            e.chain().map(|e| e.to_string()).collect(),
+        )),
+    }
+}
+
+/// Checks a graph name, which can't reach outside the graph directory, and
+/// returns it without any `.json` suffix.
+fn graph_name(name: &str) -> Result<&str> {
+    let name = name.strip_suffix(".json").unwrap_or(name);
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!("Invalid graph name '{}'", name);
+    }
+    Ok(name)
+}
+
+/// Token buckets per client: each holds up to `rate` queries and refills at
+/// `rate` per second.
+struct RateLimiter {
+    rate: f64,
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> RateLimiter {
+        RateLimiter {
+            rate: rate as f64,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Takes `queries` from `client`'s bucket, all or none, returning false
+    /// if it doesn't hold that many. A batch larger than the bucket is
+    /// never allowed.
+    fn allow(&self, client: &str, queries: usize) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let (tokens, refilled) = buckets
+            .entry(client.to_string())
+            .or_insert((self.rate, now));
+        *tokens =
+            (*tokens + now.duration_since(*refilled).as_secs_f64() * self.rate).min(self.rate);
+        *refilled = now;
+        if *tokens < queries as f64 {
+            return false;
+        }
+        *tokens -= queries as f64;
+        true
+    }
+}
+
+/// A graph kept in memory, with the file modification time it was loaded at
+/// and the warnings loading it raised. A reload swaps the graph, so queries
+/// already running keep the one they started with.
+struct Resident {
+    modified: Option<SystemTime>,
+    graph: ArcGraph,
+    warnings: Vec<WarningOutput>,
+}
+
+/// Serves `graph` for `file` from now on, returning true if it replaced a
+/// graph already held.
+fn hold(
+    graphs: &Graphs,
+    file: &Path,
+    modified: Option<SystemTime>,
+    graph: Graph,
+    warnings: Vec<WarningOutput>,
+) -> bool {
+    let mut graphs = graphs.lock().unwrap();
+    match graphs.get_mut(file) {
+        Some(r) => {
+            r.graph.swap(graph);
+            r.modified = modified;
+            r.warnings = warnings;
+            true
+        }
+        None => {
+            graphs.insert(
+                file.to_path_buf(),
+                Resident {
+                    modified,
+                    graph: ArcGraph::new(graph),
+                    warnings,
+                },
+            );
+            false
+        }
+    }
+}
+
+/// Listens on `socket` and answers shortest path queries until SIGTERM or
+/// SIGINT.
+///
+/// Each graph file is loaded on the first query that names it and kept in
+/// memory; it is reloaded when the file's modification time changes. With
+/// `graph_dir`, queries may name a graph in it instead of giving a path.
+/// Connections are served on their own threads and may send any number of
+/// requests. With `tokens`, a connection must first send a `Hello` with one
+/// of them; with `rate_limit`, a client's queries past the limit are
+/// rejected. Clients are told apart by token, or without tokens by
+/// connection, so one caller can't use up another's limit.
+///
+/// On a signal the daemon stops accepting connections, lets each open one
+/// finish the message it's answering, waits up to `shutdown_timeout` for
+/// them to close, and removes the socket.
+pub(crate) fn serve(socket: &Path, options: Options) -> Result<()> {
+    let daemon = Arc::new(Daemon::new(options)?);
+    let listener = bind(socket)?;
+    let mut signals = signal_hook::iterator::Signals::new([
+        signal_hook::consts::SIGTERM,
+        signal_hook::consts::SIGINT,
+    ])?;
+    let stopper = Arc::clone(&daemon);
+    let path = socket.to_path_buf();
+    std::thread::spawn(move || {
+        if signals.forever().next().is_some() {
+            stopper.stop(&path);
+        }
+    });
+    run(&daemon, listener, socket)
+}
+
+fn bind(socket: &Path) -> Result<UnixListener> {
+    // a socket left behind by a previous daemon would make bind fail
+    if UnixStream::connect(socket).is_err() {
+        let _ = std::fs::remove_file(socket);
+    }
+    let listener =
+        UnixListener::bind(socket).context(format!("Failed to listen on {}", socket.display()))?;
+    if !crate::progress::quiet() {
+        eprintln!("Listening on {}", socket.display());
+    }
+    Ok(listener)
+}
+
+/// Connections accepted so far, to number them in the access log.
+static CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Preloads graphs and serves connections until `daemon` is stopped.
+fn run(daemon: &Arc<Daemon>, listener: UnixListener, socket: &Path) -> Result<()> {
+    let preloader = Arc::clone(daemon);
+    std::thread::spawn(move || {
+        for graph in &preloader.preload {
+            let loaded = preloader
+                .locate(graph)
+                .and_then(|file| resident(&file, &preloader));
+            if let Err(e) = loaded {
+                let error = format!("{}: {:#}", graph.display(), e);
+                eprintln!("Warning: failed to preload {}", error);
+                preloader.failed.lock().unwrap().push(error);
+            }
+            preloader.loading.fetch_sub(1, Ordering::SeqCst);
+        }
+    });
+
+    for stream in listener.incoming() {
+        if daemon.stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let connection = CONNECTIONS.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Ok(open) = stream.try_clone() {
+            daemon.open.lock().unwrap().insert(connection, open);
+        }
+        let daemon = Arc::clone(daemon);
+        std::thread::spawn(move || {
+            let _ = handle(stream, connection, &daemon);
+            daemon.open.lock().unwrap().remove(&connection);
+        });
+    }
+
+    // connections see the end of their input once their current message is
+    // answered
+    for stream in daemon.open.lock().unwrap().values() {
+        let _ = stream.shutdown(std::net::Shutdown::Read);
+    }
+    let deadline = Instant::now() + daemon.shutdown_timeout;
+    while !daemon.open.lock().unwrap().is_empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let _ = std::fs::remove_file(socket);
+    if !crate::progress::quiet() {
+        eprintln!("Stopped");
+    }
+    Ok(())
+}
+
+/// A connected client, as the access log and rate limiter know it.
+struct Client {
+    connection: u64,
+    /// Fingerprint of the client's token, if it sent one
+    id: Option<String>,
+}
+
+fn handle(stream: UnixStream, connection: u64, daemon: &Daemon) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut lines = BufReader::new(stream).lines();
+    let mut client = Client {
+        connection,
+        id: None,
+    };
+    if !daemon.tokens.is_empty() {
+        let started = Instant::now();
+        // a client that hangs up without a word isn't worth logging
+        let Some(hello) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        let hello = serde_json::from_str::<Hello>(&hello).ok();
+        client.id = hello.as_ref().map(|h| access::fingerprint(&h.token));
+        if !hello.is_some_and(|h| daemon.tokens.iter().any(|t| same_token(t, &h.token))) {
+            let rejected = format!("unauthorized; set {} to a daemon token", TOKEN_VAR);
+            let entry = Entry {
+                kind: "connect",
+                outcome: "rejected",
+                error: Some(rejected.clone()),
+                ..Entry::default()
+            };
+            daemon.audit(&client, started, entry)?;
+            return write_line(&mut writer, &Rejected { rejected });
+        }
+    }
+
+    let mut subscribed = None;
+    for line in lines.by_ref() {
+        let line = line?;
+        let started = Instant::now();
+        match serde_json::from_str(&line) {
+            Ok(Control::Stats) => {
+                daemon.audit(&client, started, Entry::control("stats"))?;
+                write_line(&mut writer, &stats(daemon))?;
+                continue;
+            }
+            Ok(Control::Schema) => {
+                daemon.audit(&client, started, Entry::control("schema"))?;
+                write_line(&mut writer, &schema())?;
+                continue;
+            }
+            Ok(Control::Health) => {
+                daemon.audit(&client, started, Entry::control("health"))?;
+                write_line(&mut writer, &daemon.health())?;
+                continue;
+            }
+            Err(_) => {}
+        }
+        // clients with a token send it even if this daemon doesn't need one
+        if serde_json::from_str::<Hello>(&line).is_ok() {
+            continue;
+        }
+        let upload = serde_json::from_str::<Upload>(&line);
+        let subscribe = serde_json::from_str::<Subscribe>(&line);
+        let batch = serde_json::from_str::<Batch>(&line);
+        let request = serde_json::from_str::<Request>(&line);
+        let kind = match (&upload, &subscribe, &batch) {
+            (Ok(_), _, _) => "upload",
+            (_, Ok(_), _) => "subscribe",
+            (_, _, Ok(_)) => "batch",
+            _ => "query",
+        };
+        // each query in a batch counts against the limit; an upload or
+        // subscription counts as one
+        let queries = match &batch {
+            Ok(Batch::Whole(requests) | Batch::Streamed { stream: requests }) => requests.len(),
+            Err(_) => 1,
+        };
+        let bucket = client
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("connection {}", client.connection));
+        if (upload.is_ok() || subscribe.is_ok() || batch.is_ok() || request.is_ok())
+            && daemon
+                .limiter
+                .as_ref()
+                .is_some_and(|l| !l.allow(&bucket, queries))
+        {
+            let rejected = "rate limit exceeded".to_string();
+            let entry = Entry {
+                kind,
+                outcome: "rejected",
+                error: Some(rejected.clone()),
+                ..Entry::default()
+            };
+            daemon.audit(&client, started, entry)?;
+            write_line(&mut writer, &Rejected { rejected })?;
+            continue;
+        }
+        if let Ok(upload) = upload {
+            let name = upload.upload.clone();
+            let response = daemon.upload(upload);
+            let (outcome, error) = match &response {
+                UploadResponse::Uploaded { .. } => ("uploaded", None),
+                UploadResponse::InvalidGraph(errors) => ("invalid_graph", Some(errors.join(": "))),
+                UploadResponse::InvalidInput(e) => ("invalid_input", Some(e.clone())),
+            };
+            let entry = Entry {
+                kind,
+                graph: Some(name),
+                outcome,
+                error,
+                ..Entry::default()
+            };
+            daemon.audit(&client, started, entry)?;
+            write_line(&mut writer, &response)?;
+            continue;
+        }
+        if let Ok(subscribe) = subscribe {
+            daemon.audit(&client, started, Entry::control(kind))?;
+            subscribed = Some(subscribe.subscribe);
+            break;
+        }
+        match (batch, request) {
+            (Ok(Batch::Whole(requests)), _) => {
+                let responses = requests
+                    .into_iter()
+                    .map(|request| respond(daemon, &client, request))
+                    .collect::<Result<Vec<_>>>()?;
+                write_line(&mut writer, &responses)?;
+            }
+            (Ok(Batch::Streamed { stream }), _) => {
+                for request in stream {
+                    write_line(&mut writer, &respond(daemon, &client, request)?)?;
+                }
+            }
+            (_, Ok(request)) => write_line(&mut writer, &respond(daemon, &client, request)?)?,
+            (_, Err(e)) => {
+                let error = format!("Malformed request: {}", e);
+                let entry = Entry {
+                    kind,
+                    outcome: "invalid_input",
+                    error: Some(error.clone()),
+                    ..Entry::default()
+                };
+                daemon.audit(&client, started, entry)?;
+                write_line(&mut writer, &Response::InvalidInput(error))?;
+            }
+        }
+        // the daemon never exits, so spans go out after every request
+        let _ = crate::telemetry::export();
+    }
+    match subscribed {
+        Some(watches) => push(&mut writer, &mut lines, daemon, &watches),
+        None => Ok(()),
+    }
+}
+
+/// What a subscriber is told again when it changes.
+#[derive(PartialEq)]
+enum Seen {
+    Path(Vec<Arc<str>>, Option<bool>),
+    Failed(String),
+}
+
+/// Sends an `Update` for each watched route, then re-checks them every
+/// poll interval and sends those that changed, until the client hangs up
+/// or the daemon stops. Graph files are reloaded by the checks as usual,
+/// and unchanged graphs are answered from the cache.
+fn push(
+    writer: &mut UnixStream,
+    lines: &mut std::io::Lines<BufReader<UnixStream>>,
+    daemon: &Daemon,
+    watches: &[Watch],
+) -> Result<()> {
+    // waiting for the client doubles as the pause between checks; a zero
+    // timeout would be an error
+    writer.set_read_timeout(Some(daemon.poll_interval.max(Duration::from_millis(10))))?;
+    let mut last: Vec<Option<Seen>> = watches.iter().map(|_| None).collect();
+    loop {
+        for (route, watch) in watches.iter().enumerate() {
+            let response = answer(&watch.request, daemon).0;
+            let met = watch.max_latency_ms.map(|max| {
+                matches!(&response, Response::Path { path, .. } if path.total_latency_ms <= max)
+            });
+            let seen = match &response {
+                Response::Path { path, .. } => Seen::Path(path.path.clone(), met),
+                Response::InvalidInput(e) | Response::QueryFailed(e) => Seen::Failed(e.clone()),
+            };
+            if last[route].as_ref() != Some(&seen) {
+                write_line(
+                    writer,
+                    &Update {
+                        route,
+                        met,
+                        response,
+                    },
+                )?;
+                last[route] = Some(seen);
+            }
+        }
+        match lines.next() {
+            // hung up, or the daemon is stopping
+            None => return Ok(()),
+            Some(Err(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Some(Err(e)) => return Err(e.into()),
+            // the connection belongs to the subscription now
+            Some(Ok(_)) => {}
+        }
+    }
+}
+
+/// Answers one query, logging it and recording it if the daemon does.
+fn respond(daemon: &Daemon, client: &Client, request: Request) -> Result<Response> {
+    let started = Instant::now();
+    let (response, cache_hit) = answer(&request, daemon);
+    let elapsed = started.elapsed().as_secs_f64();
+    daemon.metrics.queries.lock().unwrap().observe(elapsed);
+    let (outcome, latency_ms, error) = match &response {
+        Response::Path { path, .. } => ("path", Some(path.total_latency_ms), None),
+        Response::InvalidInput(e) => ("invalid_input", None, Some(e.clone())),
+        Response::QueryFailed(e) => ("query_failed", None, Some(e.clone())),
+    };
+    let entry = Entry {
+        kind: "query",
+        graph: Some(request.graph.display().to_string()),
+        from: Some(request.from.clone()),
+        to: Some(request.to.clone()),
+        at: request.at.clone(),
+        outcome,
+        latency_ms,
+        cache_hit: daemon.cache.as_ref().map(|_| cache_hit),
+        error,
+        ..Entry::default()
+    };
+    daemon.audit(client, started, entry)?;
+    match &daemon.recorder {
+        Some(recorder) => record(recorder, request, response),
+        None => Ok(response),
+    }
+}
+
+fn write_line<T: Serialize>(writer: &mut UnixStream, message: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    Ok(())
+}
+
+/// Compares tokens in time independent of where they first differ.
+fn same_token(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Appends one request and its response to the session file, handing the
+/// response back.
+fn record(
+    recorder: &Mutex<std::fs::File>,
+    request: Request,
+    response: Response,
+) -> Result<Response> {
+    let recorded = Recorded { request, response };
+    let mut line = serde_json::to_vec(&recorded)?;
+    line.push(b'\n');
+    // one write per line under the lock keeps lines from interleaving
+    recorder.lock().unwrap().write_all(&line)?;
+    Ok(recorded.response)
+}
+
+/// Answers `request`, and whether the answer came from the cache.
+fn answer(request: &Request, daemon: &Daemon) -> (Response, bool) {
+    let resident = daemon
+        .locate(&request.graph)
+        .and_then(|file| resident(&file, daemon));
+    let (graph, warnings) = match resident {
+        Ok(resident) => resident,
+        Err(e) => return (Response::InvalidInput(format!("{:#}", e)), false),
+    };
+    match answer_from(request, &graph, daemon.cache.as_ref()) {
+        (Response::Path { path, .. }, cached) => (Response::Path { path, warnings }, cached),
+        answered => answered,
+    }
+}
+
+/// Answers `request` from `graph` rather than the file it names, without
+/// warnings. Used to replay recorded sessions.
+pub(crate) fn answer_with(request: &Request, graph: &Graph) -> Response {
+    answer_from(request, graph, None).0
+}
+
+fn answer_from(request: &Request, graph: &Graph, cache: Option<&PathCache>) -> (Response, bool) {
+    let at = match request
+        .at
+        .as_deref()
+        .map(gt_path_core::time::parse_timestamp)
+    {
+        Some(Ok(at)) => Some(at),
+        Some(Err(e)) => return (Response::InvalidInput(e), false),
+        None => None,
+    };
+    let constraints = match request.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Response::InvalidInput(format!("{:#}", e)), false),
+    };
+
+    let computed = std::cell::Cell::new(false);
+    let query = || {
+        computed.set(true);
+        let _query = crate::telemetry::span("query");
+        let resolved;
+        let graph = match at {
+            Some(at) => {
+                resolved = graph.at(at);
+                &resolved
+            }
+            None => graph,
+        };
+        let path = graph.shortest_path_with(&request.from, &request.to, &constraints)?;
+        Ok(graph.path_output(&path))
+    };
+    let result = match cache {
+        Some(cache) => cache.get_or_compute(
+            CacheKey {
+                fingerprint: fingerprint(graph, at),
+                from: request.from.clone(),
+                to: request.to.clone(),
+                constraints: constraints.clone(),
+            },
+            query,
+        ),
+        None => query(),
+    };
+    let response = match result {
+        Ok(path) => Response::Path {
+            path: Box::new(path),
+            warnings: Vec::new(),
+        },
+        Err(e) => Response::QueryFailed(e.to_string()),
+    };
+    (response, cache.is_some() && !computed.get())
+}
+
+/// Cache key form of the graph's content hash and the time queried at.
+fn fingerprint(graph: &Graph, at: Option<i64>) -> u64 {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    graph.content_hash().hash(&mut hasher);
+    at.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn stats(daemon: &Daemon) -> DaemonStatsOutput {
+    let stats = daemon.cache.as_ref().map(|c| c.stats()).unwrap_or_default();
+    DaemonStatsOutput {
+        graphs: daemon.graphs.lock().unwrap().len(),
+        cache_enabled: daemon.cache.is_some(),
+        hits: stats.hits,
+        misses: stats.misses,
+        expired: stats.expired,
+        entries: stats.entries,
+        hit_rate: stats.hit_rate(),
+        reloads: daemon.metrics.reloads.load(Ordering::Relaxed),
+        load_seconds: daemon.metrics.loads.lock().unwrap().clone(),
+        query_seconds: daemon.metrics.queries.lock().unwrap().clone(),
+    }
+}
+
+/// Returns the loaded graph for `file`, loading it on first use or when
+/// the file, or a file it includes, has changed since.
+///
+/// A reload that fails, say on a half-written file, keeps serving the
+/// graph already held, with a warning on stderr, and is retried on the
+/// file's next change.
+fn resident(file: &Path, daemon: &Daemon) -> Result<(Arc<Graph>, Vec<WarningOutput>)> {
+    let graphs = &daemon.graphs;
+    let name = file.display().to_string();
+    // a file that can't be read counts as changed, and fails to load
+    let modified = modified(file).ok().flatten();
+
+    if let Some(r) = graphs.lock().unwrap().get(file)
+        && r.modified == modified
+    {
+        return Ok((r.graph.snapshot(), r.warnings.clone()));
+    }
+
+    // loading can take a while, so other graphs stay available meanwhile
+    let started = Instant::now();
+    let (loaded, warnings) = crate::collect_warnings(|| crate::load_graph(&name));
+    let graph = match loaded {
+        Ok(graph) => graph,
+        Err(e) => {
+            let e = e.context(format!("Failed to load graph from {}", name));
+            let mut held = graphs.lock().unwrap();
+            let Some(r) = held.get_mut(file) else {
+                return Err(e);
+            };
+            if !crate::progress::quiet() {
+                eprintln!("Warning: {:#}; serving the previous graph", e);
+            }
+            r.modified = modified;
+            return Ok((r.graph.snapshot(), r.warnings.clone()));
+        }
+    };
+    let replaced = hold(graphs, file, modified, graph, warnings);
+    daemon.metrics.loaded(started.elapsed(), replaced);
+    // graphs are never dropped once held
+    let r = &graphs.lock().unwrap()[file];
+    Ok((r.graph.snapshot(), r.warnings.clone()))
+}
+
+/// Sends `request` to the daemon listening on `socket`.
+pub(crate) fn query(socket: &Path, request: &Request) -> Result<Response> {
+    exchange(socket, request)
+}
+
+/// JSON Schema of the protocol, generated from the message types: a line
+/// from a client is a `ClientLine` and each answer a `DaemonLine`.
+pub(crate) fn schema() -> serde_json::Value {
+    let mut generator = schemars::SchemaGenerator::default();
+    let client = [
+        generator.subschema_for::<Hello>(),
+        generator.subschema_for::<Request>(),
+        generator.subschema_for::<Batch>(),
+        generator.subschema_for::<Control>(),
+        generator.subschema_for::<Upload>(),
+        generator.subschema_for::<Subscribe>(),
+    ];
+    let daemon = [
+        generator.subschema_for::<Response>(),
+        generator.subschema_for::<Vec<Response>>(),
+        generator.subschema_for::<Rejected>(),
+        generator.subschema_for::<DaemonStatsOutput>(),
+        generator.subschema_for::<UploadResponse>(),
+        generator.subschema_for::<DaemonHealthOutput>(),
+        generator.subschema_for::<Update>(),
+    ];
+    let mut defs = generator.take_definitions(true);
+    defs.insert(
+        "ClientLine".to_string(),
+        serde_json::json!({
+            "description": "One line sent to the daemon",
+            "oneOf": client,
+        }),
+    );
+    defs.insert(
+        "DaemonLine".to_string(),
+        serde_json::json!({
+            "description": "One line the daemon answers with; a `schema` control \
+                            request is answered with this document",
+            "oneOf": daemon,
+        }),
+    );
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "gt-path daemon protocol",
+        "description": "Newline-delimited JSON over a Unix socket",
+        "$comment": format!("Generated by gt-path {}", env!("CARGO_PKG_VERSION")),
+        "$defs": defs,
+        "oneOf": [
+            {"$ref": "#/$defs/ClientLine"},
+            {"$ref": "#/$defs/DaemonLine"},
+        ],
+    })
+}
+
+/// When `file` last changed; a composed graph changes when any of its files
+/// does.
+fn modified(file: &Path) -> Result<Option<SystemTime>> {
+    Ok(gt_path_core::compose::files(file)?
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+        .max())
+}
+
+/// Uploads a graph to the daemon listening on `socket`.
+pub(crate) fn upload(socket: &Path, upload: &Upload) -> Result<UploadResponse> {
+    exchange(socket, upload)
+}
+
+/// Sends `requests` to the daemon listening on `socket` as one batch,
+/// returning the responses in the same order.
+pub(crate) fn query_batch(socket: &Path, requests: Vec<Request>) -> Result<Vec<Response>> {
+    let count = requests.len();
+    let responses: Vec<Response> = exchange(socket, &Batch::Whole(requests))?;
+    if responses.len() != count {
+        anyhow::bail!(
+            "daemon answered {} of {} queries in the batch",
+            responses.len(),
+            count
+        );
+    }
+    Ok(responses)
+}
+
+/// Asks the daemon listening on `socket` whether it's ready.
+pub(crate) fn query_health(socket: &Path) -> Result<DaemonHealthOutput> {
+    exchange(socket, &Control::Health)
+}
+
+/// Asks the daemon listening on `socket` for its counters.
+pub(crate) fn query_stats(socket: &Path) -> Result<DaemonStatsOutput> {
+    exchange(socket, &Control::Stats)
+}
+
+/// Sends `message`, preceded by a `Hello` if `GT_PATH_DAEMON_TOKEN` is set,
+/// and reads one reply.
+fn exchange<T: Serialize, R: serde::de::DeserializeOwned>(socket: &Path, message: &T) -> Result<R> {
+    let mut stream = UnixStream::connect(socket).context(format!(
+        "Failed to connect to daemon at {}",
+        socket.display()
+    ))?;
+    if let Ok(token) = std::env::var(TOKEN_VAR) {
+        write_line(&mut stream, &Hello { token })?;
+    }
+    write_line(&mut stream, message)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    if let Ok(Rejected { rejected }) = serde_json::from_str(&line) {
+        anyhow::bail!("daemon rejected the query: {}", rejected);
+    }
+    serde_json::from_str(&line).context("Malformed response from daemon")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(from: &str, to: &str) -> Request {
+        Request {
+            graph: std::fs::canonicalize("src/testdata/sample_graph.json").unwrap(),
+            from: from.to_string(),
+            to: to.to_string(),
+            at: None,
+            constraints: ConstraintArgs::default(),
+        }
+    }
+
+    fn wait_for(socket: &Path) {
+        let deadline = SystemTime::now() + std::time::Duration::from_secs(5);
+        while UnixStream::connect(socket).is_err() && SystemTime::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    /// Sends raw lines on one connection and reads a reply per query.
+    fn converse(socket: &Path, lines: &[String], replies: usize) -> Vec<serde_json::Value> {
+        let mut stream = UnixStream::connect(socket).unwrap();
+        for line in lines {
+            // a rejected client is disconnected before sending everything
+            let _ = stream.write_all(format!("{}\n", line).as_bytes());
+        }
+        BufReader::new(stream)
+            .lines()
+            .take(replies)
+            .map(|l| serde_json::from_str(&l.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2);
+        assert!(limiter.allow("a", 1));
+        assert!(limiter.allow("a", 1));
+        assert!(!limiter.allow("a", 1));
+        // each client has its own bucket
+        assert!(limiter.allow("b", 1));
+        // a batch takes one token per query, or none if they don't all fit
+        assert!(!limiter.allow("c", 3));
+        assert!(limiter.allow("c", 2));
+        assert!(!limiter.allow("c", 1));
+    }
+
+    #[test]
+    fn test_same_token() {
+        assert!(same_token("s3cret", "s3cret"));
+        assert!(!same_token("s3cret", "s3creT"));
+        assert!(!same_token("s3cret", "s3cre"));
+    }
+
+    #[test]
+    fn test_rejects_clients() {
+        let socket = std::env::temp_dir().join(format!("gt-path-auth-{}.sock", std::process::id()));
+        let path = socket.clone();
+        let log = std::env::temp_dir().join(format!("gt-path-access-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&log);
+        let options = Options {
+            tokens: vec!["s3cret".to_string()],
+            rate_limit: Some(1),
+            access_log: Some(log.clone()),
+            ..Options::default()
+        };
+        std::thread::spawn(move || serve(&path, options));
+        wait_for(&socket);
+
+        let query = serde_json::to_string(&request("api", "db")).unwrap();
+        let hello = |token: &str| {
+            serde_json::to_string(&Hello {
+                token: token.into(),
+            })
+            .unwrap()
+        };
+
+        let replies = converse(&socket, std::slice::from_ref(&query), 1);
+        assert!(
+            replies[0]["rejected"]
+                .as_str()
+                .unwrap()
+                .starts_with("unauthorized")
+        );
+        let replies = converse(&socket, &[hello("wrong"), query.clone()], 1);
+        assert!(
+            replies[0]["rejected"]
+                .as_str()
+                .unwrap()
+                .starts_with("unauthorized")
+        );
+
+        // a burst of one, so the second query in the same second is rejected
+        let replies = converse(&socket, &[hello("s3cret"), query.clone(), query], 2);
+        assert!(replies[0].get("path").is_some());
+        assert_eq!(replies[1]["rejected"], "rate limit exceeded");
+
+        let entries: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let fields = |e: &serde_json::Value| (e["kind"].clone(), e["outcome"].clone());
+        assert_eq!(
+            entries.iter().map(fields).collect::<Vec<_>>(),
+            [
+                ("connect".into(), "rejected".into()),
+                ("connect".into(), "rejected".into()),
+                ("query".into(), "path".into()),
+                ("query".into(), "rejected".into()),
+            ]
+        );
+        // tokens are logged by fingerprint only
+        assert!(entries[0].get("client").is_none());
+        assert_eq!(entries[1]["client"], access::fingerprint("wrong"));
+        assert_eq!(entries[2]["client"], access::fingerprint("s3cret"));
+        assert_eq!(entries[2]["latency_ms"], 8);
+        assert_eq!(entries[2]["from"], "api");
+        assert!(!std::fs::read_to_string(&log).unwrap().contains("s3cret"));
+
+        let _ = std::fs::remove_file(&socket);
+        let _ = std::fs::remove_file(&log);
+    }
+
+    #[test]
+    fn test_rate_limits_anonymous_clients_apart() {
+        let socket = std::env::temp_dir().join(format!("gt-path-anon-{}.sock", std::process::id()));
+        let path = socket.clone();
+        let options = Options {
+            rate_limit: Some(1),
+            ..Options::default()
+        };
+        std::thread::spawn(move || serve(&path, options));
+        wait_for(&socket);
+
+        let query = serde_json::to_string(&request("api", "db")).unwrap();
+        let replies = converse(&socket, &[query.clone(), query.clone()], 2);
+        assert!(replies[0].get("path").is_some());
+        assert_eq!(replies[1]["rejected"], "rate limit exceeded");
+        // another connection has a bucket of its own
+        let replies = converse(&socket, std::slice::from_ref(&query), 1);
+        assert!(replies[0].get("path").is_some());
+        // a batch larger than the rate is refused whole
+        let batch = serde_json::to_string(&Batch::Whole(vec![
+            request("api", "db"),
+            request("api", "cache"),
+        ]))
+        .unwrap();
+        let replies = converse(&socket, &[batch], 1);
+        assert_eq!(replies[0]["rejected"], "rate limit exceeded");
+
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[test]
+    fn test_serves_batches() {
+        let socket =
+            std::env::temp_dir().join(format!("gt-path-batch-{}.sock", std::process::id()));
+        let path = socket.clone();
+        std::thread::spawn(move || serve(&path, Options::default()));
+        wait_for(&socket);
+
+        let responses = query_batch(&socket, vec![request("api", "db"), request("db", "api")]);
+        let responses = responses.unwrap();
+        assert!(matches!(&responses[0], Response::Path { path, .. } if path.total_latency_ms == 8));
+        assert!(matches!(responses[1], Response::QueryFailed(_)));
+
+        let stream = Batch::Streamed {
+            stream: vec![request("api", "cache"), request("cache", "db")],
+        };
+        let replies = converse(&socket, &[serde_json::to_string(&stream).unwrap()], 2);
+        assert_eq!(replies[0]["path"]["path"]["total_latency_ms"], 7);
+        assert_eq!(replies[1]["path"]["path"]["total_latency_ms"], 2);
+
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[test]
+    fn test_named_graphs() {
+        let daemon = Daemon::new(Options {
+            graph_dir: Some(std::fs::canonicalize("src/testdata").unwrap()),
+            ..Options::default()
+        })
+        .unwrap();
+        let mut named = request("api", "db");
+        for name in ["sample_graph", "sample_graph.json"] {
+            named.graph = PathBuf::from(name);
+            assert!(matches!(answer(&named, &daemon).0, Response::Path { .. }));
+        }
+        for name in ["missing", "../testdata/sample_graph", ".hidden", "a/b"] {
+            named.graph = PathBuf::from(name);
+            assert!(
+                matches!(answer(&named, &daemon).0, Response::InvalidInput(_)),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_graph() {
+        let file = std::env::temp_dir().join(format!("gt-path-reload-{}.json", std::process::id()));
+        std::fs::copy("src/testdata/sample_graph.json", &file).unwrap();
+        let daemon = Daemon::new(Options::default()).unwrap();
+        let mut query = request("api", "db");
+        query.graph = file.clone();
+        let latency = |daemon: &Daemon| match answer(&query, daemon).0 {
+            Response::Path { path, .. } => path.total_latency_ms,
+            _ => panic!("expected a path"),
+        };
+        assert_eq!(latency(&daemon), 8);
+
+        // a later modification time, however fast the test runs
+        let touch = |contents: &str, secs: u64| {
+            std::fs::write(&file, contents).unwrap();
+            let at = SystemTime::now() + std::time::Duration::from_secs(secs);
+            std::fs::File::options()
+                .write(true)
+                .open(&file)
+                .unwrap()
+                .set_modified(at)
+                .unwrap();
+        };
+        touch("{ half-written", 10);
+        assert_eq!(latency(&daemon), 8);
+        assert_eq!(daemon.metrics.reloads.load(Ordering::Relaxed), 0);
+
+        touch(
+            r#"{"nodes": ["api", "db"], "edges": [{"from": "api", "to": "db", "latency_ms": 3}]}"#,
+            20,
+        );
+        assert_eq!(latency(&daemon), 3);
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[test]
+    fn test_health_and_stop() {
+        assert!(Daemon::new(Options::default()).unwrap().health().ready);
+
+        let testdata = std::fs::canonicalize("src/testdata").unwrap();
+        let daemon = Arc::new(
+            Daemon::new(Options {
+                preload: vec![testdata.join("sample_graph.json"), PathBuf::from("missing")],
+                graph_dir: Some(testdata),
+                shutdown_timeout: std::time::Duration::from_secs(5),
+                ..Options::default()
+            })
+            .unwrap(),
+        );
+        assert_eq!(daemon.health().status, "loading");
+
+        let socket = std::env::temp_dir().join(format!("gt-path-stop-{}.sock", std::process::id()));
+        let listener = bind(&socket).unwrap();
+        let server = {
+            let (daemon, socket) = (Arc::clone(&daemon), socket.clone());
+            std::thread::spawn(move || run(&daemon, listener, &socket))
+        };
+        wait_for(&socket);
+        let deadline = SystemTime::now() + std::time::Duration::from_secs(5);
+        let health = loop {
+            let health = query_health(&socket).unwrap();
+            if health.loading == 0 || SystemTime::now() > deadline {
+                break health;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert_eq!(health.status, "failed");
+        assert!(!health.ready);
+        assert_eq!(health.errors.len(), 1);
+        assert_eq!(daemon.graphs.lock().unwrap().len(), 1);
+
+        // an idle connection doesn't hold up stopping
+        let idle = UnixStream::connect(&socket).unwrap();
+        daemon.stop(&socket);
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+        assert!(daemon.open.lock().unwrap().is_empty());
+        drop(idle);
+    }
+
+    #[test]
+    fn test_upload() {
+        let dir = std::env::temp_dir().join(format!("gt-path-upload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut daemon = Daemon::new(Options {
+            graph_dir: Some(dir.clone()),
+            allow_upload: true,
+            ..Options::default()
+        })
+        .unwrap();
+        let sample: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string("src/testdata/sample_graph.json").unwrap(),
+        )
+        .unwrap();
+        let upload = |graph: &serde_json::Value| Upload {
+            upload: "prod".to_string(),
+            graph: graph.clone(),
+        };
+
+        match daemon.upload(upload(&sample)) {
+            UploadResponse::Uploaded { graph, .. } => {
+                assert_eq!((graph.nodes, graph.edges), (4, 4))
+            }
+            _ => panic!("expected the upload to succeed"),
+        }
+        let mut named = request("api", "db");
+        named.graph = PathBuf::from("prod");
+        assert!(matches!(answer(&named, &daemon).0, Response::Path { .. }));
+
+        // an invalid graph leaves the served one in place
+        let mut broken = sample.clone();
+        broken["edges"][0]["to"] = "nowhere".into();
+        match daemon.upload(upload(&broken)) {
+            UploadResponse::InvalidGraph(errors) => assert!(errors.len() > 1),
+            _ => panic!("expected the upload to be rejected"),
+        }
+        assert!(matches!(answer(&named, &daemon).0, Response::Path { .. }));
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+
+        // uploading over a served graph counts as a reload
+        assert!(matches!(
+            daemon.upload(upload(&sample)),
+            UploadResponse::Uploaded { .. }
+        ));
+        let counted = stats(&daemon);
+        assert_eq!((counted.reloads, counted.load_seconds.count), (1, 2));
+
+        let mut bad_name = upload(&sample);
+        bad_name.upload = "../prod".to_string();
+        assert!(matches!(
+            daemon.upload(bad_name),
+            UploadResponse::InvalidInput(_)
+        ));
+        daemon.allow_upload = false;
+        assert!(matches!(
+            daemon.upload(upload(&sample)),
+            UploadResponse::InvalidInput(_)
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let dir = std::env::temp_dir().join(format!("gt-path-subscribe-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let graph = dir.join("prod.json");
+        let write_graph = |auth_db: f64, cache_db: f64, age: u64| {
+            let edges = [("api", "auth", 5.2), ("auth", "db", auth_db)];
+            let edges = edges
+                .into_iter()
+                .chain([("api", "cache", 7.4), ("cache", "db", cache_db)])
+                .map(|(from, to, latency_ms)| {
+                    serde_json::json!({"from": from, "to": to, "latency_ms": latency_ms})
+                })
+                .collect::<Vec<_>>();
+            let json = serde_json::json!({"nodes": ["api", "auth", "db", "cache"], "edges": edges});
+            std::fs::write(&graph, json.to_string()).unwrap();
+            // a distinct modification time, however coarse the filesystem's
+            let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(age);
+            std::fs::File::options()
+                .write(true)
+                .open(&graph)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+        write_graph(3.1, 2.3, 1_000);
+
+        let socket = dir.join("daemon.sock");
+        let path = socket.clone();
+        let options = Options {
+            cache: Some(PathCache::new(16)),
+            poll_interval: std::time::Duration::from_millis(20),
+            ..Options::default()
+        };
+        std::thread::spawn(move || serve(&path, options));
+        wait_for(&socket);
+
+        let mut watch = request("api", "db");
+        watch.graph = graph.clone();
+        let subscribe = Subscribe {
+            subscribe: vec![Watch {
+                request: watch,
+                max_latency_ms: Some(9),
+            }],
+        };
+        let mut stream = UnixStream::connect(&socket).unwrap();
+        let line = serde_json::to_string(&subscribe).unwrap();
+        stream.write_all(format!("{}\n", line).as_bytes()).unwrap();
+        let mut updates = BufReader::new(stream).lines().map(|l| {
+            let update: Update = serde_json::from_str(&l.unwrap()).unwrap();
+            match update.response {
+                Response::Path { path, .. } => (update.route, path.path, update.met),
+                _ => panic!("expected a path"),
+            }
+        });
+        let via = |hop: &str| vec!["api".into(), hop.into(), "db".into()];
+
+        assert_eq!(updates.next().unwrap(), (0, via("auth"), Some(true)));
+        // a new path that misses the SLO
+        write_graph(30.0, 10.0, 2_000);
+        assert_eq!(updates.next().unwrap(), (0, via("cache"), Some(false)));
+        // the same path, back within the SLO
+        write_graph(30.0, 1.0, 3_000);
+        assert_eq!(updates.next().unwrap(), (0, via("cache"), Some(true)));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = schema();
+        let defs = schema["$defs"].as_object().unwrap();
+        for name in [
+            "ClientLine",
+            "DaemonLine",
+            "Request",
+            "Response",
+            "PathOutput",
+        ] {
+            assert!(defs.contains_key(name), "{}", name);
+        }
+        assert_eq!(defs["Request"]["required"][0], "graph");
+        assert_eq!(defs["ClientLine"]["oneOf"].as_array().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_serves_queries() {
+        let socket =
+            std::env::temp_dir().join(format!("gt-path-daemon-{}.sock", std::process::id()));
+        let path = socket.clone();
+        let session =
+            std::env::temp_dir().join(format!("gt-path-session-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&session);
+        let record = session.clone();
+        let options = Options {
+            record: Some(record),
+            cache: Some(PathCache::new(16)),
+            ..Options::default()
+        };
+        std::thread::spawn(move || serve(&path, options));
+        wait_for(&socket);
+
+        // the second query is answered from the resident graph and cache
+        for _ in 0..2 {
+            match query(&socket, &request("api", "db")).unwrap() {
+                Response::Path { path: p, .. } => {
+                    assert_eq!(p.path, vec!["api".into(), "auth".into(), "db".into()]);
+                    assert_eq!(p.total_latency_ms, 8);
+                }
+                _ => panic!("expected a path"),
+            }
+        }
+        assert!(matches!(
+            query(&socket, &request("db", "api")).unwrap(),
+            Response::QueryFailed(_)
+        ));
+        let mut missing = request("api", "db");
+        missing.graph = PathBuf::from("/nonexistent/graph.json");
+        assert!(matches!(
+            query(&socket, &missing).unwrap(),
+            Response::InvalidInput(_)
+        ));
+
+        let recorded: Vec<Recorded> = std::fs::read_to_string(&session)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let stats = query_stats(&socket).unwrap();
+        assert!(stats.cache_enabled);
+        assert_eq!((stats.hits, stats.misses, stats.graphs), (1, 2, 1));
+        assert_eq!(stats.query_seconds.count, 4);
+        assert_eq!((stats.load_seconds.count, stats.reloads), (1, 0));
+
+        assert_eq!(recorded.len(), 4);
+        assert_eq!(recorded[2].request.from, "db");
+        assert!(matches!(recorded[2].response, Response::QueryFailed(_)));
+
+        let _ = std::fs::remove_file(&socket);
+        let _ = std::fs::remove_file(&session);
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use gt_path_core::PathError;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{SloHistoryOutput, SnapshotSloOutput};
+use gt_path_core::simulation::glob_match;
+use std::path::{Path, PathBuf};
+
+/// The files matching `pattern`, sorted by name, so dated snapshot names
+/// (`2024-06-01.json`) come out in time order. Only the last component of
+/// the pattern may contain wildcards.
+pub(crate) fn snapshots(pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = Path::new(pattern);
+    let name = pattern
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context(format!("Invalid snapshot pattern: {}", pattern.display()))?;
+    let dir = match pattern.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if dir.to_string_lossy().contains(['*', '?']) {
+        anyhow::bail!(
+            "Only the file name may contain wildcards: {}",
+            pattern.display()
+        );
+    }
+
+    let mut files = Vec::new();
+    for entry in
+        std::fs::read_dir(dir).context(format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| glob_match(name, n))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    if files.is_empty() {
+        anyhow::bail!("No snapshots match {}", pattern.display());
+    }
+    Ok(files)
+}
+
+/// Checks the route `from → to` against `max_latency_ms` in each snapshot,
+/// given as (file, graph) in time order. A snapshot without a path
+/// violates the SLO.
+///
+/// The trend is the least-squares slope of latency over the snapshots with
+/// a path: `stable` if it adds up to less than 1ms across the series.
+pub(crate) fn evaluate(
+    snapshots: &[(PathBuf, Graph)],
+    from: &str,
+    to: &str,
+    max_latency_ms: u32,
+    constraints: &Constraints,
+) -> Result<SloHistoryOutput> {
+    let mut outputs = Vec::with_capacity(snapshots.len());
+    for (file, graph) in snapshots {
+        let latency_ms = match graph.shortest_path_with(from, to, constraints) {
+            Ok(path) => Some(path.cost),
+            Err(PathError::PathNotFound { .. }) => None,
+            Err(e) => return Err(e).context(format!("Invalid route in {}", file.display())),
+        };
+        outputs.push(SnapshotSloOutput {
+            file: file.display().to_string(),
+            latency_ms,
+            slo_met: latency_ms.is_some_and(|l| l <= max_latency_ms),
+            graph_hash: graph.content_hash().to_string(),
+        });
+    }
+
+    let points: Vec<(f64, f64)> = outputs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.latency_ms.map(|l| (i as f64, l as f64)))
+        .collect();
+    let slope = slope(&points);
+    let span = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => last.0 - first.0,
+        _ => 0.0,
+    };
+    let trend = if (slope * span).abs() < 1.0 {
+        "stable"
+    } else if slope < 0.0 {
+        "improving"
+    } else {
+        "degrading"
+    };
+
+    let violations = outputs.iter().filter(|s| !s.slo_met).count();
+    Ok(SloHistoryOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        max_latency_ms,
+        violation_rate: violations as f64 / outputs.len().max(1) as f64,
+        violations,
+        snapshots: outputs,
+        latency_slope_ms: slope,
+        trend: trend.to_string(),
+    })
+}
+
+/// Least-squares slope of `points`, or 0 with fewer than two x values.
+fn slope(points: &[(f64, f64)]) -> f64 {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if points.len() < 2 || var == 0.0 {
+        return 0.0;
+    }
+    points
+        .iter()
+        .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+        .sum::<f64>()
+        / var
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::io::GraphInput;
+
+    fn snapshot(auth_db: Option<u32>) -> Graph {
+        let mut edges = vec![r#"{"from": "api", "to": "auth", "latency_ms": 5}"#.to_string()];
+        if let Some(latency) = auth_db {
+            edges.push(format!(
+                r#"{{"from": "auth", "to": "db", "latency_ms": {}}}"#,
+                latency
+            ));
+        }
+        let input: GraphInput = serde_json::from_str(&format!(
+            r#"{{"nodes": ["api", "auth", "db"], "edges": [{}]}}"#,
+            edges.join(",")
+        ))
+        .unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    #[test]
+    fn test_degrading_history() {
+        let snapshots: Vec<(PathBuf, Graph)> = [Some(3), Some(5), None, Some(9)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, l)| (PathBuf::from(format!("day{}.json", i)), snapshot(l)))
+            .collect();
+        let output = evaluate(&snapshots, "api", "db", 12, &Constraints::default()).unwrap();
+        assert_eq!(output.snapshots[0].latency_ms, Some(8));
+        assert_eq!(output.snapshots[2].latency_ms, None);
+        // the missing path and the 14ms day both violate
+        assert_eq!(output.violations, 2);
+        assert_eq!(output.violation_rate, 0.5);
+        assert!((output.latency_slope_ms - 2.0).abs() < 1e-9);
+        assert_eq!(output.trend, "degrading");
+
+        let steady = vec![(PathBuf::from("a.json"), snapshot(Some(3)))];
+        let output = evaluate(&steady, "api", "db", 12, &Constraints::default()).unwrap();
+        assert_eq!(output.trend, "stable");
+    }
+
+    #[test]
+    fn test_snapshot_glob() {
+        let dir = std::env::temp_dir().join(format!("gt-path-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["2024-06-02.json", "2024-06-01.json", "notes.txt"] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        let files = snapshots(dir.join("2024-*.json").to_str().unwrap()).unwrap();
+        let names: Vec<_> = files.iter().map(|f| f.file_name().unwrap()).collect();
+        assert_eq!(names, vec!["2024-06-01.json", "2024-06-02.json"]);
+        assert!(snapshots(dir.join("*.yaml").to_str().unwrap()).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the watchdog checks elapsed time and memory use.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A limit that was exceeded while running a command.
+#[derive(Debug, PartialEq)]
+pub(crate) enum LimitExceeded {
+    /// The command ran longer than `--timeout`
+    Timeout(Duration),
+    /// The process grew past `--max-memory` (bytes)
+    Memory(u64),
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::Timeout(d) => write!(f, "Timed out after {:?}", d),
+            LimitExceeded::Memory(bytes) => {
+                write!(f, "Memory use exceeded the limit of {} bytes", bytes)
+            }
+        }
+    }
+}
+
+/// Wall-clock and memory limits for a single command.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Limits {
+    pub(crate) timeout: Option<Duration>,
+    /// Maximum resident set size in bytes
+    pub(crate) max_memory: Option<u64>,
+}
+
+impl Limits {
+    /// Builds limits from the raw `--timeout` and `--max-memory` values.
+    pub(crate) fn parse(timeout: Option<&str>, max_memory: Option<&str>) -> Result<Limits> {
+        let max_memory = max_memory.map(parse_size).transpose()?;
+        if max_memory.is_some() && resident_bytes().is_none() {
+            anyhow::bail!("--max-memory is not supported on this platform");
+        }
+
+        Ok(Limits {
+            timeout: timeout.map(parse_duration).transpose()?,
+            max_memory,
+        })
+    }
+
+    /// Runs `work` on a worker thread and waits for it within the limits.
+    ///
+    /// On a breach the worker is abandoned rather than interrupted; callers
+    /// are expected to exit the process right after.
+    pub(crate) fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, LimitExceeded> {
+        if self.timeout.is_none() && self.max_memory.is_none() {
+            return Ok(work());
+        }
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(work());
+        });
+
+        let start = Instant::now();
+        loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(result) => return Ok(result),
+                // the worker panicked; surface the panic on this thread
+                Err(mpsc::RecvTimeoutError::Disconnected) => panic!("command thread panicked"),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Some(timeout) = self.timeout
+                && start.elapsed() >= timeout
+            {
+                return Err(LimitExceeded::Timeout(timeout));
+            }
+            if let Some(max) = self.max_memory
+                && resident_bytes().is_some_and(|rss| rss > max)
+            {
+                return Err(LimitExceeded::Memory(max));
+            }
+        }
+    }
+}
+
+/// Parses a duration such as `30s`, `500ms`, `2m`, or `1h`. A bare number
+/// is seconds.
+pub(crate) fn parse_duration(raw: &str) -> Result<Duration> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(raw.len());
+    let (value, unit) = raw.split_at(split);
+    let value: f64 = value
+        .parse()
+        .ok()
+        .filter(|v: &f64| v.is_finite())
+        .context(format!(
+            "Invalid duration '{}'. Expected e.g. 30s or 500ms",
+            raw
+        ))?;
+
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => anyhow::bail!("Invalid duration unit '{}'. Expected ms, s, m, or h", unit),
+    };
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}': duration too large", raw))
+}
+
+/// Parses a size such as `512M`, `2G`, or `100000` (bytes). Units are
+/// binary (1K = 1024 bytes).
+pub(crate) fn parse_size(raw: &str) -> Result<u64> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (value, unit) = raw.split_at(split);
+    let value: u64 = value
+        .parse()
+        .context(format!("Invalid size '{}'. Expected e.g. 512M or 2G", raw))?;
+
+    let multiplier: u64 = match unit.to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => anyhow::bail!("Invalid size unit '{}'. Expected K, M, or G", unit),
+    };
+    value
+        .checked_mul(multiplier)
+        .context(format!("Size '{}' is too large", raw))
+}
+
+/// Current resident set size of this process, where the platform exposes it.
+fn resident_bytes() -> Option<u64> {
+    status_bytes("VmRSS:")
+}
+
+/// Peak resident set size of this process in bytes, if the platform
+/// reports it.
+pub(crate) fn peak_resident_bytes() -> Option<u64> {
+    status_bytes("VmHWM:")
+}
+
+/// Reads a memory field of `/proc/self/status`, given in kB.
+fn status_bytes(field: &str) -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with(field))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_and_size() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("1.5").unwrap(), Duration::from_millis(1500));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("1e23h").is_err());
+        let err = parse_duration("100000000000000000000000h").unwrap_err();
+        assert!(err.to_string().contains("duration too large"), "{}", err);
+
+        assert_eq!(parse_size("512M").unwrap(), 512 << 20);
+        assert_eq!(parse_size("2gb").unwrap(), 2 << 30);
+        assert_eq!(parse_size("100").unwrap(), 100);
+        assert!(parse_size("lots").is_err());
+    }
+
+    #[test]
+    fn test_run_times_out() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(50)),
+            max_memory: None,
+        };
+        let result = limits.run(|| thread::sleep(Duration::from_secs(5)));
+        assert_eq!(
+            result,
+            Err(LimitExceeded::Timeout(Duration::from_millis(50)))
+        );
+
+        assert_eq!(limits.run(|| 42), Ok(42));
+    }
+}
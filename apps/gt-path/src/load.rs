@@ -0,0 +1,519 @@
+use crate::progress;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::error::PathError;
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{
+    DemandInput, EdgeLoadOutput, LoadOutput, SharedEdgeOutput, SharedFateOutput,
+    UnroutedDemandOutput,
+};
+use gt_path_core::path::Edge;
+use gt_path_core::simulation;
+use std::collections::{BTreeSet, HashMap};
+
+/// Aggregate traffic carried by a single edge.
+pub(crate) struct EdgeLoad {
+    /// The loaded edge
+    pub(crate) edge: Edge,
+    /// Total requests per second routed over the edge
+    pub(crate) load_rps: f64,
+    /// Declared capacity in requests per second, from the `capacity` attribute
+    pub(crate) capacity: Option<f64>,
+    /// Indices of the demands routed over the edge
+    pub(crate) demands: Vec<usize>,
+    /// Latency of the edge under its load, for equilibrium assignment
+    pub(crate) loaded_latency_ms: Option<f64>,
+}
+
+impl EdgeLoad {
+    /// Load as a fraction of declared capacity, if a capacity is declared.
+    pub(crate) fn utilization(&self) -> Option<f64> {
+        self.capacity.map(|c| self.load_rps / c)
+    }
+
+    /// Returns true if the load exceeds the declared capacity.
+    pub(crate) fn over_capacity(&self) -> bool {
+        self.capacity.is_some_and(|c| self.load_rps > c)
+    }
+}
+
+/// Result of routing a set of demands through the graph.
+pub(crate) struct LoadReport {
+    /// Per-edge load, heaviest first
+    pub(crate) edges: Vec<EdgeLoad>,
+    /// Demands that could not be routed, with the reason
+    pub(crate) unrouted: Vec<(DemandInput, PathError)>,
+    /// Total requests per second of all routed demands
+    pub(crate) routed_rps: f64,
+}
+
+/// Routes every demand along its shortest path and sums the load per edge.
+///
+/// Demands between the same pair of nodes share a route. Demands whose
+/// endpoints don't exist or aren't connected are reported as unrouted
+/// instead of failing the whole analysis.
+pub(crate) fn route_demands(
+    graph: &Graph,
+    demands: &[DemandInput],
+    constraints: &Constraints,
+) -> LoadReport {
+    let mut loads: HashMap<usize, EdgeLoad> = HashMap::new();
+    let mut unrouted = Vec::new();
+    let mut routed_rps = 0.0;
+
+    let bar = progress::items(demands.len() as u64, "Routing demands");
+    for (i, demand) in bar.wrap_iter(demands.iter().enumerate()) {
+        let path = match graph.shortest_path_with(&demand.from, &demand.to, constraints) {
+            Ok(path) => path,
+            Err(e) => {
+                unrouted.push((demand.clone(), e));
+                continue;
+            }
+        };
+
+        routed_rps += demand.rps;
+        for edge in path.edges {
+            let load = loads.entry(edge.index).or_insert_with(|| EdgeLoad {
+                capacity: graph.edge_attrs[edge.index]
+                    .get("capacity")
+                    .and_then(serde_json::Value::as_f64),
+                edge,
+                load_rps: 0.0,
+                demands: Vec::new(),
+                loaded_latency_ms: None,
+            });
+            load.load_rps += demand.rps;
+            load.demands.push(i);
+        }
+    }
+
+    bar.finish_and_clear();
+
+    let mut edges: Vec<EdgeLoad> = loads.into_values().collect();
+    edges.sort_by(|a, b| {
+        b.load_rps
+            .total_cmp(&a.load_rps)
+            .then(a.edge.index.cmp(&b.edge.index))
+    });
+
+    LoadReport {
+        edges,
+        unrouted,
+        routed_rps,
+    }
+}
+
+/// Settings for equilibrium traffic assignment.
+pub(crate) struct AssignOptions {
+    /// Most rounds of routing before giving up on convergence
+    pub(crate) max_iterations: u32,
+    /// Relative gap below which the assignment has converged
+    pub(crate) gap: f64,
+}
+
+/// How close an equilibrium assignment came to converging.
+pub(crate) struct Assignment {
+    /// Rounds of routing run
+    pub(crate) iterations: u32,
+    /// Relative gap after the last round
+    pub(crate) relative_gap: f64,
+    /// Whether the gap fell below the target
+    pub(crate) converged: bool,
+}
+
+/// Highest utilization an edge is priced at, so a saturated edge is very
+/// slow rather than unusable.
+const MAX_UTILIZATION: f64 = 0.99;
+
+/// Routes every demand toward user equilibrium, where no traffic could
+/// reach its destination faster by switching routes, with the method of
+/// successive averages.
+///
+/// Each round prices every edge with a `capacity` at the latency its
+/// current load gives it, modelled as an M/M/1 queue the way
+/// `simulate --demand` does, routes every demand along its shortest path
+/// at those latencies, and moves 1/k of the traffic onto those routes in
+/// round k. It stops once the relative gap, how much less total latency
+/// the traffic would see on the current shortest paths as a fraction of
+/// what it sees now, falls below `opts.gap`.
+///
+/// Edges without a capacity keep their latency. A demand's traffic may be
+/// split across several routes, so an edge's `demands` are all those with
+/// some traffic on it.
+pub(crate) fn assign_demands(
+    graph: &Graph,
+    demands: &[DemandInput],
+    constraints: &Constraints,
+    opts: &AssignOptions,
+) -> (LoadReport, Assignment) {
+    let n = graph.edge_ids.len();
+    let mut links = vec![None; n];
+    for (u, adj) in graph.adj.iter().enumerate() {
+        for link in adj {
+            links[link.edge] = Some((u, link.to, link.latency_ms));
+        }
+    }
+    let capacity: Vec<Option<f64>> = graph
+        .edge_attrs
+        .iter()
+        .map(|attrs| {
+            attrs
+                .get("capacity")
+                .and_then(serde_json::Value::as_f64)
+                .filter(|&c| c > 0.0)
+        })
+        .collect();
+    // utilization the latencies were measured at, to recover idle latency
+    let measured: Vec<f64> = graph
+        .edge_attrs
+        .iter()
+        .map(|attrs| {
+            simulation::utilization(attrs)
+                .filter(|u| (0.0..1.0).contains(u))
+                .unwrap_or(0.0)
+        })
+        .collect();
+    let price = |flow: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|e| {
+                let latency = links[e].map_or(0.0, |(_, _, l)| l as f64);
+                match capacity[e] {
+                    Some(c) => {
+                        let load = (flow[e] / c).min(MAX_UTILIZATION);
+                        simulation::queue_latency(latency, measured[e], load).unwrap_or(latency)
+                    }
+                    None => latency,
+                }
+            })
+            .collect()
+    };
+
+    let mut priced = graph.clone();
+    let mut users: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+    let mut unrouted = Vec::new();
+    let mut flow = vec![0.0; n];
+    let mut assignment = Assignment {
+        iterations: 0,
+        relative_gap: f64::INFINITY,
+        converged: false,
+    };
+
+    let bar = progress::items(opts.max_iterations as u64, "Assigning traffic");
+    for k in 1..=opts.max_iterations {
+        let latency = price(&flow);
+        for adj in &mut priced.adj {
+            for link in adj {
+                link.latency_ms = latency[link.edge].round().min(u32::MAX as f64) as u32;
+            }
+        }
+
+        let mut target = vec![0.0; n];
+        for (i, demand) in demands.iter().enumerate() {
+            match priced.shortest_path_with(&demand.from, &demand.to, constraints) {
+                Ok(path) => {
+                    for edge in path.edges {
+                        target[edge.index] += demand.rps;
+                        users[edge.index].insert(i);
+                    }
+                }
+                // latencies never make a route impossible, so only the
+                // first round fails
+                Err(e) if k == 1 => unrouted.push((demand.clone(), e)),
+                Err(_) => {}
+            }
+        }
+        assignment.iterations = k;
+        bar.inc(1);
+
+        if k > 1 {
+            let total = |flow: &[f64]| flow.iter().zip(&latency).map(|(f, l)| f * l).sum::<f64>();
+            let current = total(&flow);
+            assignment.relative_gap = if current > 0.0 {
+                ((current - total(&target)) / current).max(0.0)
+            } else {
+                0.0
+            };
+            if assignment.relative_gap < opts.gap {
+                assignment.converged = true;
+                break;
+            }
+        }
+        for (f, t) in flow.iter_mut().zip(&target) {
+            *f += (t - *f) / k as f64;
+        }
+    }
+    bar.finish_and_clear();
+
+    let latency = price(&flow);
+    let mut edges: Vec<EdgeLoad> = (0..n)
+        .filter(|&e| flow[e] > 1e-9)
+        .filter_map(|e| {
+            let (u, to, latency_ms) = links[e]?;
+            Some(EdgeLoad {
+                edge: Edge {
+                    from: gt_path_core::graph::NodeId(u as u32),
+                    to,
+                    latency_ms,
+                    index: e,
+                },
+                load_rps: flow[e],
+                capacity: capacity[e],
+                demands: users[e].iter().copied().collect(),
+                loaded_latency_ms: Some(latency[e]),
+            })
+        })
+        .collect();
+    edges.sort_by(|a, b| {
+        b.load_rps
+            .total_cmp(&a.load_rps)
+            .then(a.edge.index.cmp(&b.edge.index))
+    });
+
+    let unrouted_rps: f64 = unrouted.iter().map(|(d, _)| d.rps).sum();
+    let report = LoadReport {
+        edges,
+        routed_rps: demands.iter().map(|d| d.rps).sum::<f64>() - unrouted_rps,
+        unrouted,
+    };
+    (report, assignment)
+}
+
+impl LoadReport {
+    /// Converts the report to its JSON output form with node names.
+    pub(crate) fn output(&self, graph: &Graph) -> LoadOutput {
+        LoadOutput {
+            routed_rps: self.routed_rps,
+            over_capacity: self.edges.iter().filter(|e| e.over_capacity()).count(),
+            edges: self
+                .edges
+                .iter()
+                .map(|e| EdgeLoadOutput {
+                    id: graph.edge_ids[e.edge.index].clone(),
+                    from: graph.to_name[e.edge.from.0 as usize].clone(),
+                    to: graph.to_name[e.edge.to.0 as usize].clone(),
+                    load_rps: e.load_rps,
+                    capacity_rps: e.capacity,
+                    utilization: e.utilization(),
+                    over_capacity: e.over_capacity(),
+                    loaded_latency_ms: e.loaded_latency_ms,
+                })
+                .collect(),
+            unrouted: self
+                .unrouted
+                .iter()
+                .map(|(d, e)| UnroutedDemandOutput {
+                    from: d.from.clone(),
+                    to: d.to.clone(),
+                    rps: d.rps,
+                    reason: e.to_string(),
+                })
+                .collect(),
+            graph_hash: graph.content_hash().to_string(),
+            assignment: None,
+        }
+    }
+
+    /// Ranks the loaded edges by how many routed demands share them, and
+    /// counts for each the demands its failure would leave with no path at
+    /// all, rerouting them the way `route_demands` routed them.
+    ///
+    /// Ties go to the edge stranding more demands, then to the heavier one.
+    pub(crate) fn shared_fate(
+        &self,
+        graph: &Graph,
+        demands: &[DemandInput],
+        constraints: &Constraints,
+    ) -> SharedFateOutput {
+        let routed = demands.len() - self.unrouted.len();
+        let mut down = vec![false; graph.edge_ids.len()];
+
+        let bar = progress::items(self.edges.len() as u64, "Failing shared edges");
+        let mut edges: Vec<(&EdgeLoad, usize)> = bar
+            .wrap_iter(self.edges.iter())
+            .map(|e| {
+                down[e.edge.index] = true;
+                let stranded = e
+                    .demands
+                    .iter()
+                    .filter(|&&i| {
+                        let d = &demands[i];
+                        graph
+                            .shortest_path_without(&d.from, &d.to, constraints, &down)
+                            .is_err()
+                    })
+                    .count();
+                down[e.edge.index] = false;
+                (e, stranded)
+            })
+            .collect();
+        bar.finish_and_clear();
+
+        edges.sort_by(|(a, a_stranded), (b, b_stranded)| {
+            b.demands
+                .len()
+                .cmp(&a.demands.len())
+                .then(b_stranded.cmp(a_stranded))
+                .then(b.load_rps.total_cmp(&a.load_rps))
+                .then(a.edge.index.cmp(&b.edge.index))
+        });
+
+        let load = self.output(graph);
+        SharedFateOutput {
+            routed,
+            edges: edges
+                .into_iter()
+                .map(|(e, stranded)| SharedEdgeOutput {
+                    id: graph.edge_ids[e.edge.index].clone(),
+                    from: graph.to_name[e.edge.from.0 as usize].clone(),
+                    to: graph.to_name[e.edge.to.0 as usize].clone(),
+                    routes: e.demands.len(),
+                    share: e.demands.len() as f64 / routed as f64,
+                    stranded,
+                    load_rps: e.load_rps,
+                    demands: e
+                        .demands
+                        .iter()
+                        .map(|&i| (demands[i].from.clone(), demands[i].to.clone()))
+                        .collect(),
+                })
+                .collect(),
+            unrouted: load.unrouted,
+            graph_hash: load.graph_hash,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demand(from: &str, to: &str, rps: f64) -> DemandInput {
+        DemandInput {
+            from: from.to_string(),
+            to: to.to_string(),
+            rps,
+        }
+    }
+
+    #[test]
+    fn test_aggregates_load_per_edge() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let demands = vec![
+            demand("api", "db", 600.0),
+            demand("api", "auth", 500.0),
+            demand("cache", "db", 50.0),
+        ];
+
+        let report = route_demands(&graph, &demands, &Constraints::default());
+        assert_eq!(report.routed_rps, 1150.0);
+        assert!(report.unrouted.is_empty());
+
+        // api→auth carries both api→db and api→auth traffic
+        let heaviest = &report.edges[0];
+        assert_eq!(&*graph.to_name[heaviest.edge.from.0 as usize], "api");
+        assert_eq!(&*graph.to_name[heaviest.edge.to.0 as usize], "auth");
+        assert_eq!(heaviest.load_rps, 1100.0);
+        assert_eq!(heaviest.capacity, Some(1000.0));
+        assert!(heaviest.over_capacity());
+
+        // edges without a declared capacity are never over capacity
+        assert!(report.edges[1..].iter().all(|e| !e.over_capacity()));
+    }
+
+    #[test]
+    fn test_shared_fate() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let demands = vec![
+            demand("api", "db", 600.0),
+            demand("api", "auth", 500.0),
+            demand("cache", "db", 50.0),
+            demand("db", "api", 10.0),
+        ];
+
+        let constraints = Constraints::default();
+        let report = route_demands(&graph, &demands, &constraints);
+        let output = report.shared_fate(&graph, &demands, &constraints);
+        assert_eq!(output.routed, 3);
+        assert_eq!(output.unrouted.len(), 1);
+
+        let ranked: Vec<_> = output
+            .edges
+            .iter()
+            .map(|e| (&*e.from, &*e.to, e.routes, e.stranded))
+            .collect();
+        // api→auth is shared by two routes, but only api→auth is stranded
+        // without it: api→db can detour via cache
+        assert_eq!(
+            ranked,
+            vec![
+                ("api", "auth", 2, 1),
+                ("cache", "db", 1, 1),
+                ("auth", "db", 1, 0)
+            ]
+        );
+        assert!((output.edges[0].share - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            output.edges[0].demands,
+            vec![
+                ("api".to_string(), "db".to_string()),
+                ("api".to_string(), "auth".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_equilibrium_assignment() {
+        // a fast direct link too small for the demand, and a slower detour
+        // with room to spare
+        let input: gt_path_core::io::GraphInput = serde_json::from_str(
+            r#"{"nodes": ["api", "cache", "db"], "edges": [
+                {"from": "api", "to": "db", "latency_ms": 10, "capacity": 100},
+                {"from": "api", "to": "cache", "latency_ms": 6, "capacity": 1000},
+                {"from": "cache", "to": "db", "latency_ms": 6, "capacity": 1000}
+            ]}"#,
+        )
+        .unwrap();
+        let graph = Graph::try_from(input).unwrap();
+        let demands = vec![demand("api", "db", 90.0)];
+        let opts = AssignOptions {
+            max_iterations: 200,
+            gap: 0.01,
+        };
+
+        let (report, assignment) = assign_demands(&graph, &demands, &Constraints::default(), &opts);
+        assert!(assignment.converged);
+        assert!(assignment.iterations > 1);
+        assert_eq!(report.routed_rps, 90.0);
+
+        let load = |from: &str, to: &str| {
+            report
+                .edges
+                .iter()
+                .find(|e| {
+                    &*graph.to_name[e.edge.from.0 as usize] == from
+                        && &*graph.to_name[e.edge.to.0 as usize] == to
+                })
+                .unwrap()
+        };
+        // all-or-nothing would put all 90 rps on the direct link
+        let direct = load("api", "db");
+        let detour = load("api", "cache");
+        assert!(direct.load_rps < 40.0, "{}", direct.load_rps);
+        assert!((direct.load_rps + detour.load_rps - 90.0).abs() < 1e-6);
+        // both routes end up about as fast
+        let via_cache =
+            detour.loaded_latency_ms.unwrap() + load("cache", "db").loaded_latency_ms.unwrap();
+        assert!((direct.loaded_latency_ms.unwrap() - via_cache).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_reports_unrouted_demands() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let demands = vec![demand("db", "api", 10.0), demand("api", "nowhere", 5.0)];
+
+        let report = route_demands(&graph, &demands, &Constraints::default());
+        assert_eq!(report.routed_rps, 0.0);
+        assert!(report.edges.is_empty());
+        assert_eq!(report.unrouted.len(), 2);
+        assert!(matches!(report.unrouted[1].1, PathError::NodeNotFound(_)));
+    }
+}
@@ -1,11 +1,40 @@
-mod error;
-mod graph;
-mod io;
-mod path;
+mod access;
+mod anonymize;
+mod baseline;
+mod budget;
+mod chaos;
+mod compare;
+mod daemon;
+mod history;
+mod limits;
+mod listing;
+mod load;
+mod openmetrics;
+mod optimize;
+mod pattern;
+mod progress;
+mod prom;
+mod reduce;
+mod replay;
+mod scenarios;
+mod stability;
+mod stretch;
+mod telemetry;
+mod terraform;
+mod tour;
+mod units;
+mod watch;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use gt_path_core::{
+    CostExpr, DiskCache, Modifications, PathCache, Simulation, SloCheck, backbone, compose,
+    constraint, graph, io, path, predicate::Predicate, time,
+};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::{Mutex, OnceLock};
 
 /// Graph path analyzer - find shortest paths and bottlenecks in network graphs
 #[derive(Parser)]
@@ -14,386 +43,4126 @@ use std::process;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Suppress progress bars and text reports; JSON is printed compactly
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Abort if the command runs longer than this (e.g., 30s, 500ms, 2m)
+    #[arg(long, global = true, value_name = "DURATION")]
+    timeout: Option<String>,
+
+    /// Abort if resident memory exceeds this (e.g., 512M, 2G; Linux only)
+    #[arg(long, global = true, value_name = "SIZE")]
+    max_memory: Option<String>,
+
+    /// Reuse parsed graphs and landmark tables saved in this directory,
+    /// keyed by content hash
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<String>,
+
+    /// Add a `stats` block to JSON output: searches, nodes visited, edges
+    /// relaxed, heap pushes, wall time, and peak memory
+    #[arg(long, global = true)]
+    stats: bool,
+
+    /// Export spans for the load, parse, and query phases to this OTLP/HTTP
+    /// collector (e.g., http://localhost:4318)
+    #[arg(long, global = true, value_name = "URL")]
+    otel_endpoint: Option<String>,
+
+    /// Answer `path` and `slo` queries from a running `gt-path daemon`
+    /// (default socket if no path is given), falling back to loading the
+    /// graph locally if it can't be reached
+    #[arg(long, global = true, value_name = "SOCKET", num_args = 0..=1)]
+    use_daemon: Option<Option<PathBuf>>,
+
+    /// Rename nodes on load from a CSV file of `old,new` lines, e.g. IP
+    /// addresses to service names
+    #[arg(long, global = true, value_name = "FILE")]
+    node_map: Option<String>,
+
+    /// Route by a per-edge cost computed on load instead of latency, e.g.
+    /// 'latency_ms + 10*hops + 0.01*cost_usd'; other names read edge attributes
+    #[arg(long, global = true, value_name = "EXPR")]
+    cost_expr: Option<String>,
+
+    /// Drop edges faster than this many milliseconds when loading the graph
+    #[arg(long, global = true, value_name = "MS")]
+    min_weight: Option<f32>,
+
+    /// Drop edges slower than this many milliseconds when loading the graph
+    #[arg(long, global = true, value_name = "MS")]
+    max_weight: Option<f32>,
+
+    /// Show latencies in text output with a scaled unit (`1.2 s`, `350 ms`,
+    /// `80 µs`); JSON keeps raw milliseconds
+    #[arg(long, global = true)]
+    human: bool,
+
+    /// Show at most this many rows of each list in text output (0 for
+    /// all); the default is 50 on a terminal and all otherwise
+    #[arg(long, global = true, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Print only counts and totals in text output, without lists
+    #[arg(long, global = true)]
+    summary_only: bool,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Find shortest path between two nodes
+    Path(PathArgs),
+
+    /// Check if path meets SLO (Service Level Objective)
+    Slo(SloArgs),
+
+    /// Evaluate an SLO across a time-ordered series of graph snapshots
+    SloHistory(SloHistoryArgs),
+
+    /// Simulate path changes with modified edge weights
+    Simulate(SimulateArgs),
+
+    /// Anonymize node labels (and optionally latencies) for sharing
+    Anonymize(AnonymizeArgs),
+
+    /// Route traffic demands along shortest paths and report per-edge load
+    Load(LoadArgs),
+
+    /// Rank edges by how many demand routes share them
+    SharedFate(SharedFateArgs),
+
+    /// Compare the minimum spanning tree with the shortest-path tree from a root
+    Backbone(BackboneArgs),
+
+    /// Measure how much longer routes get using only a subset of edges
+    Stretch(StretchArgs),
+
+    /// Latency between every pair of nodes, exact or estimated from landmarks
+    Matrix(MatrixArgs),
+
+    /// Split an end-to-end latency budget across the hops of a path
+    Budget(BudgetArgs),
+
+    /// Estimate end-to-end availability, optionally across redundant paths
+    Availability(AvailabilityArgs),
+
+    /// Estimate how often random edge failures break a route's SLO
+    Chaos(ChaosArgs),
+
+    /// Measure how often a route's shortest path changes under latency noise
+    Stability(StabilityArgs),
+
+    /// Propose edges to add from a candidate pool, within a budget
+    Optimize(OptimizeArgs),
+
+    /// Find a short order for visiting a set of nodes
+    Tour(TourArgs),
+
+    /// Find occurrences of a small subgraph pattern
+    FindPattern(FindPatternArgs),
+
+    /// Score how structurally similar two graphs are
+    Compare(CompareArgs),
+
+    /// Remove edges implied by other paths (transitive reduction of a DAG)
+    Reduce(ReduceArgs),
+
+    /// Refresh edge latencies in a graph from an external metrics source
+    #[command(subcommand)]
+    Import(ImportSource),
+
+    /// Keep graphs in memory and answer queries from `--use-daemon` clients
+    Daemon(DaemonArgs),
+
+    /// Re-run queries recorded by `daemon --record` against another graph and diff the answers
+    Replay(ReplayArgs),
+}
+
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Fill edge latencies from a Prometheus instant query
+    Prom(PromImportArgs),
+
+    /// Convert `terraform graph` DOT output into a graph file
+    Terraform(TerraformImportArgs),
+}
+
+#[derive(Args)]
+struct PathArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Only use the edges of a minimum spanning tree of the graph, with
+    /// edge direction ignored, e.g. to price a planned backbone tree
+    #[arg(long, conflicts_with = "restrict_to")]
+    restrict_to_mst: bool,
+
+    /// Only use the edges in this file: a CSV list of `from,to` node pairs,
+    /// or the spanning tree in `gt-connect analyze --format json` output
+    /// for the same graph
+    #[arg(long, value_name = "FILE")]
+    restrict_to: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: MetricsFormat,
+}
+
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("client").args(["status", "upload", "probe"]))]
+struct DaemonArgs {
+    /// Unix socket to listen on (default: gt-path.sock in $XDG_RUNTIME_DIR or
+    /// the temp directory)
+    #[arg(long)]
+    socket: Option<PathBuf>,
+
+    /// Append every query and its answer to this JSONL file, for `replay`
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Answers to keep cached, keyed by graph version, route, and filters
+    /// (0 to disable)
+    #[arg(long, value_name = "N", default_value_t = 1024)]
+    cache_size: usize,
+
+    /// Recompute cached answers older than this (e.g., 5s, 1m)
+    #[arg(long, value_name = "DURATION", default_value = "60s")]
+    cache_ttl: String,
+
+    /// Only answer clients that send one of the tokens in this file, one per
+    /// line (clients read theirs from GT_PATH_DAEMON_TOKEN)
+    #[arg(long, value_name = "FILE", conflicts_with = "status")]
+    token_file: Option<PathBuf>,
+
+    /// Directory of graph files that queries may name without a path, as
+    /// `-g NAME` for NAME.json
+    #[arg(long, value_name = "DIR", conflicts_with = "status")]
+    graph_dir: Option<PathBuf>,
+
+    /// Log every message, who sent it, and how it was answered to this file
+    /// as JSON lines (`-` for stdout)
+    #[arg(long, value_name = "FILE", conflicts_with = "client")]
+    access_log: Option<PathBuf>,
+
+    /// Let clients upload graphs into --graph-dir with `daemon --upload`
+    #[arg(long, requires = "graph_dir")]
+    allow_upload: bool,
+
+    /// Load this graph, a file or a name in --graph-dir, at startup; the
+    /// daemon isn't ready until every one has loaded (repeatable)
+    #[arg(long, value_name = "GRAPH", conflicts_with = "client")]
+    preload: Vec<String>,
+
+    /// On SIGTERM or SIGINT, wait this long for open connections to finish
+    /// (e.g., 10s, 1m)
+    #[arg(long, value_name = "DURATION", default_value = "10s")]
+    shutdown_timeout: String,
+
+    /// How often to re-check routes that clients subscribed to, pushing
+    /// the ones whose path or SLO status changed (e.g., 1s, 30s)
+    #[arg(long, value_name = "DURATION", default_value = "1s")]
+    poll_interval: String,
+
+    /// Queries per second to answer per client token, in bursts of up to N
+    /// (each query in a batch counts); requires --token-file to tell
+    /// clients apart
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "token_file"
+    )]
+    rate_limit: Option<u32>,
+
+    /// Print the counters of the daemon already listening on the socket
+    /// instead of starting one
+    #[arg(long, conflicts_with = "record")]
+    status: bool,
+
+    /// Print the JSON Schema of the daemon's protocol, for generating
+    /// clients, instead of starting one
+    #[arg(long, conflicts_with_all = ["status", "record", "token_file"])]
+    schema: bool,
+
+    /// Upload this graph file to the daemon already listening on the socket,
+    /// replacing the graph of the same name once it passes validation
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["status", "schema", "record"])]
+    upload: Option<PathBuf>,
+
+    /// Name to serve the uploaded graph under (default: the file name
+    /// without .json)
+    #[arg(long, requires = "upload")]
+    name: Option<String>,
+
+    /// Check the daemon already listening on the socket, failing if it
+    /// isn't live or ready, for liveness and readiness probes
+    #[arg(long, value_enum, conflicts_with_all = ["schema", "record"])]
+    probe: Option<Probe>,
+
+    /// Output format for --status, --upload, and --probe; openmetrics is
+    /// only for --status
+    #[arg(long, value_enum, default_value = "text", requires = "client")]
+    format: MetricsFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Probe {
+    /// The daemon answers
+    Live,
+    /// The daemon answers and has preloaded its graphs
+    Ready,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    /// Session file written by `daemon --record`
+    session: String,
+
+    /// Graph JSON file to answer the queries from
+    #[arg(short, long)]
+    graph: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct SloArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long, required_unless_present = "routes")]
+    from: Option<String>,
+
+    /// Destination node name
+    #[arg(short, long, required_unless_present = "routes")]
+    to: Option<String>,
+
+    /// Check several routes in one run: FROM:TO (repeatable, replaces --from/--to)
+    #[arg(long = "route", value_name = "FROM:TO", conflicts_with_all = ["from", "to"])]
+    routes: Vec<String>,
+
+    /// Stop at the first route that misses the SLO or has no path
+    #[arg(long, requires = "routes")]
+    fail_fast: bool,
+
+    /// Keep re-checking at this interval (e.g., 30s), reloading the graph
+    /// when it changes, and report status changes
+    #[arg(long, value_name = "INTERVAL", conflicts_with = "fail_fast")]
+    watch: Option<String>,
+
+    /// With --watch, run this command when a route starts failing, with
+    /// {route}, {from}, {to}, {latency}, and {max_latency} filled in; an
+    /// http:// URL is posted the check as JSON instead
+    #[arg(long, value_name = "CMD|URL", requires = "watch")]
+    on_violation: Option<String>,
+
+    /// Fail (exit code 7) if a route is slower than in this earlier output
+    #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+    baseline: Option<String>,
+
+    /// Write the latencies to the --baseline file instead of comparing
+    #[arg(long, requires = "baseline", conflicts_with = "fail_fast")]
+    update_baseline: bool,
+
+    /// Maximum allowed latency in milliseconds
+    #[arg(short, long)]
+    max_latency: u32,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: MetricsFormat,
+}
+
+#[derive(Args)]
+struct SloHistoryArgs {
+    /// Snapshot graph files, e.g. 'snapshots/*.json'; taken in file name order
+    #[arg(long, value_name = "PATTERN")]
+    glob: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// Maximum allowed latency in milliseconds
+    #[arg(short, long)]
+    max_latency: u32,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct SimulateArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long, required_unless_present = "routes")]
+    from: Option<String>,
+
+    /// Destination node name
+    #[arg(short, long, required_unless_present = "routes")]
+    to: Option<String>,
+
+    /// Compare several routes: FROM:TO (repeatable, replaces --from/--to;
+    /// needs --scenarios)
+    #[arg(
+        long = "route",
+        value_name = "FROM:TO",
+        conflicts_with_all = ["from", "to"],
+        requires = "scenarios"
+    )]
+    routes: Vec<String>,
+
+    /// Compare the named scenarios in this YAML or JSON file side by side
+    /// instead of applying --override/--drop
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["overrides", "drop", "create_missing", "save_modified", "demand"])]
+    scenarios: Option<String>,
+
+    /// With --scenarios, add the unmodified graph as the first column and
+    /// show each scenario's change from it
+    #[arg(long, requires = "scenarios")]
+    baseline: bool,
+
+    /// With --scenarios, mark whether each route meets this latency SLO (ms)
+    #[arg(short, long, requires = "scenarios")]
+    max_latency: Option<u32>,
+
+    /// Override edge weights: from:to:weight (e.g., "api:auth:100"), or
+    /// id:weight for one edge by ID. Nodes may be glob patterns and weights
+    /// relative: "eu-*:us-*:+30", "api:auth:+20%", "*:db:*1.5"
+    #[arg(long = "override", value_delimiter = ',')]
+    overrides: Vec<String>,
+
+    /// Drop edges: from:to (e.g., "api:cache"), or an edge ID
+    #[arg(long, value_delimiter = ',')]
+    drop: Vec<String>,
+
+    /// Add the edge for an override that names one the graph doesn't have,
+    /// instead of failing
+    #[arg(long)]
+    create_missing: bool,
+
+    /// Change the traffic on every edge, e.g. "+20%" or "*1.5", inflating
+    /// the latency of edges with a `utilization` (or `load_rps` and
+    /// `capacity`) as an M/M/1 queue would; saturated edges are dropped
+    #[arg(long, value_name = "CHANGE", allow_hyphen_values = true)]
+    demand: Option<String>,
+
+    /// Write the modified graph to this JSON file
+    #[arg(long)]
+    save_modified: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct AnonymizeArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// How node labels are rewritten
+    #[arg(long, value_enum, default_value = "hash")]
+    mode: AnonymizeMode,
+
+    /// Salt mixed into hashed labels
+    #[arg(long, default_value = "")]
+    salt: String,
+
+    /// Write the original-to-anonymized label mapping to this JSON file
+    #[arg(long)]
+    mapping: Option<String>,
+
+    /// Randomly perturb each latency by up to this percentage (e.g., 10 for ±10%)
+    #[arg(long)]
+    perturb: Option<f32>,
+
+    /// Seed for latency perturbation
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Run again with the same seed and fail (exit code 9) unless the results
+    /// match; needs an explicit --seed
+    #[arg(long, requires = "seed")]
+    verify: bool,
+
+    /// Output file for the anonymized graph (defaults to stdout)
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Args)]
+struct FindPatternArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Path to pattern JSON file (nodes and from/to edges)
+    #[arg(short, long)]
+    pattern: String,
+
+    /// Stop after this many matches
+    #[arg(long, default_value = "1000")]
+    limit: usize,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    /// First graph JSON file
+    a: String,
+
+    /// Second graph JSON file
+    b: String,
+
+    /// Score to compute
+    #[arg(long, value_enum, default_value = "all")]
+    metric: CompareMetric,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CompareMetric {
+    /// Node and edge Jaccard similarity
+    Jaccard,
+    /// Distance between degree distributions
+    Degree,
+    /// Approximate graph edit distance
+    Ged,
+    /// Every score
+    All,
+}
+
+#[derive(Args)]
+struct ReduceArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Output file for the reduced graph (defaults to stdout)
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Args)]
+struct LoadArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Path to demands JSON file
+    #[arg(short, long)]
+    demands: String,
+
+    /// How to spread demands over routes
+    #[arg(long, value_enum, default_value = "shortest")]
+    assign: AssignMethod,
+
+    /// Most rounds of rerouting for --assign equilibrium
+    #[arg(long, default_value = "50", value_parser = clap::value_parser!(u32).range(1..))]
+    max_iterations: u32,
+
+    /// Relative gap at which --assign equilibrium has converged
+    #[arg(long, default_value = "0.001")]
+    gap: f64,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AssignMethod {
+    /// Each demand takes its shortest path at the graph's latencies
+    Shortest,
+    /// Reroute demands as congestion slows loaded edges, until no demand
+    /// has a faster route
+    Equilibrium,
+}
+
+#[derive(Args)]
+struct SharedFateArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Path to demands JSON file
+    #[arg(short, long)]
+    demands: String,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct BackboneArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Node to root the shortest-path tree at
+    #[arg(short, long)]
+    root: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: MetricsFormat,
+}
+
+#[derive(Args)]
+struct StretchArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Edges to keep: a CSV list of `from,to` node pairs, or the spanning
+    /// tree in `gt-connect analyze --format json` output
+    #[arg(short, long, value_name = "FILE")]
+    subset: String,
+
+    /// Measure from this many randomly chosen nodes instead of all of them
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Seed for choosing sampled nodes
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Fail (exit code 3) unless every pair stays connected within this
+    /// stretch, e.g. 1.5
+    #[arg(long, value_name = "FACTOR")]
+    max_stretch: Option<f64>,
+
+    /// Number of most stretched pairs to list
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: MetricsFormat,
+}
+
+#[derive(Args)]
+struct MatrixArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Nodes to include, in order (default: every node)
+    #[arg(short, long, value_delimiter = ',')]
+    nodes: Vec<String>,
+
+    /// Estimate latencies from this many landmarks instead of exact all-pairs
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    landmarks: Option<u32>,
+
+    /// Largest relative error allowed for an estimate (e.g., 0.1 for 10%)
+    #[arg(long, default_value_t = 0.1, requires = "landmarks")]
+    max_error: f64,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct BudgetArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// End-to-end latency budget in milliseconds
+    #[arg(short, long)]
+    max_latency: u32,
+
+    /// How the budget is split across hops
+    #[arg(long, value_enum, default_value = "proportional")]
+    policy: BudgetPolicyArg,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct AvailabilityArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// Use up to this many node-disjoint paths
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    redundancy: u32,
+
+    /// Fail (exit code 3) if the route may be less available than this (e.g., 0.999)
+    #[arg(long)]
+    min_availability: Option<f64>,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ChaosArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// Maximum allowed latency in milliseconds
+    #[arg(short, long)]
+    max_latency: u32,
+
+    /// Number of simulated periods
+    #[arg(long, default_value_t = 10_000)]
+    trials: u32,
+
+    /// Seed for the random failure draws
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Run again with the same seed and fail (exit code 9) unless the results
+    /// match; needs an explicit --seed
+    #[arg(long, requires = "seed")]
+    verify: bool,
+
+    /// Failure probability per period for edges without a failure_rate attribute
+    #[arg(long, default_value_t = 0.0)]
+    default_failure_rate: f64,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct StabilityArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Source node name
+    #[arg(short, long)]
+    from: String,
+
+    /// Destination node name
+    #[arg(short, long)]
+    to: String,
+
+    /// Largest change to each edge latency, in percent (e.g., 10 for ±10%)
+    #[arg(long, default_value_t = 10.0)]
+    noise: f64,
+
+    /// Number of perturbed graphs to route on
+    #[arg(long, default_value_t = 1000)]
+    trials: u32,
+
+    /// Seed for the random perturbations
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Run again with the same seed and fail (exit code 9) unless the results
+    /// match; needs an explicit --seed
+    #[arg(long, requires = "seed")]
+    verify: bool,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct OptimizeArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Path to candidate edge pool JSON file
+    #[arg(short, long)]
+    candidates: String,
+
+    /// What to minimize
+    #[arg(long, value_enum, default_value = "max-latency")]
+    objective: OptimizeObjective,
+
+    /// Key route scored by max-latency: FROM:TO (repeatable)
+    #[arg(long = "route", value_name = "FROM:TO")]
+    routes: Vec<String>,
+
+    /// Most the added edges may cost in total
+    #[arg(long)]
+    budget: f64,
+
+    /// Number of annealing steps
+    #[arg(long, default_value_t = 2000)]
+    iterations: u32,
+
+    /// Seed for the random search
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Run again with the same seed and fail (exit code 9) unless the results
+    /// match; needs an explicit --seed
+    #[arg(long, requires = "seed")]
+    verify: bool,
+
+    /// Write the graph with the proposed edges added to this JSON file
+    #[arg(short, long)]
+    output: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OptimizeObjective {
+    /// Highest latency over the --route pairs
+    MaxLatency,
+    /// Number of bridges in the graph read as undirected
+    Bridges,
+}
+
+#[derive(Args)]
+struct TourArgs {
+    /// Path to graph JSON file
+    #[arg(short, long)]
+    graph: String,
+
+    /// Nodes to visit, starting with the first (e.g., "a,b,c,d")
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    nodes: Vec<String>,
+
+    /// Return to the starting node at the end
+    #[arg(long)]
+    round_trip: bool,
+
+    /// Evaluate time-varying latencies at this timestamp (e.g., 2024-06-01T14:00Z)
+    #[arg(long)]
+    at: Option<String>,
+
+    #[command(flatten)]
+    constraints: ConstraintArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct PromImportArgs {
+    /// Path to graph JSON file providing the topology
+    #[arg(short, long)]
+    graph: String,
+
+    /// PromQL query returning one series per edge (e.g., a histogram_quantile)
+    #[arg(short, long)]
+    query: String,
+
+    /// YAML file mapping series labels to edge endpoints
+    #[arg(short, long)]
+    map: String,
+
+    /// Prometheus base URL
+    #[arg(long, default_value = "http://localhost:9090")]
+    url: String,
+
+    /// Read a saved /api/v1/query response from this file instead of querying the server
+    #[arg(long)]
+    response: Option<String>,
+
+    /// Output file for the updated graph (defaults to stdout)
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Args)]
+struct TerraformImportArgs {
+    /// Path to the DOT file written by `terraform graph`
+    #[arg(short, long)]
+    dot: String,
+
+    /// Output file for the graph (defaults to stdout)
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BudgetPolicyArg {
+    /// Share proportional to each hop's current latency
+    Proportional,
+    /// Same share for every hop
+    Equal,
+}
+
+/// Path search restrictions shared by the path-finding subcommands
+#[derive(Args, Clone, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+struct ConstraintArgs {
+    /// Avoid nodes with a matching attribute: key=value (e.g., "tier=2")
+    #[arg(long = "avoid-node-attr")]
+    avoid_node_attr: Vec<String>,
+
+    /// Avoid nodes in a region (shorthand for --avoid-node-attr region=REGION)
+    #[arg(long)]
+    avoid_region: Vec<String>,
+
+    /// Avoid edges with a matching attribute: key=value (e.g., "provider=acme")
+    #[arg(long = "avoid-edge-attr")]
+    avoid_edge_attr: Vec<String>,
+
+    /// Avoid the edge with this ID (as shown in JSON output)
+    #[arg(long = "avoid-edge", value_name = "ID")]
+    #[serde(default)]
+    avoid_edge: Vec<String>,
+
+    /// Only use nodes and edges whose attributes satisfy a filter, e.g.
+    /// 'region != "eu-west" && tier >= 2'; `node.` and `edge.` scope a name
+    #[arg(long = "where", value_name = "EXPR")]
+    #[serde(default, rename = "where")]
+    where_expr: Option<String>,
+}
+
+impl ConstraintArgs {
+    fn parse(&self) -> Result<constraint::Constraints> {
+        let mut avoid_nodes = Vec::new();
+        for raw in &self.avoid_node_attr {
+            avoid_nodes.push(constraint::AttrFilter::parse(raw)?);
+        }
+        for region in &self.avoid_region {
+            avoid_nodes.push(constraint::AttrFilter {
+                key: "region".to_string(),
+                value: region.clone(),
+            });
+        }
+
+        let mut avoid_edges = Vec::new();
+        for raw in &self.avoid_edge_attr {
+            avoid_edges.push(constraint::AttrFilter::parse(raw)?);
+        }
+
+        Ok(constraint::Constraints {
+            avoid_nodes,
+            avoid_edges,
+            avoid_edge_ids: self.avoid_edge.clone(),
+            filter: self
+                .where_expr
+                .as_deref()
+                .map(|expr| Predicate::parse(expr).context(format!("Invalid --where '{}'", expr)))
+                .transpose()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AnonymizeMode {
+    /// Salted SHA-256 digest of the label
+    Hash,
+    /// node-0, node-1, ... in declaration order
+    Sequential,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text output
+    Text,
+    /// JSON output for scripting
+    Json,
+}
+
+/// `OutputFormat` for commands that can also print their results as metrics.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MetricsFormat {
+    /// Human-readable text output
+    Text,
+    /// JSON output for scripting
+    Json,
+    /// OpenMetrics text for node_exporter's textfile collector
+    #[value(name = "openmetrics")]
+    OpenMetrics,
+}
+
+// Exit codes from spec
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_NO_PATH: i32 = 2;
+const EXIT_SLO_VIOLATED: i32 = 3;
+const EXIT_INVALID_INPUT: i32 = 4;
+const EXIT_TIMEOUT: i32 = 5;
+const EXIT_RESOURCE_LIMIT: i32 = 6;
+const EXIT_REGRESSED: i32 = 7;
+const EXIT_ANSWERS_CHANGED: i32 = 8;
+const EXIT_NOT_REPRODUCIBLE: i32 = 9;
+
+fn main() {
+    let cli = Cli::parse();
+    // clap can't make an option conflict with one of another's values
+    if let Commands::Daemon(args) = &cli.command
+        && args.format == MetricsFormat::OpenMetrics
+        && (args.upload.is_some() || args.probe.is_some())
+    {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--format openmetrics can only be used with --status",
+            )
+            .exit();
+    }
+    progress::set_quiet(cli.quiet);
+    units::set_human(cli.human);
+    listing::init(cli.limit, cli.summary_only);
+    if let Some(dir) = &cli.cache_dir {
+        let _ = CACHE.set(DiskCache::new(dir));
+    }
+    if let Some(socket) = cli.use_daemon {
+        let _ = DAEMON.set(socket.unwrap_or_else(daemon::default_socket));
+    }
+    if let Some(file) = cli.node_map {
+        let _ = NODE_MAP.set(file);
+    }
+    if let Some(expr) = cli.cost_expr {
+        let _ = COST_EXPR.set(expr);
+    }
+    if cli.min_weight.is_some() || cli.max_weight.is_some() {
+        let _ = WEIGHT_RANGE.set((cli.min_weight, cli.max_weight));
+    }
+    if cli.stats {
+        let _ = STATS.set((
+            std::time::Instant::now(),
+            gt_path_core::SearchStats::total(),
+        ));
+    }
+    if let Some(endpoint) = &cli.otel_endpoint {
+        telemetry::enable(endpoint);
+    }
+    let mut root = telemetry::root("gt-path");
+    root.set(
+        "process.command_args",
+        std::env::args().collect::<Vec<_>>().join(" "),
+    );
+
+    let (result, exit_code) =
+        match limits::Limits::parse(cli.timeout.as_deref(), cli.max_memory.as_deref()) {
+            Ok(limits) => {
+                let command = cli.command;
+                match limits.run(move || run_command(&command)) {
+                    Ok(outcome) => outcome,
+                    Err(e @ limits::LimitExceeded::Timeout(_)) => {
+                        (Err(anyhow::anyhow!(e)), EXIT_TIMEOUT)
+                    }
+                    Err(e @ limits::LimitExceeded::Memory(_)) => {
+                        (Err(anyhow::anyhow!(e)), EXIT_RESOURCE_LIMIT)
+                    }
+                }
+            }
+            Err(e) => (Err(e), EXIT_INVALID_INPUT),
+        };
+
+    root.set("process.exit_code", exit_code);
+    drop(root);
+    if let Err(e) = telemetry::export()
+        && !progress::quiet()
+    {
+        eprintln!("Warning: {:#}", e);
+    }
+
+    match result {
+        Ok(()) => process::exit(exit_code),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+
+            // commands that know why they failed report their own code
+            let exit_code = if exit_code != EXIT_SUCCESS {
+                exit_code
+            } else if e.is::<NotReproducible>() {
+                EXIT_NOT_REPRODUCIBLE
+            } else if e.to_string().contains("No path") || e.to_string().contains("PathNotFound") {
+                EXIT_NO_PATH
+            } else {
+                EXIT_INVALID_INPUT
+            };
+
+            process::exit(exit_code);
+        }
+    }
+}
+
+fn run_command(command: &Commands) -> (Result<()>, i32) {
+    match command {
+        Commands::Path(args) => (run_path(args), EXIT_SUCCESS),
+        Commands::Slo(args) => run_check_slo(args),
+        Commands::SloHistory(args) => (run_slo_history(args), EXIT_SUCCESS),
+        Commands::Simulate(args) => (run_simulate(args), EXIT_SUCCESS),
+        Commands::Anonymize(args) => (run_anonymize(args), EXIT_SUCCESS),
+        Commands::Load(args) => (run_load(args), EXIT_SUCCESS),
+        Commands::SharedFate(args) => (run_shared_fate(args), EXIT_SUCCESS),
+        Commands::Backbone(args) => (run_backbone(args), EXIT_SUCCESS),
+        Commands::Stretch(args) => run_stretch(args),
+        Commands::Matrix(args) => (run_matrix(args), EXIT_SUCCESS),
+        Commands::Budget(args) => run_budget(args),
+        Commands::Availability(args) => run_availability(args),
+        Commands::Chaos(args) => run_chaos(args),
+        Commands::Stability(args) => run_stability(args),
+        Commands::Optimize(args) => (run_optimize(args), EXIT_SUCCESS),
+        Commands::Tour(args) => run_tour(args),
+        Commands::FindPattern(args) => (run_find_pattern(args), EXIT_SUCCESS),
+        Commands::Compare(args) => (run_compare(args), EXIT_SUCCESS),
+        Commands::Reduce(args) => (run_reduce(args), EXIT_SUCCESS),
+        Commands::Import(ImportSource::Prom(args)) => (run_import_prom(args), EXIT_SUCCESS),
+        Commands::Import(ImportSource::Terraform(args)) => {
+            (run_import_terraform(args), EXIT_SUCCESS)
+        }
+        Commands::Daemon(args) => (run_daemon(args), EXIT_SUCCESS),
+        Commands::Replay(args) => run_replay(args),
+    }
+}
+
+fn run_daemon(args: &DaemonArgs) -> Result<()> {
+    if args.schema {
+        let schema = serde_json::to_string_pretty(&daemon::schema())?;
+        println!("{}", schema);
+        return Ok(());
+    }
+    let socket = args.socket.clone().unwrap_or_else(daemon::default_socket);
+    if let Some(file) = &args.upload {
+        return upload_graph(&socket, file, args);
+    }
+    if let Some(probe) = args.probe {
+        return probe_daemon(&socket, probe, args.format);
+    }
+    if args.status {
+        let stats = daemon::query_stats(&socket)?;
+        return match args.format {
+            MetricsFormat::Text if progress::quiet() => Ok(()),
+            MetricsFormat::Text => {
+                print_daemon_stats_text(&stats);
+                Ok(())
+            }
+            MetricsFormat::Json => print_json(&stats),
+            MetricsFormat::OpenMetrics => {
+                print_daemon_stats_openmetrics(&stats);
+                Ok(())
+            }
+        };
+    }
+
+    let ttl = limits::parse_duration(&args.cache_ttl).context("Invalid --cache-ttl")?;
+    let cache = (args.cache_size > 0).then(|| PathCache::new(args.cache_size).with_ttl(ttl));
+    let tokens = match &args.token_file {
+        Some(path) => read_tokens(path)?,
+        None => Vec::new(),
+    };
+    let preload = args
+        .preload
+        .iter()
+        .map(|graph| {
+            daemon_graph(graph).ok_or_else(|| anyhow::anyhow!("Invalid --preload: {}", graph))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let shutdown_timeout =
+        limits::parse_duration(&args.shutdown_timeout).context("Invalid --shutdown-timeout")?;
+    let poll_interval =
+        limits::parse_duration(&args.poll_interval).context("Invalid --poll-interval")?;
+    daemon::serve(
+        &socket,
+        daemon::Options {
+            record: args.record.clone(),
+            cache,
+            tokens,
+            rate_limit: args.rate_limit,
+            allow_upload: args.allow_upload,
+            access_log: args.access_log.clone(),
+            preload,
+            shutdown_timeout,
+            poll_interval,
+            graph_dir: args
+                .graph_dir
+                .as_deref()
+                .map(|dir| {
+                    std::fs::canonicalize(dir)
+                        .context(format!("Invalid --graph-dir: {}", dir.display()))
+                })
+                .transpose()?,
+        },
+    )
+}
+
+/// Runs `daemon --upload`: sends a graph file to the daemon and reports
+/// whether it replaced the served graph.
+fn upload_graph(socket: &Path, file: &Path, args: &DaemonArgs) -> Result<()> {
+    let data = std::fs::read(file).context(format!("Failed to read file: {}", file.display()))?;
+    let graph = serde_json::from_slice(&data)
+        .context(format!("Failed to parse JSON in {}", file.display()))?;
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => file
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let upload = daemon::Upload {
+        upload: name,
+        graph,
+    };
+
+    let output = match daemon::upload(socket, &upload)? {
+        daemon::UploadResponse::Uploaded { graph, warnings } => {
+            warnings.into_iter().for_each(warn);
+            graph
+        }
+        daemon::UploadResponse::InvalidGraph(errors) => {
+            anyhow::bail!("Graph rejected by the daemon: {}", errors.join(": "))
+        }
+        daemon::UploadResponse::InvalidInput(e) => anyhow::bail!(e),
+    };
+    match args.format {
+        MetricsFormat::Text if progress::quiet() => Ok(()),
+        MetricsFormat::Text => {
+            println!(
+                "Uploaded {}: {} nodes, {} edges (graph hash {})",
+                output.name, output.nodes, output.edges, output.graph_hash
+            );
+            Ok(())
+        }
+        MetricsFormat::Json => print_json(&output),
+        MetricsFormat::OpenMetrics => unreachable!("rejected when parsing arguments"),
+    }
+}
+
+/// Runs `daemon --probe`: fails unless the daemon answers and, for
+/// readiness, has finished preloading.
+fn probe_daemon(socket: &Path, probe: Probe, format: MetricsFormat) -> Result<()> {
+    let health = daemon::query_health(socket)?;
+    match format {
+        MetricsFormat::Text if progress::quiet() => {}
+        MetricsFormat::Text => {
+            println!("Daemon: {}", health.status);
+            if health.loading > 0 {
+                println!("  {} graphs loading", health.loading);
+            }
+            for error in &health.errors {
+                println!("  {}", error);
+            }
+        }
+        MetricsFormat::Json => print_json(&health)?,
+        MetricsFormat::OpenMetrics => unreachable!("rejected when parsing arguments"),
+    }
+    if probe == Probe::Ready && !health.ready {
+        anyhow::bail!("daemon is not ready: {}", health.status);
+    }
+    Ok(())
+}
+
+/// Reads one token per line, skipping blank lines and `#` comments.
+fn read_tokens(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read token file: {}", path.display()))?;
+    let tokens: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if tokens.is_empty() {
+        anyhow::bail!("Token file {} has no tokens", path.display());
+    }
+    Ok(tokens)
+}
+
+fn print_daemon_stats_text(stats: &io::DaemonStatsOutput) {
+    println!("Daemon:");
+    println!(
+        "  Graphs in memory: {} ({} loads, {} reloads, mean {})",
+        stats.graphs,
+        stats.load_seconds.count,
+        stats.reloads,
+        units::ms_f(stats.load_seconds.mean() * 1000.0, 1)
+    );
+    println!(
+        "  Queries answered: {} (mean {})",
+        stats.query_seconds.count,
+        units::ms_f(stats.query_seconds.mean() * 1000.0, 2)
+    );
+    if !stats.cache_enabled {
+        println!("  Cache: disabled");
+        return;
+    }
+    println!("  Cached answers: {}", stats.entries);
+    println!(
+        "  Hits: {}, misses: {} ({} expired)",
+        stats.hits, stats.misses, stats.expired
+    );
+    println!("  Hit rate: {:.1}%", stats.hit_rate * 100.0);
+}
+
+/// Prints daemon counters as OpenMetrics gauges, and its timings as
+/// histograms.
+fn print_daemon_stats_openmetrics(stats: &io::DaemonStatsOutput) {
+    use openmetrics::{Gauge, Histogram};
+
+    let gauge = |name, help, value: f64| Gauge::new(name, help).sample(vec![], value);
+    let histogram = |name, help, timings: &io::HistogramOutput| Histogram {
+        name,
+        help,
+        unit: Some("seconds"),
+        buckets: timings
+            .bounds
+            .iter()
+            .copied()
+            .zip(timings.counts.iter().copied())
+            .collect(),
+        sum: timings.sum,
+        count: timings.count,
+    };
+    let gauges = [
+        gauge(
+            "gt_path_daemon_graphs",
+            "Graph files held in memory",
+            stats.graphs as f64,
+        ),
+        gauge(
+            "gt_path_daemon_cache_hits",
+            "Queries answered from the cache",
+            stats.hits as f64,
+        ),
+        gauge(
+            "gt_path_daemon_cache_misses",
+            "Queries that had to run, including expired entries",
+            stats.misses as f64,
+        ),
+        gauge(
+            "gt_path_daemon_cache_expired",
+            "Queries whose cached answer was older than the TTL",
+            stats.expired as f64,
+        ),
+        gauge(
+            "gt_path_daemon_cache_entries",
+            "Answers currently cached",
+            stats.entries as f64,
+        ),
+        Gauge::new(
+            "gt_path_daemon_cache_hit_ratio",
+            "Fraction of queries answered from the cache",
+        )
+        .unit("ratio")
+        .sample(vec![], stats.hit_rate),
+        gauge(
+            "gt_path_daemon_graph_reloads",
+            "Graphs loaded again because their file changed, or replaced by an upload",
+            stats.reloads as f64,
+        ),
+    ];
+    let histograms = [
+        histogram(
+            "gt_path_daemon_query_duration_seconds",
+            "Time taken to answer a query",
+            &stats.query_seconds,
+        ),
+        histogram(
+            "gt_path_daemon_graph_load_duration_seconds",
+            "Time taken to load and validate a graph",
+            &stats.load_seconds,
+        ),
+    ];
+    print!(
+        "{}",
+        openmetrics::render_with_histograms(&gauges, &histograms)
+    );
+}
+
+fn run_path(args: &PathArgs) -> Result<()> {
+    let (graph_file, from, to) = (args.graph.as_str(), args.from.as_str(), args.to.as_str());
+    let constraints = args.constraints.parse()?;
+    // the daemon only searches whole graphs
+    let restricted = args.restrict_to_mst || args.restrict_to.is_some();
+    let answer = match restricted {
+        true => None,
+        false => ask_daemon(graph_file, from, to, &args.at, &args.constraints),
+    };
+
+    if let Some(response) = answer {
+        let path = match response {
+            daemon::Response::Path { path, warnings } => {
+                warnings.into_iter().for_each(warn);
+                path
+            }
+            daemon::Response::InvalidInput(e) => anyhow::bail!(e),
+            daemon::Response::QueryFailed(e) => {
+                return Err(anyhow::anyhow!(e))
+                    .context(format!("Failed to find path from {} to {}", from, to));
+            }
+        };
+        return match args.format {
+            MetricsFormat::Text if progress::quiet() => Ok(()),
+            MetricsFormat::Text => {
+                print_text(&path);
+                Ok(())
+            }
+            MetricsFormat::Json => print_json(&path),
+            MetricsFormat::OpenMetrics => {
+                print_path_openmetrics(&path);
+                Ok(())
+            }
+        };
+    }
+    let graph =
+        load_graph(graph_file).context(format!("Failed to load graph from {}", graph_file))?;
+    let graph = resolve_at(graph, args.at.as_deref())?;
+    let kept = match &args.restrict_to {
+        Some(file) => Some(read_edge_subset(&graph, file)?),
+        None if args.restrict_to_mst => Some(backbone::minimum_spanning_tree(&graph)),
+        None => None,
+    };
+    let down: Vec<bool> = kept.iter().flatten().map(|&kept| !kept).collect();
+
+    let query = telemetry::span("query");
+    let path = graph
+        .shortest_path_without(from, to, &constraints, &down)
+        .context(format!("Failed to find path from {} to {}", from, to))?;
+    drop(query);
+
+    match args.format {
+        MetricsFormat::Text if progress::quiet() => {}
+        MetricsFormat::Text => print_text(&graph.path_output(&path)),
+        MetricsFormat::Json => print_json(&graph.path_output(&path))?,
+        MetricsFormat::OpenMetrics => print_path_openmetrics(&graph.path_output(&path)),
+    }
+
+    Ok(())
+}
+
+fn print_path_openmetrics(path: &io::PathOutput) {
+    let labels = vec![("from", path.from.to_string()), ("to", path.to.to_string())];
+    let latency = openmetrics::Gauge::new(
+        "gt_path_route_latency_seconds",
+        "Shortest path latency of the route",
+    )
+    .unit("seconds")
+    .sample(labels.clone(), path.total_latency_ms as f64 / 1000.0);
+    let hops = openmetrics::Gauge::new("gt_path_route_hops", "Edges on the shortest path")
+        .sample(labels, path.path.len().saturating_sub(1) as f64);
+    print!("{}", openmetrics::render(&[latency, hops]));
+}
+
+/// Marks the edges of `graph` joining the node pairs listed in `file`: a
+/// CSV edge list of node names, or, for a `.json` file, the spanning tree
+/// written by `gt-connect analyze --format json`, which names the node of
+/// each ID in `nodes`.
+fn read_edge_subset(graph: &graph::Graph, file: &str) -> Result<Vec<bool>> {
+    let node = |name: &str| -> Result<graph::NodeId> {
+        graph
+            .to_id
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Node '{}' in {} is not in the graph", name, file))
+    };
+    if !file.ends_with(".json") {
+        let contents =
+            std::fs::read_to_string(file).context(format!("Failed to read file: {}", file))?;
+        let pairs = backbone::parse_edge_pairs(&contents)
+            .context(format!("Invalid edge list {}", file))?
+            .iter()
+            .map(|(from, to)| Ok((node(from)?, node(to)?)))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(backbone::edges_joining(graph, &pairs));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Subset {
+        #[serde(default)]
+        nodes: Vec<String>,
+        mst: Tree,
+    }
+    #[derive(serde::Deserialize)]
+    struct Tree {
+        edges: Vec<TreeEdge>,
+    }
+    #[derive(serde::Deserialize)]
+    struct TreeEdge {
+        u: usize,
+        v: usize,
+    }
+
+    let data = std::fs::read(file).context(format!("Failed to read file: {}", file))?;
+    let subset: Subset = serde_json::from_slice(&data).context(format!(
+        "Failed to parse {}; expected `gt-connect analyze --format json` output",
+        file
+    ))?;
+    if subset.nodes.is_empty() {
+        anyhow::bail!(
+            "{} has no node names; run gt-connect analyze on the graph's JSON file",
+            file
+        );
+    }
+    let node_of = |id: usize| -> Result<graph::NodeId> {
+        let name = subset
+            .nodes
+            .get(id)
+            .ok_or_else(|| anyhow::anyhow!("Node ID {} in {} has no name", id, file))?;
+        node(name)
+    };
+    let pairs = subset
+        .mst
+        .edges
+        .iter()
+        .map(|e| Ok((node_of(e.u)?, node_of(e.v)?)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(backbone::edges_joining(graph, &pairs))
+}
+
+/// Resolves time-varying latencies when a point in time was requested.
+/// Reads a graph input file, with a progress bar for large files.
+fn read_graph_input(path: &str) -> Result<io::GraphInput> {
+    let mut span = telemetry::span("parse");
+    span.set("graph.file", path);
+    let file = std::fs::File::open(path).context(format!("Failed to read file: {}", path))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    span.set("graph.bytes", len);
+
+    let bar = progress::bytes(len, "Loading graph");
+    let input = io::GraphInput::from_reader(bar.wrap_read(file));
+    bar.finish_and_clear();
+
+    let input = compose::resolve(input?, Path::new(path))?;
+    rename_nodes(input).and_then(apply_cost).map(filter_weights)
+}
+
+/// Set from the global `--node-map` flag.
+static NODE_MAP: OnceLock<String> = OnceLock::new();
+
+/// Renames nodes by the `--node-map` file, if given.
+fn rename_nodes(mut input: io::GraphInput) -> Result<io::GraphInput> {
+    if let Some(file) = NODE_MAP.get() {
+        input.rename_nodes(&io::read_node_map(file)?);
+    }
+    Ok(input)
+}
+
+/// Set from the global `--cost-expr` flag.
+static COST_EXPR: OnceLock<String> = OnceLock::new();
+
+/// Replaces edge latencies with the `--cost-expr` cost, if given. Weighted
+/// input comes back normalized.
+fn apply_cost(input: io::GraphInput) -> Result<io::GraphInput> {
+    let Some(expr) = COST_EXPR.get() else {
+        return Ok(input);
+    };
+    let cost = CostExpr::parse(expr).context(format!("Invalid --cost-expr '{}'", expr))?;
+    let mut input = input.normalize();
+    cost.apply(&mut input)?;
+    Ok(input)
+}
+
+/// Set from the global `--min-weight` and `--max-weight` flags.
+static WEIGHT_RANGE: OnceLock<(Option<f32>, Option<f32>)> = OnceLock::new();
+
+/// Drops edges outside `--min-weight`/`--max-weight`, if given, with a
+/// warning counting them. Filtered input comes back normalized.
+fn filter_weights(input: io::GraphInput) -> io::GraphInput {
+    let Some(&(min, max)) = WEIGHT_RANGE.get() else {
+        return input;
+    };
+    let mut input = input.normalize();
+    let dropped = input.retain_latency(min, max);
+    if dropped > 0 {
+        let message = format!("{} edge(s) outside the weight range were dropped", dropped);
+        if !progress::quiet() {
+            eprintln!("Note: {}", message);
+        }
+        warn(io::WarningOutput::new("edges_filtered", message));
+    }
+    input
+}
+
+/// Set from the global `--use-daemon` flag.
+static DAEMON: OnceLock<PathBuf> = OnceLock::new();
+
+/// The daemon to forward queries to, unless a global flag changes the
+/// graph the query runs on. The daemon only ever loads graphs as they are
+/// on disk, so those queries run locally.
+fn daemon_socket() -> Option<&'static PathBuf> {
+    if NODE_MAP.get().is_some() || COST_EXPR.get().is_some() || WEIGHT_RANGE.get().is_some() {
+        return None;
+    }
+    DAEMON.get()
+}
+
+/// Forwards a shortest path query to the daemon, if `--use-daemon` was
+/// given. Returns None when the query should run locally instead: no daemon
+/// was requested, a flag like `--node-map` or `--cost-expr` changes the
+/// graph, or the daemon couldn't be reached (with a warning).
+fn ask_daemon(
+    graph_file: &str,
+    from: &str,
+    to: &str,
+    at: &Option<String>,
+    constraints: &ConstraintArgs,
+) -> Option<daemon::Response> {
+    let socket = daemon_socket()?;
+    let graph = daemon_graph(graph_file)?;
+    let request = daemon::Request {
+        graph,
+        from: from.to_string(),
+        to: to.to_string(),
+        at: at.clone(),
+        constraints: constraints.clone(),
+    };
+    daemon_fallback(daemon::query(socket, &request))
+}
+
+/// Like `ask_daemon` for several routes at once, sent as one batch.
+fn ask_daemon_batch(
+    graph_file: &str,
+    routes: &[(String, String)],
+    at: &Option<String>,
+    constraints: &ConstraintArgs,
+) -> Option<Vec<daemon::Response>> {
+    let socket = daemon_socket()?;
+    let graph = daemon_graph(graph_file)?;
+    let requests = routes
+        .iter()
+        .map(|(from, to)| daemon::Request {
+            graph: graph.clone(),
+            from: from.clone(),
+            to: to.clone(),
+            at: at.clone(),
+            constraints: constraints.clone(),
+        })
+        .collect();
+    daemon_fallback(daemon::query_batch(socket, requests))
+}
+
+/// The graph a daemon request names: the absolute path of a local file, or
+/// else a bare name for the daemon to find in its `--graph-dir`.
+fn daemon_graph(graph_file: &str) -> Option<PathBuf> {
+    match std::fs::canonicalize(graph_file) {
+        Ok(path) => Some(path),
+        // a missing file is reported by the local load
+        Err(_) if graph_file.contains(std::path::MAIN_SEPARATOR) => None,
+        Err(_) => Some(PathBuf::from(graph_file)),
+    }
+}
+
+/// Warns about a daemon that couldn't answer, so the caller loads the graph
+/// locally instead.
+fn daemon_fallback<T>(answer: Result<T>) -> Option<T> {
+    match answer {
+        Ok(answer) => Some(answer),
+        Err(e) => {
+            if !progress::quiet() {
+                eprintln!("Warning: {:#}; loading the graph locally", e);
+            }
+            None
+        }
+    }
+}
+
+/// Set from the global `--cache-dir` flag.
+static CACHE: OnceLock<DiskCache> = OnceLock::new();
+
+/// Loads and validates a graph file, with a progress bar for large files.
+/// With `--cache-dir`, a graph already parsed from identical file contents
+/// is read back from the cache instead.
+fn load_graph(path: &str) -> Result<graph::Graph> {
+    let mut span = telemetry::span("load");
+    span.set("graph.file", path);
+    // the cache holds unfiltered graphs weighted by latency
+    let Some(cache) = CACHE
+        .get()
+        .filter(|_| WEIGHT_RANGE.get().is_none() && COST_EXPR.get().is_none())
+    else {
+        let input = read_graph_input(path)?;
+        return build_graph(input);
+    };
+
+    let file = std::fs::File::open(path).context(format!("Failed to read file: {}", path))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let bar = progress::bytes(len, "Loading graph");
+    let mut data = Vec::with_capacity(len as usize);
+    let read = std::io::Read::read_to_end(&mut bar.wrap_read(file), &mut data);
+    bar.finish_and_clear();
+    read.context(format!("Failed to read file: {}", path))?;
+
+    // a composed graph is out of date when any of its files changes
+    let mut keyed = data.clone();
+    for file in compose::files(Path::new(path))?.iter().skip(1) {
+        keyed.extend(
+            std::fs::read(file).context(format!("Failed to read file: {}", file.display()))?,
+        );
+    }
+    if let Some(file) = NODE_MAP.get() {
+        keyed.extend(std::fs::read(file).context(format!("Failed to read file: {}", file))?);
+    }
+    let key = DiskCache::key(&keyed);
+    if let Some(graph) = cache.get::<graph::Graph>("graph", &key) {
+        span.set("cache.hit", true);
+        graph.lint().into_iter().for_each(warn);
+        return Ok(graph);
+    }
+    span.set("cache.hit", false);
+    let input = {
+        let _parse = telemetry::span("parse");
+        rename_nodes(compose::resolve(
+            io::GraphInput::from_reader(data.as_slice())?,
+            Path::new(path),
+        )?)?
+    };
+    let graph = build_graph(input)?;
+    cache_put("graph", &key, &graph);
+    Ok(graph)
+}
+
+/// Validates parsed input into a graph, recording warnings for likely
+/// mistakes in it.
+fn build_graph(input: io::GraphInput) -> Result<graph::Graph> {
+    let mut span = telemetry::span("build");
+    let graph = graph::Graph::try_from(input).context("Failed to build graph from input")?;
+    span.set("graph.nodes", graph.to_name.len());
+    span.set("graph.edges", graph.edge_attrs.len());
+    graph.lint().into_iter().for_each(warn);
+    Ok(graph)
+}
+
+/// Saves a cache entry, warning instead of failing: a read-only or full
+/// cache directory shouldn't stop the command.
+fn cache_put<T: serde::Serialize>(kind: &str, key: &str, value: &T) {
+    if let Some(cache) = CACHE.get()
+        && let Err(e) = cache.put(kind, key, value)
+        && !progress::quiet()
+    {
+        eprintln!("Warning: {:#}", e);
+    }
+}
+
+fn resolve_at(graph: graph::Graph, at: Option<&str>) -> Result<graph::Graph> {
+    match at {
+        Some(at) => {
+            let ts = time::parse_timestamp(at).map_err(anyhow::Error::msg)?;
+            Ok(graph.at(ts))
+        }
+        None => Ok(graph),
+    }
+}
+
+fn print_text(path: &io::PathOutput) {
+    println!("Shortest Path:");
+    println!("  Route: {}", path.path.join(" → "));
+    println!("  Total Cost: {}", units::ms(path.total_latency_ms));
+    if let Some(at) = &path.at {
+        println!("  At: {}", at);
+    }
+
+    if let Some(bottleneck) = &path.bottleneck {
+        println!(
+            "  Bottleneck: {} → {} ({})",
+            bottleneck.from,
+            bottleneck.to,
+            units::ms(bottleneck.latency_ms)
+        );
+    }
+}
+
+/// Data issues noticed while running the command, reported in JSON output.
+static WARNINGS: Mutex<Vec<io::WarningOutput>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Warnings recorded on this thread while `collect_warnings` runs.
+    static COLLECTED: RefCell<Option<Vec<io::WarningOutput>>> = const { RefCell::new(None) };
+}
+
+/// Records a warning for the JSON `warnings` array.
+fn warn(warning: io::WarningOutput) {
+    COLLECTED.with_borrow_mut(|collected| match collected {
+        Some(collected) => collected.push(warning),
+        None => WARNINGS.lock().unwrap().push(warning),
+    });
+}
+
+/// Runs `f`, returning the warnings it records on this thread instead of
+/// queuing them for this process's JSON report. The daemon loads graphs for
+/// many connections at once, each wanting only its own graph's warnings.
+fn collect_warnings<T>(f: impl FnOnce() -> T) -> (T, Vec<io::WarningOutput>) {
+    let outer = COLLECTED.replace(Some(Vec::new()));
+    let value = f();
+    let collected = COLLECTED.replace(outer).unwrap_or_default();
+    (value, collected)
+}
+
+/// Removes and returns the warnings recorded so far.
+fn take_warnings() -> Vec<io::WarningOutput> {
+    std::mem::take(&mut *WARNINGS.lock().unwrap())
+}
+
+/// Set from the global `--stats` flag: when the command started, and the
+/// search totals at that point.
+static STATS: OnceLock<(std::time::Instant, gt_path_core::SearchStats)> = OnceLock::new();
+
+/// Prints JSON output: pretty by default, compact with `--quiet`. With
+/// `--stats`, objects get a `stats` block.
+fn print_json<T: serde::Serialize>(output: &T) -> Result<()> {
+    /// Output with format and tool versions before its own fields, and
+    /// `warnings` and `stats` after them.
+    #[derive(serde::Serialize)]
+    struct Report<'a, T> {
+        schema_version: u32,
+        tool_version: &'static str,
+        #[serde(flatten)]
+        output: &'a T,
+        warnings: Vec<io::WarningOutput>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stats: Option<io::StatsOutput>,
+    }
+
+    print_json_value(&Report {
+        schema_version: io::SCHEMA_VERSION,
+        tool_version: env!("CARGO_PKG_VERSION"),
+        output,
+        warnings: take_warnings(),
+        stats: STATS.get().map(|(start, before)| io::StatsOutput {
+            search: gt_path_core::SearchStats::total().since(before),
+            wall_time_ms: start.elapsed().as_secs_f64() * 1000.0,
+            peak_rss_bytes: limits::peak_resident_bytes(),
+        }),
+    })
+}
+
+fn print_json_value<T: serde::Serialize>(output: &T) -> Result<()> {
+    let json = if progress::quiet() {
+        serde_json::to_string(output)
+    } else {
+        serde_json::to_string_pretty(output)
+    }
+    .context("Failed to serialize output to JSON")?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn run_check_slo(args: &SloArgs) -> (Result<()>, i32) {
+    let graph_file = args.graph.as_str();
+    let max_latency = args.max_latency;
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let routes = match parse_routes(&args.routes) {
+        Ok(r) => r,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    // clap requires --from/--to when no --route is given
+    let (from, to) = (
+        args.from.as_deref().unwrap_or_default(),
+        args.to.as_deref().unwrap_or_default(),
+    );
+    if let Some(interval) = &args.watch {
+        let routes = if routes.is_empty() {
+            vec![(from.to_string(), to.to_string())]
+        } else {
+            routes
+        };
+        return (
+            watch_slo(args, interval, &routes, &constraints),
+            EXIT_SUCCESS,
+        );
+    }
+    if routes.is_empty()
+        && let Some(response) = ask_daemon(graph_file, from, to, &args.at, &args.constraints)
+    {
+        let path = match response {
+            daemon::Response::Path { path, warnings } => {
+                warnings.into_iter().for_each(warn);
+                path
+            }
+            daemon::Response::InvalidInput(e) => {
+                return (Err(anyhow::anyhow!(e)), EXIT_INVALID_INPUT);
+            }
+            daemon::Response::QueryFailed(e) => {
+                let e = anyhow::anyhow!(e)
+                    .context(format!("Failed to find path from {} to {}", from, to));
+                return (Err(e), EXIT_NO_PATH);
+            }
+        };
+        return print_slo(&path, args);
+    }
+    if !routes.is_empty()
+        && let Some(responses) = ask_daemon_batch(graph_file, &routes, &args.at, &args.constraints)
+    {
+        let mut warned = false;
+        let mut paths = Vec::with_capacity(responses.len());
+        for response in responses {
+            match response {
+                daemon::Response::Path { path, warnings } => {
+                    // every query names the same graph, so its warnings repeat
+                    if !std::mem::replace(&mut warned, true) {
+                        warnings.into_iter().for_each(warn);
+                    }
+                    paths.push(Ok(*path));
+                }
+                daemon::Response::InvalidInput(e) => {
+                    return (Err(anyhow::anyhow!(e)), EXIT_INVALID_INPUT);
+                }
+                daemon::Response::QueryFailed(e) => paths.push(Err(anyhow::anyhow!(e))),
+            }
+        }
+        return run_check_slo_batch(args, &routes, paths.into_iter());
+    }
+
+    let graph = match load_graph(graph_file)
+        .context(format!("Failed to load graph from {}", graph_file))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    if !routes.is_empty() {
+        let paths = routes.iter().map(|(from, to)| {
+            SloCheck::run(&graph, from, to, max_latency, &constraints)
+                .map(|check| graph.path_output(&check.path))
+                .map_err(anyhow::Error::from)
+        });
+        return run_check_slo_batch(args, &routes, paths);
+    }
+
+    let query = telemetry::span("query");
+    let check = match SloCheck::run(&graph, from, to, max_latency, &constraints)
+        .context(format!("Failed to find path from {} to {}", from, to))
+    {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_NO_PATH),
+    };
+    drop(query);
+
+    print_slo(&graph.path_output(&check.path), args)
+}
+
+/// Runs `slo --watch` until the process is stopped.
+fn watch_slo(
+    args: &SloArgs,
+    interval: &str,
+    routes: &[(String, String)],
+    constraints: &constraint::Constraints,
+) -> Result<()> {
+    let interval = limits::parse_duration(interval)?;
+    if interval.is_zero() {
+        anyhow::bail!("--watch interval must be greater than zero");
+    }
+    if args.format == MetricsFormat::OpenMetrics {
+        anyhow::bail!("--format openmetrics is not supported with --watch");
+    }
+    let opts = watch::WatchOptions {
+        routes,
+        max_latency: args.max_latency,
+        constraints,
+        interval,
+        hook: args
+            .on_violation
+            .as_deref()
+            .map(watch::Hook::parse)
+            .transpose()?,
+        json: matches!(args.format, MetricsFormat::Json),
+    };
+    let at = args.at.as_deref();
+    watch::run(
+        &args.graph,
+        |file| {
+            load_graph(file)
+                .context(format!("Failed to load graph from {}", file))
+                .and_then(|g| resolve_at(g, at))
+        },
+        &opts,
+    )
+}
+
+/// Reports a single route's SLO check and returns its exit code.
+fn print_slo(path: &io::PathOutput, args: &SloArgs) -> (Result<()>, i32) {
+    let max_latency = args.max_latency;
+    let slo_met = path.total_latency_ms <= max_latency;
+    let exit_code = if slo_met {
+        EXIT_SUCCESS
+    } else {
+        EXIT_SLO_VIOLATED
+    };
+
+    let route = RouteSlo {
+        from: path.path.first().map(|n| n.to_string()).unwrap_or_default(),
+        to: path.path.last().map(|n| n.to_string()).unwrap_or_default(),
+        latency_ms: Some(path.total_latency_ms),
+        slo_met,
+    };
+    let result = match args.format {
+        MetricsFormat::Text => {
+            if !progress::quiet() {
+                print_slo_text(path, max_latency, slo_met);
+            }
+            Ok(())
+        }
+        MetricsFormat::Json => print_slo_json(path, max_latency, slo_met),
+        MetricsFormat::OpenMetrics => {
+            print_slo_openmetrics(std::slice::from_ref(&route), max_latency);
+            Ok(())
+        }
+    };
+
+    check_slo_baseline(args, &[route], (result, exit_code))
+}
+
+/// Applies `--baseline` to a finished SLO check: writes the baseline with
+/// `--update-baseline`, otherwise lists any regressions and turns a passing
+/// exit code into `EXIT_REGRESSED`.
+fn check_slo_baseline(
+    args: &SloArgs,
+    routes: &[RouteSlo],
+    (result, exit_code): (Result<()>, i32),
+) -> (Result<()>, i32) {
+    let Some(path) = &args.baseline else {
+        return (result, exit_code);
+    };
+    if result.is_err() {
+        return (result, exit_code);
+    }
+    if args.update_baseline {
+        if let Err(e) = baseline::write(path, routes, args.max_latency) {
+            return (Err(e), EXIT_INVALID_INPUT);
+        }
+        if !progress::quiet() {
+            eprintln!("Note: baseline written to {}", path);
+        }
+        return (result, exit_code);
+    }
+    let found = match baseline::regressions(path, routes) {
+        Ok(found) => found,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    if found.is_empty() {
+        return (result, exit_code);
+    }
+    eprintln!("Error: {} regression(s) against {}", found.len(), path);
+    for r in &found {
+        eprintln!("  {}", r);
+    }
+    let exit_code = if exit_code == EXIT_SUCCESS {
+        EXIT_REGRESSED
+    } else {
+        exit_code
+    };
+    (result, exit_code)
+}
+
+/// Parses `--route FROM:TO` values.
+fn parse_routes(raw: &[String]) -> Result<Vec<(String, String)>> {
+    raw.iter()
+        .map(|r| match r.split_once(':') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+                Ok((from.to_string(), to.to_string()))
+            }
+            _ => anyhow::bail!("Invalid route '{}'. Expected FROM:TO", r),
+        })
+        .collect()
+}
+
+/// Checks each route against the SLO. A route without a path is reported
+/// rather than aborting the run; the exit code is that of the first failing
+/// route.
+/// Reports the SLO checks of several routes, given each route's path as it
+/// is found, or the reason none was.
+fn run_check_slo_batch(
+    args: &SloArgs,
+    routes: &[(String, String)],
+    paths: impl Iterator<Item = Result<io::PathOutput>>,
+) -> (Result<()>, i32) {
+    use serde_json::json;
+
+    let max_latency = args.max_latency;
+    let quiet = progress::quiet();
+    let mut exit_code = EXIT_SUCCESS;
+    let mut results = Vec::new();
+    let mut checked = Vec::new();
+    // routes past the row limit are checked but not printed
+    let shown = listing::shown(routes.len());
+
+    for (i, ((from, to), path)) in routes.iter().zip(paths).enumerate() {
+        let print = args.format == MetricsFormat::Text && !quiet && i < shown;
+        let (code, result) = match path {
+            Ok(path) => {
+                let slo_met = path.total_latency_ms <= max_latency;
+                checked.push(RouteSlo {
+                    from: from.clone(),
+                    to: to.clone(),
+                    latency_ms: Some(path.total_latency_ms),
+                    slo_met,
+                });
+                if print {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_slo_text(&path, max_latency, slo_met);
+                }
+                (
+                    if slo_met {
+                        EXIT_SUCCESS
+                    } else {
+                        EXIT_SLO_VIOLATED
+                    },
+                    json!({
+                        "from": from,
+                        "to": to,
+                        "slo_met": slo_met,
+                        "actual_latency_ms": path.total_latency_ms,
+                        "path": path,
+                    }),
+                )
+            }
+            Err(e) => {
+                checked.push(RouteSlo {
+                    from: from.clone(),
+                    to: to.clone(),
+                    latency_ms: None,
+                    slo_met: false,
+                });
+                if print {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("SLO Check:");
+                    println!("  Route: {} → {}", from, to);
+                    println!("  Status: ✗ NO PATH ({})", e);
+                }
+                (
+                    EXIT_NO_PATH,
+                    json!({
+                        "from": from,
+                        "to": to,
+                        "slo_met": false,
+                        "error": e.to_string(),
+                    }),
+                )
+            }
+        };
+
+        results.push(result);
+        if code != EXIT_SUCCESS && exit_code == EXIT_SUCCESS {
+            exit_code = code;
+        }
+        if code != EXIT_SUCCESS && args.fail_fast {
+            break;
+        }
+    }
+
+    let passed = results.iter().filter(|r| r["slo_met"] == true).count();
+    let result = match args.format {
+        MetricsFormat::Text => {
+            if !quiet {
+                if let Some(more) = listing::more(shown.min(results.len()), results.len()) {
+                    println!();
+                    println!("{}", more);
+                }
+                if shown > 0 {
+                    println!();
+                }
+                println!(
+                    "{} of {} routes met the SLO ({} checked)",
+                    passed,
+                    routes.len(),
+                    results.len()
+                );
+            }
+            Ok(())
+        }
+        MetricsFormat::Json => print_json(&json!({
+            "slo_met": exit_code == EXIT_SUCCESS,
+            "max_latency_ms": max_latency,
+            "checked": results.len(),
+            "skipped": routes.len() - results.len(),
+            "routes": results,
+        })),
+        MetricsFormat::OpenMetrics => {
+            print_slo_openmetrics(&checked, max_latency);
+            Ok(())
+        }
+    };
+
+    check_slo_baseline(args, &checked, (result, exit_code))
+}
+
+fn print_slo_text(path: &io::PathOutput, max_latency: u32, slo_met: bool) {
+    println!("SLO Check:");
+    println!("  Route: {}", path.path.join(" → "));
+    println!("  Actual Latency: {}", units::ms(path.total_latency_ms));
+    println!("  Max Allowed: {}", units::ms(max_latency));
+    println!("  Status: {}", if slo_met { "✓ PASS" } else { "✗ FAIL" });
+    if let Some(at) = &path.at {
+        println!("  At: {}", at);
+    }
+
+    if let Some(bottleneck) = &path.bottleneck {
+        println!(
+            "  Bottleneck: {} → {} ({})",
+            bottleneck.from,
+            bottleneck.to,
+            units::ms(bottleneck.latency_ms)
+        );
+    }
+}
+
+/// One route's SLO result, for OpenMetrics output and baselines.
+pub(crate) struct RouteSlo {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    /// Shortest path latency, None without a path
+    pub(crate) latency_ms: Option<u32>,
+    pub(crate) slo_met: bool,
+}
+
+/// Prints SLO results as OpenMetrics gauges. Routes without a path have no
+/// latency sample.
+fn print_slo_openmetrics(routes: &[RouteSlo], max_latency: u32) {
+    let labels = |r: &RouteSlo| vec![("from", r.from.clone()), ("to", r.to.clone())];
+    let mut latency = openmetrics::Gauge::new(
+        "gt_path_route_latency_seconds",
+        "Shortest path latency of the route",
+    )
+    .unit("seconds");
+    let mut met = openmetrics::Gauge::new(
+        "gt_path_slo_met",
+        "1 if the route meets the latency SLO, 0 otherwise",
+    );
+    for r in routes {
+        if let Some(l) = r.latency_ms {
+            latency = latency.sample(labels(r), l as f64 / 1000.0);
+        }
+        met = met.sample(labels(r), if r.slo_met { 1.0 } else { 0.0 });
+    }
+    let max = openmetrics::Gauge::new(
+        "gt_path_slo_max_latency_seconds",
+        "Latency SLO the routes were checked against",
+    )
+    .unit("seconds")
+    .sample(vec![], max_latency as f64 / 1000.0);
+    print!("{}", openmetrics::render(&[latency, met, max]));
+}
+
+fn print_slo_json(path: &io::PathOutput, max_latency: u32, slo_met: bool) -> Result<()> {
+    use serde_json::json;
+
+    let output = json!({
+        "slo_met": slo_met,
+        "max_latency_ms": max_latency,
+        "actual_latency_ms": path.total_latency_ms,
+        "path": path,
+    });
+
+    print_json(&output)
+}
+
+fn run_replay(args: &ReplayArgs) -> (Result<()>, i32) {
+    let records = match replay::read(&args.session) {
+        Ok(r) => r,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let graph = match load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    let output = replay::replay(&args.session, &records, &graph);
+    let exit_code = if output.changed > 0 {
+        EXIT_ANSWERS_CHANGED
+    } else {
+        EXIT_SUCCESS
+    };
+    let result = match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_replay_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+    (result, exit_code)
+}
+
+fn print_replay_text(output: &io::ReplayOutput) {
+    let answer = |a: &io::ReplayAnswerOutput| match (&a.path, a.latency_ms, &a.error) {
+        (Some(path), Some(latency), _) => {
+            format!("{} ({})", path.join(" → "), units::ms(latency))
+        }
+        (_, _, Some(error)) => format!("error: {}", error),
+        _ => "no answer".to_string(),
+    };
+    println!("Session Replay:");
+    println!("  Session: {}", output.session);
+    println!(
+        "  Changed: {} of {} queries",
+        output.changed, output.queries
+    );
+    let changed: Vec<_> = output.results.iter().filter(|r| r.changed).collect();
+    let shown = listing::shown(changed.len());
+    for r in &changed[..shown] {
+        match &r.at {
+            Some(at) => println!("\n{} → {} (at {}):", r.from, r.to, at),
+            None => println!("\n{} → {}:", r.from, r.to),
+        }
+        println!("  Recorded: {}", answer(&r.recorded));
+        println!("  Replayed: {}", answer(&r.replayed));
+    }
+    if let Some(more) = listing::more(shown, changed.len()) {
+        println!("\n{}", more);
+    }
+}
+
+fn run_slo_history(args: &SloHistoryArgs) -> Result<()> {
+    let constraints = args.constraints.parse()?;
+    let files = history::snapshots(&args.glob)?;
+
+    let mut snapshots = Vec::with_capacity(files.len());
+    for file in files {
+        let name = file.display().to_string();
+        let graph = load_graph(&name).context(format!("Failed to load graph from {}", name))?;
+        snapshots.push((file, graph));
+    }
+
+    let output = history::evaluate(
+        &snapshots,
+        &args.from,
+        &args.to,
+        args.max_latency,
+        &constraints,
+    )?;
+
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_slo_history_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+    }
+
+    Ok(())
+}
+
+fn print_slo_history_text(output: &io::SloHistoryOutput) {
+    println!(
+        "SLO History: {} → {} (max {})",
+        output.from,
+        output.to,
+        units::ms(output.max_latency_ms)
+    );
+    println!("  Snapshots: {}", output.snapshots.len());
+    println!(
+        "  Violations: {} ({:.1}%)",
+        output.violations,
+        output.violation_rate * 100.0
+    );
+    println!(
+        "  Trend: {} ({} per snapshot)",
+        output.trend,
+        units::ms_delta(output.latency_slope_ms, 2)
+    );
+    if listing::summary_only() {
+        return;
+    }
+    println!();
+    let shown = listing::shown(output.snapshots.len());
+    for s in &output.snapshots[..shown] {
+        let latency = match s.latency_ms {
+            Some(l) => units::ms(l),
+            None => "no path".to_string(),
+        };
+        let status = if s.slo_met { "✓" } else { "✗" };
+        println!("  {}  {} {}", s.file, latency, status);
+    }
+    if let Some(more) = listing::more(shown, output.snapshots.len()) {
+        println!("  {}", more);
+    }
+}
+
+fn run_simulate(args: &SimulateArgs) -> Result<()> {
+    if let Some(file) = &args.scenarios {
+        return run_simulate_scenarios(args, file);
+    }
+    let graph_file = args.graph.as_str();
+    // clap requires --from/--to unless --route is given, which needs --scenarios
+    let (from, to) = (
+        args.from.as_deref().unwrap_or_default(),
+        args.to.as_deref().unwrap_or_default(),
+    );
+    let constraints = args.constraints.parse()?;
+
+    let mut mods = Modifications::parse(&args.overrides, &args.drop)?;
+    mods.create_missing = args.create_missing;
+    mods.demand = args
+        .demand
+        .as_deref()
+        .map(Modifications::parse_demand)
+        .transpose()?;
+
+    let graph =
+        load_graph(graph_file).context(format!("Failed to load graph from {}", graph_file))?;
+    if mods.demand.is_some() && gt_path_core::simulation::congestion(&graph, 1.0).is_empty() {
+        warn(io::WarningOutput::new(
+            "no_utilization",
+            "no edge has a utilization, or load_rps and capacity; --demand changes nothing",
+        ));
+    }
+
+    for (from, to) in mods.unmatched_drops(&graph) {
+        warn(io::WarningOutput::new(
+            "unmatched_modification",
+            format!("no edge {} → {} to drop; ignored", from, to),
+        ));
+    }
+    let sim = Simulation::run(&graph, from, to, &mods, &constraints)?;
+
+    if let Some(path) = &args.save_modified {
+        io::GraphInput::from(&sim.graph)
+            .write(Some(path.as_str()))
+            .context("Failed to save modified graph")?;
+    }
+
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_simulate_text(&graph, &sim),
+        OutputFormat::Json => print_simulate_json(&graph, &sim)?,
+    }
+
+    Ok(())
+}
+
+/// Runs every scenario in `file` over the requested routes and reports
+/// them side by side.
+fn run_simulate_scenarios(args: &SimulateArgs, file: &str) -> Result<()> {
+    let constraints = args.constraints.parse()?;
+    let scenarios = scenarios::read(file)?;
+    let routes = match (&args.from, &args.to) {
+        (Some(from), Some(to)) => vec![(from.clone(), to.clone())],
+        _ => parse_routes(&args.routes)?,
+    };
+    let graph =
+        load_graph(&args.graph).context(format!("Failed to load graph from {}", args.graph))?;
+
+    let output = scenarios::compare(
+        &graph,
+        &scenarios,
+        &routes,
+        args.max_latency,
+        args.baseline,
+        &constraints,
+    )?;
+
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => scenarios::print_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+    }
+
+    Ok(())
+}
+
+fn print_simulate_text(original_graph: &graph::Graph, sim: &Simulation) {
+    let (modified_graph, original_path, new_path) = (&sim.graph, &sim.original, &sim.modified);
+    println!("Simulation Results:");
+    println!();
+    println!("Original Path:");
+    println!("  Route: {}", original_graph.format_path(original_path));
+    println!("  Latency: {}", units::ms(original_path.cost));
+
+    if let Some(bottleneck) = &original_path.bottleneck {
+        let from_name = &original_graph.to_name[bottleneck.from.0 as usize];
+        let to_name = &original_graph.to_name[bottleneck.to.0 as usize];
+        println!(
+            "  Bottleneck: {} → {} ({})",
+            from_name,
+            to_name,
+            units::ms(bottleneck.latency_ms)
+        );
+    }
+
+    println!();
+    println!("Modified Path:");
+    println!("  Route: {}", modified_graph.format_path(new_path));
+    println!("  Latency: {}", units::ms(new_path.cost));
+
+    if let Some(bottleneck) = &new_path.bottleneck {
+        let from_name = &modified_graph.to_name[bottleneck.from.0 as usize];
+        let to_name = &modified_graph.to_name[bottleneck.to.0 as usize];
+        println!(
+            "  Bottleneck: {} → {} ({})",
+            from_name,
+            to_name,
+            units::ms(bottleneck.latency_ms)
+        );
+    }
+
+    if !sim.congestion.is_empty() {
+        println!();
+        println!("Congestion:");
+        for c in &sim.congestion {
+            let (from, to) = edge_names(original_graph, c.edge);
+            let utilization = format!(
+                "utilization {:.0}% → {:.0}%",
+                c.utilization * 100.0,
+                c.scaled_utilization * 100.0
+            );
+            match c.congested_latency_ms {
+                Some(latency_ms) => println!(
+                    "  {} → {}: {} → {} ({})",
+                    from,
+                    to,
+                    units::ms(c.latency_ms),
+                    units::ms(latency_ms),
+                    utilization
+                ),
+                None => println!(
+                    "  {} → {}: ✗ SATURATED ({}), dropped",
+                    from, to, utilization
+                ),
+            }
+        }
+    }
+
+    println!();
+    let diff = sim.latency_change_ms();
+    let change = if diff > 0 {
+        format!("{} (slower)", units::ms_delta(diff as f64, 0))
+    } else if diff < 0 {
+        format!("{} (faster)", units::ms_delta(diff as f64, 0))
+    } else {
+        "no change".to_string()
+    };
+    println!("Impact: {}", change);
+}
+
+fn print_simulate_json(original_graph: &graph::Graph, sim: &Simulation) -> Result<()> {
+    use serde_json::json;
+
+    let original_output = original_graph.path_output(&sim.original);
+    let new_output = sim.graph.path_output(&sim.modified);
+
+    let mut output = json!({
+        "original": original_output,
+        "modified": new_output,
+        "latency_change_ms": sim.latency_change_ms(),
+    });
+    if !sim.congestion.is_empty() {
+        output["congestion"] = sim
+            .congestion
+            .iter()
+            .map(|c| {
+                let (from, to) = edge_names(original_graph, c.edge);
+                json!({
+                    "id": original_graph.edge_ids[c.edge],
+                    "from": from,
+                    "to": to,
+                    "utilization": c.utilization,
+                    "scaled_utilization": c.scaled_utilization,
+                    "latency_ms": c.latency_ms,
+                    "congested_latency_ms": c.congested_latency_ms,
+                    "saturated": c.congested_latency_ms.is_none(),
+                })
+            })
+            .collect();
+    }
+
+    print_json(&output)
+}
+
+/// Names of the endpoints of edge `edge`, as indexed by `Link::edge`.
+fn edge_names(graph: &graph::Graph, edge: usize) -> (&str, &str) {
+    match graph.find_link(edge) {
+        Some((u, i)) => (
+            &graph.to_name[u],
+            &graph.to_name[graph.adj[u][i].to.0 as usize],
+        ),
+        None => ("?", "?"),
+    }
+}
+
+fn run_anonymize(args: &AnonymizeArgs) -> Result<()> {
+    let graph_file = args.graph.as_str();
+    if let Some(p) = args.perturb
+        && !(0.0..100.0).contains(&p)
+    {
+        anyhow::bail!(
+            "Invalid perturbation '{}'. Expected a percentage in [0, 100)",
+            p
+        );
+    }
+
+    let input = read_graph_input(graph_file)
+        .context(format!("Failed to load graph from {}", graph_file))?;
+
+    // validate before rewriting so errors reference the original labels
+    graph::Graph::try_from(input.clone())
+        .context(format!("Failed to load graph from {}", graph_file))?;
+
+    let opts = anonymize::AnonymizeOptions {
+        mode: match args.mode {
+            AnonymizeMode::Hash => anonymize::LabelMode::Hash,
+            AnonymizeMode::Sequential => anonymize::LabelMode::Sequential,
+        },
+        salt: args.salt.clone(),
+        perturb: args.perturb.map(|p| p / 100.0),
+        seed: args.seed,
+    };
+    let result = anonymize::anonymize(&input, &opts)?;
+    if args.verify {
+        let again = anonymize::anonymize(&input, &opts)?;
+        if serde_json::to_value(&again.graph)? != serde_json::to_value(&result.graph)?
+            || again.mapping != result.mapping
+        {
+            return Err(not_reproducible(args.seed));
+        }
+    }
+    if args.perturb.is_some() && !progress::quiet() {
+        eprintln!("Note: latencies perturbed with seed {}", args.seed);
+    }
+
+    if let Some(path) = &args.mapping {
+        let json = serde_json::to_string_pretty(&result.mapping)
+            .context("Failed to serialize mapping to JSON")?;
+        std::fs::write(path, json + "\n").context(format!("Failed to write file: {}", path))?;
+    }
+
+    result.graph.write(args.output.as_deref())
+}
+
+/// The error `--verify` reports when a second run with the same seed
+/// disagrees with the first; exits with `EXIT_NOT_REPRODUCIBLE`.
+#[derive(Debug)]
+struct NotReproducible(u64);
+
+impl std::fmt::Display for NotReproducible {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "--verify: a second run with seed {} gave a different result",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for NotReproducible {}
+
+fn not_reproducible(seed: u64) -> anyhow::Error {
+    NotReproducible(seed).into()
+}
+
+fn run_availability(args: &AvailabilityArgs) -> (Result<()>, i32) {
+    let (from, to) = (args.from.as_str(), args.to.as_str());
+    if let Some(min) = args.min_availability
+        && !(0.0..=1.0).contains(&min)
+    {
+        return (
+            Err(anyhow::anyhow!(
+                "--min-availability must be between 0 and 1, got {}",
+                min
+            )),
+            EXIT_INVALID_INPUT,
+        );
+    }
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let graph = match load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    let route = match graph.route_availability(from, to, args.redundancy as usize, &constraints) {
+        Ok(r) => r,
+        Err(e @ gt_path_core::PathError::PathNotFound { .. }) => {
+            return (
+                Err(anyhow::Error::new(e).context(format!("No path from {} to {}", from, to))),
+                EXIT_NO_PATH,
+            );
+        }
+        Err(e) => return (Err(e.into()), EXIT_INVALID_INPUT),
+    };
+
+    let slo_met = args.min_availability.map(|min| route.lower_bound >= min);
+    let output = io::AvailabilityOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        lower_bound: route.lower_bound,
+        upper_bound: route.upper_bound,
+        min_availability: args.min_availability,
+        slo_met,
+        paths: route
+            .paths
+            .iter()
+            .zip(&route.path_availability)
+            .map(|(p, &availability)| io::PathAvailabilityOutput {
+                availability,
+                path: graph.path_output(p),
+            })
+            .collect(),
+        graph_hash: graph.content_hash().to_string(),
+    };
+
+    let result = match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_availability_text(&graph, &route, &output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+
+    let exit_code = if slo_met == Some(false) {
+        EXIT_SLO_VIOLATED
+    } else {
+        EXIT_SUCCESS
+    };
+    (result, exit_code)
+}
+
+fn print_availability_text(
+    graph: &graph::Graph,
+    route: &gt_path_core::RouteAvailability,
+    output: &io::AvailabilityOutput,
+) {
+    println!("Availability: {} → {}", output.from, output.to);
+    if route.paths.len() == 1 {
+        println!("  Availability: {:.4}%", output.lower_bound * 100.0);
+    } else {
+        println!("  Disjoint Paths: {}", route.paths.len());
+        println!("  Lower Bound: {:.4}%", output.lower_bound * 100.0);
+        println!("  Upper Bound: {:.4}%", output.upper_bound * 100.0);
+    }
+    if let (Some(min), Some(met)) = (output.min_availability, output.slo_met) {
+        println!("  Min Allowed: {:.4}%", min * 100.0);
+        println!("  Status: {}", if met { "✓ PASS" } else { "✗ FAIL" });
+    }
+
+    println!();
+    println!("Paths:");
+    for (path, availability) in route.paths.iter().zip(&route.path_availability) {
+        println!(
+            "  {} ({:.4}%, {})",
+            graph.format_path(path),
+            availability * 100.0,
+            units::ms(path.cost)
+        );
+    }
+}
+
+fn run_chaos(args: &ChaosArgs) -> (Result<()>, i32) {
+    let (from, to) = (args.from.as_str(), args.to.as_str());
+    if !(0.0..=1.0).contains(&args.default_failure_rate) {
+        return (
+            Err(anyhow::anyhow!(
+                "--default-failure-rate must be between 0 and 1, got {}",
+                args.default_failure_rate
+            )),
+            EXIT_INVALID_INPUT,
+        );
+    }
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let graph = match load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let rates = match chaos::failure_rates(&graph, args.default_failure_rate) {
+        Ok(r) => r,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    let opts = chaos::ChaosOptions {
+        trials: args.trials,
+        seed: args.seed,
+    };
+    let report = match chaos::simulate(
+        &graph,
+        from,
+        to,
+        args.max_latency,
+        &constraints,
+        &rates,
+        &opts,
+    ) {
+        Ok(r) => r,
+        Err(e @ gt_path_core::PathError::PathNotFound { .. }) => {
+            return (
+                Err(anyhow::Error::new(e).context(format!("No path from {} to {}", from, to))),
+                EXIT_NO_PATH,
+            );
+        }
+        Err(e) => return (Err(e.into()), EXIT_INVALID_INPUT),
+    };
+    if args.verify
+        && chaos::simulate(
+            &graph,
+            from,
+            to,
+            args.max_latency,
+            &constraints,
+            &rates,
+            &opts,
+        )
+        .ok()
+        .as_ref()
+            != Some(&report)
+    {
+        return (Err(not_reproducible(args.seed)), EXIT_NOT_REPRODUCIBLE);
+    }
+
+    let output = io::ChaosOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        max_latency_ms: args.max_latency,
+        baseline_latency_ms: report.baseline_ms,
+        trials: args.trials,
+        seed: args.seed,
+        verified: args.verify,
+        violations: report.violations,
+        disconnected: report.disconnected,
+        violation_probability: if args.trials == 0 {
+            0.0
+        } else {
+            report.violations as f64 / args.trials as f64
+        },
+        confidence_interval: chaos::wilson_interval(report.violations, args.trials),
+        graph_hash: graph.content_hash().to_string(),
+    };
+
+    let result = match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_chaos_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+    (result, EXIT_SUCCESS)
+}
+
+fn print_chaos_text(output: &io::ChaosOutput) {
+    println!("Chaos Simulation: {} → {}", output.from, output.to);
+    println!(
+        "  Trials: {} (seed {}{})",
+        output.trials,
+        output.seed,
+        if output.verified { ", verified" } else { "" }
+    );
+    println!(
+        "  Baseline Latency: {} (max allowed {})",
+        units::ms(output.baseline_latency_ms),
+        units::ms(output.max_latency_ms)
+    );
+    let (lo, hi) = output.confidence_interval;
+    println!(
+        "  SLO Violations: {} ({:.2}%, 95% CI {:.2}%–{:.2}%)",
+        output.violations,
+        output.violation_probability * 100.0,
+        lo * 100.0,
+        hi * 100.0
+    );
+    println!("  Disconnected: {}", output.disconnected);
+}
+
+fn run_stability(args: &StabilityArgs) -> (Result<()>, i32) {
+    let (from, to) = (args.from.as_str(), args.to.as_str());
+    if !(0.0..=100.0).contains(&args.noise) {
+        return (
+            Err(anyhow::anyhow!(
+                "--noise must be between 0 and 100, got {}",
+                args.noise
+            )),
+            EXIT_INVALID_INPUT,
+        );
+    }
+    if args.trials == 0 {
+        return (
+            Err(anyhow::anyhow!("--trials must be at least 1")),
+            EXIT_INVALID_INPUT,
+        );
+    }
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let graph = match load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    let opts = stability::StabilityOptions {
+        trials: args.trials,
+        noise: args.noise / 100.0,
+        seed: args.seed,
+    };
+    let report = match stability::simulate(&graph, from, to, &constraints, &opts) {
+        Ok(r) => r,
+        Err(e @ gt_path_core::PathError::PathNotFound { .. }) => {
+            return (
+                Err(anyhow::Error::new(e).context(format!("No path from {} to {}", from, to))),
+                EXIT_NO_PATH,
+            );
+        }
+        Err(e) => return (Err(e.into()), EXIT_INVALID_INPUT),
+    };
+    if args.verify
+        && stability::simulate(&graph, from, to, &constraints, &opts)
+            .ok()
+            .as_ref()
+            != Some(&report)
+    {
+        return (Err(not_reproducible(args.seed)), EXIT_NOT_REPRODUCIBLE);
+    }
+
+    let latencies = &report.latencies;
+    let output = io::StabilityOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        noise_percent: args.noise,
+        trials: args.trials,
+        seed: args.seed,
+        verified: args.verify,
+        baseline_path: graph.path_output(&report.baseline).path,
+        baseline_latency_ms: report.baseline.cost,
+        route_changes: report.changed,
+        route_change_probability: report.changed as f64 / args.trials as f64,
+        latency: io::LatencyDistributionOutput {
+            min: latencies[0],
+            p50: stability::percentile(latencies, 50.0),
+            p95: stability::percentile(latencies, 95.0),
+            p99: stability::percentile(latencies, 99.0),
+            max: latencies[latencies.len() - 1],
+            mean: latencies.iter().map(|&l| l as f64).sum::<f64>() / latencies.len() as f64,
+        },
+        routes: report
+            .routes
+            .into_iter()
+            .map(|(path, count)| io::RouteFrequencyOutput { path, count })
+            .collect(),
+        graph_hash: graph.content_hash().to_string(),
+    };
+
+    let result = match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_stability_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+    (result, EXIT_SUCCESS)
+}
+
+fn print_stability_text(output: &io::StabilityOutput) {
+    println!("Path Stability: {} → {}", output.from, output.to);
+    println!(
+        "  Trials: {} (±{}% noise, seed {}{})",
+        output.trials,
+        output.noise_percent,
+        output.seed,
+        if output.verified { ", verified" } else { "" }
+    );
+    println!(
+        "  Baseline: {} ({})",
+        output.baseline_path.join(" → "),
+        units::ms(output.baseline_latency_ms)
+    );
+    println!(
+        "  Route Changes: {} ({:.2}%)",
+        output.route_changes,
+        output.route_change_probability * 100.0
+    );
+
+    let l = &output.latency;
+    println!();
+    println!("Latency:");
+    println!(
+        "  min {}, p50 {}, p95 {}, p99 {}, max {}",
+        units::ms(l.min),
+        units::ms(l.p50),
+        units::ms(l.p95),
+        units::ms(l.p99),
+        units::ms(l.max)
+    );
+    println!("  mean {}", units::ms_f(l.mean, 1));
+
+    listing::section("Routes:", output.routes.iter(), |r| {
+        format!(
+            "{:.2}%  {}",
+            r.count as f64 / output.trials as f64 * 100.0,
+            r.path.join(" → ")
+        )
+    });
+}
+
+fn run_optimize(args: &OptimizeArgs) -> Result<()> {
+    let constraints = args.constraints.parse()?;
+    let routes = parse_routes(&args.routes)?;
+    let objective = match args.objective {
+        OptimizeObjective::MaxLatency => optimize::Objective::MaxLatency,
+        OptimizeObjective::Bridges => optimize::Objective::Bridges,
+    };
+    if objective == optimize::Objective::MaxLatency && routes.is_empty() {
+        anyhow::bail!("--objective max-latency needs at least one --route FROM:TO");
+    }
+    if args.budget.is_nan() || args.budget < 0.0 {
+        anyhow::bail!("--budget must be non-negative, got {}", args.budget);
+    }
+
+    let input = read_graph_input(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))?;
+    let graph = graph::Graph::try_from(input.clone())
+        .context(format!("Failed to load graph from {}", args.graph))?;
+    for (from, to) in &routes {
+        for node in [from, to] {
+            if !graph.to_id.contains_key(node.as_str()) {
+                anyhow::bail!("Unknown node in route {}:{}: {}", from, to, node);
+            }
+        }
+    }
+    let pool = optimize::CandidatePool::read(&args.candidates, &input).context(format!(
+        "Failed to load candidates from {}",
+        args.candidates
+    ))?;
+
+    let opts = optimize::OptimizeOptions {
+        objective,
+        routes,
+        budget: args.budget,
+        iterations: args.iterations,
+        seed: args.seed,
+    };
+    let plan = optimize::anneal(&input, &pool, &constraints, &opts)?;
+    if args.verify && optimize::anneal(&input, &pool, &constraints, &opts)? != plan {
+        return Err(not_reproducible(args.seed));
+    }
+
+    let mut chosen = vec![false; pool.candidates.len()];
+    for &i in &plan.chosen {
+        chosen[i] = true;
+    }
+    let improved = optimize::with_candidates(&input, &pool, &chosen)?;
+    let latency = |g: &graph::Graph, from: &str, to: &str| {
+        g.shortest_path_with(from, to, &constraints)
+            .ok()
+            .map(|p| p.cost)
+    };
+
+    let output = io::OptimizeOutput {
+        objective: match objective {
+            optimize::Objective::MaxLatency => "max-latency",
+            optimize::Objective::Bridges => "bridges",
+        }
+        .to_string(),
+        before: plan.before,
+        after: plan.after,
+        budget: args.budget,
+        total_cost: plan.total_cost,
+        changes: plan
+            .chosen
+            .iter()
+            .map(|&i| {
+                let c = &pool.candidates[i];
+                io::ProposedEdgeOutput {
+                    from: c.from.clone(),
+                    to: c.to.clone(),
+                    latency_ms: c.latency_ms,
+                    cost: c.cost,
+                }
+            })
+            .collect(),
+        routes: opts
+            .routes
+            .iter()
+            .map(|(from, to)| io::RouteChangeOutput {
+                from: from.clone(),
+                to: to.clone(),
+                before_ms: latency(&graph, from, to),
+                after_ms: latency(&improved, from, to),
+            })
+            .collect(),
+        iterations: args.iterations,
+        seed: args.seed,
+        verified: args.verify,
+        graph_hash: graph.content_hash().to_string(),
+    };
+
+    if let Some(path) = &args.output {
+        optimize::apply(&input, &pool, &chosen).write(Some(path))?;
+    }
+
+    match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_optimize_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    }
+}
+
+fn print_optimize_text(output: &io::OptimizeOutput) {
+    let value = |v: f64| {
+        if v >= optimize::UNREACHABLE {
+            "unreachable".to_string()
+        } else if output.objective == "max-latency" {
+            units::ms(v)
+        } else {
+            v.to_string()
+        }
+    };
+    let ms = |v: Option<u32>| v.map_or("unreachable".to_string(), units::ms);
+
+    println!("Topology Optimization ({}):", output.objective);
+    println!("  Before: {}", value(output.before));
+    println!("  After: {}", value(output.after));
+    println!("  Cost: {} of {}", output.total_cost, output.budget);
+    println!(
+        "  Search: {} iterations (seed {}{})",
+        output.iterations,
+        output.seed,
+        if output.verified { ", verified" } else { "" }
+    );
+
+    if output.changes.is_empty() {
+        println!();
+        println!("No change within budget improves the objective.");
+    }
+    listing::section("Proposed Edges:", output.changes.iter(), |c| {
+        format!(
+            "+ {} → {} ({}, cost {})",
+            c.from,
+            c.to,
+            units::ms(c.latency_ms),
+            c.cost
+        )
+    });
+    listing::section("Routes:", output.routes.iter(), |r| {
+        format!(
+            "{} → {}: {} → {}",
+            r.from,
+            r.to,
+            ms(r.before_ms),
+            ms(r.after_ms)
+        )
+    });
+}
+
+fn run_tour(args: &TourArgs) -> (Result<()>, i32) {
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+    let graph = match load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))
+    {
+        Ok(g) => g,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
+
+    let tour = match tour::plan(&graph, &args.nodes, args.round_trip, &constraints) {
+        Ok(t) => t,
+        Err(e @ gt_path_core::PathError::PathNotFound { .. }) => {
+            return (
+                Err(anyhow::Error::new(e).context("No tour connects every node")),
+                EXIT_NO_PATH,
+            );
+        }
+        Err(e) => return (Err(e.into()), EXIT_INVALID_INPUT),
+    };
+
+    let output = io::TourOutput {
+        order: tour.order.iter().map(|&i| args.nodes[i].clone()).collect(),
+        round_trip: args.round_trip,
+        total_latency_ms: tour.total_ms,
+        legs: tour.legs.iter().map(|p| graph.path_output(p)).collect(),
+        graph_hash: graph.content_hash().to_string(),
+    };
+
+    let result = match args.format {
+        OutputFormat::Text => {
+            if !progress::quiet() {
+                print_tour_text(&graph, &tour, &output);
+            }
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+
+    (result, EXIT_SUCCESS)
+}
+
+fn print_tour_text(graph: &graph::Graph, tour: &tour::Tour, output: &io::TourOutput) {
+    println!("Tour:");
+    let mut order = output.order.clone();
+    if output.round_trip && !order.is_empty() {
+        order.push(order[0].clone());
+    }
+    println!("  Order: {}", order.join(" → "));
+    println!(
+        "  Total Latency: {}",
+        units::ms(output.total_latency_ms as f64)
+    );
+
+    println!();
+    println!("Legs:");
+    for leg in &tour.legs {
+        println!("  {} ({})", graph.format_path(leg), units::ms(leg.cost));
+    }
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Find shortest path between two nodes
-    Path {
-        /// Path to graph JSON file
-        #[arg(short, long)]
-        graph: String,
+fn run_find_pattern(args: &FindPatternArgs) -> Result<()> {
+    let input = read_graph_input(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))?
+        .normalize();
+    let graph = graph::Graph::try_from(input.clone())
+        .context(format!("Failed to load graph from {}", args.graph))?;
+    let pattern = pattern::PatternInput::read(&args.pattern)
+        .context(format!("Failed to load pattern from {}", args.pattern))?;
+
+    // one extra match tells whether the limit cut the search short
+    let mut matches = pattern::find_matches(&pattern, &input, args.limit.saturating_add(1));
+    let truncated = matches.len() > args.limit;
+    matches.truncate(args.limit);
 
-        /// Source node name
-        #[arg(short, long)]
-        from: String,
+    let output = io::PatternMatchOutput {
+        pattern_nodes: pattern.nodes.iter().map(|n| n.name().to_string()).collect(),
+        count: matches.len(),
+        truncated,
+        matches: matches
+            .iter()
+            .map(|binding| {
+                pattern
+                    .nodes
+                    .iter()
+                    .zip(binding)
+                    .map(|(p, &g)| (p.name().to_string(), input.nodes[g].name().to_string()))
+                    .collect()
+            })
+            .collect(),
+        graph_hash: graph.content_hash().to_string(),
+    };
 
-        /// Destination node name
-        #[arg(short, long)]
-        to: String,
+    match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_pattern_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    }
+}
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+fn print_pattern_text(output: &io::PatternMatchOutput) {
+    if output.truncated {
+        println!("Pattern Matches: {} (limit reached)", output.count);
+    } else {
+        println!("Pattern Matches: {}", output.count);
+    }
+    let shown = listing::shown(output.matches.len());
+    for (i, binding) in output.matches[..shown].iter().enumerate() {
+        let pairs: Vec<String> = output
+            .pattern_nodes
+            .iter()
+            .map(|p| format!("{}={}", p, binding[p]))
+            .collect();
+        println!("  {}. {}", i + 1, pairs.join(", "));
+    }
+    if let Some(more) = listing::more(shown, output.matches.len()) {
+        println!("  {}", more);
+    }
+}
 
-    /// Check if path meets SLO (Service Level Objective)
-    Slo {
-        /// Path to graph JSON file
-        #[arg(short, long)]
-        graph: String,
+fn run_compare(args: &CompareArgs) -> Result<()> {
+    let read = |path: &str| -> Result<(io::GraphInput, String)> {
+        let input = read_graph_input(path)
+            .context(format!("Failed to load graph from {}", path))?
+            .normalize();
+        let graph = graph::Graph::try_from(input.clone())
+            .context(format!("Failed to load graph from {}", path))?;
+        Ok((input, graph.content_hash().to_string()))
+    };
+    let (a, hash_a) = read(&args.a)?;
+    let (b, hash_b) = read(&args.b)?;
 
-        /// Source node name
-        #[arg(short, long)]
-        from: String,
+    let wants = |metric: CompareMetric| args.metric == metric || args.metric == CompareMetric::All;
+    let output = io::CompareOutput {
+        graph_hash_a: hash_a,
+        graph_hash_b: hash_b,
+        node_jaccard: wants(CompareMetric::Jaccard).then(|| compare::node_jaccard(&a, &b)),
+        edge_jaccard: wants(CompareMetric::Jaccard).then(|| compare::edge_jaccard(&a, &b)),
+        degree_distance: wants(CompareMetric::Degree).then(|| compare::degree_distance(&a, &b)),
+        edit_distance: wants(CompareMetric::Ged).then(|| {
+            let edits = compare::edit_distance(&a, &b);
+            let size = a.nodes.len() + a.edges.len() + b.nodes.len() + b.edges.len();
+            io::EditDistanceOutput {
+                total: edits.total(),
+                nodes_added: edits.nodes_added,
+                nodes_removed: edits.nodes_removed,
+                edges_added: edits.edges_added,
+                edges_removed: edits.edges_removed,
+                latency_changed: edits.latency_changed,
+                similarity: if size == 0 {
+                    1.0
+                } else {
+                    1.0 - edits.total() as f64 / size as f64
+                },
+            }
+        }),
+    };
 
-        /// Destination node name
-        #[arg(short, long)]
-        to: String,
+    match args.format {
+        OutputFormat::Text if progress::quiet() => Ok(()),
+        OutputFormat::Text => {
+            print_compare_text(&output);
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    }
+}
 
-        /// Maximum allowed latency in milliseconds
-        #[arg(short, long)]
-        max_latency: u32,
+fn print_compare_text(output: &io::CompareOutput) {
+    println!("Graph Comparison:");
+    if let Some(score) = output.node_jaccard {
+        println!("  Node Jaccard: {:.3}", score);
+    }
+    if let Some(score) = output.edge_jaccard {
+        println!("  Edge Jaccard: {:.3}", score);
+    }
+    if let Some(distance) = output.degree_distance {
+        println!("  Degree Distribution Distance: {:.3}", distance);
+    }
+    if let Some(edits) = &output.edit_distance {
+        println!(
+            "  Edit Distance: {} (similarity {:.3})",
+            edits.total, edits.similarity
+        );
+        println!();
+        println!("Edits:");
+        println!("  Nodes Added: {}", edits.nodes_added);
+        println!("  Nodes Removed: {}", edits.nodes_removed);
+        println!("  Edges Added: {}", edits.edges_added);
+        println!("  Edges Removed: {}", edits.edges_removed);
+        println!("  Latency Changed: {}", edits.latency_changed);
+    }
+}
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+fn run_reduce(args: &ReduceArgs) -> Result<()> {
+    let input = read_graph_input(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))?
+        .normalize();
+    graph::Graph::try_from(input.clone())
+        .context(format!("Failed to load graph from {}", args.graph))?;
 
-    /// Simulate path changes with modified edge weights
-    Simulate {
-        /// Path to graph JSON file
-        #[arg(short, long)]
-        graph: String,
+    let reduced = reduce::transitive_reduction(&input)?;
 
-        /// Source node name
-        #[arg(short, long)]
-        from: String,
+    if !progress::quiet() {
+        eprintln!(
+            "Removed {} of {} edges",
+            reduced.removed.len(),
+            input.edges.len()
+        );
+        let shown = listing::shown(reduced.removed.len());
+        for (from, to) in &reduced.removed[..shown] {
+            eprintln!("  {} → {}", from, to);
+        }
+        if let Some(more) = listing::more(shown, reduced.removed.len()) {
+            eprintln!("  {}", more);
+        }
+    }
+    reduced.graph.write(args.output.as_deref())
+}
+
+fn run_import_prom(args: &PromImportArgs) -> Result<()> {
+    let mut input = read_graph_input(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))?;
+    let mapping = prom::PromMapping::read(&args.map)
+        .context(format!("Failed to load mapping from {}", args.map))?;
 
-        /// Destination node name
-        #[arg(short, long)]
-        to: String,
+    let body = match &args.response {
+        Some(path) => {
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?
+        }
+        None => prom::query(&args.url, &args.query)?,
+    };
+    let samples = prom::parse_response(&body)?;
+    let report = prom::apply(&mut input, &samples, &mapping);
 
-        /// Override edge weights: from:to:weight (e.g., "api:auth:100")
-        #[arg(long = "override", value_delimiter = ',')]
-        overrides: Vec<String>,
+    // the updated graph must still be loadable
+    graph::Graph::try_from(input.clone())
+        .context("Imported latencies produced an invalid graph")?;
 
-        /// Drop edges: from:to (e.g., "api:cache")
-        #[arg(long, value_delimiter = ',')]
-        drop: Vec<String>,
+    if !progress::quiet() {
+        print_import_report(&report, input.edges.len(), samples.len());
+    }
 
-        /// Output format
-        #[arg(long, value_enum, default_value = "text")]
-        format: OutputFormat,
-    },
+    input.write(args.output.as_deref())
 }
 
-#[derive(Clone, ValueEnum)]
-enum OutputFormat {
-    /// Human-readable text output
-    Text,
-    /// JSON output for scripting
-    Json,
+fn print_import_report(report: &prom::ImportReport, edges: usize, series: usize) {
+    eprintln!(
+        "Updated {} of {} edges from {} series",
+        report.updated, edges, series
+    );
+    if report.skipped > 0 {
+        eprintln!(
+            "  Skipped {} series with missing labels or non-numeric values",
+            report.skipped
+        );
+    }
+    let shown = listing::shown(report.unmatched.len());
+    for (from, to) in &report.unmatched[..shown] {
+        eprintln!("  No edge for series {} → {}", from, to);
+    }
+    if let Some(more) = listing::more(shown, report.unmatched.len()) {
+        eprintln!("  {} series without an edge", more);
+    }
+    let shown = listing::shown(report.stale.len());
+    for (from, to) in &report.stale[..shown] {
+        eprintln!(
+            "  No data for edge {} → {}; kept previous latency",
+            from, to
+        );
+    }
+    if let Some(more) = listing::more(shown, report.stale.len()) {
+        eprintln!("  {} edges without data", more);
+    }
 }
 
-// Exit codes from spec
-const EXIT_SUCCESS: i32 = 0;
-const EXIT_NO_PATH: i32 = 2;
-const EXIT_SLO_VIOLATED: i32 = 3;
-const EXIT_INVALID_INPUT: i32 = 4;
+fn run_import_terraform(args: &TerraformImportArgs) -> Result<()> {
+    let dot =
+        std::fs::read_to_string(&args.dot).context(format!("Failed to read file: {}", args.dot))?;
+    let input = terraform::from_dot(&dot);
 
-fn main() {
-    let cli = Cli::parse();
+    if input.nodes.is_empty() {
+        anyhow::bail!("No resources found in {}", args.dot);
+    }
+    graph::Graph::try_from(input.clone()).context("Terraform graph produced an invalid graph")?;
 
-    let (result, exit_code) = match cli.command {
-        Commands::Path {
-            graph,
-            from,
-            to,
-            format,
-        } => (run_path(&graph, &from, &to, format), EXIT_SUCCESS),
-        Commands::Slo {
-            graph,
-            from,
-            to,
-            max_latency,
-            format,
-        } => run_check_slo(&graph, &from, &to, max_latency, format),
-        Commands::Simulate {
-            graph,
-            from,
-            to,
-            overrides,
-            drop,
-            format,
-        } => (
-            run_simulate(&graph, &from, &to, &overrides, &drop, format),
-            EXIT_SUCCESS,
-        ),
+    if !progress::quiet() {
+        eprintln!(
+            "Imported {} resources and {} dependencies",
+            input.nodes.len(),
+            input.edges.len()
+        );
+    }
+    input.write(args.output.as_deref())
+}
+
+fn run_load(args: &LoadArgs) -> Result<()> {
+    let constraints = args.constraints.parse()?;
+    let graph =
+        load_graph(&args.graph).context(format!("Failed to load graph from {}", args.graph))?;
+    let demands = io::DemandsInput::read(&args.demands)
+        .context(format!("Failed to load demands from {}", args.demands))?;
+
+    let output = match args.assign {
+        AssignMethod::Shortest => {
+            load::route_demands(&graph, &demands.demands, &constraints).output(&graph)
+        }
+        AssignMethod::Equilibrium => {
+            let opts = load::AssignOptions {
+                max_iterations: args.max_iterations,
+                gap: args.gap,
+            };
+            let (report, assignment) =
+                load::assign_demands(&graph, &demands.demands, &constraints, &opts);
+            if !assignment.converged {
+                warn(io::WarningOutput::new(
+                    "not_converged",
+                    format!(
+                        "assignment stopped after {} iterations at relative gap {:.4}, above --gap {}",
+                        assignment.iterations, assignment.relative_gap, args.gap
+                    ),
+                ));
+            }
+            let mut output = report.output(&graph);
+            output.assignment = Some(io::AssignmentOutput {
+                iterations: assignment.iterations,
+                relative_gap: assignment.relative_gap,
+                converged: assignment.converged,
+            });
+            output
+        }
     };
 
-    match result {
-        Ok(()) => process::exit(exit_code),
-        Err(e) => {
-            eprintln!("Error: {:#}", e);
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_load_text(&output),
+        OutputFormat::Json => print_json(&output)?,
+    }
 
-            let exit_code =
-                if e.to_string().contains("No path") || e.to_string().contains("PathNotFound") {
-                    EXIT_NO_PATH
-                } else {
-                    EXIT_INVALID_INPUT
-                };
+    Ok(())
+}
 
-            process::exit(exit_code);
+fn print_load_text(output: &io::LoadOutput) {
+    // equilibrium splits demands, so loads are rarely whole
+    let rps = |rps: f64| (rps * 10.0).round() / 10.0;
+
+    println!("Traffic Load:");
+    println!("  Routed: {} rps", output.routed_rps);
+    println!("  Unrouted Demands: {}", output.unrouted.len());
+    println!("  Edges Over Capacity: {}", output.over_capacity);
+    if let Some(assignment) = &output.assignment {
+        println!(
+            "  Assignment: equilibrium, {} after {} iterations (relative gap {:.4})",
+            if assignment.converged {
+                "converged"
+            } else {
+                "not converged"
+            },
+            assignment.iterations,
+            assignment.relative_gap
+        );
+    }
+
+    listing::section("Edges by load:", output.edges.iter(), |edge| {
+        let load = match (edge.capacity_rps, edge.utilization) {
+            (Some(capacity), Some(utilization)) => format!(
+                "{} → {}: {} rps / {} rps ({:.0}%){}",
+                edge.from,
+                edge.to,
+                rps(edge.load_rps),
+                capacity,
+                utilization * 100.0,
+                if edge.over_capacity {
+                    " ✗ OVER CAPACITY"
+                } else {
+                    ""
+                }
+            ),
+            _ => format!("{} → {}: {} rps", edge.from, edge.to, rps(edge.load_rps)),
+        };
+        match edge.loaded_latency_ms {
+            Some(loaded) => format!("{}, {} loaded", load, units::ms_f(loaded, 1)),
+            None => load,
         }
+    });
+
+    listing::section("Unrouted demands:", output.unrouted.iter(), |d| {
+        format!("{} → {} ({} rps): {}", d.from, d.to, d.rps, d.reason)
+    });
+}
+
+fn run_shared_fate(args: &SharedFateArgs) -> Result<()> {
+    let constraints = args.constraints.parse()?;
+    let graph =
+        load_graph(&args.graph).context(format!("Failed to load graph from {}", args.graph))?;
+    let demands = io::DemandsInput::read(&args.demands)
+        .context(format!("Failed to load demands from {}", args.demands))?;
+
+    let report = load::route_demands(&graph, &demands.demands, &constraints);
+    let output = report.shared_fate(&graph, &demands.demands, &constraints);
+
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_shared_fate_text(&output),
+        OutputFormat::Json => print_json(&output)?,
     }
+
+    Ok(())
 }
 
-fn run_path(graph_file: &str, from: &str, to: &str, format: OutputFormat) -> Result<()> {
-    let graph = graph::Graph::load_json(graph_file)
-        .context(format!("Failed to load graph from {}", graph_file))?;
+fn print_shared_fate_text(output: &io::SharedFateOutput) {
+    println!("Shared Fate:");
+    println!("  Routed Demands: {}", output.routed);
+    println!("  Unrouted Demands: {}", output.unrouted.len());
 
-    let path = graph
-        .shortest_path(from, to)
-        .context(format!("Failed to find path from {} to {}", from, to))?;
+    listing::section(
+        "Edges by routes sharing them:",
+        output.edges.iter(),
+        |edge| {
+            let routes: Vec<String> = edge
+                .demands
+                .iter()
+                .map(|(from, to)| format!("{} → {}", from, to))
+                .collect();
+            format!(
+                "{} → {}: {} of {} routes ({:.0}%), {} rps{}\n    {}",
+                edge.from,
+                edge.to,
+                edge.routes,
+                output.routed,
+                edge.share * 100.0,
+                edge.load_rps,
+                if edge.stranded > 0 {
+                    format!(", {} stranded if it fails", edge.stranded)
+                } else {
+                    String::new()
+                },
+                listing::join(&routes)
+            )
+        },
+    );
 
-    match format {
-        OutputFormat::Text => print_text(&graph, &path),
-        OutputFormat::Json => print_json(&graph, &path)?,
+    listing::section("Unrouted demands:", output.unrouted.iter(), |d| {
+        format!("{} → {} ({} rps): {}", d.from, d.to, d.rps, d.reason)
+    });
+}
+
+fn run_backbone(args: &BackboneArgs) -> Result<()> {
+    let graph =
+        load_graph(&args.graph).context(format!("Failed to load graph from {}", args.graph))?;
+    let output = backbone::compare(&graph, &args.root)?;
+
+    match args.format {
+        MetricsFormat::Text if progress::quiet() => {}
+        MetricsFormat::Text => print_backbone_text(&output),
+        MetricsFormat::Json => print_json(&output)?,
+        MetricsFormat::OpenMetrics => print_backbone_openmetrics(&output),
     }
 
     Ok(())
 }
 
-fn print_text(graph: &graph::Graph, path: &path::Path) {
-    println!("Shortest Path:");
-    println!("  Route: {}", graph.format_path(path));
-    println!("  Total Cost: {}ms", path.cost);
+fn print_backbone_text(output: &io::BackboneOutput) {
+    let total = |tree: &io::TreeOutput| units::ms_f(tree.total_latency_ms as f64, 0);
+    println!("Backbone Trees (root {}):", output.root);
+    println!(
+        "  Minimum Spanning Tree: {} over {} links",
+        total(&output.mst),
+        output.mst.links
+    );
+    println!(
+        "  Shortest-Path Tree: {} over {} links",
+        total(&output.spt),
+        output.spt.links
+    );
 
-    if let Some(bottleneck) = &path.bottleneck {
-        let from_name = &graph.to_name[bottleneck.from.0 as usize];
-        let to_name = &graph.to_name[bottleneck.to.0 as usize];
-        println!(
-            "  Bottleneck: {} → {} ({}ms)",
-            from_name, to_name, bottleneck.latency_ms
-        );
+    listing::section(
+        &format!(
+            "Latency from {} (spanning tree vs shortest path):",
+            output.root
+        ),
+        output.destinations.iter(),
+        |d| {
+            format!(
+                "{}: {} vs {} ({:.2}x)",
+                d.node,
+                units::ms(d.mst_latency_ms),
+                units::ms(d.spt_latency_ms),
+                d.stretch
+            )
+        },
+    );
+
+    for (title, pairs) in [
+        ("Only in the spanning tree:", &output.mst_only),
+        ("Only in the shortest-path tree:", &output.spt_only),
+    ] {
+        listing::section(title, pairs.iter(), |(a, b)| format!("{} — {}", a, b));
     }
 }
 
-fn print_json(graph: &graph::Graph, path: &path::Path) -> Result<()> {
-    let output = graph.path_output(path);
-    let json =
-        serde_json::to_string_pretty(&output).context("Failed to serialize output to JSON")?;
-    println!("{}", json);
-    Ok(())
+/// Prints tree totals and per-destination stretch as OpenMetrics gauges.
+fn print_backbone_openmetrics(output: &io::BackboneOutput) {
+    let total = openmetrics::Gauge::new(
+        "gt_path_backbone_latency_seconds",
+        "Sum of the link latencies of the tree",
+    )
+    .unit("seconds")
+    .sample(
+        vec![("tree", "mst".to_string())],
+        output.mst.total_latency_ms as f64 / 1000.0,
+    )
+    .sample(
+        vec![("tree", "spt".to_string())],
+        output.spt.total_latency_ms as f64 / 1000.0,
+    );
+    let mut stretch = openmetrics::Gauge::new(
+        "gt_path_backbone_stretch",
+        "Latency along the spanning tree over the shortest path latency",
+    );
+    for d in &output.destinations {
+        stretch = stretch.sample(
+            vec![("root", output.root.clone()), ("to", d.node.clone())],
+            d.stretch,
+        );
+    }
+    print!("{}", openmetrics::render(&[total, stretch]));
 }
 
-fn run_check_slo(
-    graph_file: &str,
-    from: &str,
-    to: &str,
-    max_latency: u32,
-    format: OutputFormat,
-) -> (Result<()>, i32) {
-    let graph = match graph::Graph::load_json(graph_file)
-        .context(format!("Failed to load graph from {}", graph_file))
+fn run_stretch(args: &StretchArgs) -> (Result<()>, i32) {
+    if let Some(t) = args.max_stretch
+        && (t.is_nan() || t < 1.0)
     {
-        Ok(g) => g,
+        let e = anyhow::anyhow!("--max-stretch must be at least 1, got {}", t);
+        return (Err(e), EXIT_INVALID_INPUT);
+    }
+    let loaded = args.constraints.parse().and_then(|constraints| {
+        let graph =
+            load_graph(&args.graph).context(format!("Failed to load graph from {}", args.graph))?;
+        let kept = read_edge_subset(&graph, &args.subset)?;
+        Ok((constraints, graph, kept))
+    });
+    let (constraints, graph, kept) = match loaded {
+        Ok(loaded) => loaded,
         Err(e) => return (Err(e), EXIT_INVALID_INPUT),
     };
 
-    let path = match graph
-        .shortest_path(from, to)
-        .context(format!("Failed to find path from {} to {}", from, to))
-    {
-        Ok(p) => p,
-        Err(e) => return (Err(e), EXIT_NO_PATH),
+    let opts = stretch::StretchOptions {
+        sample: args.sample,
+        seed: args.seed,
+        top: args.top,
+        max_stretch: args.max_stretch,
     };
-
-    let slo_met = path.cost <= max_latency;
-    let exit_code = if slo_met {
-        EXIT_SUCCESS
-    } else {
-        EXIT_SLO_VIOLATED
+    let output = stretch::measure(&graph, &kept, &constraints, &opts);
+    let exit_code = match output.spanner {
+        Some(false) => EXIT_SLO_VIOLATED,
+        _ => EXIT_SUCCESS,
     };
 
-    let result = match format {
-        OutputFormat::Text => {
-            print_slo_text(&graph, &path, max_latency, slo_met);
+    let result = match args.format {
+        MetricsFormat::Text => {
+            if !progress::quiet() {
+                print_stretch_text(&output);
+            }
+            Ok(())
+        }
+        MetricsFormat::Json => print_json(&output),
+        MetricsFormat::OpenMetrics => {
+            print_stretch_openmetrics(&output);
             Ok(())
         }
-        OutputFormat::Json => print_slo_json(&graph, &path, max_latency, slo_met),
     };
 
     (result, exit_code)
 }
 
-fn print_slo_text(graph: &graph::Graph, path: &path::Path, max_latency: u32, slo_met: bool) {
-    println!("SLO Check:");
-    println!("  Route: {}", graph.format_path(path));
-    println!("  Actual Latency: {}ms", path.cost);
-    println!("  Max Allowed: {}ms", max_latency);
-    println!("  Status: {}", if slo_met { "✓ PASS" } else { "✗ FAIL" });
-
-    if let Some(bottleneck) = &path.bottleneck {
-        let from_name = &graph.to_name[bottleneck.from.0 as usize];
-        let to_name = &graph.to_name[bottleneck.to.0 as usize];
+fn print_stretch_text(output: &io::SpannerOutput) {
+    let factor = |s: Option<f64>| s.map_or("-".to_string(), |s| format!("{:.2}x", s));
+    println!(
+        "Stretch ({} of {} edges kept):",
+        output.kept_edges, output.edges
+    );
+    if output.sampled {
         println!(
-            "  Bottleneck: {} → {} ({}ms)",
-            from_name, to_name, bottleneck.latency_ms
+            "  Pairs: {} (from {} sampled nodes)",
+            output.pairs, output.sources
+        );
+    } else {
+        println!("  Pairs: {}", output.pairs);
+    }
+    println!("  Disconnected Pairs: {}", output.disconnected);
+    println!("  Max Stretch: {}", factor(output.max_stretch));
+    println!("  Mean Stretch: {}", factor(output.mean_stretch));
+    if let (Some(allowed), Some(spanner)) = (output.max_allowed, output.spanner) {
+        println!(
+            "  Status: {} (max {:.2}x)",
+            if spanner { "✓ PASS" } else { "✗ FAIL" },
+            allowed
         );
     }
-}
-
-fn print_slo_json(
-    graph: &graph::Graph,
-    path: &path::Path,
-    max_latency: u32,
-    slo_met: bool,
-) -> Result<()> {
-    use serde_json::json;
 
-    let path_output = graph.path_output(path);
-    let output = json!({
-        "slo_met": slo_met,
-        "max_latency_ms": max_latency,
-        "actual_latency_ms": path.cost,
-        "path": path_output,
+    listing::section("Most stretched pairs:", output.worst.iter(), |p| {
+        match (p.subset_latency_ms, p.stretch) {
+            (Some(subset), Some(stretch)) => format!(
+                "{} → {}: {} vs {} ({:.2}x)",
+                p.from,
+                p.to,
+                units::ms(subset),
+                units::ms(p.latency_ms),
+                stretch
+            ),
+            _ => format!(
+                "{} → {}: disconnected vs {}",
+                p.from,
+                p.to,
+                units::ms(p.latency_ms)
+            ),
+        }
     });
-
-    let json =
-        serde_json::to_string_pretty(&output).context("Failed to serialize output to JSON")?;
-    println!("{}", json);
-    Ok(())
 }
 
-fn run_simulate(
-    graph_file: &str,
-    from: &str,
-    to: &str,
-    overrides_raw: &[String],
-    drop_raw: &[String],
-    format: OutputFormat,
-) -> Result<()> {
-    let mut overrides = Vec::new();
-    for override_str in overrides_raw {
-        let parts: Vec<&str> = override_str.split(':').collect();
-        if parts.len() != 3 {
-            anyhow::bail!(
-                "Invalid override format '{}'. Expected 'from:to:weight'",
-                override_str
-            );
+/// Prints stretch summary gauges; pairs are left to the JSON output.
+fn print_stretch_openmetrics(output: &io::SpannerOutput) {
+    let mut stretch = openmetrics::Gauge::new(
+        "gt_path_stretch",
+        "Latency using only the edge subset over the whole graph's",
+    );
+    for (stat, value) in [("max", output.max_stretch), ("mean", output.mean_stretch)] {
+        if let Some(value) = value {
+            stretch = stretch.sample(vec![("stat", stat.to_string())], value);
         }
-        let weight = parts[2].parse::<u32>().context(format!(
-            "Invalid weight '{}' in override '{}'",
-            parts[2], override_str
-        ))?;
-        overrides.push((parts[0].to_string(), parts[1].to_string(), weight));
     }
+    let disconnected = openmetrics::Gauge::new(
+        "gt_path_stretch_disconnected_pairs",
+        "Pairs the edge subset disconnects",
+    )
+    .sample(vec![], output.disconnected as f64);
+    print!("{}", openmetrics::render(&[stretch, disconnected]));
+}
 
-    let mut drops = Vec::new();
-    for drop_str in drop_raw {
-        let parts: Vec<&str> = drop_str.split(':').collect();
-        if parts.len() != 2 {
-            anyhow::bail!("Invalid drop format '{}'. Expected 'from:to'", drop_str);
-        }
-        drops.push((parts[0].to_string(), parts[1].to_string()));
-    }
+fn run_matrix(args: &MatrixArgs) -> Result<()> {
+    use gt_path_core::oracle::{self, DistanceOracle, UNREACHABLE};
 
-    let graph = graph::Graph::load_json(graph_file)
-        .context(format!("Failed to load graph from {}", graph_file))?;
+    if args.max_error.is_nan() || args.max_error < 0.0 {
+        anyhow::bail!("--max-error must be non-negative, got {}", args.max_error);
+    }
+    let constraints = args.constraints.parse()?;
+    let graph = load_graph(&args.graph)
+        .context(format!("Failed to load graph from {}", args.graph))
+        .and_then(|g| resolve_at(g, args.at.as_deref()))?;
 
-    let original_path = graph
-        .shortest_path(from, to)
-        .context(format!("Failed to find path from {} to {}", from, to))?;
+    let nodes = if args.nodes.is_empty() {
+        graph.to_name.iter().map(|n| n.to_string()).collect()
+    } else {
+        args.nodes.clone()
+    };
+    let ids = nodes
+        .iter()
+        .map(|name| {
+            graph
+                .to_id
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| gt_path_core::PathError::NodeNotFound(name.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let modified_graph = graph
-        .with_modifications(&overrides, &drops)
-        .context("Failed to apply modifications to graph")?;
+    let oracle = args.landmarks.map(|k| {
+        let key =
+            DiskCache::key(format!("{}|{}|{:?}", graph.content_hash(), k, constraints).as_bytes());
+        if let Some(oracle) = CACHE.get().and_then(|c| c.get("landmarks", &key)) {
+            return oracle;
+        }
+        let oracle = DistanceOracle::build(&graph, k as usize, &constraints);
+        cache_put("landmarks", &key, &oracle);
+        oracle
+    });
+    let mut exact_rows = 0;
+    let bar = progress::items(ids.len() as u64, "Computing latencies");
+    let latency_ms = bar
+        .wrap_iter(ids.iter())
+        .map(|&u| {
+            let estimates: Option<Vec<Option<u32>>> = oracle.as_ref().map(|o| {
+                ids.iter()
+                    .map(|&v| o.estimate(u, v, args.max_error))
+                    .collect()
+            });
+            if let Some(row) = estimates.filter(|row| row.iter().all(Option::is_some)) {
+                return row;
+            }
+            exact_rows += 1;
+            let exact = oracle::distances(&graph, u, &constraints);
+            ids.iter()
+                .map(|&v| Some(exact[v.0 as usize]).filter(|&d| d != UNREACHABLE))
+                .collect()
+        })
+        .collect();
+    bar.finish_and_clear();
 
-    let new_path = modified_graph.shortest_path(from, to).context(format!(
-        "Failed to find path from {} to {} in modified graph",
-        from, to
-    ))?;
+    let output = io::MatrixOutput {
+        nodes,
+        latency_ms,
+        approximate: oracle.is_some(),
+        landmarks: oracle
+            .as_ref()
+            .map(|o| {
+                o.landmarks
+                    .iter()
+                    .map(|id| graph.to_name[id.0 as usize].to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        max_error: oracle.as_ref().map(|_| args.max_error),
+        exact_rows: if oracle.is_some() { exact_rows } else { 0 },
+        graph_hash: graph.content_hash().to_string(),
+    };
 
-    match format {
-        OutputFormat::Text => {
-            print_simulate_text(&graph, &modified_graph, &original_path, &new_path)
-        }
-        OutputFormat::Json => {
-            print_simulate_json(&graph, &modified_graph, &original_path, &new_path)?
-        }
+    match args.format {
+        OutputFormat::Text if progress::quiet() => {}
+        OutputFormat::Text => print_matrix_text(&output),
+        OutputFormat::Json => print_json(&output)?,
     }
 
     Ok(())
 }
 
-fn print_simulate_text(
-    original_graph: &graph::Graph,
-    modified_graph: &graph::Graph,
-    original_path: &path::Path,
-    new_path: &path::Path,
-) {
-    println!("Simulation Results:");
-    println!();
-    println!("Original Path:");
-    println!("  Route: {}", original_graph.format_path(original_path));
-    println!("  Latency: {}ms", original_path.cost);
+fn print_matrix_text(output: &io::MatrixOutput) {
+    let cell = |d: &Option<u32>| {
+        d.map_or("-".to_string(), |ms| {
+            if units::human() {
+                units::ms(ms)
+            } else {
+                ms.to_string()
+            }
+        })
+    };
+    let unit = if units::human() { "" } else { "ms, " };
+    let width = output
+        .nodes
+        .iter()
+        .map(|n| n.chars().count())
+        .chain(output.latency_ms.iter().flatten().map(|d| cell(d).len()))
+        .max()
+        .unwrap_or(1);
 
-    if let Some(bottleneck) = &original_path.bottleneck {
-        let from_name = &original_graph.to_name[bottleneck.from.0 as usize];
-        let to_name = &original_graph.to_name[bottleneck.to.0 as usize];
+    if output.approximate {
         println!(
-            "  Bottleneck: {} → {} ({}ms)",
-            from_name, to_name, bottleneck.latency_ms
+            "Latency Matrix ({}landmark estimates within {}%):",
+            unit,
+            output.max_error.unwrap_or_default() * 100.0
         );
-    }
-
-    println!();
-    println!("Modified Path:");
-    println!("  Route: {}", modified_graph.format_path(new_path));
-    println!("  Latency: {}ms", new_path.cost);
-
-    if let Some(bottleneck) = &new_path.bottleneck {
-        let from_name = &modified_graph.to_name[bottleneck.from.0 as usize];
-        let to_name = &modified_graph.to_name[bottleneck.to.0 as usize];
+        println!("  Landmarks: {}", output.landmarks.join(", "));
+        println!("  Exact Rows: {}", output.exact_rows);
+    } else {
         println!(
-            "  Bottleneck: {} → {} ({}ms)",
-            from_name, to_name, bottleneck.latency_ms
+            "Latency Matrix{}:",
+            if units::human() { "" } else { " (ms)" }
         );
     }
 
-    println!();
-    let diff = new_path.cost as i64 - original_path.cost as i64;
-    let change = if diff > 0 {
-        format!("+{}ms (slower)", diff)
-    } else if diff < 0 {
-        format!("{}ms (faster)", diff)
+    if listing::summary_only() {
+        return;
+    }
+    // the row limit cuts columns too, marking the cut with a … column
+    let shown = listing::shown(output.nodes.len());
+    let cut = if shown < output.nodes.len() {
+        "  …"
     } else {
-        "no change".to_string()
+        ""
     };
-    println!("Impact: {}", change);
+    println!();
+    let header: Vec<String> = output.nodes[..shown]
+        .iter()
+        .map(|n| format!("{:>width$}", n))
+        .collect();
+    println!("  {:width$}  {}{}", "", header.join("  "), cut);
+    for (name, row) in output.nodes.iter().zip(&output.latency_ms).take(shown) {
+        let cells: Vec<String> = row[..shown]
+            .iter()
+            .map(|d| format!("{:>width$}", cell(d)))
+            .collect();
+        println!("  {:width$}  {}{}", name, cells.join("  "), cut);
+    }
+    if let Some(more) = listing::more(shown, output.nodes.len()) {
+        println!("  {} rows and columns", more);
+    }
 }
 
-fn print_simulate_json(
-    original_graph: &graph::Graph,
-    modified_graph: &graph::Graph,
-    original_path: &path::Path,
-    new_path: &path::Path,
-) -> Result<()> {
-    use serde_json::json;
+fn run_budget(args: &BudgetArgs) -> (Result<()>, i32) {
+    let (graph_file, from, to) = (args.graph.as_str(), args.from.as_str(), args.to.as_str());
+    let constraints = match args.constraints.parse() {
+        Ok(c) => c,
+        Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+    };
 
-    let original_output = original_graph.path_output(original_path);
-    let new_output = modified_graph.path_output(new_path);
+    let graph =
+        match load_graph(graph_file).context(format!("Failed to load graph from {}", graph_file)) {
+            Ok(g) => g,
+            Err(e) => return (Err(e), EXIT_INVALID_INPUT),
+        };
 
-    let output = json!({
-        "original": original_output,
-        "modified": new_output,
-        "latency_change_ms": new_path.cost as i64 - original_path.cost as i64,
-    });
+    let path = match graph
+        .shortest_path_with(from, to, &constraints)
+        .context(format!("Failed to find path from {} to {}", from, to))
+    {
+        Ok(p) => p,
+        Err(e) => return (Err(e), EXIT_NO_PATH),
+    };
 
-    let json =
-        serde_json::to_string_pretty(&output).context("Failed to serialize output to JSON")?;
-    println!("{}", json);
-    Ok(())
+    let (policy, policy_name) = match args.policy {
+        BudgetPolicyArg::Proportional => (budget::BudgetPolicy::Proportional, "proportional"),
+        BudgetPolicyArg::Equal => (budget::BudgetPolicy::Equal, "equal"),
+    };
+    let hops = budget::allocate(&path, args.max_latency, policy);
+
+    let slo_met = path.cost <= args.max_latency;
+    let exit_code = if slo_met {
+        EXIT_SUCCESS
+    } else {
+        EXIT_SLO_VIOLATED
+    };
+
+    let output = io::BudgetOutput {
+        policy: policy_name.to_string(),
+        max_latency_ms: args.max_latency,
+        actual_latency_ms: path.cost,
+        slo_met,
+        hops: hops
+            .iter()
+            .map(|h| io::HopBudgetOutput {
+                id: graph.edge_ids[h.edge.index].clone(),
+                from: graph.to_name[h.edge.from.0 as usize].clone(),
+                to: graph.to_name[h.edge.to.0 as usize].clone(),
+                latency_ms: h.edge.latency_ms,
+                budget_ms: h.budget_ms,
+                headroom_ms: h.headroom_ms(),
+            })
+            .collect(),
+        path: graph.path_output(&path),
+    };
+
+    let result = match args.format {
+        OutputFormat::Text => {
+            if !progress::quiet() {
+                print_budget_text(&graph, &path, &output);
+            }
+            Ok(())
+        }
+        OutputFormat::Json => print_json(&output),
+    };
+
+    (result, exit_code)
+}
+
+fn print_budget_text(graph: &graph::Graph, path: &path::Path, output: &io::BudgetOutput) {
+    println!("Latency Budget ({}):", output.policy);
+    println!("  Route: {}", graph.format_path(path));
+    println!("  Actual Latency: {}", units::ms(output.actual_latency_ms));
+    println!("  Max Allowed: {}", units::ms(output.max_latency_ms));
+    println!(
+        "  Status: {}",
+        if output.slo_met {
+            "✓ PASS"
+        } else {
+            "✗ FAIL"
+        }
+    );
+
+    println!();
+    println!("Per-hop budgets:");
+    for hop in &output.hops {
+        println!(
+            "  {} → {}: {} / {} budget ({}){}",
+            hop.from,
+            hop.to,
+            units::ms(hop.latency_ms),
+            units::ms_f(hop.budget_ms, 1),
+            units::ms_delta(hop.headroom_ms, 1),
+            if hop.headroom_ms < 0.0 {
+                " ✗ OVER"
+            } else {
+                ""
+            }
+        );
+    }
 }
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+
+/// A gauge and its samples, one per label set.
+pub(crate) struct Gauge {
+    pub(crate) name: &'static str,
+    pub(crate) help: &'static str,
+    /// OpenMetrics unit; `name` must end in `_<unit>`
+    pub(crate) unit: Option<&'static str>,
+    pub(crate) samples: Vec<(Vec<(&'static str, String)>, f64)>,
+}
+
+impl Gauge {
+    pub(crate) fn new(name: &'static str, help: &'static str) -> Gauge {
+        Gauge {
+            name,
+            help,
+            unit: None,
+            samples: Vec::new(),
+        }
+    }
+
+    pub(crate) fn unit(mut self, unit: &'static str) -> Gauge {
+        self.unit = Some(unit);
+        self
+    }
+
+    pub(crate) fn sample(mut self, labels: Vec<(&'static str, String)>, value: f64) -> Gauge {
+        self.samples.push((labels, value));
+        self
+    }
+}
+
+/// A histogram without labels, in cumulative buckets.
+pub(crate) struct Histogram {
+    pub(crate) name: &'static str,
+    pub(crate) help: &'static str,
+    /// OpenMetrics unit; `name` must end in `_<unit>`
+    pub(crate) unit: Option<&'static str>,
+    /// Upper bound of each bucket and the observations at or below it,
+    /// ascending; the `+Inf` bucket is `count`
+    pub(crate) buckets: Vec<(f64, u64)>,
+    pub(crate) sum: f64,
+    pub(crate) count: u64,
+}
+
+/// Renders the gauges in the OpenMetrics text format, ending with the
+/// `# EOF` marker. node_exporter's textfile collector reads this as well.
+pub(crate) fn render(gauges: &[Gauge]) -> String {
+    render_with_histograms(gauges, &[])
+}
+
+/// Renders gauges followed by histograms, like `render`.
+pub(crate) fn render_with_histograms(gauges: &[Gauge], histograms: &[Histogram]) -> String {
+    let mut out = String::new();
+    for g in gauges {
+        let _ = writeln!(out, "# TYPE {} gauge", g.name);
+        if let Some(unit) = g.unit {
+            let _ = writeln!(out, "# UNIT {} {}", g.name, unit);
+        }
+        let _ = writeln!(out, "# HELP {} {}", g.name, g.help);
+        for (labels, value) in &g.samples {
+            out += g.name;
+            if !labels.is_empty() {
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+                    .collect();
+                let _ = write!(out, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(out, " {}", value);
+        }
+    }
+    for h in histograms {
+        let _ = writeln!(out, "# TYPE {} histogram", h.name);
+        if let Some(unit) = h.unit {
+            let _ = writeln!(out, "# UNIT {} {}", h.name, unit);
+        }
+        let _ = writeln!(out, "# HELP {} {}", h.name, h.help);
+        // bounds are written as canonical floats, 1.0 rather than 1
+        for (bound, count) in &h.buckets {
+            let _ = writeln!(out, "{}_bucket{{le=\"{:?}\"}} {}", h.name, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", h.name, h.count);
+        let _ = writeln!(out, "{}_sum {}", h.name, h.sum);
+        let _ = writeln!(out, "{}_count {}", h.name, h.count);
+    }
+    out + "# EOF\n"
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let gauges = [
+            Gauge::new("gt_path_route_latency_seconds", "Route latency")
+                .unit("seconds")
+                .sample(
+                    vec![("from", "api".to_string()), ("to", "d\"b".to_string())],
+                    0.008,
+                ),
+            Gauge::new("gt_path_slo_routes", "Routes checked").sample(vec![], 2.0),
+        ];
+        assert_eq!(
+            render(&gauges),
+            "# TYPE gt_path_route_latency_seconds gauge\n\
+             # UNIT gt_path_route_latency_seconds seconds\n\
+             # HELP gt_path_route_latency_seconds Route latency\n\
+             gt_path_route_latency_seconds{from=\"api\",to=\"d\\\"b\"} 0.008\n\
+             # TYPE gt_path_slo_routes gauge\n\
+             # HELP gt_path_slo_routes Routes checked\n\
+             gt_path_slo_routes 2\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn test_render_histogram() {
+        let histogram = Histogram {
+            name: "gt_path_daemon_query_duration_seconds",
+            help: "Time to answer a query",
+            unit: Some("seconds"),
+            buckets: vec![(0.001, 1), (1.0, 3)],
+            sum: 0.0205,
+            count: 4,
+        };
+        assert_eq!(
+            render_with_histograms(&[], &[histogram]),
+            "# TYPE gt_path_daemon_query_duration_seconds histogram\n\
+             # UNIT gt_path_daemon_query_duration_seconds seconds\n\
+             # HELP gt_path_daemon_query_duration_seconds Time to answer a query\n\
+             gt_path_daemon_query_duration_seconds_bucket{le=\"0.001\"} 1\n\
+             gt_path_daemon_query_duration_seconds_bucket{le=\"1.0\"} 3\n\
+             gt_path_daemon_query_duration_seconds_bucket{le=\"+Inf\"} 4\n\
+             gt_path_daemon_query_duration_seconds_sum 0.0205\n\
+             gt_path_daemon_query_duration_seconds_count 4\n\
+             # EOF\n"
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use anyhow::Context;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{Attributes, EdgeInput, GraphInput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Edges that could be added to the graph, with what each would cost.
+///
+/// Expected format:
+/// ```json
+/// {
+///   "candidates": [
+///     { "from": "api", "to": "db", "latency_ms": 4.0, "cost": 30.0 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub(crate) struct CandidatePool {
+    pub(crate) candidates: Vec<Candidate>,
+}
+
+/// A possible new edge.
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct Candidate {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) latency_ms: f32,
+    /// Cost of adding the edge, in any unit the budget uses
+    pub(crate) cost: f64,
+}
+
+impl CandidatePool {
+    /// Reads a candidate pool and checks it against the graph's nodes.
+    pub(crate) fn read(path: &str, graph: &GraphInput) -> anyhow::Result<CandidatePool> {
+        let contents =
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+        let pool: CandidatePool =
+            serde_json::from_str(&contents).context("Failed to parse JSON")?;
+
+        let names: HashSet<&str> = graph.nodes.iter().map(|n| n.name()).collect();
+        for c in &pool.candidates {
+            if !names.contains(c.from.as_str()) || !names.contains(c.to.as_str()) {
+                anyhow::bail!("candidate {}->{} uses an unknown node", c.from, c.to);
+            }
+            if c.from == c.to || c.latency_ms < 0.0 || c.cost.is_nan() || c.cost < 0.0 {
+                anyhow::bail!(
+                    "candidate {}->{} needs distinct endpoints and non-negative latency and cost",
+                    c.from,
+                    c.to
+                );
+            }
+        }
+        Ok(pool)
+    }
+}
+
+/// What the optimizer tries to minimize.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Objective {
+    /// Highest shortest-path latency over the key routes
+    MaxLatency,
+    /// Bridges of the graph read as undirected
+    Bridges,
+}
+
+/// Settings for a simulated annealing run.
+pub(crate) struct OptimizeOptions {
+    pub(crate) objective: Objective,
+    /// Routes whose latency is scored by `Objective::MaxLatency`
+    pub(crate) routes: Vec<(String, String)>,
+    /// Most the chosen candidates may cost in total
+    pub(crate) budget: f64,
+    pub(crate) iterations: u32,
+    pub(crate) seed: u64,
+}
+
+/// The best change set found.
+#[derive(PartialEq)]
+pub(crate) struct Plan {
+    /// Indices of the chosen candidates, ascending
+    pub(crate) chosen: Vec<usize>,
+    pub(crate) total_cost: f64,
+    /// Objective value before any change
+    pub(crate) before: f64,
+    /// Objective value with the chosen candidates added
+    pub(crate) after: f64,
+}
+
+/// Objective value of a route without a path: worse than any latency.
+pub(crate) const UNREACHABLE: f64 = 1e9;
+
+/// Searches for a set of candidate edges within the budget that minimizes
+/// the objective, using simulated annealing.
+///
+/// Each step adds or removes one random candidate (skipping additions that
+/// break the budget) and keeps the change if it helps, or with a
+/// probability that shrinks as the temperature cools if it hurts. Among
+/// equally good sets the cheaper one wins. The result is a good set, not a
+/// proven optimum; the same seed always gives the same result.
+pub(crate) fn anneal(
+    graph: &GraphInput,
+    pool: &CandidatePool,
+    constraints: &Constraints,
+    opts: &OptimizeOptions,
+) -> anyhow::Result<Plan> {
+    let k = pool.candidates.len();
+    let score = |chosen: &[bool]| -> anyhow::Result<(f64, f64)> {
+        let cost = (0..k)
+            .filter(|&i| chosen[i])
+            .fold(0.0, |sum, i| sum + pool.candidates[i].cost);
+        let g = with_candidates(graph, pool, chosen)?;
+        Ok((objective(&g, opts, constraints), cost))
+    };
+    // cost only breaks ties between equally good sets
+    let tiebreak = 1e-6 / opts.budget.max(1.0);
+    let energy = |(value, cost): (f64, f64)| value + cost * tiebreak;
+
+    let mut current = vec![false; k];
+    let mut current_score = score(&current)?;
+    let before = current_score.0;
+    let (mut best, mut best_score) = (current.clone(), current_score);
+
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let start_temp = (before.min(UNREACHABLE / 1e6) * 0.1).max(1.0);
+    for step in 0..opts.iterations {
+        if k == 0 {
+            break;
+        }
+        let temp = start_temp * (1.0 - step as f64 / opts.iterations as f64) + 1e-9;
+        let i = rng.gen_range(0..k);
+        current[i] = !current[i];
+
+        if current[i] && current_score.1 + pool.candidates[i].cost > opts.budget {
+            current[i] = false;
+            continue;
+        }
+        let candidate_score = score(&current)?;
+        let delta = energy(candidate_score) - energy(current_score);
+        if delta <= 0.0 || rng.gen_bool((-delta / temp).exp().min(1.0)) {
+            current_score = candidate_score;
+            if energy(current_score) < energy(best_score) {
+                best = current.clone();
+                best_score = current_score;
+            }
+        } else {
+            current[i] = !current[i];
+        }
+    }
+
+    Ok(Plan {
+        chosen: (0..k).filter(|&i| best[i]).collect(),
+        total_cost: best_score.1,
+        before,
+        after: best_score.0,
+    })
+}
+
+/// The graph with the chosen candidates added as edges.
+pub(crate) fn with_candidates(
+    graph: &GraphInput,
+    pool: &CandidatePool,
+    chosen: &[bool],
+) -> anyhow::Result<Graph> {
+    Graph::try_from(apply(graph, pool, chosen)).context("Candidate edges produced an invalid graph")
+}
+
+/// The graph input with the chosen candidates appended to its edges.
+pub(crate) fn apply(graph: &GraphInput, pool: &CandidatePool, chosen: &[bool]) -> GraphInput {
+    let mut input = graph.clone();
+    for (c, _) in pool.candidates.iter().zip(chosen).filter(|(_, on)| **on) {
+        input.edges.push(EdgeInput {
+            from: c.from.clone(),
+            to: c.to.clone(),
+            id: None,
+            latency_ms: Some(c.latency_ms),
+            timeline: Vec::new(),
+            bidirectional: false,
+            attrs: Attributes::new(),
+        });
+    }
+    input
+}
+
+/// Latency of a route in the graph, or `UNREACHABLE`.
+pub(crate) fn route_latency(g: &Graph, from: &str, to: &str, constraints: &Constraints) -> f64 {
+    g.shortest_path_with(from, to, constraints)
+        .map_or(UNREACHABLE, |p| p.cost as f64)
+}
+
+fn objective(g: &Graph, opts: &OptimizeOptions, constraints: &Constraints) -> f64 {
+    match opts.objective {
+        Objective::MaxLatency => opts
+            .routes
+            .iter()
+            .map(|(from, to)| route_latency(g, from, to, constraints))
+            .fold(0.0, f64::max),
+        Objective::Bridges => count_bridges(g) as f64,
+    }
+}
+
+/// Number of bridges of the graph read as undirected, where parallel or
+/// opposite edges between two nodes count as one link that is never a
+/// bridge.
+pub(crate) fn count_bridges(g: &Graph) -> usize {
+    let n = g.to_name.len();
+    let mut links: HashSet<(usize, usize)> = HashSet::new();
+    let mut doubled: HashSet<(usize, usize)> = HashSet::new();
+    for (u, out) in g.adj.iter().enumerate() {
+        for link in out {
+            let v = link.to.0 as usize;
+            let key = (u.min(v), u.max(v));
+            if !links.insert(key) {
+                doubled.insert(key);
+            }
+        }
+    }
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in &links {
+        adj[u].push(v);
+        adj[v].push(u);
+    }
+
+    // iterative Tarjan low-link
+    let mut disc = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut time = 0;
+    let mut bridges = 0;
+    for root in 0..n {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        // (node, parent, next neighbor index)
+        let mut stack = vec![(root, usize::MAX, 0)];
+        while let Some(&mut (u, parent, ref mut next)) = stack.last_mut() {
+            if let Some(&v) = adj[u].get(*next) {
+                *next += 1;
+                if disc[v] == usize::MAX {
+                    disc[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    stack.push((v, u, 0));
+                } else if v != parent {
+                    low[u] = low[u].min(disc[v]);
+                }
+            } else {
+                stack.pop();
+                if parent != usize::MAX {
+                    low[parent] = low[parent].min(low[u]);
+                    let key = (u.min(parent), u.max(parent));
+                    if low[u] > disc[parent] && !doubled.contains(&key) {
+                        bridges += 1;
+                    }
+                }
+            }
+        }
+    }
+    bridges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> GraphInput {
+        serde_json::from_str(
+            r#"{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5},
+                {"from": "auth", "to": "db", "latency_ms": 30},
+                {"from": "api", "to": "cache", "latency_ms": 7}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn pool() -> CandidatePool {
+        serde_json::from_str(
+            r#"{"candidates": [
+                {"from": "api", "to": "db", "latency_ms": 10, "cost": 50},
+                {"from": "cache", "to": "db", "latency_ms": 2, "cost": 20},
+                {"from": "auth", "to": "db", "latency_ms": 1, "cost": 500}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn opts(objective: Objective, budget: f64) -> OptimizeOptions {
+        OptimizeOptions {
+            objective,
+            routes: vec![("api".to_string(), "db".to_string())],
+            budget,
+            iterations: 500,
+            seed: 1,
+        }
+    }
+
+    #[test]
+    fn test_picks_cheapest_improvement() {
+        let plan = anneal(
+            &graph(),
+            &pool(),
+            &Constraints::default(),
+            &opts(Objective::MaxLatency, 100.0),
+        )
+        .unwrap();
+        // cache → db gives 9ms for 20; api → db would give 10ms for 50
+        assert_eq!(plan.before, 35.0);
+        assert_eq!(plan.after, 9.0);
+        assert_eq!(plan.chosen, vec![1]);
+        assert_eq!(plan.total_cost, 20.0);
+    }
+
+    #[test]
+    fn test_bridges_objective() {
+        let g = Graph::try_from(graph()).unwrap();
+        assert_eq!(count_bridges(&g), 3);
+
+        let plan = anneal(
+            &graph(),
+            &pool(),
+            &Constraints::default(),
+            &opts(Objective::Bridges, 100.0),
+        )
+        .unwrap();
+        // cache → db closes the cycle api-auth-db-cache, leaving no bridges
+        assert_eq!(plan.after, 0.0);
+        assert_eq!(plan.chosen, vec![1]);
+    }
+}
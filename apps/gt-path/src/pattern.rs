@@ -0,0 +1,309 @@
+use anyhow::Context;
+use gt_path_core::io::{GraphInput, NodeInput};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// A small graph to search for, in the graph input format. Edges need no
+/// latency, and node attributes restrict which nodes may match.
+///
+/// Expected format:
+/// ```json
+/// {
+///   "nodes": ["service", {"name": "cache", "kind": "redis"}, "db"],
+///   "edges": [
+///     { "from": "service", "to": "cache" },
+///     { "from": "cache", "to": "db" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub(crate) struct PatternInput {
+    pub(crate) nodes: Vec<NodeInput>,
+    pub(crate) edges: Vec<PatternEdge>,
+}
+
+/// A directed edge the pattern requires.
+#[derive(Debug, Deserialize)]
+pub(crate) struct PatternEdge {
+    pub(crate) from: String,
+    pub(crate) to: String,
+}
+
+impl PatternInput {
+    /// Reads a pattern file and checks that its edges name declared nodes.
+    pub(crate) fn read(path: &str) -> anyhow::Result<PatternInput> {
+        let contents =
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+        let pattern: PatternInput =
+            serde_json::from_str(&contents).context("Failed to parse JSON")?;
+
+        let names: HashSet<&str> = pattern.nodes.iter().map(|n| n.name()).collect();
+        if names.len() != pattern.nodes.len() {
+            anyhow::bail!("pattern declares a node more than once");
+        }
+        for e in &pattern.edges {
+            for end in [&e.from, &e.to] {
+                if !names.contains(end.as_str()) {
+                    anyhow::bail!(
+                        "pattern edge {}->{} uses undeclared node '{}'",
+                        e.from,
+                        e.to,
+                        end
+                    );
+                }
+            }
+        }
+        Ok(pattern)
+    }
+}
+
+/// Out- and in-neighbor sets of a graph, indexed by node position.
+struct Adjacency {
+    out: Vec<HashSet<usize>>,
+    inc: Vec<HashSet<usize>>,
+}
+
+impl Adjacency {
+    fn new(n: usize, edges: impl Iterator<Item = (usize, usize)>) -> Adjacency {
+        let mut adj = Adjacency {
+            out: vec![HashSet::new(); n],
+            inc: vec![HashSet::new(); n],
+        };
+        for (u, v) in edges {
+            adj.out[u].insert(v);
+            adj.inc[v].insert(u);
+        }
+        adj
+    }
+}
+
+/// Finds occurrences of `pattern` in `graph` with VF2-style backtracking:
+/// pattern nodes are bound one at a time, each new node connected to one
+/// already bound where possible, and a partial binding is abandoned as soon
+/// as a pattern edge is missing or a degree can't fit.
+///
+/// A match binds each pattern node to a distinct graph node so that every
+/// pattern edge is a graph edge (extra graph edges are allowed) and every
+/// pattern node attribute equals the graph node's. Symmetric patterns match
+/// the same nodes once per symmetry.
+///
+/// Returns up to `limit` matches, each a graph node index per pattern node,
+/// in search order.
+pub(crate) fn find_matches(
+    pattern: &PatternInput,
+    graph: &GraphInput,
+    limit: usize,
+) -> Vec<Vec<usize>> {
+    let p = pattern.nodes.len();
+    let p_index: HashMap<&str, usize> = pattern
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.name(), i))
+        .collect();
+    let g_index: HashMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.name(), i))
+        .collect();
+
+    let pa = Adjacency::new(
+        p,
+        pattern
+            .edges
+            .iter()
+            .map(|e| (p_index[e.from.as_str()], p_index[e.to.as_str()])),
+    );
+    let ga = Adjacency::new(
+        graph.nodes.len(),
+        graph
+            .edges
+            .iter()
+            .map(|e| (g_index[e.from.as_str()], g_index[e.to.as_str()])),
+    );
+
+    let mut search = Search {
+        pattern,
+        graph,
+        pa: &pa,
+        ga: &ga,
+        order: match_order(&pa),
+        binding: vec![usize::MAX; p],
+        used: vec![false; graph.nodes.len()],
+        matches: Vec::new(),
+        limit,
+    };
+    if p > 0 && limit > 0 {
+        search.extend(0);
+    }
+    search.matches
+}
+
+/// Orders pattern nodes so each one after the first in its component is
+/// adjacent to an earlier one, starting from the highest-degree node.
+fn match_order(pa: &Adjacency) -> Vec<usize> {
+    let p = pa.out.len();
+    let degree = |v: usize| pa.out[v].len() + pa.inc[v].len();
+    let mut order = Vec::with_capacity(p);
+    let mut placed = vec![false; p];
+    while order.len() < p {
+        let start = (0..p)
+            .filter(|&v| !placed[v])
+            .max_by_key(|&v| (degree(v), std::cmp::Reverse(v)))
+            .expect("unplaced node");
+        placed[start] = true;
+        order.push(start);
+
+        let mut next = order.len() - 1;
+        while next < order.len() {
+            let v = order[next];
+            next += 1;
+            let mut neighbors: Vec<usize> = pa.out[v]
+                .iter()
+                .chain(&pa.inc[v])
+                .copied()
+                .filter(|&u| !placed[u])
+                .collect();
+            neighbors.sort_unstable_by_key(|&u| (std::cmp::Reverse(degree(u)), u));
+            neighbors.dedup();
+            for u in neighbors {
+                if !placed[u] {
+                    placed[u] = true;
+                    order.push(u);
+                }
+            }
+        }
+    }
+    order
+}
+
+struct Search<'a> {
+    pattern: &'a PatternInput,
+    graph: &'a GraphInput,
+    pa: &'a Adjacency,
+    ga: &'a Adjacency,
+    order: Vec<usize>,
+    /// Graph node bound to each pattern node, or usize::MAX
+    binding: Vec<usize>,
+    used: Vec<bool>,
+    matches: Vec<Vec<usize>>,
+    limit: usize,
+}
+
+impl Search<'_> {
+    fn extend(&mut self, depth: usize) {
+        if depth == self.order.len() {
+            self.matches.push(self.binding.clone());
+            return;
+        }
+        let v = self.order[depth];
+
+        // candidates come from a bound neighbor when there is one
+        let bound_out = self.pa.inc[v]
+            .iter()
+            .find(|&&u| self.binding[u] != usize::MAX);
+        let bound_in = self.pa.out[v]
+            .iter()
+            .find(|&&u| self.binding[u] != usize::MAX);
+        let mut candidates: Vec<usize> = match (bound_out, bound_in) {
+            (Some(&u), _) => self.ga.out[self.binding[u]].iter().copied().collect(),
+            (None, Some(&u)) => self.ga.inc[self.binding[u]].iter().copied().collect(),
+            (None, None) => (0..self.graph.nodes.len()).collect(),
+        };
+        candidates.sort_unstable();
+
+        for c in candidates {
+            if self.matches.len() >= self.limit {
+                return;
+            }
+            if self.feasible(v, c) {
+                self.binding[v] = c;
+                self.used[c] = true;
+                self.extend(depth + 1);
+                self.binding[v] = usize::MAX;
+                self.used[c] = false;
+            }
+        }
+    }
+
+    /// Whether pattern node `v` can be bound to graph node `c`.
+    fn feasible(&self, v: usize, c: usize) -> bool {
+        if self.used[c]
+            || self.ga.out[c].len() < self.pa.out[v].len()
+            || self.ga.inc[c].len() < self.pa.inc[v].len()
+            || !attrs_match(&self.pattern.nodes[v], &self.graph.nodes[c])
+        {
+            return false;
+        }
+        let bound = |u: usize| self.binding[u] != usize::MAX;
+        self.pa.out[v]
+            .iter()
+            .filter(|&&u| bound(u))
+            .all(|&u| self.ga.out[c].contains(&self.binding[u]))
+            && self.pa.inc[v]
+                .iter()
+                .filter(|&&u| bound(u))
+                .all(|&u| self.ga.inc[c].contains(&self.binding[u]))
+            && (!self.pa.out[v].contains(&v) || self.ga.out[c].contains(&c))
+    }
+}
+
+/// Every attribute on the pattern node must be present and equal on the
+/// graph node.
+fn attrs_match(pattern: &NodeInput, node: &NodeInput) -> bool {
+    match (pattern, node) {
+        (NodeInput::Name(_), _) => true,
+        (NodeInput::Detailed { attrs, .. }, NodeInput::Name(_)) => attrs.is_empty(),
+        (NodeInput::Detailed { attrs, .. }, NodeInput::Detailed { attrs: have, .. }) => {
+            attrs.iter().all(|(k, v)| have.get(k) == Some(v))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> GraphInput {
+        serde_json::from_str(
+            r#"{"nodes": ["api", {"name": "auth", "tier": "mid"}, "db", "cache"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5},
+                {"from": "auth", "to": "db", "latency_ms": 3},
+                {"from": "api", "to": "cache", "latency_ms": 7},
+                {"from": "cache", "to": "db", "latency_ms": 2}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn pattern(json: &str) -> PatternInput {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_diamond() {
+        let diamond = pattern(
+            r#"{"nodes": ["top", "left", "right", "bottom"], "edges": [
+                {"from": "top", "to": "left"}, {"from": "top", "to": "right"},
+                {"from": "left", "to": "bottom"}, {"from": "right", "to": "bottom"}
+            ]}"#,
+        );
+        // once per way of assigning auth and cache to left and right
+        let matches = find_matches(&diamond, &graph(), 100);
+        assert_eq!(matches.len(), 2);
+        assert!(matches.contains(&vec![0, 1, 3, 2]));
+        assert!(matches.contains(&vec![0, 3, 1, 2]));
+        assert_eq!(find_matches(&diamond, &graph(), 1).len(), 1);
+    }
+
+    #[test]
+    fn test_attributes_restrict_matches() {
+        let chain = pattern(
+            r#"{"nodes": ["a", {"name": "b", "tier": "mid"}, "c"], "edges": [
+                {"from": "a", "to": "b"}, {"from": "b", "to": "c"}
+            ]}"#,
+        );
+        assert_eq!(find_matches(&chain, &graph(), 100), vec![vec![0, 1, 2]]);
+    }
+}
@@ -0,0 +1,49 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Disables all progress output (set from the global `--quiet` flag).
+pub(crate) fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Returns true if `--quiet` was given: text reports are skipped, JSON is
+/// printed compactly, and only errors and exit codes remain.
+pub(crate) fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Returns true if progress should be drawn: stderr is a terminal and
+/// `--quiet` wasn't given. Progress never goes to stdout, so piped JSON
+/// output is unaffected either way.
+fn enabled() -> bool {
+    !quiet() && std::io::stderr().is_terminal()
+}
+
+/// Progress bar for reading `len` bytes, with transfer rate and ETA.
+pub(crate) fn bytes(len: u64, message: &str) -> ProgressBar {
+    styled(
+        len,
+        message,
+        "{msg} [{bar:30}] {bytes}/{total_bytes} ({eta})",
+    )
+}
+
+/// Progress bar for `len` units of work, with ETA.
+pub(crate) fn items(len: u64, message: &str) -> ProgressBar {
+    styled(len, message, "{msg} [{bar:30}] {pos}/{len} ({eta})")
+}
+
+fn styled(len: u64, message: &str, template: &str) -> ProgressBar {
+    if !enabled() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(len).with_message(message.to_string());
+    if let Ok(style) = ProgressStyle::with_template(template) {
+        bar.set_style(style.progress_chars("=> "));
+    }
+    bar
+}
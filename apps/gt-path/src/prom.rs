@@ -0,0 +1,220 @@
+use anyhow::{Context, Result};
+use gt_path_core::io::GraphInput;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Describes how Prometheus series map onto graph edges.
+///
+/// # Example
+///
+/// ```yaml
+/// from_label: source_service
+/// to_label: destination_service
+/// scale: 1000          # query returns seconds
+/// nodes:
+///   api-gateway: api   # label value -> graph node name
+/// ```
+#[derive(Debug, Deserialize)]
+pub(crate) struct PromMapping {
+    /// Label holding the source node of an edge
+    pub(crate) from_label: String,
+    /// Label holding the destination node of an edge
+    pub(crate) to_label: String,
+    /// Multiplier converting sample values to milliseconds
+    #[serde(default = "default_scale")]
+    pub(crate) scale: f64,
+    /// Label values that differ from graph node names
+    #[serde(default)]
+    pub(crate) nodes: BTreeMap<String, String>,
+}
+
+/// Prometheus reports durations in seconds by convention.
+fn default_scale() -> f64 {
+    1000.0
+}
+
+impl PromMapping {
+    /// Reads a mapping from a YAML file.
+    pub(crate) fn read(path: &str) -> Result<PromMapping> {
+        let content =
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+        serde_yaml::from_str(&content).context("Failed to parse mapping YAML")
+    }
+
+    fn node<'a>(&'a self, label: &'a str) -> &'a str {
+        self.nodes.get(label).map(String::as_str).unwrap_or(label)
+    }
+}
+
+/// One series from an instant-vector query result.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Sample {
+    /// Series labels
+    pub(crate) metric: BTreeMap<String, String>,
+    /// `[timestamp, "value"]` pair
+    value: (f64, String),
+}
+
+impl Sample {
+    /// The sample value, or `None` if Prometheus returned a non-number (e.g., `NaN`).
+    pub(crate) fn value(&self) -> Option<f64> {
+        self.value.1.parse().ok().filter(|v: &f64| v.is_finite())
+    }
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    status: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    data: Option<QueryData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryData {
+    result_type: String,
+    result: Vec<Sample>,
+}
+
+/// Parses the body of a `/api/v1/query` response into its samples.
+pub(crate) fn parse_response(body: &str) -> Result<Vec<Sample>> {
+    let response: QueryResponse =
+        serde_json::from_str(body).context("Failed to parse Prometheus response")?;
+
+    if response.status != "success" {
+        anyhow::bail!(
+            "Prometheus query failed: {}",
+            response.error.unwrap_or(response.status)
+        );
+    }
+
+    let data = response.data.context("Prometheus response has no data")?;
+    if data.result_type != "vector" {
+        anyhow::bail!(
+            "Unsupported Prometheus result type '{}'. Expected an instant vector",
+            data.result_type
+        );
+    }
+
+    Ok(data.result)
+}
+
+/// Runs an instant query against a Prometheus server and returns the raw response body.
+pub(crate) fn query(url: &str, promql: &str) -> Result<String> {
+    let endpoint = format!("{}/api/v1/query", url.trim_end_matches('/'));
+    ureq::get(&endpoint)
+        .query("query", promql)
+        .call()
+        .context(format!("Failed to query {}", endpoint))?
+        .into_string()
+        .context("Failed to read Prometheus response")
+}
+
+/// Outcome of applying samples to a graph.
+#[derive(Debug, Default)]
+pub(crate) struct ImportReport {
+    /// Number of edges whose latency was updated
+    pub(crate) updated: usize,
+    /// `from → to` pairs from samples that matched no edge
+    pub(crate) unmatched: Vec<(String, String)>,
+    /// Samples skipped for missing labels or non-numeric values
+    pub(crate) skipped: usize,
+    /// Edges left at their previous latency because no sample covered them
+    pub(crate) stale: Vec<(String, String)>,
+}
+
+/// Overwrites edge latencies in `graph` with the matching sample values.
+///
+/// Topology is never changed: samples for edges that don't exist are only
+/// reported. When several samples map to the same edge, the last one wins.
+pub(crate) fn apply(
+    graph: &mut GraphInput,
+    samples: &[Sample],
+    mapping: &PromMapping,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    let mut latencies: HashMap<(&str, &str), f32> = HashMap::new();
+    for sample in samples {
+        let (Some(from), Some(to), Some(value)) = (
+            sample.metric.get(&mapping.from_label),
+            sample.metric.get(&mapping.to_label),
+            sample.value(),
+        ) else {
+            report.skipped += 1;
+            continue;
+        };
+        if value < 0.0 {
+            report.skipped += 1;
+            continue;
+        }
+        latencies.insert(
+            (mapping.node(from), mapping.node(to)),
+            (value * mapping.scale) as f32,
+        );
+    }
+
+    let mut matched = HashSet::new();
+    for edge in &mut graph.edges {
+        match latencies.get(&(edge.from.as_str(), edge.to.as_str())) {
+            Some(&latency) => {
+                // samples are in milliseconds, the file may use another unit
+                edge.latency_ms = Some(graph.unit.from_ms(latency));
+                matched.insert((edge.from.clone(), edge.to.clone()));
+                report.updated += 1;
+            }
+            None => report.stale.push((edge.from.clone(), edge.to.clone())),
+        }
+    }
+
+    let mut unmatched: Vec<(String, String)> = latencies
+        .keys()
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .filter(|pair| !matched.contains(pair))
+        .collect();
+    unmatched.sort();
+    report.unmatched = unmatched;
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> PromMapping {
+        serde_yaml::from_str("from_label: source\nto_label: dest\nnodes:\n  api-gateway: api\n")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply_response() {
+        let body = std::fs::read_to_string("src/testdata/prom_response.json").unwrap();
+        let samples = parse_response(&body).unwrap();
+        let mut graph = GraphInput::read("src/testdata/sample_graph.json").unwrap();
+
+        let report = apply(&mut graph, &samples, &mapping());
+        assert_eq!(report.updated, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(
+            report.unmatched,
+            vec![("api".to_string(), "search".to_string())]
+        );
+
+        let api_auth = graph
+            .edges
+            .iter()
+            .find(|e| e.from == "api" && e.to == "auth")
+            .unwrap();
+        assert_eq!(api_auth.latency_ms, Some(12.0));
+    }
+
+    #[test]
+    fn test_rejects_failed_query() {
+        let body = r#"{"status":"error","errorType":"bad_data","error":"parse error"}"#;
+        let err = parse_response(body).unwrap_err();
+        assert!(err.to_string().contains("parse error"));
+    }
+}
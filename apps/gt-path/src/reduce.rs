@@ -0,0 +1,158 @@
+use gt_path_core::io::GraphInput;
+use std::collections::HashMap;
+
+/// Result of a transitive reduction.
+pub(crate) struct Reduced {
+    /// The graph with redundant edges removed
+    pub(crate) graph: GraphInput,
+    /// Removed edges as (from, to) names, in input order
+    pub(crate) removed: Vec<(String, String)>,
+}
+
+/// Computes the transitive reduction of a DAG: the fewest edges with the
+/// same reachability. An edge `u → w` is dropped when `w` is also reachable
+/// through another edge out of `u`; duplicate edges are dropped as well.
+/// Kept edges retain their latency and attributes, and nodes are unchanged.
+///
+/// Reachability is tracked as one bitset per node, so memory grows with
+/// the square of the node count.
+///
+/// # Returns
+///
+/// * `Ok(Reduced)` - The reduced graph and the edges that were removed
+/// * `Err` - If the graph has a cycle, since the reduction isn't unique then
+pub(crate) fn transitive_reduction(input: &GraphInput) -> anyhow::Result<Reduced> {
+    let n = input.nodes.len();
+    let index: HashMap<&str, usize> = input
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.name(), i))
+        .collect();
+
+    // (target, edge index) per source; endpoints are validated by the caller
+    let mut out: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    let mut in_degree = vec![0; n];
+    for (i, e) in input.edges.iter().enumerate() {
+        let (u, v) = (index[e.from.as_str()], index[e.to.as_str()]);
+        out[u].push((v, i));
+        in_degree[v] += 1;
+    }
+
+    // Kahn's algorithm; position in `order` is each node's topological rank
+    let mut order: Vec<usize> = (0..n).filter(|&u| in_degree[u] == 0).collect();
+    let mut next = 0;
+    while next < order.len() {
+        let u = order[next];
+        next += 1;
+        for &(v, _) in &out[u] {
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                order.push(v);
+            }
+        }
+    }
+    if order.len() < n {
+        let stuck = (0..n).find(|&u| in_degree[u] > 0).unwrap_or(0);
+        anyhow::bail!(
+            "graph has a cycle through '{}'; transitive reduction needs a DAG",
+            input.nodes[stuck].name()
+        );
+    }
+    let mut rank = vec![0; n];
+    for (r, &u) in order.iter().enumerate() {
+        rank[u] = r;
+    }
+
+    let words = n.div_ceil(64);
+    let mut reach = vec![vec![0u64; words]; n];
+    let mut keep = vec![true; input.edges.len()];
+    for &u in order.iter().rev() {
+        // a child reachable from an earlier child is redundant
+        let mut children = out[u].clone();
+        children.sort_by_key(|&(v, i)| (rank[v], i));
+
+        let mut covered = vec![0u64; words];
+        for (v, i) in children {
+            if covered[v / 64] & (1 << (v % 64)) != 0 {
+                keep[i] = false;
+                continue;
+            }
+            covered[v / 64] |= 1 << (v % 64);
+            for (c, r) in covered.iter_mut().zip(&reach[v]) {
+                *c |= r;
+            }
+        }
+        reach[u] = covered;
+    }
+
+    let mut graph = input.clone();
+    let mut removed = Vec::new();
+    let mut kept = keep.iter();
+    graph.edges.retain(|e| {
+        let keep = *kept.next().unwrap_or(&true);
+        if !keep {
+            removed.push((e.from.clone(), e.to.clone()));
+        }
+        keep
+    });
+
+    Ok(Reduced { graph, removed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> GraphInput {
+        let edges: Vec<String> = edges
+            .iter()
+            .map(|(from, to)| {
+                format!(
+                    r#"{{"from": "{}", "to": "{}", "latency_ms": 1.0}}"#,
+                    from, to
+                )
+            })
+            .collect();
+        let nodes: Vec<String> = nodes.iter().map(|n| format!("\"{}\"", n)).collect();
+        serde_json::from_str(&format!(
+            r#"{{"nodes": [{}], "edges": [{}]}}"#,
+            nodes.join(","),
+            edges.join(",")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_removes_implied_edges() {
+        // a → b → c → d, plus shortcuts a → c, a → d and a duplicate b → c
+        let input = graph(
+            &["a", "b", "c", "d"],
+            &[
+                ("a", "b"),
+                ("a", "c"),
+                ("b", "c"),
+                ("c", "d"),
+                ("a", "d"),
+                ("b", "c"),
+            ],
+        );
+
+        let reduced = transitive_reduction(&input).unwrap();
+        let kept: Vec<(&str, &str)> = reduced
+            .graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(kept, vec![("a", "b"), ("b", "c"), ("c", "d")]);
+        assert_eq!(reduced.removed.len(), 3);
+        assert_eq!(reduced.graph.nodes.len(), 4);
+    }
+
+    #[test]
+    fn test_rejects_cycles() {
+        let input = graph(&["a", "b"], &[("a", "b"), ("b", "a")]);
+        assert!(transitive_reduction(&input).is_err());
+    }
+}
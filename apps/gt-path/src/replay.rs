@@ -0,0 +1,115 @@
+use crate::daemon::{Recorded, Response};
+use anyhow::{Context, Result};
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{ReplayAnswerOutput, ReplayOutput, ReplayQueryOutput};
+
+/// Reads a session file written by `daemon --record`, one JSON line per
+/// query. Blank lines are skipped.
+pub(crate) fn read(path: &str) -> Result<Vec<Recorded>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).context(format!("Invalid session entry on line {}", i + 1))
+        })
+        .collect()
+}
+
+/// Re-runs every recorded query against `graph`, whatever graph it was
+/// recorded against, and compares the answers. Paths are compared by their
+/// nodes and total latency; errors by their message.
+pub(crate) fn replay(session: &str, records: &[Recorded], graph: &Graph) -> ReplayOutput {
+    let results: Vec<ReplayQueryOutput> = records
+        .iter()
+        .map(|r| {
+            let recorded = answer(&r.response);
+            let replayed = answer(&crate::daemon::answer_with(&r.request, graph));
+            ReplayQueryOutput {
+                from: r.request.from.clone(),
+                to: r.request.to.clone(),
+                at: r.request.at.clone(),
+                changed: recorded != replayed,
+                recorded,
+                replayed,
+            }
+        })
+        .collect();
+
+    ReplayOutput {
+        session: session.to_string(),
+        graph_hash: graph.content_hash().to_string(),
+        queries: results.len(),
+        changed: results.iter().filter(|r| r.changed).count(),
+        results,
+    }
+}
+
+fn answer(response: &Response) -> ReplayAnswerOutput {
+    match response {
+        Response::Path { path, .. } => ReplayAnswerOutput {
+            path: Some(path.path.clone()),
+            latency_ms: Some(path.total_latency_ms),
+            error: None,
+        },
+        Response::InvalidInput(e) | Response::QueryFailed(e) => ReplayAnswerOutput {
+            path: None,
+            latency_ms: None,
+            error: Some(e.clone()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstraintArgs;
+    use crate::daemon::Request;
+    use gt_path_core::io::GraphInput;
+
+    fn record(graph: &Graph, from: &str, to: &str) -> Recorded {
+        let request = Request {
+            graph: "recorded.json".into(),
+            from: from.to_string(),
+            to: to.to_string(),
+            at: None,
+            constraints: ConstraintArgs::default(),
+        };
+        let response = crate::daemon::answer_with(&request, graph);
+        Recorded { request, response }
+    }
+
+    #[test]
+    fn test_replay_diffs_answers() {
+        let before = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let records = vec![
+            record(&before, "api", "db"),
+            record(&before, "api", "auth"),
+            record(&before, "db", "api"),
+        ];
+
+        // the migrated graph has a slower auth -> db link
+        let mut input: GraphInput = serde_json::from_str(
+            &std::fs::read_to_string("src/testdata/sample_graph.json").unwrap(),
+        )
+        .unwrap();
+        for e in &mut input.edges {
+            if (e.from.as_str(), e.to.as_str()) == ("auth", "db") {
+                e.latency_ms = Some(20.0);
+            }
+        }
+        let after = Graph::try_from(input).unwrap();
+
+        let output = replay("session.jsonl", &records, &after);
+        assert_eq!(output.queries, 3);
+        assert_eq!(output.changed, 1);
+        assert!(output.results[0].changed);
+        assert_eq!(output.results[0].recorded.latency_ms, Some(8));
+        assert_eq!(output.results[0].replayed.latency_ms, Some(9));
+        // an unchanged error is not a change
+        assert!(!output.results[2].changed);
+        assert!(output.results[2].replayed.error.is_some());
+    }
+}
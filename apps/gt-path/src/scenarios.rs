@@ -0,0 +1,254 @@
+use crate::units;
+use anyhow::{Context, Result};
+use gt_path_core::constraint::Constraints;
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{ScenarioMatrixOutput, ScenarioResultOutput, ScenarioRouteOutput};
+use gt_path_core::{Modifications, PathError};
+use serde::Deserialize;
+
+/// A scenarios file, in YAML or JSON:
+///
+/// ```yaml
+/// scenarios:
+///   - name: eu-degraded
+///     override: ["eu-*:us-*:+30"]
+///   - name: cache-down
+///     drop: ["api:cache"]
+/// ```
+#[derive(Deserialize)]
+struct ScenarioFile {
+    scenarios: Vec<ScenarioInput>,
+}
+
+/// One named set of modifications, written as on the command line.
+#[derive(Deserialize)]
+struct ScenarioInput {
+    name: String,
+    #[serde(default, rename = "override")]
+    overrides: Vec<String>,
+    #[serde(default)]
+    drop: Vec<String>,
+    #[serde(default)]
+    create_missing: bool,
+}
+
+/// A named set of modifications to compare against the others.
+pub(crate) struct Scenario {
+    pub(crate) name: String,
+    pub(crate) mods: Modifications,
+}
+
+/// Name of the unmodified graph's column.
+pub(crate) const BASELINE: &str = "baseline";
+
+/// Reads and parses a scenarios file.
+pub(crate) fn read(path: &str) -> Result<Vec<Scenario>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+    // YAML is a superset of JSON, so one parser reads both
+    let file: ScenarioFile =
+        serde_yaml::from_str(&content).context("Failed to parse scenarios file")?;
+    parse(file)
+}
+
+fn parse(file: ScenarioFile) -> Result<Vec<Scenario>> {
+    if file.scenarios.is_empty() {
+        anyhow::bail!("scenarios file lists no scenarios");
+    }
+    let mut scenarios: Vec<Scenario> = Vec::new();
+    for s in file.scenarios {
+        if s.name == BASELINE || scenarios.iter().any(|other| other.name == s.name) {
+            anyhow::bail!("duplicate scenario name '{}'", s.name);
+        }
+        let mut mods = Modifications::parse(&s.overrides, &s.drop)
+            .context(format!("Invalid scenario '{}'", s.name))?;
+        mods.create_missing = s.create_missing;
+        scenarios.push(Scenario { name: s.name, mods });
+    }
+    Ok(scenarios)
+}
+
+/// Finds every route's shortest path under every scenario. With
+/// `baseline`, the unmodified graph comes first and every other result
+/// carries its change from it.
+///
+/// A route without a path under some scenario is reported, not an error;
+/// a scenario whose modifications don't apply to the graph is.
+pub(crate) fn compare(
+    graph: &Graph,
+    scenarios: &[Scenario],
+    routes: &[(String, String)],
+    max_latency: Option<u32>,
+    baseline: bool,
+    constraints: &Constraints,
+) -> Result<ScenarioMatrixOutput> {
+    let mut columns: Vec<(String, Option<Graph>)> = Vec::new();
+    if baseline {
+        columns.push((BASELINE.to_string(), None));
+    }
+    for s in scenarios {
+        let modified = s
+            .mods
+            .apply(graph)
+            .context(format!("Failed to apply scenario '{}'", s.name))?;
+        columns.push((s.name.clone(), Some(modified)));
+    }
+
+    let mut rows = Vec::with_capacity(routes.len());
+    for (from, to) in routes {
+        let mut results: Vec<ScenarioResultOutput> = Vec::with_capacity(columns.len());
+        for (name, modified) in &columns {
+            let latency_ms =
+                match modified
+                    .as_ref()
+                    .unwrap_or(graph)
+                    .shortest_path_with(from, to, constraints)
+                {
+                    Ok(path) => Some(path.cost),
+                    Err(PathError::PathNotFound { .. }) => None,
+                    Err(e) => return Err(e).context(format!("Invalid route {}:{}", from, to)),
+                };
+            let change_ms = match (baseline, results.first().and_then(|b| b.latency_ms)) {
+                (true, Some(base)) if modified.is_some() => {
+                    latency_ms.map(|l| l as i64 - base as i64)
+                }
+                _ => None,
+            };
+            results.push(ScenarioResultOutput {
+                scenario: name.clone(),
+                latency_ms,
+                slo_met: max_latency.map(|max| latency_ms.is_some_and(|l| l <= max)),
+                change_ms,
+            });
+        }
+        rows.push(ScenarioRouteOutput {
+            from: from.clone(),
+            to: to.clone(),
+            results,
+        });
+    }
+
+    Ok(ScenarioMatrixOutput {
+        scenarios: columns.into_iter().map(|(name, _)| name).collect(),
+        max_latency_ms: max_latency,
+        routes: rows,
+        graph_hash: graph.content_hash().to_string(),
+    })
+}
+
+/// Prints the matrix as a table: one row per route, one column per
+/// scenario.
+pub(crate) fn print_text(output: &ScenarioMatrixOutput) {
+    let cell = |r: &ScenarioResultOutput| {
+        let mut text = match r.latency_ms {
+            Some(l) => units::ms(l),
+            None => "no path".to_string(),
+        };
+        if let Some(change) = r.change_ms {
+            if units::human() {
+                text += &format!(" ({})", units::ms_delta(change as f64, 0));
+            } else {
+                text += &format!(" ({:+})", change);
+            }
+        }
+        match r.slo_met {
+            Some(true) => text + " ✓",
+            Some(false) => text + " ✗",
+            None => text,
+        }
+    };
+    let rows: Vec<(String, Vec<String>)> = output
+        .routes
+        .iter()
+        .map(|r| {
+            (
+                format!("{} → {}", r.from, r.to),
+                r.results.iter().map(cell).collect(),
+            )
+        })
+        .collect();
+
+    let width = |header: &str, column: &mut dyn Iterator<Item = &String>| {
+        column
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(header.chars().count())
+    };
+    let route_width = width("Route", &mut rows.iter().map(|(route, _)| route));
+    let widths: Vec<usize> = output
+        .scenarios
+        .iter()
+        .enumerate()
+        .map(|(i, name)| width(name, &mut rows.iter().map(|(_, cells)| &cells[i])))
+        .collect();
+
+    match output.max_latency_ms {
+        Some(max) => println!("Scenario Comparison (SLO {}):", units::ms(max)),
+        None => println!("Scenario Comparison:"),
+    }
+    let pad = |s: &str, width: usize| format!("{}{}", s, " ".repeat(width - s.chars().count()));
+    let mut header = format!("  {}", pad("Route", route_width));
+    for (name, &w) in output.scenarios.iter().zip(&widths) {
+        header += &format!("  {}", pad(name, w));
+    }
+    println!("{}", header.trim_end());
+    for (route, cells) in &rows {
+        let mut line = format!("  {}", pad(route, route_width));
+        for (cell, &w) in cells.iter().zip(&widths) {
+            line += &format!("  {}", pad(cell, w));
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenarios(yaml: &str) -> Result<Vec<Scenario>> {
+        parse(serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[test]
+    fn test_compare_scenarios() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let scenarios = scenarios(
+            "scenarios:\n  - name: slow-auth\n    override: [\"auth:db:+10\"]\n  - name: no-cache\n    drop: [\"api:cache\", \"auth:db\"]\n",
+        )
+        .unwrap();
+        let routes = vec![
+            ("api".to_string(), "db".to_string()),
+            ("api".to_string(), "auth".to_string()),
+        ];
+
+        let output = compare(
+            &graph,
+            &scenarios,
+            &routes,
+            Some(10),
+            true,
+            &Constraints::default(),
+        )
+        .unwrap();
+        assert_eq!(output.scenarios, vec!["baseline", "slow-auth", "no-cache"]);
+        let api_db = &output.routes[0].results;
+        assert_eq!(api_db[0].latency_ms, Some(8));
+        assert_eq!(api_db[0].change_ms, None);
+        // the cache detour (9ms) now beats auth (5 + 13)
+        assert_eq!(api_db[1].latency_ms, Some(9));
+        assert_eq!(api_db[1].change_ms, Some(1));
+        assert_eq!(api_db[1].slo_met, Some(true));
+        assert_eq!(api_db[2].latency_ms, None);
+        assert_eq!(api_db[2].slo_met, Some(false));
+        assert_eq!(output.routes[1].results[2].latency_ms, Some(5));
+    }
+
+    #[test]
+    fn test_rejects_bad_scenarios() {
+        assert!(scenarios("scenarios: []\n").is_err());
+        assert!(scenarios("scenarios:\n  - name: baseline\n").is_err());
+        assert!(scenarios("scenarios:\n  - name: a\n  - name: a\n").is_err());
+        assert!(scenarios("scenarios:\n  - name: a\n    override: [\"x:y\"]\n").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::progress;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::error::PathError;
+use gt_path_core::graph::Graph;
+use gt_path_core::path::Path;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Settings for a weight-noise simulation.
+pub(crate) struct StabilityOptions {
+    /// Number of noisy copies of the graph to route on
+    pub(crate) trials: u32,
+    /// Largest relative change to any edge latency, e.g. 0.1 for ±10%
+    pub(crate) noise: f64,
+    /// Seed for the random perturbations
+    pub(crate) seed: u64,
+}
+
+/// How a route's shortest path holds up when edge latencies are noisy.
+#[derive(PartialEq)]
+pub(crate) struct StabilityReport {
+    /// Shortest path on the unperturbed graph
+    pub(crate) baseline: Path,
+    /// Trials whose shortest path differs from the baseline
+    pub(crate) changed: u32,
+    /// Shortest path latency of every trial, ascending
+    pub(crate) latencies: Vec<u32>,
+    /// Every route chosen at least once, as node names with the number of
+    /// trials that chose it; most frequent first
+    pub(crate) routes: Vec<(Vec<Arc<str>>, u32)>,
+}
+
+/// Perturbs every edge latency independently and uniformly by up to
+/// `noise` of its value, `trials` times, and finds the shortest path on
+/// each perturbed graph.
+///
+/// Routes are compared edge by edge, so switching between parallel edges
+/// counts as a change. Perturbed latencies are rounded to whole
+/// milliseconds, so noise smaller than half a millisecond has no effect.
+///
+/// # Returns
+///
+/// * `Ok(StabilityReport)` - Route changes and latencies over all trials
+/// * `Err(PathError)` - If either node doesn't exist or there is no path
+pub(crate) fn simulate(
+    graph: &Graph,
+    from: &str,
+    to: &str,
+    constraints: &Constraints,
+    opts: &StabilityOptions,
+) -> Result<StabilityReport, PathError> {
+    let baseline = graph.shortest_path_with(from, to, constraints)?;
+    let baseline_edges: Vec<usize> = baseline.edges.iter().map(|e| e.index).collect();
+
+    let mut rng = StdRng::seed_from_u64(opts.seed);
+    let mut noisy = graph.clone();
+    let mut changed = 0;
+    let mut latencies = Vec::with_capacity(opts.trials as usize);
+    let mut routes: Vec<(Vec<usize>, Vec<Arc<str>>, u32)> = Vec::new();
+
+    let bar = progress::items(opts.trials as u64, "Perturbing latencies");
+    for _ in bar.wrap_iter(0..opts.trials) {
+        for (links, noisy_links) in graph.adj.iter().zip(&mut noisy.adj) {
+            for (link, noisy_link) in links.iter().zip(noisy_links) {
+                let factor = 1.0 + rng.gen_range(-opts.noise..=opts.noise);
+                noisy_link.latency_ms = (link.latency_ms as f64 * factor).round() as u32;
+            }
+        }
+
+        // every edge is still there, so a path always exists
+        let path = noisy.shortest_path_with(from, to, constraints)?;
+        let edges: Vec<usize> = path.edges.iter().map(|e| e.index).collect();
+        if edges != baseline_edges {
+            changed += 1;
+        }
+        latencies.push(path.cost);
+        match routes.iter_mut().find(|(e, _, _)| *e == edges) {
+            Some((_, _, count)) => *count += 1,
+            None => {
+                let names = path
+                    .path
+                    .iter()
+                    .map(|id| graph.to_name[id.0 as usize].clone())
+                    .collect();
+                routes.push((edges, names, 1));
+            }
+        }
+    }
+    bar.finish_and_clear();
+
+    latencies.sort_unstable();
+    routes.sort_by_key(|r| Reverse(r.2));
+
+    Ok(StabilityReport {
+        baseline,
+        changed,
+        latencies,
+        routes: routes
+            .into_iter()
+            .map(|(_, names, count)| (names, count))
+            .collect(),
+    })
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+pub(crate) fn percentile(sorted: &[u32], p: f64) -> u32 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::io::GraphInput;
+
+    fn graph(detour_ms: u32) -> Graph {
+        let input: GraphInput = serde_json::from_str(&format!(
+            r#"{{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {{"from": "api", "to": "auth", "latency_ms": 50}},
+                {{"from": "auth", "to": "db", "latency_ms": 50}},
+                {{"from": "api", "to": "cache", "latency_ms": 50}},
+                {{"from": "cache", "to": "db", "latency_ms": {}}}
+            ]}}"#,
+            detour_ms
+        ))
+        .unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    fn opts(noise: f64) -> StabilityOptions {
+        StabilityOptions {
+            trials: 500,
+            noise,
+            seed: 3,
+        }
+    }
+
+    #[test]
+    fn test_distant_alternative_never_wins() {
+        let report = simulate(
+            &graph(200),
+            "api",
+            "db",
+            &Constraints::default(),
+            &opts(0.1),
+        )
+        .unwrap();
+        assert_eq!(report.baseline.cost, 100);
+        assert_eq!(report.changed, 0);
+        assert_eq!(report.routes.len(), 1);
+        // two edges of 45-55ms each
+        assert!(report.latencies[0] >= 90 && report.latencies[499] <= 110);
+    }
+
+    #[test]
+    fn test_close_alternative_flips() {
+        // 100ms vs 101ms: noise decides which route wins
+        let report =
+            simulate(&graph(51), "api", "db", &Constraints::default(), &opts(0.1)).unwrap();
+        assert!(report.changed > 100 && report.changed < 400);
+        assert_eq!(report.routes.len(), 2);
+
+        let none = simulate(&graph(51), "api", "db", &Constraints::default(), &opts(0.0)).unwrap();
+        assert_eq!(none.changed, 0);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&sorted, 50.0), 5);
+        assert_eq!(percentile(&sorted, 95.0), 10);
+        assert_eq!(percentile(&sorted, 0.0), 1);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::progress;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::graph::{Graph, NodeId};
+use gt_path_core::io::{PairStretchOutput, SpannerOutput};
+use gt_path_core::oracle::{self, UNREACHABLE};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Settings for measuring how well a subset of edges keeps latencies.
+pub(crate) struct StretchOptions {
+    /// Measure from this many randomly chosen source nodes instead of all
+    pub(crate) sample: Option<usize>,
+    /// Seed for choosing sources
+    pub(crate) seed: u64,
+    /// Number of worst pairs to report
+    pub(crate) top: usize,
+    /// Largest stretch any pair may have for the subset to pass
+    pub(crate) max_stretch: Option<f64>,
+}
+
+/// One measured pair, by node ID; a latency of `UNREACHABLE` in the subset
+/// means it disconnects the pair.
+struct Pair {
+    from: NodeId,
+    to: NodeId,
+    latency_ms: u32,
+    subset_latency_ms: u32,
+}
+
+impl Pair {
+    fn stretch(&self) -> f64 {
+        match self.subset_latency_ms {
+            UNREACHABLE => f64::INFINITY,
+            l => l as f64 / self.latency_ms as f64,
+        }
+    }
+}
+
+/// Compares the latency of node pairs using only the `kept` edges, indexed
+/// by `Link::edge`, with their latency in the whole graph.
+///
+/// Every pair the whole graph connects is measured, from every node or from
+/// a sample of them. A pair's stretch is the ratio of the two latencies;
+/// pairs at zero latency have none and are skipped. Pairs the subset
+/// disconnects are reported first among the worst and fail `max_stretch`,
+/// but are left out of the mean and max.
+pub(crate) fn measure(
+    graph: &Graph,
+    kept: &[bool],
+    constraints: &Constraints,
+    opts: &StretchOptions,
+) -> SpannerOutput {
+    let n = graph.to_name.len();
+    let sources: Vec<NodeId> = match opts.sample {
+        Some(k) if k < n => {
+            let mut rng = StdRng::seed_from_u64(opts.seed);
+            let mut picked = rand::seq::index::sample(&mut rng, n, k).into_vec();
+            picked.sort_unstable();
+            picked.into_iter().map(|i| NodeId(i as u32)).collect()
+        }
+        _ => (0..n as u32).map(NodeId).collect(),
+    };
+    let down: Vec<bool> = kept.iter().map(|&kept| !kept).collect();
+
+    let (mut pairs, mut disconnected, mut sum, mut max) = (0, 0, 0.0, None::<f64>);
+    let mut worst: Vec<Pair> = Vec::new();
+    let keep_worst = |worst: &mut Vec<Pair>| {
+        worst.sort_by(|a, b| {
+            b.stretch()
+                .total_cmp(&a.stretch())
+                .then(b.latency_ms.cmp(&a.latency_ms))
+                .then((a.from.0, a.to.0).cmp(&(b.from.0, b.to.0)))
+        });
+        worst.truncate(opts.top);
+    };
+
+    let bar = progress::items(sources.len() as u64, "Measuring stretch");
+    for &source in bar.wrap_iter(sources.iter()) {
+        let whole = oracle::distances(graph, source, constraints);
+        let subset = oracle::distances_without(graph, source, constraints, &down);
+        for (v, (&latency_ms, &subset_latency_ms)) in whole.iter().zip(&subset).enumerate() {
+            if v == source.0 as usize || latency_ms == UNREACHABLE || latency_ms == 0 {
+                continue;
+            }
+            let pair = Pair {
+                from: source,
+                to: NodeId(v as u32),
+                latency_ms,
+                subset_latency_ms,
+            };
+            pairs += 1;
+            if subset_latency_ms == UNREACHABLE {
+                disconnected += 1;
+            } else {
+                sum += pair.stretch();
+                max = Some(max.map_or(pair.stretch(), |m| m.max(pair.stretch())));
+            }
+            worst.push(pair);
+            if worst.len() > 2 * opts.top.max(512) {
+                keep_worst(&mut worst);
+            }
+        }
+    }
+    bar.finish_and_clear();
+    keep_worst(&mut worst);
+
+    let connected = pairs - disconnected;
+    SpannerOutput {
+        kept_edges: kept.iter().filter(|&&k| k).count(),
+        edges: kept.len(),
+        sources: sources.len(),
+        sampled: sources.len() < n,
+        pairs,
+        disconnected,
+        max_stretch: max,
+        mean_stretch: (connected > 0).then(|| sum / connected as f64),
+        max_allowed: opts.max_stretch,
+        spanner: opts
+            .max_stretch
+            .map(|t| disconnected == 0 && max.is_none_or(|m| m <= t)),
+        worst: worst
+            .iter()
+            .map(|p| PairStretchOutput {
+                from: graph.to_name[p.from.0 as usize].to_string(),
+                to: graph.to_name[p.to.0 as usize].to_string(),
+                latency_ms: p.latency_ms,
+                subset_latency_ms: Some(p.subset_latency_ms).filter(|&l| l != UNREACHABLE),
+                stretch: Some(p.stretch()).filter(|s| s.is_finite()),
+            })
+            .collect(),
+        graph_hash: graph.content_hash().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::backbone;
+
+    fn opts(max_stretch: Option<f64>) -> StretchOptions {
+        StretchOptions {
+            sample: None,
+            seed: 0,
+            top: 3,
+            max_stretch,
+        }
+    }
+
+    #[test]
+    fn test_spanning_tree_stretch() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let tree = backbone::minimum_spanning_tree(&graph);
+        let output = measure(&graph, &tree, &Constraints::default(), &opts(Some(1.5)));
+
+        assert_eq!((output.kept_edges, output.edges), (3, 4));
+        // every pair a directed path joins: api reaches all three, auth and
+        // cache reach db
+        assert_eq!(output.pairs, 5);
+        // api→cache is dropped, so api reaches cache via auth and db
+        assert_eq!(output.disconnected, 1);
+        let worst = &output.worst[0];
+        assert_eq!((worst.from.as_str(), worst.to.as_str()), ("api", "cache"));
+        assert_eq!(worst.stretch, None);
+        assert_eq!(output.spanner, Some(false));
+        assert_eq!(output.max_stretch, Some(1.0));
+    }
+
+    #[test]
+    fn test_whole_graph_is_a_spanner() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let all = vec![true; graph.edge_ids.len()];
+        let mut opts = opts(Some(1.0));
+        opts.sample = Some(2);
+        let output = measure(&graph, &all, &Constraints::default(), &opts);
+
+        assert!(output.sampled);
+        assert_eq!(output.sources, 2);
+        assert_eq!(output.disconnected, 0);
+        assert_eq!(output.spanner, Some(true));
+        assert!(output.worst.len() <= 3);
+    }
+}
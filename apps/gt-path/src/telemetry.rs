@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::cell::RefCell;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// OTLP/HTTP collector base URL, set from the global `--otel-endpoint` flag.
+static ENDPOINT: OnceLock<String> = OnceLock::new();
+
+/// Trace shared by every span of this process.
+static TRACE_ID: OnceLock<u128> = OnceLock::new();
+
+/// Span covering the whole command; parent of spans opened on threads with
+/// no span of their own.
+static ROOT: OnceLock<u64> = OnceLock::new();
+
+/// Spans that have ended and not been exported yet.
+static FINISHED: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Spans currently open on this thread, innermost last.
+    static OPEN: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Turns on span recording; spans are sent to `endpoint` by `export`.
+pub(crate) fn enable(endpoint: &str) {
+    let _ = ENDPOINT.set(endpoint.trim_end_matches('/').to_string());
+    let _ = TRACE_ID.set(rand::random::<u128>().max(1));
+}
+
+struct Record {
+    id: u64,
+    parent: Option<u64>,
+    name: &'static str,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(&'static str, String)>,
+}
+
+/// A timed phase of the command, recorded when dropped. Spans nest: one
+/// opened while another is open on the same thread becomes its child.
+/// Without `--otel-endpoint` spans record nothing.
+pub(crate) struct Span(Option<Record>);
+
+/// Opens a span named `name`.
+pub(crate) fn span(name: &'static str) -> Span {
+    if ENDPOINT.get().is_none() {
+        return Span(None);
+    }
+
+    let id = rand::random::<u64>().max(1);
+    let parent = OPEN.with_borrow_mut(|open| {
+        let parent = open.last().copied().or_else(|| ROOT.get().copied());
+        open.push(id);
+        parent
+    });
+    Span(Some(Record {
+        id,
+        parent,
+        name,
+        start: SystemTime::now(),
+        end: SystemTime::now(),
+        attributes: Vec::new(),
+    }))
+}
+
+/// Opens the span covering the whole command.
+pub(crate) fn root(name: &'static str) -> Span {
+    let span = span(name);
+    if let Some(record) = &span.0 {
+        let _ = ROOT.set(record.id);
+    }
+    span
+}
+
+impl Span {
+    /// Attaches an attribute, such as the file being loaded.
+    pub(crate) fn set(&mut self, key: &'static str, value: impl ToString) {
+        if let Some(record) = &mut self.0 {
+            record.attributes.push((key, value.to_string()));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(mut record) = self.0.take() else {
+            return;
+        };
+        record.end = SystemTime::now();
+        OPEN.with_borrow_mut(|open| open.retain(|&id| id != record.id));
+        FINISHED.lock().unwrap().push(record);
+    }
+}
+
+/// Sends the spans ended so far to the collector as OTLP/HTTP JSON.
+/// Does nothing without `--otel-endpoint`.
+pub(crate) fn export() -> Result<()> {
+    let Some(endpoint) = ENDPOINT.get() else {
+        return Ok(());
+    };
+    let spans = std::mem::take(&mut *FINISHED.lock().unwrap());
+    if spans.is_empty() {
+        return Ok(());
+    }
+
+    let url = format!("{}/v1/traces", endpoint);
+    ureq::post(&url)
+        // a slow collector shouldn't hold up the command's exit
+        .timeout(std::time::Duration::from_secs(5))
+        .set("Content-Type", "application/json")
+        .send_string(&request(&spans).to_string())
+        .context(format!("Failed to export spans to {}", url))?;
+    Ok(())
+}
+
+/// Builds an OTLP `ExportTraceServiceRequest` in its JSON encoding.
+fn request(spans: &[Record]) -> serde_json::Value {
+    let nanos = |t: SystemTime| {
+        t.duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let trace_id = format!("{:032x}", TRACE_ID.get().copied().unwrap_or(1));
+    let spans: Vec<_> = spans
+        .iter()
+        .map(|s| {
+            let mut span = json!({
+                "traceId": trace_id,
+                "spanId": format!("{:016x}", s.id),
+                "name": s.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": nanos(s.start),
+                "endTimeUnixNano": nanos(s.end),
+                "attributes": s.attributes.iter().map(|(k, v)| json!({
+                    "key": k,
+                    "value": {"stringValue": v},
+                })).collect::<Vec<_>>(),
+            });
+            if let Some(parent) = s.parent {
+                span["parentSpanId"] = json!(format!("{:016x}", parent));
+            }
+            span
+        })
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{"key": "service.name", "value": {"stringValue": "gt-path"}}],
+            },
+            "scopeSpans": [{
+                "scope": {"name": "gt-path", "version": env!("CARGO_PKG_VERSION")},
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_encoding() {
+        let start = UNIX_EPOCH + std::time::Duration::from_millis(1500);
+        let spans = [
+            Record {
+                id: 0xab,
+                parent: None,
+                name: "gt-path",
+                start,
+                end: start + std::time::Duration::from_millis(20),
+                attributes: vec![("graph.file", "graph.json".to_string())],
+            },
+            Record {
+                id: 0xcd,
+                parent: Some(0xab),
+                name: "load",
+                start,
+                end: start,
+                attributes: Vec::new(),
+            },
+        ];
+
+        let request = request(&spans);
+        let spans = &request["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(spans[0]["spanId"], "00000000000000ab");
+        assert_eq!(spans[0]["startTimeUnixNano"], "1500000000");
+        assert_eq!(spans[0]["endTimeUnixNano"], "1520000000");
+        assert_eq!(spans[0]["attributes"][0]["key"], "graph.file");
+        assert!(spans[0].get("parentSpanId").is_none());
+        assert_eq!(spans[1]["parentSpanId"], "00000000000000ab");
+        assert_eq!(spans[1]["traceId"].as_str().unwrap().len(), 32);
+    }
+}
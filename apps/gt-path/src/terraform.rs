@@ -0,0 +1,199 @@
+use gt_path_core::io::{Attributes, EdgeInput, GraphInput, NodeInput};
+use std::collections::{BTreeMap, HashSet};
+
+/// Converts `terraform graph` DOT output into a graph input.
+///
+/// Each resource, data source, and module becomes a node; an edge `a → b`
+/// means `a` depends on `b`. Every edge gets a latency of 1 so path costs
+/// count dependency steps. Providers, variables, outputs, and Terraform's
+/// internal `meta`/`root` nodes are dropped, along with any edges that
+/// touch them.
+///
+/// Both the legacy `"[root] aws_vpc.main (expand)"` and the plain
+/// `"aws_vpc.main"` node spellings are accepted.
+///
+/// # Example
+///
+/// ```ignore
+/// let dot = std::fs::read_to_string("graph.dot")?;
+/// let input = terraform::from_dot(&dot);
+/// input.write(Some("infra.json"))?;
+/// ```
+pub(crate) fn from_dot(dot: &str) -> GraphInput {
+    let mut nodes: BTreeMap<String, Attributes> = BTreeMap::new();
+    let mut edges = Vec::new();
+    let mut seen = HashSet::new();
+
+    for line in dot.lines() {
+        let ids = quoted(line);
+        let is_edge = ids.len() >= 2;
+
+        let mut names = Vec::new();
+        for id in ids.iter().take(if is_edge { 2 } else { 1 }) {
+            if let Some(name) = resource_name(id) {
+                nodes
+                    .entry(name.to_string())
+                    .or_insert_with(|| attributes(name));
+                names.push(name);
+            }
+        }
+
+        if is_edge
+            && let [from, to] = names[..]
+            && from != to
+            && seen.insert((from.to_string(), to.to_string()))
+        {
+            edges.push(EdgeInput {
+                from: from.to_string(),
+                to: to.to_string(),
+                id: None,
+                latency_ms: Some(1.0),
+                timeline: Vec::new(),
+                bidirectional: false,
+                attrs: Attributes::new(),
+            });
+        }
+    }
+
+    GraphInput::new(
+        nodes
+            .into_iter()
+            .map(|(name, attrs)| NodeInput::new(name, attrs))
+            .collect(),
+        edges,
+    )
+}
+
+/// Extracts the leading double-quoted ids on a DOT statement, in order.
+///
+/// Lines that don't start with a quoted id (graph attributes, `subgraph`,
+/// braces) yield nothing. Escaped quotes inside an id are kept as-is.
+fn quoted(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = line.trim_start();
+
+    while let Some(body) = rest.strip_prefix('"') {
+        let mut end = None;
+        let mut escaped = false;
+        for (i, c) in body.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    end = Some(i);
+                    break;
+                }
+                _ => escaped = false,
+            }
+        }
+
+        let Some(end) = end else { break };
+        out.push(&body[..end]);
+
+        // only ids joined by an edge operator matter; stop at the attribute list
+        rest = body[end + 1..].trim_start();
+        match rest.strip_prefix("->") {
+            Some(next) => rest = next.trim_start(),
+            None => break,
+        }
+    }
+
+    out
+}
+
+/// Normalizes a DOT node id to a resource address, or `None` for nodes
+/// that aren't part of the infrastructure dependency graph.
+fn resource_name(id: &str) -> Option<&str> {
+    let name = id.strip_prefix("[root] ").unwrap_or(id);
+    let name = name
+        .strip_suffix(" (expand)")
+        .or_else(|| name.strip_suffix(" (close)"))
+        .unwrap_or(name);
+
+    // module.app.aws_instance.web -> aws_instance.web
+    let mut local = name;
+    while let Some(rest) = local.strip_prefix("module.") {
+        match rest.split_once('.') {
+            Some((_, tail)) => local = tail,
+            // the module node itself
+            None => return Some(name),
+        }
+    }
+
+    let skipped = ["provider", "var.", "output.", "local.", "meta.", "root"];
+    if name.is_empty() || skipped.iter().any(|p| local.starts_with(p)) {
+        return None;
+    }
+
+    Some(name)
+}
+
+/// Derives `type` and `module` attributes from a resource address.
+fn attributes(name: &str) -> Attributes {
+    let mut attrs = Attributes::new();
+    let mut module = Vec::new();
+    let mut local = name;
+
+    while let Some(rest) = local.strip_prefix("module.") {
+        match rest.split_once('.') {
+            Some((m, tail)) => {
+                module.push(m);
+                local = tail;
+            }
+            None => {
+                attrs.insert("type".to_string(), "module".into());
+                return attrs;
+            }
+        }
+    }
+
+    let kind = match local.strip_prefix("data.") {
+        Some(rest) => format!("data.{}", rest.split('.').next().unwrap_or(rest)),
+        None => local.split('.').next().unwrap_or(local).to_string(),
+    };
+    attrs.insert("type".to_string(), kind.into());
+    if !module.is_empty() {
+        attrs.insert("module".to_string(), module.join(".").into());
+    }
+
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gt_path_core::graph::Graph;
+
+    #[test]
+    fn test_from_dot() {
+        let dot = std::fs::read_to_string("src/testdata/terraform_graph.dot").unwrap();
+        let input = from_dot(&dot);
+
+        let names: Vec<&str> = input.nodes.iter().map(|n| n.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "aws_instance.web",
+                "aws_security_group.web",
+                "aws_subnet.public",
+                "aws_vpc.main",
+                "data.aws_ami.ubuntu",
+                "module.db",
+                "module.db.aws_db_instance.main",
+            ]
+        );
+        assert!(input.edges.iter().all(|e| !e.from.starts_with("provider")
+            && !e.to.starts_with("provider")
+            && !e.to.starts_with("var.")));
+
+        let (_, attrs) = input.nodes[6].clone().into_parts();
+        assert_eq!(attrs["type"], "aws_db_instance");
+        assert_eq!(attrs["module"], "db");
+
+        // instance → subnet → vpc: two dependency steps
+        let graph = Graph::try_from(input).unwrap();
+        let path = graph
+            .shortest_path("aws_instance.web", "aws_vpc.main")
+            .unwrap();
+        assert_eq!(path.cost, 2);
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HUMAN: AtomicBool = AtomicBool::new(false);
+
+/// Formats latencies with a scaled unit (set from the global `--human` flag).
+pub(crate) fn set_human(human: bool) {
+    HUMAN.store(human, Ordering::Relaxed);
+}
+
+/// Returns true if `--human` was given.
+pub(crate) fn human() -> bool {
+    HUMAN.load(Ordering::Relaxed)
+}
+
+/// A latency in milliseconds for text output: `350ms`, or with `--human`
+/// `350 ms`, `1.2 s` or `80 µs`.
+pub(crate) fn ms<T: Display + Into<f64>>(value: T) -> String {
+    if human() {
+        scaled(value.into())
+    } else {
+        format!("{}ms", value)
+    }
+}
+
+/// Like `ms`, shown with `decimals` places unless `--human` was given.
+pub(crate) fn ms_f(value: f64, decimals: usize) -> String {
+    if human() {
+        scaled(value)
+    } else {
+        format!("{:.*}ms", decimals, value)
+    }
+}
+
+/// Like `ms_f`, for a change in latency, always signed.
+pub(crate) fn ms_delta(value: f64, decimals: usize) -> String {
+    if human() {
+        let sign = if value < 0.0 { "-" } else { "+" };
+        format!("{}{}", sign, scaled(value.abs()))
+    } else {
+        format!("{:+.*}ms", decimals, value)
+    }
+}
+
+/// Scales `ms` to seconds from 1000ms and to microseconds below 1ms, with
+/// at most one decimal place. The unit is picked by the value as shown, so
+/// 999.96ms is `1 s` rather than `1000 ms`.
+fn scaled(ms: f64) -> String {
+    let shown = |value: f64| (value.abs() * 10.0).round() / 10.0;
+    let (value, unit) = if shown(ms) >= 1000.0 {
+        (ms / 1000.0, "s")
+    } else if ms != 0.0 && shown(ms * 1000.0) < 1000.0 {
+        (ms * 1000.0, "µs")
+    } else {
+        (ms, "ms")
+    };
+    let value = format!("{:.1}", value);
+    format!("{} {}", value.strip_suffix(".0").unwrap_or(&value), unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled() {
+        assert_eq!(scaled(1200.0), "1.2 s");
+        assert_eq!(scaled(60000.0), "60 s");
+        assert_eq!(scaled(350.0), "350 ms");
+        assert_eq!(scaled(2.5), "2.5 ms");
+        assert_eq!(scaled(0.08), "80 µs");
+        assert_eq!(scaled(0.0), "0 ms");
+        // rounding up crosses into the next unit
+        assert_eq!(scaled(999.96), "1 s");
+        assert_eq!(scaled(0.99996), "1 ms");
+        assert_eq!(scaled(-999.96), "-1 s");
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{progress, units};
+use anyhow::{Context, Result};
+use gt_path_core::compose;
+use gt_path_core::constraint::Constraints;
+use gt_path_core::graph::Graph;
+use serde_json::json;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// What `--on-violation` runs when a route starts failing its SLO.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Hook {
+    /// A shell command; `{route}`, `{from}`, `{to}`, `{latency}`, and
+    /// `{max_latency}` are replaced with the failing check's values
+    Command(String),
+    /// An `http://` URL the check is posted to as JSON
+    Webhook(String),
+}
+
+impl Hook {
+    /// An `http://` URL is a webhook; anything else a command. `https://`
+    /// is rejected: like the other HTTP clients here, webhooks are sent
+    /// without TLS.
+    pub(crate) fn parse(raw: &str) -> Result<Hook> {
+        if raw.starts_with("https://") {
+            anyhow::bail!(
+                "HTTPS webhooks aren't supported; run curl from a command instead: {}",
+                raw
+            );
+        }
+        Ok(if raw.starts_with("http://") {
+            Hook::Webhook(raw.to_string())
+        } else {
+            Hook::Command(raw.to_string())
+        })
+    }
+
+    fn fire(&self, check: &Check, max_latency: u32) -> Result<()> {
+        match self {
+            Hook::Command(template) => {
+                let latency = check
+                    .latency_ms
+                    .map_or("none".to_string(), |l| l.to_string());
+                let status = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(expand(template))
+                    .arg("sh")
+                    .arg(format!("{}:{}", check.from, check.to))
+                    .arg(&check.from)
+                    .arg(&check.to)
+                    .arg(latency)
+                    .arg(max_latency.to_string())
+                    .status()
+                    .context(format!("Failed to run '{}'", template))?;
+                if !status.success() {
+                    anyhow::bail!("'{}' exited with {}", template, status);
+                }
+            }
+            Hook::Webhook(url) => {
+                ureq::post(url)
+                    .timeout(Duration::from_secs(10))
+                    .set("Content-Type", "application/json")
+                    .send_string(&check.json(max_latency).to_string())
+                    .context(format!("Failed to post to {}", url))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Placeholders of a command template, in the order their values are
+/// passed to the shell as positional parameters.
+const PLACEHOLDERS: [&str; 5] = ["route", "from", "to", "latency", "max_latency"];
+
+/// Turns a command template into a script that reads each placeholder's
+/// value from its positional parameter, so values never become part of
+/// the script and reach the command intact, whatever they contain.
+///
+/// The template is scanned once, tracking shell quoting: a placeholder
+/// becomes `"${1}"` unquoted, `${1}` inside double quotes, and closes and
+/// reopens single quotes around `"${1}"`.
+fn expand(template: &str) -> String {
+    let mut script = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        let placeholder = (c == '{' && !escaped)
+            .then(|| {
+                PLACEHOLDERS.iter().position(|p| {
+                    rest[1..]
+                        .strip_prefix(p)
+                        .is_some_and(|r| r.starts_with('}'))
+                })
+            })
+            .flatten();
+        if let Some(i) = placeholder {
+            let param = format!("${{{}}}", i + 1);
+            match quote {
+                None => script.push_str(&format!("\"{}\"", param)),
+                Some('"') => script.push_str(&param),
+                _ => script.push_str(&format!("'\"{}\"'", param)),
+            }
+            rest = &rest[PLACEHOLDERS[i].len() + 2..];
+            continue;
+        }
+
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None | Some('"'), '\\') => escaped = true,
+            _ => {}
+        }
+        script.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    script
+}
+
+/// One route's result in one round of checks.
+struct Check {
+    from: String,
+    to: String,
+    /// Shortest path latency, None without a path
+    latency_ms: Option<u32>,
+    slo_met: bool,
+}
+
+impl Check {
+    fn json(&self, max_latency: u32) -> serde_json::Value {
+        json!({
+            "schema_version": gt_path_core::io::SCHEMA_VERSION,
+            "tool_version": env!("CARGO_PKG_VERSION"),
+            "route": format!("{}:{}", self.from, self.to),
+            "from": self.from,
+            "to": self.to,
+            "latency_ms": self.latency_ms,
+            "max_latency_ms": max_latency,
+            "slo_met": self.slo_met,
+        })
+    }
+}
+
+/// Settings for `slo --watch`.
+pub(crate) struct WatchOptions<'a> {
+    pub(crate) routes: &'a [(String, String)],
+    pub(crate) max_latency: u32,
+    pub(crate) constraints: &'a Constraints,
+    pub(crate) interval: Duration,
+    pub(crate) hook: Option<Hook>,
+    pub(crate) json: bool,
+}
+
+/// Checks every route each `interval` until the process is stopped,
+/// reloading the graph whenever its file, or a file it includes, changes.
+///
+/// The first round reports every route; later rounds only report routes
+/// whose status changed. A route going from passing (or unchecked) to
+/// failing fires the hook. A graph that fails to reload keeps the previous
+/// one, with a warning.
+pub(crate) fn run(
+    graph_file: &str,
+    load: impl Fn(&str) -> Result<Graph>,
+    opts: &WatchOptions,
+) -> Result<()> {
+    let mut seen = modified(graph_file);
+    let mut graph = load(graph_file)?;
+    let mut previous: Vec<Option<bool>> = vec![None; opts.routes.len()];
+
+    loop {
+        for ((from, to), last) in opts.routes.iter().zip(&mut previous) {
+            let latency_ms = graph
+                .shortest_path_with(from, to, opts.constraints)
+                .ok()
+                .map(|p| p.cost);
+            let check = Check {
+                from: from.clone(),
+                to: to.clone(),
+                latency_ms,
+                slo_met: latency_ms.is_some_and(|l| l <= opts.max_latency),
+            };
+            if *last == Some(check.slo_met) {
+                continue;
+            }
+            report(&check, opts);
+            if !check.slo_met
+                && let Some(hook) = &opts.hook
+                && let Err(e) = hook.fire(&check, opts.max_latency)
+            {
+                eprintln!("Warning: {:#}", e);
+            }
+            *last = Some(check.slo_met);
+        }
+
+        std::thread::sleep(opts.interval);
+        let current = modified(graph_file);
+        if current != seen {
+            seen = current;
+            match load(graph_file) {
+                Ok(g) => graph = g,
+                Err(e) => eprintln!("Warning: keeping the previous graph: {:#}", e),
+            }
+        }
+    }
+}
+
+fn report(check: &Check, opts: &WatchOptions) {
+    if opts.json {
+        println!("{}", check.json(opts.max_latency));
+    } else if !progress::quiet() {
+        let latency = check.latency_ms.map_or("no path".to_string(), units::ms);
+        let status = if check.slo_met {
+            "✓ PASS"
+        } else {
+            "✗ FAIL"
+        };
+        println!(
+            "{} → {}: {} ({}, max {})",
+            check.from,
+            check.to,
+            status,
+            latency,
+            units::ms(opts.max_latency)
+        );
+    }
+}
+
+/// Latest modification time of the graph file and the files it includes.
+fn modified(graph_file: &str) -> Option<SystemTime> {
+    compose::files(Path::new(graph_file))
+        .unwrap_or_else(|_| vec![graph_file.into()])
+        .iter()
+        .filter_map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(latency_ms: Option<u32>) -> Check {
+        Check {
+            from: "api".to_string(),
+            to: "it's-db".to_string(),
+            latency_ms,
+            slo_met: false,
+        }
+    }
+
+    #[test]
+    fn test_hook_parse_and_expand() {
+        assert_eq!(
+            Hook::parse("http://alerts.internal/x").unwrap(),
+            Hook::Webhook("http://alerts.internal/x".to_string())
+        );
+        assert_eq!(
+            Hook::parse("page {route}").unwrap(),
+            Hook::Command("page {route}".to_string())
+        );
+        assert!(Hook::parse("https://hooks.example.com/x").is_err());
+        assert_eq!(
+            expand("page {route} {latency}/{max_latency} {nodes}"),
+            r#"page "${1}" "${4}"/"${5}" {nodes}"#
+        );
+        assert_eq!(
+            expand(r#"page "on {to}" '{from}' \{to}"#),
+            r#"page "on ${3}" ''"${2}"'' \{to}"#
+        );
+    }
+
+    #[test]
+    fn test_command_hook_passes_values_intact() {
+        let out = std::env::temp_dir().join(format!("gt-path-hook-args-{}", std::process::id()));
+        // a value that looks like a placeholder must not be expanded again
+        let check = Check {
+            from: "{to}".to_string(),
+            to: "'; touch /tmp/pwned #".to_string(),
+            latency_ms: None,
+            slo_met: false,
+        };
+        let hook = Hook::Command(format!(
+            "printf '%s|' {{route}} \"{{from}}\" '{{to}}' {{latency}} > {}",
+            out.display()
+        ));
+        hook.fire(&check, 10).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "{to}:'; touch /tmp/pwned #|{to}|'; touch /tmp/pwned #|none|"
+        );
+        let _ = std::fs::remove_file(&out);
+    }
+
+    #[test]
+    fn test_command_hook_runs() {
+        let out = std::env::temp_dir().join(format!("gt-path-hook-{}", std::process::id()));
+        let hook = Hook::Command(format!("echo {{from}} {{latency}} > {}", out.display()));
+        hook.fire(&check(Some(15)), 10).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "api 15\n");
+        assert!(
+            Hook::Command("exit 3".to_string())
+                .fire(&check(None), 10)
+                .is_err()
+        );
+        let _ = std::fs::remove_file(&out);
+    }
+}
@@ -0,0 +1,255 @@
+//! Minimum spanning arborescence of an edge list read as directed arcs
+//! `u → v`.
+
+use crate::graph::{Edge, Graph, NodeId};
+use std::collections::VecDeque;
+
+/// A spanning tree with every arc directed away from the root.
+pub struct Arborescence {
+    /// One incoming arc per non-root node, sorted by head node ID
+    pub arcs: Vec<Edge>,
+    pub total_weight: f32,
+}
+
+/// An arc at one contraction level: endpoints at that level, reduced
+/// weight, and the index of the arc it came from one level up.
+#[derive(Clone, Copy)]
+struct Arc {
+    u: usize,
+    v: usize,
+    w: f64,
+    parent: usize,
+}
+
+/// One round of Chu-Liu/Edmonds: the cheapest arc into each node and the
+/// cycles they form, which the next level contracts.
+struct Level {
+    arcs: Vec<Arc>,
+    /// Index into `arcs` of the cheapest arc into each node (unused for root)
+    best_in: Vec<usize>,
+    /// Whether each node lies on a contracted cycle
+    on_cycle: Vec<bool>,
+}
+
+/// Finds a minimum-weight arborescence rooted at `root` using the
+/// Chu-Liu/Edmonds algorithm, in O(E V) time. Arcs into the root and self
+/// loops are never used.
+///
+/// # Returns
+///
+/// * `Ok(Arborescence)` - The cheapest set of arcs reaching every node from `root`
+/// * `Err(nodes)` - The nodes not reachable from `root`, sorted by ID
+///
+/// Panics if `root` is out of bounds.
+pub fn min_arborescence(g: &Graph, root: NodeId) -> Result<Arborescence, Vec<NodeId>> {
+    let n = g.size();
+    let root = root.0 as usize;
+    assert!(root < n, "root out of bounds");
+
+    let edges = g.edges();
+    let unreachable = unreachable_from(n, &edges, root);
+    if !unreachable.is_empty() {
+        return Err(unreachable);
+    }
+
+    let mut arcs: Vec<Arc> = edges
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.u != e.v && e.v.0 as usize != root)
+        .map(|(i, e)| Arc {
+            u: e.u.0 as usize,
+            v: e.v.0 as usize,
+            w: e.weight as f64,
+            parent: i,
+        })
+        .collect();
+
+    // contract cycles until the cheapest incoming arcs form a tree
+    let (mut size, mut root_at) = (n, root);
+    let mut levels: Vec<Level> = Vec::new();
+    loop {
+        let mut best_in = vec![usize::MAX; size];
+        for (i, a) in arcs.iter().enumerate() {
+            if best_in[a.v] == usize::MAX || a.w < arcs[best_in[a.v]].w {
+                best_in[a.v] = i;
+            }
+        }
+
+        let (comp, on_cycle, count) = contract(size, root_at, &arcs, &best_in);
+        let done = count == size;
+        let next: Vec<Arc> = if done {
+            Vec::new()
+        } else {
+            arcs.iter()
+                .enumerate()
+                .filter(|(_, a)| comp[a.u] != comp[a.v])
+                .map(|(i, a)| Arc {
+                    u: comp[a.u],
+                    v: comp[a.v],
+                    // entering a cycle replaces the cycle arc into a.v
+                    w: if on_cycle[a.v] {
+                        a.w - arcs[best_in[a.v]].w
+                    } else {
+                        a.w
+                    },
+                    parent: i,
+                })
+                .collect()
+        };
+        root_at = comp[root_at];
+
+        levels.push(Level {
+            arcs: std::mem::replace(&mut arcs, next),
+            best_in,
+            on_cycle,
+        });
+        if done {
+            break;
+        }
+        size = count;
+    }
+
+    // expand back out: chosen arcs at the deepest level are the cheapest
+    // incoming arcs; each contracted cycle keeps all its arcs but the one
+    // into the node where the chosen arc enters it
+    let last = levels.last().expect("at least one level");
+    let mut chosen: Vec<usize> = (0..last.best_in.len())
+        .filter(|&v| v != root_at)
+        .map(|v| last.best_in[v])
+        .collect();
+    for depth in (0..levels.len() - 1).rev() {
+        let level = &levels[depth];
+        let mut entered = vec![false; level.best_in.len()];
+        let mut expanded = Vec::with_capacity(level.best_in.len());
+        for &i in &chosen {
+            let parent = levels[depth + 1].arcs[i].parent;
+            entered[level.arcs[parent].v] = true;
+            expanded.push(parent);
+        }
+        for (v, &arc) in level.best_in.iter().enumerate() {
+            if level.on_cycle[v] && !entered[v] {
+                expanded.push(arc);
+            }
+        }
+        chosen = expanded;
+    }
+
+    let mut arcs: Vec<Edge> = chosen
+        .iter()
+        .map(|&i| edges[levels[0].arcs[i].parent])
+        .collect();
+    arcs.sort_by_key(|e| e.v);
+    let total_weight = arcs.iter().map(|e| e.weight).sum();
+    Ok(Arborescence { arcs, total_weight })
+}
+
+/// Finds the cycles formed by following `best_in` backwards and maps each
+/// to a single node. Returns the new node of each node, whether it is on a
+/// cycle, and the number of new nodes.
+fn contract(
+    size: usize,
+    root: usize,
+    arcs: &[Arc],
+    best_in: &[usize],
+) -> (Vec<usize>, Vec<bool>, usize) {
+    const UNSEEN: usize = usize::MAX;
+    let mut comp = vec![UNSEEN; size];
+    let mut on_cycle = vec![false; size];
+    let mut walk = vec![UNSEEN; size];
+    let mut count = 0;
+
+    for start in 0..size {
+        // walk backwards until reaching the root, an earlier walk, or this one
+        let mut v = start;
+        while v != root && walk[v] == UNSEEN && comp[v] == UNSEEN {
+            walk[v] = start;
+            v = arcs[best_in[v]].u;
+        }
+        if v != root && walk[v] == start && comp[v] == UNSEEN {
+            // closed a cycle through v
+            let mut x = v;
+            loop {
+                comp[x] = count;
+                on_cycle[x] = true;
+                x = arcs[best_in[x]].u;
+                if x == v {
+                    break;
+                }
+            }
+            count += 1;
+        }
+    }
+    for c in comp.iter_mut() {
+        if *c == UNSEEN {
+            *c = count;
+            count += 1;
+        }
+    }
+    (comp, on_cycle, count)
+}
+
+fn unreachable_from(n: usize, edges: &[Edge], root: usize) -> Vec<NodeId> {
+    let mut out = vec![Vec::new(); n];
+    for e in edges {
+        out[e.u.0 as usize].push(e.v.0 as usize);
+    }
+    let mut seen = vec![false; n];
+    let mut queue = VecDeque::from([root]);
+    seen[root] = true;
+    while let Some(u) = queue.pop_front() {
+        for &v in &out[u] {
+            if !seen[v] {
+                seen[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+    (0..n)
+        .filter(|&v| !seen[v])
+        .map(|v| NodeId(v as u32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(nodes: usize, arcs: &[(u32, u32, f32)]) -> Graph {
+        let mut g = Graph::new(nodes);
+        for &(u, v, weight) in arcs {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight,
+            });
+        }
+        g
+    }
+
+    #[test]
+    fn test_contracts_cycle() {
+        // 1 and 2 prefer each other (a cycle of weight 2); the cheapest way
+        // into the cycle is 0 → 2, which replaces 1 → 2
+        let g = graph(
+            4,
+            &[
+                (0, 1, 10.0),
+                (0, 2, 4.0),
+                (1, 2, 1.0),
+                (2, 1, 1.0),
+                (2, 3, 3.0),
+                (1, 3, 5.0),
+            ],
+        );
+        let tree = min_arborescence(&g, NodeId(0)).unwrap();
+        let arcs: Vec<(u32, u32)> = tree.arcs.iter().map(|e| (e.u.0, e.v.0)).collect();
+        assert_eq!(arcs, vec![(2, 1), (0, 2), (2, 3)]);
+        assert_eq!(tree.total_weight, 8.0);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let g = graph(3, &[(0, 1, 1.0), (2, 1, 1.0)]);
+        assert_eq!(min_arborescence(&g, NodeId(0)).err(), Some(vec![NodeId(2)]));
+    }
+}
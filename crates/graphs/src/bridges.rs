@@ -0,0 +1,190 @@
+use crate::dsu::DisjointSet;
+use crate::graph::{Graph, NodeId};
+use std::collections::HashSet;
+
+/// The 2-edge-connected components of a graph: maximal groups of nodes that
+/// stay connected when any single edge is removed. Bridges are exactly the
+/// edges between different components.
+pub struct TwoEdgeComponents {
+    /// Component ID of each node, numbered in order of their lowest node ID
+    pub component: Vec<usize>,
+    /// Number of nodes in each component
+    pub sizes: Vec<usize>,
+}
+
+/// A bridge together with the parts of the graph it holds together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeSplit {
+    pub u: NodeId,
+    pub v: NodeId,
+    /// 2-edge-connected components of `u` and `v`
+    pub components: (usize, usize),
+    /// Number of nodes in each of those components
+    pub component_sizes: (usize, usize),
+    /// Nodes left on `u`'s and `v`'s side once the bridge is removed
+    pub side_sizes: (usize, usize),
+}
+
+/// Groups nodes into 2-edge-connected components by joining the endpoints
+/// of every non-bridge edge.
+pub fn two_edge_components(g: &Graph) -> TwoEdgeComponents {
+    let (_, bridges) = g.critical_components();
+    two_edge_components_with(g, &bridges)
+}
+
+fn two_edge_components_with(g: &Graph, bridges: &[(NodeId, NodeId)]) -> TwoEdgeComponents {
+    let bridges: HashSet<(NodeId, NodeId)> = bridges.iter().copied().collect();
+    let mut ds = DisjointSet::new(g.size());
+    for e in g.edges() {
+        if !bridges.contains(&(e.u.min(e.v), e.u.max(e.v))) {
+            ds.union(e.u.0 as usize, e.v.0 as usize);
+        }
+    }
+
+    // renumber roots densely so IDs don't depend on union order
+    let mut id_of_root = vec![usize::MAX; g.size()];
+    let mut component = Vec::with_capacity(g.size());
+    let mut sizes = Vec::new();
+    for n in 0..g.size() {
+        let root = ds.find(n);
+        if id_of_root[root] == usize::MAX {
+            id_of_root[root] = sizes.len();
+            sizes.push(0);
+        }
+        component.push(id_of_root[root]);
+        sizes[id_of_root[root]] += 1;
+    }
+
+    TwoEdgeComponents { component, sizes }
+}
+
+/// Describes every bridge by the components it connects and how many nodes
+/// end up on each side if it fails, in the same order as the bridges from
+/// `Graph::critical_components`.
+///
+/// A bridge to a single leaf has side sizes `(n - 1, 1)`; a bridge that
+/// splits the network in half has roughly equal sides.
+pub fn bridge_splits(g: &Graph) -> Vec<BridgeSplit> {
+    let (_, bridges) = g.critical_components();
+    let comps = two_edge_components_with(g, &bridges);
+    let count = comps.sizes.len();
+
+    // collapsing each component to a single node turns the bridges into a forest
+    let mut tree = vec![Vec::new(); count];
+    for (u, v) in &bridges {
+        let (cu, cv) = (comps.component[u.0 as usize], comps.component[v.0 as usize]);
+        tree[cu].push(cv);
+        tree[cv].push(cu);
+    }
+
+    // subtree node counts, with each tree rooted at its lowest component
+    let mut parent = vec![None; count];
+    let mut subtree = comps.sizes.clone();
+    let mut tree_size = vec![0; count];
+    let mut visited = vec![false; count];
+    for root in 0..count {
+        if visited[root] {
+            continue;
+        }
+
+        let mut order = vec![root];
+        visited[root] = true;
+        let mut i = 0;
+        while i < order.len() {
+            let c = order[i];
+            for &next in &tree[c] {
+                if !visited[next] {
+                    visited[next] = true;
+                    parent[next] = Some(c);
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+
+        for &c in order.iter().rev() {
+            if let Some(p) = parent[c] {
+                subtree[p] += subtree[c];
+            }
+        }
+        for &c in &order {
+            tree_size[c] = subtree[root];
+        }
+    }
+
+    bridges
+        .iter()
+        .map(|&(u, v)| {
+            let (cu, cv) = (comps.component[u.0 as usize], comps.component[v.0 as usize]);
+            let total = tree_size[cu];
+            let side_sizes = if parent[cv] == Some(cu) {
+                (total - subtree[cv], subtree[cv])
+            } else {
+                (subtree[cu], total - subtree[cu])
+            };
+
+            BridgeSplit {
+                u,
+                v,
+                components: (cu, cv),
+                component_sizes: (comps.sizes[cu], comps.sizes[cv]),
+                side_sizes,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_and_half_splits() {
+        // two triangles joined by 2-3, plus a leaf 6 hanging off 5
+        let g = Graph::from_unit_edges(
+            7,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 3),
+                (5, 6),
+            ],
+        );
+
+        let comps = two_edge_components(&g);
+        assert_eq!(comps.component, vec![0, 0, 0, 1, 1, 1, 2]);
+        assert_eq!(comps.sizes, vec![3, 3, 1]);
+
+        let splits = bridge_splits(&g);
+        assert_eq!(
+            splits,
+            vec![
+                BridgeSplit {
+                    u: NodeId(2),
+                    v: NodeId(3),
+                    components: (0, 1),
+                    component_sizes: (3, 3),
+                    side_sizes: (3, 4),
+                },
+                BridgeSplit {
+                    u: NodeId(5),
+                    v: NodeId(6),
+                    components: (1, 2),
+                    component_sizes: (3, 1),
+                    side_sizes: (6, 1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_bridges() {
+        let g = Graph::from_unit_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert!(bridge_splits(&g).is_empty());
+        assert_eq!(two_edge_components(&g).sizes, vec![3]);
+    }
+}
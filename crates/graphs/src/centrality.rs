@@ -0,0 +1,100 @@
+use crate::graph::Graph;
+use std::collections::VecDeque;
+
+/// Computes the betweenness of every edge with Brandes' algorithm, in
+/// O(V E) time: for each pair of nodes, the share of their shortest paths
+/// that run through the edge, summed over all unordered pairs.
+///
+/// Shortest paths are counted in hops, so weights are ignored. Parallel
+/// edges are separate routes and split the paths between them; self loops
+/// carry none. The result is indexed like `g.edges()`.
+pub fn edge_betweenness(g: &Graph) -> Vec<f64> {
+    let n = g.size();
+    let edges = g.edges();
+    // (neighbor, edge index)
+    let mut adj = vec![Vec::new(); n];
+    for (i, e) in edges.iter().enumerate() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if u != v {
+            adj[u].push((v, i));
+            adj[v].push((u, i));
+        }
+    }
+
+    let mut betweenness = vec![0.0; edges.len()];
+    let mut dist = vec![usize::MAX; n];
+    let mut sigma = vec![0.0f64; n];
+    let mut delta = vec![0.0f64; n];
+    for s in 0..n {
+        dist.fill(usize::MAX);
+        sigma.fill(0.0);
+        delta.fill(0.0);
+        dist[s] = 0;
+        sigma[s] = 1.0;
+
+        // BFS, recording nodes in order of distance
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            for &(v, _) in &adj[u] {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    queue.push_back(v);
+                }
+                if dist[v] == dist[u] + 1 {
+                    sigma[v] += sigma[u];
+                }
+            }
+        }
+
+        // accumulate dependencies from the farthest nodes back
+        for &w in order.iter().rev() {
+            for &(v, i) in &adj[w] {
+                if dist[v] != usize::MAX && dist[v] + 1 == dist[w] {
+                    let share = sigma[v] / sigma[w] * (1.0 + delta[w]);
+                    betweenness[i] += share;
+                    delta[v] += share;
+                }
+            }
+        }
+    }
+
+    // every unordered pair was counted from both ends
+    for b in &mut betweenness {
+        *b /= 2.0;
+    }
+    betweenness
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_betweenness() {
+        // path 0-1-2: each edge carries its own pair and the 0-2 pair
+        let path = Graph::from_unit_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(edge_betweenness(&path), vec![2.0, 2.0]);
+
+        // square 0-1-2-3: opposite corners split their two routes
+        let square = Graph::from_unit_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(edge_betweenness(&square), vec![2.0; 4]);
+
+        // parallel edges share the pair they connect; self loops carry nothing
+        let parallel = Graph::from_unit_edges(2, &[(0, 1), (0, 1), (1, 1)]);
+        assert_eq!(edge_betweenness(&parallel), vec![0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_bridge_carries_crossing_pairs() {
+        // two triangles joined by the bridge 2-3: all 9 crossing pairs use it
+        let g =
+            Graph::from_unit_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)]);
+        let b = edge_betweenness(&g);
+        assert_eq!(b[3], 9.0);
+        let total: f64 = b.iter().sum();
+        // sum over edges = sum over pairs of their distance
+        assert!((total - 27.0).abs() < 1e-9);
+    }
+}
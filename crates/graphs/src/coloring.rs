@@ -0,0 +1,115 @@
+use crate::graph::Graph;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+/// An assignment of colors to nodes in which no edge joins two nodes of the
+/// same color.
+pub struct Coloring {
+    /// Color of each node, numbered from 0
+    pub colors: Vec<usize>,
+    /// Number of colors used; an upper bound on the chromatic number
+    pub num_colors: usize,
+}
+
+/// Colors nodes in ID order, giving each the smallest color not used by an
+/// already-colored neighbor. Fast, but the result depends on node order.
+pub fn greedy_coloring(g: &Graph) -> Coloring {
+    let adj = neighbors(g);
+    let mut colors = vec![usize::MAX; g.size()];
+    for v in 0..g.size() {
+        colors[v] = smallest_free(adj[v].iter().map(|&u| colors[u]));
+    }
+    finish(colors)
+}
+
+/// Colors nodes with Brélaz's DSATUR heuristic: the next node is always the
+/// one whose neighbors already use the most distinct colors, with ties
+/// broken by degree and then by lowest ID. Usually needs fewer colors than
+/// plain greedy coloring, and is exact for bipartite graphs.
+pub fn dsatur(g: &Graph) -> Coloring {
+    let n = g.size();
+    let adj = neighbors(g);
+    let mut colors = vec![usize::MAX; n];
+    // distinct neighbor colors per node
+    let mut seen: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+
+    let key = |v: usize, seen: &[BTreeSet<usize>]| (seen[v].len(), adj[v].len(), Reverse(v));
+    let mut queue: BTreeSet<_> = (0..n).map(|v| key(v, &seen)).collect();
+
+    while let Some((_, _, Reverse(v))) = queue.pop_last() {
+        let color = smallest_free(seen[v].iter().copied());
+        colors[v] = color;
+
+        for &u in &adj[v] {
+            if colors[u] == usize::MAX && !seen[u].contains(&color) {
+                queue.remove(&key(u, &seen));
+                seen[u].insert(color);
+                queue.insert(key(u, &seen));
+            }
+        }
+    }
+
+    finish(colors)
+}
+
+/// Deduplicated adjacency lists, ignoring self loops.
+fn neighbors(g: &Graph) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); g.size()];
+    for e in g.edges() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for list in &mut adj {
+        list.sort_unstable();
+        list.dedup();
+    }
+    adj
+}
+
+/// Smallest color not in `used` (uncolored entries are ignored).
+fn smallest_free(used: impl Iterator<Item = usize>) -> usize {
+    let used: BTreeSet<usize> = used.collect();
+    (0..).find(|c| !used.contains(c)).unwrap_or_default()
+}
+
+fn finish(colors: Vec<usize>) -> Coloring {
+    let num_colors = colors.iter().map(|&c| c + 1).max().unwrap_or(0);
+    Coloring { colors, num_colors }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_proper(g: &Graph, c: &Coloring) -> bool {
+        g.edges()
+            .iter()
+            .all(|e| e.u == e.v || c.colors[e.u.0 as usize] != c.colors[e.v.0 as usize])
+    }
+
+    #[test]
+    fn test_dsatur_beats_bad_greedy_order() {
+        // crown graph: greedy in ID order pairs 0-1, 2-3, ... and uses 3
+        // colors, while it is bipartite
+        let g = Graph::from_unit_edges(6, &[(0, 3), (0, 5), (1, 2), (1, 4), (2, 5), (3, 4)]);
+
+        let greedy = greedy_coloring(&g);
+        assert!(is_proper(&g, &greedy));
+        assert_eq!(greedy.num_colors, 3);
+
+        let dsatur = dsatur(&g);
+        assert!(is_proper(&g, &dsatur));
+        assert_eq!(dsatur.num_colors, 2);
+    }
+
+    #[test]
+    fn test_odd_cycle_needs_three() {
+        let g = Graph::from_unit_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        let c = dsatur(&g);
+        assert!(is_proper(&g, &c));
+        assert_eq!(c.num_colors, 3);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::flow::FlowNetwork;
+use crate::graph::{Graph, NodeId};
+use std::collections::HashSet;
+
+/// Finds a minimum set of nodes, other than `s` and `t`, whose removal
+/// disconnects `s` from `t`. Its size is the number of node-disjoint paths
+/// between them (Menger's theorem), so up to `size - 1` of those nodes can
+/// fail at once without cutting `s` off from `t`.
+///
+/// Computed as a max flow on the split-node graph: each node becomes an
+/// `in → out` arc of capacity 1, and each edge a pair of unbounded arcs.
+///
+/// # Returns
+///
+/// * `Some(nodes)` - The cut closest to `s`, sorted by ID (empty if `s` and
+///   `t` are already disconnected)
+/// * `None` - If `s` and `t` are the same node or adjacent, since then no
+///   set of other nodes separates them
+///
+/// Panics if either node ID is out of bounds.
+pub fn min_vertex_cut(g: &Graph, s: NodeId, t: NodeId) -> Option<Vec<NodeId>> {
+    let n = g.size();
+    let (s, t) = (s.0 as usize, t.0 as usize);
+    assert!(s < n && t < n, "node out of bounds");
+    if s == t {
+        return None;
+    }
+
+    let mut net = split_network(g, s, t)?;
+    net.max_flow(2 * s + 1, 2 * t, n as u64 + 1);
+    Some(vertex_cut_side(&net, n, s))
+}
+
+/// Global edge connectivity: the fewest edges whose removal disconnects
+/// the graph, with a cut that achieves it.
+pub struct EdgeConnectivity {
+    pub value: usize,
+    /// Edges of a minimum cut as `(smaller ID, larger ID)`, sorted
+    pub cut: Vec<(NodeId, NodeId)>,
+}
+
+/// Global vertex connectivity: the fewest nodes whose removal disconnects
+/// the graph, with a cut that achieves it.
+pub struct VertexConnectivity {
+    /// `n - 1` for a complete graph, which no node removal disconnects
+    pub value: usize,
+    /// Nodes of a minimum cut, sorted by ID (empty for a complete graph)
+    pub cut: Vec<NodeId>,
+}
+
+/// Computes the edge connectivity as the smallest minimum edge cut between
+/// node 0 and every other node; some such pair is always separated by a
+/// global minimum cut. A disconnected graph (or one with fewer than two
+/// nodes) has connectivity 0.
+pub fn edge_connectivity(g: &Graph) -> EdgeConnectivity {
+    let n = g.size();
+    let mut best = EdgeConnectivity {
+        value: g.edges().len(),
+        cut: Vec::new(),
+    };
+    if n < 2 {
+        best.value = 0;
+        return best;
+    }
+
+    for t in 1..n {
+        let mut net = FlowNetwork::new(n);
+        for e in g.edges() {
+            net.add_arc(e.u.0 as usize, e.v.0 as usize, 1);
+            net.add_arc(e.v.0 as usize, e.u.0 as usize, 1);
+        }
+
+        // stop as soon as this pair can't beat the best cut so far
+        let flow = net.max_flow(0, t, best.value as u64) as usize;
+        if flow < best.value || best.cut.is_empty() {
+            let side = net.reachable(0);
+            let mut cut: Vec<(NodeId, NodeId)> = g
+                .edges()
+                .iter()
+                .filter(|e| side[e.u.0 as usize] != side[e.v.0 as usize])
+                .map(|e| (e.u.min(e.v), e.u.max(e.v)))
+                .collect();
+            cut.sort_unstable();
+            best = EdgeConnectivity { value: flow, cut };
+        }
+        if best.value == 0 {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Computes the vertex connectivity with Even's algorithm: the smallest
+/// minimum vertex cut over non-adjacent pairs whose first node is among
+/// the first `k + 1` nodes, where `k` is the best cut found so far. At least
+/// one of those nodes lies outside any minimum cut.
+pub fn vertex_connectivity(g: &Graph) -> VertexConnectivity {
+    let n = g.size();
+    let mut adjacent = vec![HashSet::new(); n];
+    for e in g.edges() {
+        adjacent[e.u.0 as usize].insert(e.v.0 as usize);
+        adjacent[e.v.0 as usize].insert(e.u.0 as usize);
+    }
+
+    let mut best = VertexConnectivity {
+        value: n.saturating_sub(1),
+        cut: Vec::new(),
+    };
+    let mut s = 0;
+    while s <= best.value && s < n {
+        for t in s + 1..n {
+            if adjacent[s].contains(&t) {
+                continue;
+            }
+            let Some(mut net) = split_network(g, s, t) else {
+                continue;
+            };
+
+            let flow = net.max_flow(2 * s + 1, 2 * t, best.value as u64) as usize;
+            if flow < best.value {
+                best = VertexConnectivity {
+                    value: flow,
+                    cut: vertex_cut_side(&net, n, s),
+                };
+            }
+            if best.value == 0 {
+                return best;
+            }
+        }
+        s += 1;
+    }
+
+    best
+}
+
+/// Builds the split-node network for separating `s` from `t`: node `v`
+/// becomes `v_in = 2v` and `v_out = 2v + 1`. Returns None if `s` and `t`
+/// are adjacent.
+fn split_network(g: &Graph, s: usize, t: usize) -> Option<FlowNetwork> {
+    let n = g.size();
+    let unbounded = n as u64 + 1;
+    let mut net = FlowNetwork::new(2 * n);
+    for v in 0..n {
+        let cap = if v == s || v == t { unbounded } else { 1 };
+        net.add_arc(2 * v, 2 * v + 1, cap);
+    }
+    for e in g.edges() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if (u == s && v == t) || (u == t && v == s) {
+            return None;
+        }
+        net.add_arc(2 * u + 1, 2 * v, unbounded);
+        net.add_arc(2 * v + 1, 2 * u, unbounded);
+    }
+    Some(net)
+}
+
+/// Reads the vertex cut closest to `s` off a solved split-node network.
+fn vertex_cut_side(net: &FlowNetwork, n: usize, s: usize) -> Vec<NodeId> {
+    let side = net.reachable(2 * s + 1);
+    (0..n)
+        .filter(|&v| side[2 * v] && !side[2 * v + 1])
+        .map(|v| NodeId(v as u32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_vertex_cut() {
+        // 0 reaches 5 through 1-3, 2-3 or 2-4; the cut closest to 0 is {1, 2}
+        let g =
+            Graph::from_unit_edges(6, &[(0, 1), (0, 2), (1, 3), (2, 3), (2, 4), (3, 5), (4, 5)]);
+        assert_eq!(
+            min_vertex_cut(&g, NodeId(0), NodeId(5)),
+            Some(vec![NodeId(1), NodeId(2)])
+        );
+    }
+
+    #[test]
+    fn test_adjacent_and_disconnected() {
+        let g = Graph::from_unit_edges(4, &[(0, 1), (2, 3)]);
+        assert_eq!(min_vertex_cut(&g, NodeId(0), NodeId(1)), None);
+        assert_eq!(min_vertex_cut(&g, NodeId(0), NodeId(3)), Some(vec![]));
+    }
+
+    #[test]
+    fn test_connectivity() {
+        // two triangles sharing node 2, joined again by the edge 0-4
+        let g =
+            Graph::from_unit_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (0, 4)]);
+        let edge = edge_connectivity(&g);
+        assert_eq!(edge.value, 2);
+        assert_eq!(edge.cut.len(), 2);
+
+        let vertex = vertex_connectivity(&g);
+        assert_eq!(vertex.value, 2);
+        assert_eq!(vertex.cut.len(), 2);
+
+        let chain = Graph::from_unit_edges(3, &[(0, 1), (1, 2)]);
+        assert_eq!(vertex_connectivity(&chain).cut, vec![NodeId(1)]);
+        assert_eq!(edge_connectivity(&chain).value, 1);
+
+        let complete = Graph::from_unit_edges(3, &[(0, 1), (1, 2), (2, 0)]);
+        let vertex = vertex_connectivity(&complete);
+        assert_eq!((vertex.value, vertex.cut.len()), (2, 0));
+        assert_eq!(
+            edge_connectivity(&Graph::from_unit_edges(3, &[(0, 1)])).value,
+            0
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::graph::{Graph, NodeId};
+use std::collections::BTreeSet;
+
+/// A subgraph chosen for its edge density.
+pub struct DenseSubgraph {
+    /// Nodes of the subgraph, sorted by ID
+    pub nodes: Vec<NodeId>,
+    /// Number of distinct edges with both ends in the subgraph
+    pub edges: usize,
+    /// `edges / nodes`, or 0 for an empty subgraph
+    pub density: f64,
+}
+
+/// Approximates the densest subgraph with Charikar's greedy peeling:
+/// repeatedly remove a node of minimum degree and keep the densest of the
+/// intermediate subgraphs. The result is at least half as dense as the
+/// optimum.
+///
+/// Density is the number of edges per node. Parallel edges are counted
+/// once, self loops are ignored, and weights are not considered.
+pub fn densest_subgraph(g: &Graph) -> DenseSubgraph {
+    let n = g.size();
+    let mut adj = vec![Vec::new(); n];
+    for e in g.edges() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for list in &mut adj {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let mut degree: Vec<usize> = adj.iter().map(Vec::len).collect();
+    let mut edges = degree.iter().sum::<usize>() / 2;
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|v| (degree[v], v)).collect();
+    let mut removed = vec![false; n];
+
+    // peeling order; the best subgraph is everything after `best_cut`
+    let mut order = Vec::with_capacity(n);
+    let mut best = (0, 1);
+    let mut best_cut = n;
+    while !queue.is_empty() {
+        let remaining = queue.len();
+        // compare edges / remaining against best.0 / best.1 without division
+        if edges * best.1 > best.0 * remaining {
+            best = (edges, remaining);
+            best_cut = order.len();
+        }
+
+        let Some((_, v)) = queue.pop_first() else {
+            break;
+        };
+        removed[v] = true;
+        order.push(v);
+        edges -= degree[v];
+        for &u in &adj[v] {
+            if !removed[u] {
+                queue.remove(&(degree[u], u));
+                degree[u] -= 1;
+                queue.insert((degree[u], u));
+            }
+        }
+    }
+
+    let mut nodes: Vec<NodeId> = order[best_cut.min(order.len())..]
+        .iter()
+        .map(|&v| NodeId(v as u32))
+        .collect();
+    nodes.sort_unstable();
+    let density = if nodes.is_empty() {
+        0.0
+    } else {
+        best.0 as f64 / best.1 as f64
+    };
+
+    DenseSubgraph {
+        nodes,
+        edges: best.0,
+        density,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clique_with_tail() {
+        // K4 on 0-3 with a path 3-4-5-6 hanging off it
+        let g = Graph::from_unit_edges(
+            7,
+            &[
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 6),
+            ],
+        );
+        let dense = densest_subgraph(&g);
+        assert_eq!(dense.nodes, (0..4).map(NodeId).collect::<Vec<_>>());
+        assert_eq!(dense.edges, 6);
+        assert_eq!(dense.density, 1.5);
+    }
+
+    #[test]
+    fn test_no_edges() {
+        let dense = densest_subgraph(&Graph::from_unit_edges(3, &[]));
+        assert!(dense.nodes.is_empty());
+        assert_eq!(dense.density, 0.0);
+    }
+}
@@ -0,0 +1,401 @@
+//! Directed analysis of an edge list, reading each `Edge { u, v, .. }` as
+//! an arc `u → v`.
+
+use crate::graph::{Graph, NodeId};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Out- and in-adjacency lists of the graph read as directed.
+struct Digraph {
+    out: Vec<Vec<usize>>,
+    inc: Vec<Vec<usize>>,
+}
+
+impl Digraph {
+    fn new(g: &Graph) -> Digraph {
+        let mut out = vec![Vec::new(); g.size()];
+        let mut inc = vec![Vec::new(); g.size()];
+        for e in g.edges() {
+            out[e.u.0 as usize].push(e.v.0 as usize);
+            inc[e.v.0 as usize].push(e.u.0 as usize);
+        }
+        Digraph { out, inc }
+    }
+}
+
+/// Finds strongly connected components using Tarjan's algorithm.
+/// Returns the component ID of each node. IDs are numbered in order of each
+/// component's lowest node ID, so the result is deterministic.
+pub fn strongly_connected_components(g: &Graph) -> Vec<usize> {
+    let d = Digraph::new(g);
+    renumber(&tarjan_scc(&d.out, &vec![true; g.size()]))
+}
+
+/// A directed graph with each strongly connected component collapsed into a
+/// single node. The result is always acyclic.
+pub struct Condensation {
+    /// Component ID of each node (see `strongly_connected_components`)
+    pub component: Vec<usize>,
+    /// Nodes in each component, sorted by ID
+    pub members: Vec<Vec<NodeId>>,
+    /// Arcs between components, sorted by `(from, to)`
+    pub arcs: Vec<CondensedArc>,
+}
+
+/// An arc between two components of a `Condensation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CondensedArc {
+    pub from: usize,
+    pub to: usize,
+    /// Number of original arcs it stands for
+    pub count: usize,
+}
+
+/// Collapses each strongly connected component into a single node.
+pub fn condensation(g: &Graph) -> Condensation {
+    let component = strongly_connected_components(g);
+    let count = component.iter().map(|&c| c + 1).max().unwrap_or(0);
+
+    let mut members = vec![Vec::new(); count];
+    for (n, &c) in component.iter().enumerate() {
+        members[c].push(NodeId(n as u32));
+    }
+
+    let mut counts = BTreeMap::new();
+    for e in g.edges() {
+        let (from, to) = (component[e.u.0 as usize], component[e.v.0 as usize]);
+        if from != to {
+            *counts.entry((from, to)).or_insert(0) += 1;
+        }
+    }
+    let arcs = counts
+        .into_iter()
+        .map(|((from, to), count)| CondensedArc { from, to, count })
+        .collect();
+
+    Condensation {
+        component,
+        members,
+        arcs,
+    }
+}
+
+/// Tarjan's SCC over the nodes with `keep[n]` set; other nodes get
+/// `usize::MAX`. Iterative so deep graphs can't overflow the stack.
+fn tarjan_scc(out: &[Vec<usize>], keep: &[bool]) -> Vec<usize> {
+    let n = out.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut comp = vec![usize::MAX; n];
+    let mut next_index = 0;
+    let mut next_comp = 0;
+
+    for start in 0..n {
+        if !keep[start] || index[start] != usize::MAX {
+            continue;
+        }
+
+        // (node, position in its out-list)
+        let mut call = vec![(start, 0)];
+        index[start] = next_index;
+        low[start] = next_index;
+        next_index += 1;
+        stack.push(start);
+        on_stack[start] = true;
+
+        while let Some(&mut (u, ref mut i)) = call.last_mut() {
+            if let Some(&v) = out[u].get(*i) {
+                *i += 1;
+                if !keep[v] {
+                    continue;
+                }
+                if index[v] == usize::MAX {
+                    index[v] = next_index;
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    call.push((v, 0));
+                } else if on_stack[v] {
+                    low[u] = low[u].min(index[v]);
+                }
+                continue;
+            }
+
+            call.pop();
+            if let Some(&(parent, _)) = call.last() {
+                low[parent] = low[parent].min(low[u]);
+            }
+            if low[u] == index[u] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    comp[w] = next_comp;
+                    if w == u {
+                        break;
+                    }
+                }
+                next_comp += 1;
+            }
+        }
+    }
+
+    comp
+}
+
+/// Renumbers component IDs in order of first appearance by node ID.
+fn renumber(comp: &[usize]) -> Vec<usize> {
+    let mut ids = vec![usize::MAX; comp.len()];
+    let mut next = 0;
+    comp.iter()
+        .map(|&c| {
+            if ids[c] == usize::MAX {
+                ids[c] = next;
+                next += 1;
+            }
+            ids[c]
+        })
+        .collect()
+}
+
+/// Strong bridges and strong articulation points of a directed graph.
+pub struct StrongCritical {
+    /// Nodes whose removal splits a strongly connected component, sorted by ID
+    pub articulation_points: Vec<NodeId>,
+    /// Arcs `(u, v)` whose removal splits a strongly connected component,
+    /// sorted lexicographically
+    pub bridges: Vec<(NodeId, NodeId)>,
+}
+
+/// Finds strong bridges and strong articulation points (Italiano, Laura and
+/// Santaroni): the arcs and nodes whose removal increases the number of
+/// strongly connected components.
+///
+/// Within each component, with an arbitrary root `r`, they are exactly the
+/// bridges and non-trivial dominators of the flow graphs rooted at `r` in
+/// the graph and its reverse, plus `r` itself if removing it breaks the
+/// component apart. Arcs between components are never critical.
+pub fn strong_critical_components(g: &Graph) -> StrongCritical {
+    let d = Digraph::new(g);
+    let comp = strongly_connected_components(g);
+    let count = comp.iter().map(|&c| c + 1).max().unwrap_or(0);
+    let mut members = vec![Vec::new(); count];
+    for (n, &c) in comp.iter().enumerate() {
+        members[c].push(n);
+    }
+
+    let mut points = BTreeSet::new();
+    let mut bridges = BTreeSet::new();
+    let mut local = vec![usize::MAX; g.size()];
+    for nodes in members.iter().filter(|m| m.len() > 1) {
+        // local indices, with the lowest node as the root
+        for (i, &n) in nodes.iter().enumerate() {
+            local[n] = i;
+        }
+        let restrict = |adj: &[Vec<usize>]| -> Vec<Vec<usize>> {
+            nodes
+                .iter()
+                .map(|&n| {
+                    adj[n]
+                        .iter()
+                        .filter(|&&m| local[m] != usize::MAX)
+                        .map(|&m| local[m])
+                        .collect()
+                })
+                .collect()
+        };
+        let (out, inc) = (restrict(&d.out), restrict(&d.inc));
+
+        for (succ, pred, reversed) in [(&out, &inc, false), (&inc, &out, true)] {
+            let idom = dominators(succ, pred);
+            for (v, &dom) in idom.iter().enumerate().skip(1) {
+                if dom != 0 {
+                    points.insert(NodeId(nodes[dom] as u32));
+                }
+                if let Some(u) = flow_bridge_into(v, &idom, pred) {
+                    let (u, v) = (NodeId(nodes[u] as u32), NodeId(nodes[v] as u32));
+                    bridges.insert(if reversed { (v, u) } else { (u, v) });
+                }
+            }
+        }
+
+        let without_root: Vec<bool> = (0..nodes.len()).map(|i| i != 0).collect();
+        let split = tarjan_scc(&out, &without_root);
+        if split.iter().skip(1).any(|&c| c != split[1]) {
+            points.insert(NodeId(nodes[0] as u32));
+        }
+        for &n in nodes {
+            local[n] = usize::MAX;
+        }
+    }
+
+    StrongCritical {
+        articulation_points: points.into_iter().collect(),
+        bridges: bridges.into_iter().collect(),
+    }
+}
+
+/// Immediate dominators of a flow graph rooted at node 0 in which every node
+/// is reachable, using the Cooper-Harvey-Kennedy iterative algorithm. The
+/// root is its own dominator.
+fn dominators(succ: &[Vec<usize>], pred: &[Vec<usize>]) -> Vec<usize> {
+    let n = succ.len();
+
+    // reverse postorder from the root
+    let mut post = Vec::with_capacity(n);
+    let mut seen = vec![false; n];
+    let mut call = vec![(0, 0)];
+    seen[0] = true;
+    while let Some(&mut (u, ref mut i)) = call.last_mut() {
+        if let Some(&v) = succ[u].get(*i) {
+            *i += 1;
+            if !seen[v] {
+                seen[v] = true;
+                call.push((v, 0));
+            }
+        } else {
+            post.push(u);
+            call.pop();
+        }
+    }
+    let mut order = vec![0; n];
+    for (i, &u) in post.iter().enumerate() {
+        order[u] = i;
+    }
+
+    let intersect = |idom: &[usize], mut a: usize, mut b: usize| {
+        while a != b {
+            while order[a] < order[b] {
+                a = idom[a];
+            }
+            while order[b] < order[a] {
+                b = idom[b];
+            }
+        }
+        a
+    };
+
+    let mut idom = vec![usize::MAX; n];
+    idom[0] = 0;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &v in post.iter().rev().skip(1) {
+            let mut new = usize::MAX;
+            for &p in &pred[v] {
+                if idom[p] == usize::MAX {
+                    continue;
+                }
+                new = if new == usize::MAX {
+                    p
+                } else {
+                    intersect(&idom, p, new)
+                };
+            }
+            if idom[v] != new {
+                idom[v] = new;
+                changed = true;
+            }
+        }
+    }
+
+    idom
+}
+
+/// Returns `u` if the arc `u → v` is a bridge of the flow graph, i.e. every
+/// path from the root to `v` uses it: `u` is `v`'s immediate dominator, the
+/// arc isn't doubled, and every other arc into `v` comes from a node that
+/// `v` dominates.
+fn flow_bridge_into(v: usize, idom: &[usize], pred: &[Vec<usize>]) -> Option<usize> {
+    let u = idom[v];
+    let dominated_by_v = |mut w: usize| loop {
+        if w == v {
+            return true;
+        }
+        if w == 0 {
+            return false;
+        }
+        w = idom[w];
+    };
+
+    let from_u = pred[v].iter().filter(|&&w| w == u).count();
+    let others_dominated = pred[v]
+        .iter()
+        .filter(|&&w| w != u)
+        .all(|&w| dominated_by_v(w));
+    (from_u == 1 && others_dominated).then_some(u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Edge;
+
+    fn digraph(nodes: usize, arcs: &[(u32, u32)]) -> Graph {
+        let mut g = Graph::new(nodes);
+        for &(u, v) in arcs {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+        g
+    }
+
+    #[test]
+    fn test_scc() {
+        // cycle 0-1-2, then 2 → 3 ↔ 4
+        let g = digraph(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3)]);
+        assert_eq!(strongly_connected_components(&g), vec![0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_condensation() {
+        let g = digraph(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (1, 3), (3, 4), (4, 3)]);
+        let c = condensation(&g);
+        assert_eq!(c.members[0], vec![NodeId(0), NodeId(1), NodeId(2)]);
+        assert_eq!(
+            c.arcs,
+            vec![CondensedArc {
+                from: 0,
+                to: 1,
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_all_critical() {
+        let g = digraph(3, &[(0, 1), (1, 2), (2, 0)]);
+        let critical = strong_critical_components(&g);
+        assert_eq!(critical.bridges.len(), 3);
+        assert_eq!(critical.articulation_points.len(), 3);
+    }
+
+    #[test]
+    fn test_bidirectional_path() {
+        // 0 ⇄ 1 ⇄ 2, plus an arc 2 → 3 leaving the component
+        let g = digraph(4, &[(0, 1), (1, 0), (1, 2), (2, 1), (2, 3)]);
+        let critical = strong_critical_components(&g);
+        assert_eq!(critical.articulation_points, vec![NodeId(1)]);
+        assert_eq!(
+            critical.bridges,
+            vec![
+                (NodeId(0), NodeId(1)),
+                (NodeId(1), NodeId(0)),
+                (NodeId(1), NodeId(2)),
+                (NodeId(2), NodeId(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redundant_component() {
+        // complete digraph on 3 nodes survives any single failure
+        let g = digraph(3, &[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)]);
+        let critical = strong_critical_components(&g);
+        assert!(critical.bridges.is_empty());
+        assert!(critical.articulation_points.is_empty());
+    }
+}
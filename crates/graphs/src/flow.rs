@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+/// A directed flow network solved with Dinic's algorithm. Capacities are
+/// integral, which is all the cut computations need.
+pub(crate) struct FlowNetwork {
+    // arcs are stored in pairs: arc ^ 1 is the reverse (residual) arc
+    to: Vec<usize>,
+    cap: Vec<u64>,
+    adj: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    /// Creates a network with `n` nodes and no arcs.
+    pub(crate) fn new(n: usize) -> FlowNetwork {
+        FlowNetwork {
+            to: Vec::new(),
+            cap: Vec::new(),
+            adj: vec![Vec::new(); n],
+        }
+    }
+
+    /// Adds an arc `u → v` with the given capacity.
+    pub(crate) fn add_arc(&mut self, u: usize, v: usize, cap: u64) {
+        self.adj[u].push(self.to.len());
+        self.to.push(v);
+        self.cap.push(cap);
+        self.adj[v].push(self.to.len());
+        self.to.push(u);
+        self.cap.push(0);
+    }
+
+    /// Pushes as much flow as possible from `s` to `t`, stopping early once
+    /// it reaches `limit`. Returns the flow pushed. Capacities are left as
+    /// residuals, so `reachable` can recover a minimum cut afterwards.
+    pub(crate) fn max_flow(&mut self, s: usize, t: usize, limit: u64) -> u64 {
+        let mut total = 0;
+        while total < limit {
+            let Some(level) = self.levels(s, t) else {
+                break;
+            };
+            let mut next = vec![0; self.adj.len()];
+            loop {
+                let pushed = self.augment(s, t, limit - total, &level, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+                if total >= limit {
+                    break;
+                }
+            }
+        }
+        total
+    }
+
+    /// Nodes reachable from `s` in the residual network.
+    pub(crate) fn reachable(&self, s: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.adj.len()];
+        let mut queue = VecDeque::from([s]);
+        seen[s] = true;
+        while let Some(u) = queue.pop_front() {
+            for &a in &self.adj[u] {
+                let v = self.to[a];
+                if self.cap[a] > 0 && !seen[v] {
+                    seen[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        seen
+    }
+
+    /// BFS levels from `s`, or None once `t` is unreachable.
+    fn levels(&self, s: usize, t: usize) -> Option<Vec<usize>> {
+        let mut level = vec![usize::MAX; self.adj.len()];
+        let mut queue = VecDeque::from([s]);
+        level[s] = 0;
+        while let Some(u) = queue.pop_front() {
+            for &a in &self.adj[u] {
+                let v = self.to[a];
+                if self.cap[a] > 0 && level[v] == usize::MAX {
+                    level[v] = level[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        (level[t] != usize::MAX).then_some(level)
+    }
+
+    /// Finds one augmenting path along increasing levels (iteratively, so
+    /// long paths can't overflow the stack) and pushes flow along it.
+    fn augment(
+        &mut self,
+        s: usize,
+        t: usize,
+        limit: u64,
+        level: &[usize],
+        next: &mut [usize],
+    ) -> u64 {
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = s;
+        loop {
+            if u == t {
+                let pushed = path.iter().map(|&a| self.cap[a]).fold(limit, u64::min);
+                for &a in &path {
+                    self.cap[a] -= pushed;
+                    self.cap[a ^ 1] += pushed;
+                }
+                return pushed;
+            }
+
+            let mut advanced = false;
+            while next[u] < self.adj[u].len() {
+                let a = self.adj[u][next[u]];
+                let v = self.to[a];
+                if self.cap[a] > 0 && level[v] == level[u] + 1 {
+                    path.push(a);
+                    u = v;
+                    advanced = true;
+                    break;
+                }
+                next[u] += 1;
+            }
+
+            if !advanced {
+                // dead end: retreat and skip the arc that led here
+                let Some(a) = path.pop() else {
+                    return 0;
+                };
+                u = self.to[a ^ 1];
+                next[u] += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow_and_cut() {
+        // two disjoint routes 0-1-3 and 0-2-3, with 1 → 2 as a crossover
+        let mut net = FlowNetwork::new(4);
+        net.add_arc(0, 1, 3);
+        net.add_arc(0, 2, 2);
+        net.add_arc(1, 2, 1);
+        net.add_arc(1, 3, 2);
+        net.add_arc(2, 3, 3);
+
+        assert_eq!(net.max_flow(0, 3, u64::MAX), 5);
+        let side = net.reachable(0);
+        assert!(side[0] && !side[3]);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::dsu::DisjointSet;
+use rayon::prelude::*;
 use std::cmp::min;
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -24,86 +26,65 @@ impl Graph {
     /// Returns a tuple of (articulation points, bridges) where:
     /// - Articulation points are nodes whose removal disconnects the graph
     /// - Bridges are edges whose removal disconnects the graph
+    ///
+    /// The output is deterministic: articulation points are sorted by node ID,
+    /// and each bridge is returned as `(smaller ID, larger ID)` with bridges
+    /// sorted lexicographically.
+    ///
+    /// On a disconnected graph, each connected component is searched on its
+    /// own thread (via rayon) and the results are merged.
     pub fn critical_components(&self) -> (Vec<NodeId>, Vec<(NodeId, NodeId)>) {
         let adj = self.adjacency_list();
-        let mut disc: Vec<Option<u32>> = vec![None; self.nodes];
-        let mut low: Vec<u32> = vec![0; self.nodes];
-        let mut parent: Vec<Option<usize>> = vec![None; self.nodes];
-        let mut bridges: Vec<(NodeId, NodeId)> = Vec::new();
-        let mut points: HashSet<NodeId> = HashSet::new();
-        let mut time: u32 = 0;
-
-        fn dfs(
-            u: usize,
-            adj: &Vec<Vec<NodeId>>,
-            parent: &mut Vec<Option<usize>>,
-            disc: &mut Vec<Option<u32>>,
-            low: &mut Vec<u32>,
-            points: &mut HashSet<NodeId>,
-            bridges: &mut Vec<(NodeId, NodeId)>,
-            time: &mut u32,
-        ) {
-            disc[u] = Some(*time);
-            low[u] = *time;
-            *time += 1;
-
-            let mut children: u32 = 0;
-
-            for v in &adj[u] {
-                let v_i = v.0 as usize;
-                match disc[v_i] {
-                    None => {
-                        children += 1;
-                        parent[v_i] = Some(u);
-
-                        dfs(v_i, adj, parent, disc, low, points, bridges, time);
-
-                        low[u] = min(low[u], low[v_i]);
-
-                        // v or its subtree cant reach u without u-v
-                        if low[v_i] > disc[u].expect("disc[u] already initialized above") {
-                            bridges.push((NodeId(u as u32), *v))
-                        }
-
-                        // u is critical to v connectivity
-                        if low[v_i] >= disc[u].expect("disc[u] already initialized above")
-                            && parent[u].is_some()
-                        {
-                            points.insert(NodeId(u as u32));
-                        }
-                    }
-                    Some(t) => {
-                        if Some(v_i) != parent[u] {
-                            low[u] = min(low[u], t);
-                        }
-                    }
+        let components = connected_components(&adj);
+
+        let (points, bridges) = if components.len() < 2 {
+            tarjan(&adj)
+        } else {
+            // renumber each component's nodes from 0 so its search only
+            // allocates for its own nodes
+            let mut local = vec![0u32; self.nodes];
+            for nodes in &components {
+                for (i, &v) in nodes.iter().enumerate() {
+                    local[v] = i as u32;
                 }
             }
 
-            if parent[u].is_none() && children >= 2 {
-                points.insert(NodeId(u as u32));
-            }
-        }
-
-        for n in 0..self.nodes {
-            if disc[n].is_some() {
-                // already visited
-                continue;
-            }
-
-            dfs(
-                n,
-                &adj,
-                &mut parent,
-                &mut disc,
-                &mut low,
-                &mut points,
-                &mut bridges,
-                &mut time,
-            );
-        }
-
-        (points.into_iter().collect(), bridges)
+            components
+                .par_iter()
+                .map(|nodes| {
+                    let sub: Vec<Vec<NodeId>> = nodes
+                        .iter()
+                        .map(|&v| adj[v].iter().map(|w| NodeId(local[w.0 as usize])).collect())
+                        .collect();
+                    let global = |id: NodeId| NodeId(nodes[id.0 as usize] as u32);
+                    let (points, bridges) = tarjan(&sub);
+                    (
+                        points.into_iter().map(global).collect::<Vec<_>>(),
+                        bridges
+                            .into_iter()
+                            .map(|(u, v)| (global(u), global(v)))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .reduce(
+                    || (Vec::new(), Vec::new()),
+                    |mut a, b| {
+                        a.0.extend(b.0);
+                        a.1.extend(b.1);
+                        a
+                    },
+                )
+        };
+
+        let mut points = points;
+        points.sort_unstable();
+        let mut bridges: Vec<(NodeId, NodeId)> = bridges
+            .into_iter()
+            .map(|(u, v)| (min(u, v), u.max(v)))
+            .collect();
+        bridges.sort_unstable();
+
+        (points, bridges)
     }
 
     /// Adds an edge to the graph.
@@ -116,6 +97,14 @@ impl Graph {
         self.edges.push(edge);
     }
 
+    /// Removes the edges `keep` returns false for and returns how many were
+    /// removed. Nodes stay, even if they lose all their edges.
+    pub fn retain_edges(&mut self, keep: impl FnMut(&Edge) -> bool) -> usize {
+        let before = self.edges.len();
+        self.edges.retain(keep);
+        before - self.edges.len()
+    }
+
     /// Returns a copy of all edges in the graph.
     pub fn edges(&self) -> Vec<Edge> {
         self.edges.clone()
@@ -126,6 +115,109 @@ impl Graph {
         self.nodes
     }
 
+    /// Returns a stable SHA-256 hex digest of the graph's content.
+    /// Covers the node count and every edge; edge order and the orientation
+    /// of each undirected edge don't affect the result.
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut edges: Vec<(u32, u32, u32)> = self
+            .edges
+            .iter()
+            .map(|e| (e.u.0.min(e.v.0), e.u.0.max(e.v.0), e.weight.to_bits()))
+            .collect();
+        edges.sort_unstable();
+
+        let mut hasher = Sha256::new();
+        hasher.update((self.nodes as u64).to_le_bytes());
+        for (u, v, w) in edges {
+            hasher.update(u.to_le_bytes());
+            hasher.update(v.to_le_bytes());
+            hasher.update(w.to_le_bytes());
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Computes the normalized degree centrality of every node.
+    /// Each node's score is its degree divided by `n - 1`, so a node adjacent
+    /// to every other node scores 1.0. Parallel edges count once per edge.
+    pub fn degree_centrality(&self) -> Vec<f64> {
+        let mut degree = vec![0usize; self.nodes];
+        for e in &self.edges {
+            degree[e.u.0 as usize] += 1;
+            degree[e.v.0 as usize] += 1;
+        }
+
+        let scale = self.nodes.saturating_sub(1).max(1) as f64;
+        degree.into_iter().map(|d| d as f64 / scale).collect()
+    }
+
+    /// Returns the nodes no edge touches, sorted by ID. In a graph loaded
+    /// from CSV these are usually gaps in the node numbering.
+    pub fn isolated_nodes(&self) -> Vec<NodeId> {
+        let mut linked = vec![false; self.nodes];
+        for e in &self.edges {
+            linked[e.u.0 as usize] = true;
+            linked[e.v.0 as usize] = true;
+        }
+        (0..self.nodes as u32)
+            .filter(|&v| !linked[v as usize])
+            .map(NodeId)
+            .collect()
+    }
+
+    /// Returns the number of connected components, counting each isolated
+    /// node as its own component.
+    pub fn component_count(&self) -> usize {
+        connected_components(&self.adjacency_list()).len() + self.isolated_nodes().len()
+    }
+
+    /// Returns the connected components, isolated nodes included, each
+    /// sorted by node ID and ordered by their smallest node.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut ds = DisjointSet::new(self.nodes);
+        for e in &self.edges {
+            ds.union(e.u.0 as usize, e.v.0 as usize);
+        }
+        let mut index = vec![usize::MAX; self.nodes];
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+        for v in 0..self.nodes {
+            let root = ds.find(v);
+            if index[root] == usize::MAX {
+                index[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[index[root]].push(NodeId(v as u32));
+        }
+        components
+    }
+
+    /// Returns every node pair joined by more than one edge, as
+    /// `(smaller ID, larger ID, edge count)` sorted by pair. Orientation
+    /// doesn't matter, so `0,1` and `1,0` are duplicates.
+    pub fn duplicate_edges(&self) -> Vec<(NodeId, NodeId, usize)> {
+        let mut pairs: Vec<(u32, u32)> = self
+            .edges
+            .iter()
+            .map(|e| (e.u.0.min(e.v.0), e.u.0.max(e.v.0)))
+            .collect();
+        pairs.sort_unstable();
+
+        let mut duplicates = Vec::new();
+        for group in pairs.chunk_by(|a, b| a == b) {
+            if group.len() > 1 {
+                let (u, v) = group[0];
+                duplicates.push((NodeId(u), NodeId(v), group.len()));
+            }
+        }
+        duplicates
+    }
+
     /// Builds an adjacency list representation for efficient neighbor queries.
     /// For each node, returns a list of its neighbors. Since the graph is undirected,
     /// each edge (u,v) creates entries in both adj[u] and adj[v].
@@ -140,6 +232,110 @@ impl Graph {
     }
 }
 
+/// Groups the nodes of an adjacency list into connected components with at
+/// least one edge; isolated nodes can't hold critical components.
+fn connected_components(adj: &[Vec<NodeId>]) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; adj.len()];
+    let mut components = Vec::new();
+    for start in 0..adj.len() {
+        if seen[start] || adj[start].is_empty() {
+            continue;
+        }
+        seen[start] = true;
+        let mut nodes = vec![start];
+        let mut i = 0;
+        while i < nodes.len() {
+            for w in &adj[nodes[i]] {
+                let w = w.0 as usize;
+                if !seen[w] {
+                    seen[w] = true;
+                    nodes.push(w);
+                }
+            }
+            i += 1;
+        }
+        components.push(nodes);
+    }
+    components
+}
+
+/// Tarjan's DFS for articulation points and bridges over an adjacency
+/// list, unsorted.
+fn tarjan(adj: &[Vec<NodeId>]) -> (Vec<NodeId>, Vec<(NodeId, NodeId)>) {
+    let n = adj.len();
+    let mut disc: Vec<Option<u32>> = vec![None; n];
+    let mut low: Vec<u32> = vec![0; n];
+    let mut parent: Vec<Option<usize>> = vec![None; n];
+    let mut bridges: Vec<(NodeId, NodeId)> = Vec::new();
+    let mut points: HashSet<NodeId> = HashSet::new();
+    let mut time: u32 = 0;
+
+    // depth-first with an explicit stack of (node, next neighbor to visit),
+    // so a long path can't overflow the thread's stack, which is small on
+    // rayon's workers
+    let mut children: Vec<u32> = vec![0; n];
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 0..n {
+        if disc[root].is_some() {
+            // already visited
+            continue;
+        }
+
+        disc[root] = Some(time);
+        low[root] = time;
+        time += 1;
+        stack.push((root, 0));
+
+        while let Some(top) = stack.last_mut() {
+            let (u, next) = *top;
+            if let Some(v) = adj[u].get(next) {
+                top.1 += 1;
+                let v_i = v.0 as usize;
+                match disc[v_i] {
+                    None => {
+                        children[u] += 1;
+                        parent[v_i] = Some(u);
+                        disc[v_i] = Some(time);
+                        low[v_i] = time;
+                        time += 1;
+                        stack.push((v_i, 0));
+                    }
+                    Some(t) => {
+                        if Some(v_i) != parent[u] {
+                            low[u] = min(low[u], t);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // u is done: fold it into its parent
+            stack.pop();
+            let Some(p) = parent[u] else {
+                if children[u] >= 2 {
+                    points.insert(NodeId(u as u32));
+                }
+                continue;
+            };
+            low[p] = min(low[p], low[u]);
+            let disc_p = disc[p].expect("disc[p] set before its children");
+
+            // u or its subtree cant reach p without p-u
+            if low[u] > disc_p {
+                bridges.push((NodeId(p as u32), NodeId(u as u32)));
+            }
+
+            // p is critical to u connectivity
+            if low[u] >= disc_p && parent[p].is_some() {
+                points.insert(NodeId(p as u32));
+            }
+        }
+    }
+
+    (points.into_iter().collect(), bridges)
+}
+
 /// An undirected weighted edge connecting two nodes.
 #[derive(Debug, Clone, Copy)]
 pub struct Edge {
@@ -158,7 +354,7 @@ impl Eq for Edge {}
 
 impl PartialOrd for Edge {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.weight.partial_cmp(&other.weight)
+        Some(self.cmp(other))
     }
 }
 
@@ -172,9 +368,26 @@ impl Ord for Edge {
 
 /// A unique identifier for a node in the graph.
 /// Node IDs must be in the range 0..n-1 where n is the total number of nodes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NodeId(pub u32);
 
+#[cfg(test)]
+impl Graph {
+    /// Builds a graph with an edge of weight 1 for each pair, as module
+    /// tests need it.
+    pub(crate) fn from_unit_edges(nodes: usize, edges: &[(u32, u32)]) -> Graph {
+        let mut g = Graph::new(nodes);
+        for &(u, v) in edges {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+        g
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,6 +508,41 @@ mod tests {
         assert_eq!(aps.len(), 2);
     }
 
+    #[test]
+    fn test_disconnected_components_merge() {
+        // triangle 0-1-2 with tail 2-3, isolated node 4, path 5-6-7, and a
+        // second triangle 8-9-10 so components interleave with isolated IDs
+        let mut g = Graph::new(11);
+        for (u, v) in [
+            (5, 6),
+            (0, 1),
+            (1, 2),
+            (6, 7),
+            (2, 0),
+            (8, 9),
+            (2, 3),
+            (9, 10),
+            (10, 8),
+        ] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+
+        let (aps, bridges) = g.critical_components();
+        assert_eq!(aps, vec![NodeId(2), NodeId(6)]);
+        assert_eq!(
+            bridges,
+            vec![
+                (NodeId(2), NodeId(3)),
+                (NodeId(5), NodeId(6)),
+                (NodeId(6), NodeId(7)),
+            ]
+        );
+    }
+
     #[test]
     fn test_single_edge() {
         let mut g = Graph::new(2);
@@ -335,10 +583,59 @@ mod tests {
         });
 
         let (aps, bridges) = g.critical_components();
-        // all edges are bridges
-        assert_eq!(bridges.len(), 4);
-        // nodes 0, 1, and 3
-        assert_eq!(aps.len(), 3);
+        // all edges are bridges, normalized and sorted
+        assert_eq!(
+            bridges,
+            vec![
+                (NodeId(0), NodeId(1)),
+                (NodeId(0), NodeId(3)),
+                (NodeId(1), NodeId(2)),
+                (NodeId(3), NodeId(4)),
+            ]
+        );
+        // nodes 0, 1, and 3 in ID order
+        assert_eq!(aps, vec![NodeId(0), NodeId(1), NodeId(3)]);
+    }
+
+    #[test]
+    fn test_degree_centrality() {
+        // star centered on node 0
+        let mut g = Graph::new(4);
+        for v in 1..4 {
+            g.add_edge(Edge {
+                u: NodeId(0),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+
+        let centrality = g.degree_centrality();
+        assert_eq!(centrality[0], 1.0);
+        assert!((centrality[1] - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_content_hash_ignores_edge_order() {
+        let mut a = Graph::new(3);
+        let mut b = Graph::new(3);
+        let edges = [(0, 1, 1.0), (1, 2, 2.0)];
+        for &(u, v, weight) in &edges {
+            a.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight,
+            });
+        }
+        for &(u, v, weight) in edges.iter().rev() {
+            b.add_edge(Edge {
+                u: NodeId(v),
+                v: NodeId(u),
+                weight,
+            });
+        }
+
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_ne!(a.content_hash(), Graph::new(3).content_hash());
     }
 
     #[test]
@@ -348,4 +645,63 @@ mod tests {
         assert_eq!(bridges.len(), 0);
         assert_eq!(aps.len(), 0);
     }
+
+    #[test]
+    fn test_isolated_and_duplicate_edges() {
+        let mut g = Graph::new(5);
+        for (u, v) in [(0, 1), (1, 0), (1, 3), (0, 1)] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+        assert_eq!(g.isolated_nodes(), vec![NodeId(2), NodeId(4)]);
+        assert_eq!(g.duplicate_edges(), vec![(NodeId(0), NodeId(1), 3)]);
+        assert_eq!(g.component_count(), 3);
+        assert_eq!(
+            g.components(),
+            vec![
+                vec![NodeId(0), NodeId(1), NodeId(3)],
+                vec![NodeId(2)],
+                vec![NodeId(4)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retain_edges() {
+        let mut g = Graph::new(3);
+        for (u, v, weight) in [(0, 1, 0.5), (1, 2, 4.0), (0, 2, 50000.0)] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight,
+            });
+        }
+        assert_eq!(g.retain_edges(|e| (1.0..=100.0).contains(&e.weight)), 2);
+        assert_eq!(g.edges().len(), 1);
+        assert_eq!(g.size(), 3);
+    }
+
+    #[test]
+    fn test_deep_components_in_parallel() {
+        // two long paths, each searched on a rayon worker with a small stack
+        let len = 10_000;
+        let mut g = Graph::new(2 * len);
+        for start in [0, len] {
+            for i in start..start + len - 1 {
+                g.add_edge(Edge {
+                    u: NodeId(i as u32),
+                    v: NodeId(i as u32 + 1),
+                    weight: 1.0,
+                });
+            }
+        }
+
+        let (aps, bridges) = g.critical_components();
+        assert_eq!(bridges.len(), 2 * (len - 1));
+        // every node but the ends of each path
+        assert_eq!(aps.len(), 2 * (len - 2));
+    }
 }
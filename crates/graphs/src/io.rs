@@ -1,6 +1,10 @@
 use crate::graph::{Edge, Graph, NodeId};
-use csv::ReaderBuilder;
+use csv::{ReaderBuilder, StringRecord};
+use memchr::{memchr, memchr_iter};
+use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 use thiserror::Error;
 
@@ -21,6 +25,9 @@ pub enum IoError {
 
     #[error("Invalid weight: {0}")]
     InvalidWeight(String),
+
+    #[error("Invalid weight: {0} (decimal commas aren't supported; write it with '.')")]
+    DecimalComma(String),
 }
 
 /// Loads an undirected graph from a CSV file.
@@ -32,6 +39,10 @@ pub enum IoError {
 /// accommodate the maximum node ID found, so nodes don't need to be
 /// contiguous (though this may waste memory for sparse graphs).
 ///
+/// Weights may use `_` between digits (`1_000`) and exponents (`1e3`). A
+/// duration suffix (`ns`, `us` or `µs`, `ms`, `s`) converts the weight to
+/// milliseconds, so `2.5ms`, `2500us` and `0.0025s` are all 2.5.
+///
 /// # Example CSV format
 /// ```csv
 /// u,v,weight
@@ -40,13 +51,37 @@ pub enum IoError {
 /// 2,0,1.0
 /// ```
 pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Graph, IoError> {
-    let file = File::open(path)?;
-    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(file);
+    load_csv_from_reader(File::open(path)?)
+}
+
+/// Details of a CSV load that don't affect the graph but may point to a
+/// mistake in the input.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Whether the first row was column names
+    pub header: bool,
+    /// Header rows found after the first row, such as from concatenated
+    /// files; they are skipped like the first
+    pub stray_headers: usize,
+}
+
+/// Loads an undirected graph from CSV data in any reader, such as a file
+/// wrapped for progress reporting. See `load_csv` for the format.
+pub fn load_csv_from_reader<R: Read>(source: R) -> Result<Graph, IoError> {
+    load_csv_from_reader_with_report(source).map(|(graph, _)| graph)
+}
+
+/// Like `load_csv_from_reader`, also returning a `LoadReport`.
+pub fn load_csv_from_reader_with_report<R: Read>(
+    source: R,
+) -> Result<(Graph, LoadReport), IoError> {
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(source);
 
     let mut edges = Vec::new();
     let mut max_node = 0u32;
+    let mut report = LoadReport::default();
 
-    for result in reader.records() {
+    for (row, result) in reader.records().enumerate() {
         let record = result?;
 
         if record.len() < 3 {
@@ -54,10 +89,12 @@ pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Graph, IoError> {
         }
 
         // Skip header if first row looks like column names
-        if record.get(0).unwrap_or("").to_lowercase() == "u"
-            || record.get(0).unwrap_or("").to_lowercase() == "from"
-            || record.get(0).unwrap_or("").to_lowercase() == "source"
-        {
+        if is_header(&record) {
+            if row > 0 {
+                report.stray_headers += 1;
+            } else {
+                report.header = true;
+            }
             continue;
         }
 
@@ -75,17 +112,115 @@ pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Graph, IoError> {
             .parse()
             .map_err(|_| IoError::InvalidNodeId(record.get(1).unwrap().to_string()))?;
 
-        let weight: f32 = record
-            .get(2)
-            .ok_or(IoError::InvalidFormat)?
-            .trim()
-            .parse()
-            .map_err(|_| IoError::InvalidWeight(record.get(2).unwrap().to_string()))?;
+        let weight = parse_weight(record.get(2).ok_or(IoError::InvalidFormat)?)?;
 
         max_node = max_node.max(u).max(v);
         edges.push((u, v, weight));
     }
 
+    Ok((build_graph(edges, max_node), report))
+}
+
+/// Loads an undirected graph from CSV bytes already in memory, parsing
+/// chunks of rows on separate threads. Produces the same graph as
+/// `load_csv_from_reader`, with edges in file order, but only accepts
+/// plain rows: fields must not be quoted and are split on every comma.
+/// Extra columns and blank lines are ignored.
+pub fn load_csv_parallel(data: &[u8]) -> Result<Graph, IoError> {
+    load_csv_parallel_with_report(data).map(|(graph, _)| graph)
+}
+
+/// Like `load_csv_parallel`, also returning a `LoadReport`.
+pub fn load_csv_parallel_with_report(data: &[u8]) -> Result<(Graph, LoadReport), IoError> {
+    let chunks = rayon::current_num_threads();
+    let (edges, headers) = parse_chunks(data, chunks)?;
+    let max_node = edges.iter().map(|&(u, v, _)| u.max(v)).max().unwrap_or(0);
+
+    // a header on the first non-blank row is expected
+    let first = data
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .find(|line| !line.is_empty());
+    let expected = first.is_some_and(|line| {
+        let name = line.split(|&b| b == b',').next().unwrap_or_default();
+        is_column_name(&String::from_utf8_lossy(name))
+    });
+    let report = LoadReport {
+        header: expected,
+        stray_headers: headers - usize::from(expected),
+    };
+    Ok((build_graph(edges, max_node), report))
+}
+
+/// Parsed `(u, v, weight)` rows, in file order.
+type Rows = Vec<(u32, u32, f32)>;
+
+/// Splits `data` into about `chunks` pieces at line breaks and parses
+/// them in parallel, keeping rows in order.
+/// Also returns the number of header rows skipped.
+fn parse_chunks(data: &[u8], chunks: usize) -> Result<(Rows, usize), IoError> {
+    let size = data.len() / chunks.max(1) + 1;
+    let mut bounds = vec![0];
+    let mut start = 0;
+    while start < data.len() {
+        let target = (start + size).min(data.len());
+        start = match memchr(b'\n', &data[target..]) {
+            Some(i) => target + i + 1,
+            None => data.len(),
+        };
+        bounds.push(start);
+    }
+
+    let parts = bounds
+        .par_windows(2)
+        .map(|w| parse_rows(&data[w[0]..w[1]]))
+        .collect::<Result<Vec<_>, _>>()?;
+    let headers = parts.iter().map(|(_, headers)| headers).sum();
+    let edges = parts.into_iter().flat_map(|(edges, _)| edges).collect();
+    Ok((edges, headers))
+}
+
+/// Parses whole `u,v,weight` lines, counting the header rows skipped.
+fn parse_rows(chunk: &[u8]) -> Result<(Rows, usize), IoError> {
+    let mut edges = Vec::new();
+    let mut headers = 0;
+    let mut start = 0;
+    let ends = memchr_iter(b'\n', chunk).chain(std::iter::once(chunk.len()));
+    for end in ends {
+        let line = &chunk[start..end];
+        start = end + 1;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split(|&b| b == b',');
+        let (Some(u), Some(v), Some(weight)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(IoError::InvalidFormat);
+        };
+        let u = String::from_utf8_lossy(u);
+        if is_column_name(&u) {
+            headers += 1;
+            continue;
+        }
+        let v = String::from_utf8_lossy(v);
+        let weight = String::from_utf8_lossy(weight);
+
+        edges.push((
+            u.trim()
+                .parse()
+                .map_err(|_| IoError::InvalidNodeId(u.to_string()))?,
+            v.trim()
+                .parse()
+                .map_err(|_| IoError::InvalidNodeId(v.to_string()))?,
+            parse_weight(&weight)?,
+        ));
+    }
+    Ok((edges, headers))
+}
+
+/// Builds a graph sized to hold `max_node` from `(u, v, weight)` rows.
+fn build_graph(edges: Vec<(u32, u32, f32)>, max_node: u32) -> Graph {
     let num_nodes = (max_node + 1) as usize;
     let mut graph = Graph::new(num_nodes);
 
@@ -97,7 +232,115 @@ pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Graph, IoError> {
         });
     }
 
-    Ok(graph)
+    graph
+}
+
+/// Loads an undirected graph whose CSV rows name their nodes with arbitrary
+/// labels (e.g., `job-1,worker-3,1.0`) instead of integer IDs. Node IDs are
+/// assigned in order of first appearance; the returned labels are indexed
+/// by node ID. Otherwise the format is the same as for `load_csv`.
+pub fn load_labeled_csv_from_reader<R: Read>(source: R) -> Result<(Graph, Vec<String>), IoError> {
+    load_labeled_csv_from_reader_with_report(source).map(|(graph, labels, _)| (graph, labels))
+}
+
+/// Like `load_labeled_csv_from_reader`, also returning a `LoadReport`.
+pub fn load_labeled_csv_from_reader_with_report<R: Read>(
+    source: R,
+) -> Result<(Graph, Vec<String>, LoadReport), IoError> {
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(source);
+
+    let mut labels: Vec<String> = Vec::new();
+    let mut ids: HashMap<String, u32> = HashMap::new();
+    let mut edges = Vec::new();
+    let mut report = LoadReport::default();
+
+    for (row, result) in reader.records().enumerate() {
+        let record = result?;
+
+        if record.len() < 3 {
+            return Err(IoError::InvalidFormat);
+        }
+        if is_header(&record) {
+            if row > 0 {
+                report.stray_headers += 1;
+            } else {
+                report.header = true;
+            }
+            continue;
+        }
+
+        let mut id = |label: &str| {
+            let label = label.trim();
+            *ids.entry(label.to_string()).or_insert_with(|| {
+                labels.push(label.to_string());
+                (labels.len() - 1) as u32
+            })
+        };
+        let u = id(&record[0]);
+        let v = id(&record[1]);
+        let weight = parse_weight(&record[2])?;
+
+        edges.push((u, v, weight));
+    }
+
+    let mut graph = Graph::new(labels.len());
+    for (u, v, weight) in edges {
+        graph.add_edge(Edge {
+            u: NodeId(u),
+            v: NodeId(v),
+            weight,
+        });
+    }
+
+    Ok((graph, labels, report))
+}
+
+/// Parses a weight field; see `load_csv` for the accepted forms.
+fn parse_weight(field: &str) -> Result<f32, IoError> {
+    let text = field.trim();
+    if text.contains(',') {
+        return Err(IoError::DecimalComma(field.to_string()));
+    }
+
+    let (number, scale) = [
+        ("ns", 1e-6),
+        ("us", 1e-3),
+        ("µs", 1e-3),
+        ("ms", 1.0),
+        ("s", 1e3),
+    ]
+    .iter()
+    .find_map(|&(suffix, scale)| Some((text.strip_suffix(suffix)?, scale)))
+    .unwrap_or((text, 1.0));
+
+    // `_` is only a separator between digits
+    let bytes = number.as_bytes();
+    let separators_ok = bytes.iter().enumerate().all(|(i, &b)| {
+        b != b'_'
+            || (i > 0
+                && bytes[i - 1].is_ascii_digit()
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+    });
+    let number = number.trim_end().replace('_', "");
+    let value = if scale == 1.0 {
+        number.parse::<f32>().ok()
+    } else {
+        number.parse::<f64>().ok().map(|v| (v * scale) as f32)
+    };
+    match value {
+        Some(value) if separators_ok => Ok(value),
+        _ => Err(IoError::InvalidWeight(field.to_string())),
+    }
+}
+
+/// Returns true if the record looks like a row of column names.
+fn is_header(record: &StringRecord) -> bool {
+    is_column_name(record.get(0).unwrap_or(""))
+}
+
+/// Returns true if `field` is a name used for the first column.
+fn is_column_name(field: &str) -> bool {
+    matches!(field.to_lowercase().as_str(), "u" | "from" | "source")
 }
 
 #[cfg(test)]
@@ -129,4 +372,92 @@ mod tests {
         assert_eq!(graph.size(), 3);
         assert_eq!(graph.edges().len(), 2);
     }
+
+    #[test]
+    fn test_load_labeled() {
+        let data = "from,to,weight\njob-a,worker-1,1.0\njob-b,worker-1,2.0\n";
+
+        let (graph, labels) = load_labeled_csv_from_reader(data.as_bytes()).unwrap();
+        assert_eq!(labels, vec!["job-a", "worker-1", "job-b"]);
+        assert_eq!(graph.edges()[1].u, NodeId(2));
+    }
+
+    #[test]
+    fn test_parallel_matches_csv_reader() {
+        let mut data = String::from("u,v,weight\r\n0,1,1.5\r\n\n");
+        for i in 0..200 {
+            data.push_str(&format!("{},{},{}.25\n", i, (i * 7) % 301, i % 9));
+        }
+        data.push_str("300,2,4");
+
+        let expected = load_csv_from_reader(data.as_bytes()).unwrap();
+        let edges = |g: &Graph| -> Vec<(u32, u32, f32)> {
+            g.edges().iter().map(|e| (e.u.0, e.v.0, e.weight)).collect()
+        };
+        for chunks in [1, 3, 16, 1000] {
+            let (rows, headers) = parse_chunks(data.as_bytes(), chunks).unwrap();
+            assert_eq!(rows, edges(&expected), "{chunks} chunks");
+            assert_eq!(headers, 1);
+        }
+        let graph = load_csv_parallel(data.as_bytes()).unwrap();
+        assert_eq!(graph.size(), expected.size());
+        assert_eq!(edges(&graph), edges(&expected));
+    }
+
+    #[test]
+    fn test_parallel_rejects_bad_rows() {
+        assert!(matches!(
+            load_csv_parallel(b"0,1,1.0\n1,2\n"),
+            Err(IoError::InvalidFormat)
+        ));
+        assert!(matches!(
+            load_csv_parallel(b"0,x,1.0\n"),
+            Err(IoError::InvalidNodeId(id)) if id == "x"
+        ));
+    }
+
+    #[test]
+    fn test_weight_formats() {
+        let data = "0,1,1_000\n1,2,1e3\n2,3,2.5ms\n3,4,300us\n4,5,1.5s\n";
+        let weights = |g: Graph| g.edges().iter().map(|e| e.weight).collect::<Vec<_>>();
+        let expected = vec![1000.0, 1000.0, 2.5, 0.3, 1500.0];
+        assert_eq!(
+            weights(load_csv_from_reader(data.as_bytes()).unwrap()),
+            expected
+        );
+        assert_eq!(
+            weights(load_csv_parallel(data.as_bytes()).unwrap()),
+            expected
+        );
+
+        assert!(matches!(
+            load_csv_from_reader("0,1,\"1,5\"\n".as_bytes()),
+            Err(IoError::DecimalComma(w)) if w == "1,5"
+        ));
+        for bad in ["_1", "1__0", "ms", "1.5 kg"] {
+            assert!(matches!(
+                parse_weight(bad),
+                Err(IoError::InvalidWeight(w)) if w == bad
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stray_headers_reported() {
+        let data = "u,v,weight\n0,1,1.0\nu,v,weight\n1,2,2.0\n";
+        let (graph, report) = load_csv_from_reader_with_report(data.as_bytes()).unwrap();
+        assert_eq!(graph.edges().len(), 2);
+        assert!(report.header);
+        assert_eq!(report.stray_headers, 1);
+        let (_, report) = load_csv_parallel_with_report(data.as_bytes()).unwrap();
+        assert!(report.header);
+        assert_eq!(report.stray_headers, 1);
+
+        let data = "\n0,1,1.0\nfrom,to,weight\n";
+        let (_, report) = load_csv_parallel_with_report(data.as_bytes()).unwrap();
+        assert!(!report.header);
+        assert_eq!(report.stray_headers, 1);
+        let (_, _, report) = load_labeled_csv_from_reader_with_report(data.as_bytes()).unwrap();
+        assert_eq!(report.stray_headers, 1);
+    }
 }
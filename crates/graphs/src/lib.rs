@@ -1,4 +1,15 @@
+pub mod arborescence;
+pub mod bridges;
+pub mod centrality;
+pub mod coloring;
+pub mod cut;
+pub mod density;
+pub mod directed;
 mod dsu;
+mod flow;
 pub mod graph;
 pub mod io;
+pub mod matching;
 pub mod mst;
+pub mod robustness;
+pub mod spectral;
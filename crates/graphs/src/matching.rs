@@ -0,0 +1,141 @@
+use crate::graph::{Graph, NodeId};
+use std::collections::VecDeque;
+
+const UNMATCHED: usize = usize::MAX;
+
+/// Finds a maximum-cardinality matching in a bipartite graph using the
+/// Hopcroft-Karp algorithm, in O(E √V) time.
+///
+/// `left[v]` says which side node `v` is on. Edges between two nodes on the
+/// same side are ignored, and edge weights are not considered.
+///
+/// Returns the matched pairs as `(left node, right node)`, sorted by left
+/// node ID.
+///
+/// Panics if `left` doesn't have one entry per node.
+pub fn hopcroft_karp(g: &Graph, left: &[bool]) -> Vec<(NodeId, NodeId)> {
+    let n = g.size();
+    assert_eq!(left.len(), n, "left must have one entry per node");
+
+    let mut adj = vec![Vec::new(); n];
+    for e in g.edges() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        match (left[u], left[v]) {
+            (true, false) => adj[u].push(v),
+            (false, true) => adj[v].push(u),
+            _ => {}
+        }
+    }
+
+    let mut mate = vec![UNMATCHED; n];
+    let mut dist = vec![usize::MAX; n];
+    while layer(&adj, left, &mate, &mut dist) {
+        let mut next = vec![0; n];
+        for u in 0..n {
+            if left[u] && mate[u] == UNMATCHED {
+                augment(u, &adj, &mut mate, &mut dist, &mut next);
+            }
+        }
+    }
+
+    (0..n)
+        .filter(|&u| left[u] && mate[u] != UNMATCHED)
+        .map(|u| (NodeId(u as u32), NodeId(mate[u] as u32)))
+        .collect()
+}
+
+/// BFS from the free left nodes, assigning each left node its distance in
+/// alternating edges. Returns true if some free right node was reached.
+fn layer(adj: &[Vec<usize>], left: &[bool], mate: &[usize], dist: &mut [usize]) -> bool {
+    let mut queue = VecDeque::new();
+    for u in 0..adj.len() {
+        if left[u] && mate[u] == UNMATCHED {
+            dist[u] = 0;
+            queue.push_back(u);
+        } else {
+            dist[u] = usize::MAX;
+        }
+    }
+
+    let mut found = false;
+    while let Some(u) = queue.pop_front() {
+        for &v in &adj[u] {
+            match mate[v] {
+                UNMATCHED => found = true,
+                w if dist[w] == usize::MAX => {
+                    dist[w] = dist[u] + 1;
+                    queue.push_back(w);
+                }
+                _ => {}
+            }
+        }
+    }
+    found
+}
+
+/// Looks for a shortest augmenting path from the free left node `u` along
+/// the BFS layers and flips it. Iterative, so long paths can't overflow
+/// the stack.
+fn augment(
+    u: usize,
+    adj: &[Vec<usize>],
+    mate: &mut [usize],
+    dist: &mut [usize],
+    next: &mut [usize],
+) -> bool {
+    // left nodes on the current alternating path
+    let mut path = vec![u];
+    while let Some(&x) = path.last() {
+        let Some(&v) = adj[x].get(next[x]) else {
+            // dead end: drop x from this phase
+            dist[x] = usize::MAX;
+            path.pop();
+            if let Some(&prev) = path.last() {
+                next[prev] += 1;
+            }
+            continue;
+        };
+
+        match mate[v] {
+            UNMATCHED => {
+                // flip every edge along the path, ending at the free right node v
+                let mut right = v;
+                for &l in path.iter().rev() {
+                    let previous = mate[l];
+                    mate[l] = right;
+                    mate[right] = l;
+                    right = previous;
+                }
+                return true;
+            }
+            w if dist[w] == dist[x] + 1 => path.push(w),
+            _ => next[x] += 1,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Edge;
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // jobs 0-2, workers 3-5; a greedy match of 0-3 would block job 1
+        let mut g = Graph::new(6);
+        for (u, v) in [(0, 3), (0, 4), (1, 3), (2, 4), (2, 5)] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: 1.0,
+            });
+        }
+        let left = [true, true, true, false, false, false];
+
+        let pairs = hopcroft_karp(&g, &left);
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs.contains(&(NodeId(1), NodeId(3))));
+        assert!(pairs.contains(&(NodeId(0), NodeId(4))));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::dsu::DisjointSet;
-use crate::graph::{Edge, Graph};
+use crate::graph::{Edge, Graph, NodeId};
+use std::collections::HashMap;
 
 /// A minimum spanning tree of an undirected graph.
 /// Contains the edges that form the MST and their total weight.
@@ -30,6 +31,240 @@ pub fn kruskal(g: &Graph) -> Mst {
     }
 }
 
+/// Builds a spanning tree in which no node has more than `max_degree`
+/// edges, using Kruskal's algorithm but skipping any edge that would push
+/// an endpoint over the limit.
+///
+/// This is a heuristic: finding the cheapest such tree is NP-hard, and the
+/// greedy choice can leave the tree split into several pieces even when a
+/// degree-bounded spanning tree exists. Compare the edge count against
+/// `kruskal` to tell whether it spans.
+pub fn degree_constrained_kruskal(g: &Graph, max_degree: usize) -> Mst {
+    let mut edges = g.edges();
+    let n = g.size();
+    let mut ds = DisjointSet::new(n);
+    let mut degree = vec![0; n];
+
+    edges.sort();
+    let mut span = Vec::new();
+    let mut total_weight = 0.0;
+    for e in edges {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if degree[u] >= max_degree || degree[v] >= max_degree {
+            continue;
+        }
+        if ds.union(u, v) {
+            degree[u] += 1;
+            degree[v] += 1;
+            span.push(e);
+            total_weight += e.weight;
+        }
+    }
+
+    Mst {
+        edges: span,
+        total_weight,
+    }
+}
+
+/// A partition of the nodes into clusters.
+pub struct Clustering {
+    /// Cluster of each node, indexed by node ID
+    pub cluster: Vec<usize>,
+    /// Nodes of each cluster, sorted by ID; clusters are numbered in order
+    /// of their lowest node ID
+    pub members: Vec<Vec<NodeId>>,
+    /// Total weight of the edges inside each cluster
+    pub intra_weight: Vec<f32>,
+    /// Total weight of the edges between different clusters
+    pub inter_weight: f32,
+}
+
+/// Splits the graph into `k` clusters by building the MST and dropping its
+/// `k - 1` heaviest edges (single-linkage clustering). This maximizes the
+/// lightest edge between any two clusters.
+///
+/// A graph that already has more than `k` connected components yields one
+/// cluster per component. Panics if `k` is 0.
+pub fn mst_clusters(g: &Graph, k: usize) -> Clustering {
+    assert!(k > 0, "k must be at least 1");
+    let n = g.size();
+    let mut ds = DisjointSet::new(n);
+    let mut edges = g.edges();
+    edges.sort();
+
+    // Kruskal stopped at k components is the MST minus its k - 1 heaviest edges
+    let mut components = n;
+    for e in &edges {
+        if components <= k {
+            break;
+        }
+        if ds.union(e.u.0 as usize, e.v.0 as usize) {
+            components -= 1;
+        }
+    }
+
+    let mut cluster = vec![usize::MAX; n];
+    let mut root_cluster = vec![usize::MAX; n];
+    let mut members: Vec<Vec<NodeId>> = Vec::new();
+    for v in 0..n {
+        let root = ds.find(v);
+        if root_cluster[root] == usize::MAX {
+            root_cluster[root] = members.len();
+            members.push(Vec::new());
+        }
+        cluster[v] = root_cluster[root];
+        members[cluster[v]].push(NodeId(v as u32));
+    }
+
+    let mut intra_weight = vec![0.0; members.len()];
+    let mut inter_weight = 0.0;
+    for e in &edges {
+        let (cu, cv) = (cluster[e.u.0 as usize], cluster[e.v.0 as usize]);
+        if cu == cv {
+            intra_weight[cu] += e.weight;
+        } else {
+            inter_weight += e.weight;
+        }
+    }
+
+    Clustering {
+        cluster,
+        members,
+        intra_weight,
+        inter_weight,
+    }
+}
+
+/// The first property a candidate spanning tree fails, as found by
+/// `verify_mst`.
+#[derive(Debug, PartialEq)]
+pub enum MstViolation {
+    /// A tree edge with no matching edge (same endpoints and weight) in the
+    /// graph
+    UnknownEdge(Edge),
+    /// A graph edge whose endpoints the tree doesn't connect
+    Disconnected(Edge),
+    /// A tree edge that closes a cycle with earlier tree edges
+    Cycle(Edge),
+    /// A graph edge lighter than `heaviest`, the heaviest tree edge on the
+    /// tree path between its endpoints; swapping them gives a lighter tree
+    NotMinimal { edge: Edge, heaviest: Edge },
+}
+
+/// Checks that `tree` is a minimum spanning tree (or forest, for a
+/// disconnected graph) of `g`. Properties are checked in order: every tree
+/// edge belongs to the graph, the tree connects everything the graph
+/// connects, the tree has no cycle, and every non-tree edge is at least as
+/// heavy as each tree edge on the cycle it would close (cut optimality).
+///
+/// Tree edges match graph edges regardless of direction.
+pub fn verify_mst(g: &Graph, tree: &[Edge]) -> Result<(), MstViolation> {
+    let n = g.size();
+    let key = |e: &Edge| (e.u.min(e.v), e.u.max(e.v), e.weight.to_bits());
+
+    let mut unused: HashMap<_, usize> = HashMap::new();
+    for e in g.edges() {
+        *unused.entry(key(&e)).or_default() += 1;
+    }
+    for e in tree {
+        match unused.get_mut(&key(e)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return Err(MstViolation::UnknownEdge(*e)),
+        }
+    }
+
+    let mut ds = DisjointSet::new(n);
+    let mut cycle = None;
+    for e in tree {
+        if !ds.union(e.u.0 as usize, e.v.0 as usize) && cycle.is_none() {
+            cycle = Some(*e);
+        }
+    }
+    if let Some(e) = g
+        .edges()
+        .into_iter()
+        .find(|e| ds.find(e.u.0 as usize) != ds.find(e.v.0 as usize))
+    {
+        return Err(MstViolation::Disconnected(e));
+    }
+    if let Some(e) = cycle {
+        return Err(MstViolation::Cycle(e));
+    }
+
+    // Kruskal over tree edges only, with each non-tree edge checked once
+    // all tree edges of its weight are in: its endpoints must already be
+    // joined, or a heavier tree edge on their path could be swapped for it
+    let mut all: Vec<(Edge, bool)> = tree.iter().map(|&e| (e, true)).collect();
+    for (e, count) in unused_edges(g, unused) {
+        all.extend(std::iter::repeat_n((e, false), count));
+    }
+    all.sort_by(|(a, a_tree), (b, b_tree)| a.cmp(b).then(b_tree.cmp(a_tree)));
+
+    let mut ds = DisjointSet::new(n);
+    for (e, in_tree) in all {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if in_tree {
+            ds.union(u, v);
+        } else if ds.find(u) != ds.find(v) {
+            let heaviest = heaviest_on_path(n, tree, e.u, e.v);
+            return Err(MstViolation::NotMinimal { edge: e, heaviest });
+        }
+    }
+
+    Ok(())
+}
+
+/// Graph edges left over after matching the tree, with their multiplicity.
+fn unused_edges(
+    g: &Graph,
+    mut unused: HashMap<(NodeId, NodeId, u32), usize>,
+) -> Vec<(Edge, usize)> {
+    g.edges()
+        .into_iter()
+        .filter_map(|e| {
+            let count = unused.remove(&(e.u.min(e.v), e.u.max(e.v), e.weight.to_bits()))?;
+            (count > 0).then_some((e, count))
+        })
+        .collect()
+}
+
+/// The heaviest edge on the path from `from` to `to` in an acyclic `tree`.
+/// Panics if the tree doesn't connect them.
+fn heaviest_on_path(n: usize, tree: &[Edge], from: NodeId, to: NodeId) -> Edge {
+    let mut adj = vec![Vec::new(); n];
+    for e in tree {
+        adj[e.u.0 as usize].push((e.v.0 as usize, *e));
+        adj[e.v.0 as usize].push((e.u.0 as usize, *e));
+    }
+
+    // DFS from `from`, remembering the edge used to reach each node
+    let mut via: Vec<Option<Edge>> = vec![None; n];
+    let mut seen = vec![false; n];
+    let mut stack = vec![from.0 as usize];
+    seen[from.0 as usize] = true;
+    while let Some(x) = stack.pop() {
+        for &(y, e) in &adj[x] {
+            if !seen[y] {
+                seen[y] = true;
+                via[y] = Some(e);
+                stack.push(y);
+            }
+        }
+    }
+
+    let mut heaviest: Option<Edge> = None;
+    let mut x = to.0 as usize;
+    while x != from.0 as usize {
+        let e = via[x].expect("tree connects both endpoints");
+        if heaviest.is_none_or(|h| e.weight > h.weight) {
+            heaviest = Some(e);
+        }
+        x = if e.u.0 as usize == x { e.v.0 } else { e.u.0 } as usize;
+    }
+    heaviest.expect("endpoints are distinct")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +346,98 @@ mod tests {
         assert_eq!(mst.total_weight, 6.0);
         assert_eq!(mst.edges.len(), 3);
     }
+
+    #[test]
+    fn test_degree_constrained() {
+        // a star around 0 is the MST; with max degree 2 the tree must
+        // become a path using the more expensive rim edges
+        let mut g = Graph::new(4);
+        for (u, v, w) in [
+            (0, 1, 1.0),
+            (0, 2, 1.0),
+            (0, 3, 1.0),
+            (1, 2, 5.0),
+            (2, 3, 5.0),
+        ] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: w,
+            });
+        }
+
+        let mst = degree_constrained_kruskal(&g, 2);
+        assert_eq!(mst.edges.len(), 3);
+        assert_eq!(mst.total_weight, 7.0);
+
+        let mut degree = [0; 4];
+        for e in &mst.edges {
+            degree[e.u.0 as usize] += 1;
+            degree[e.v.0 as usize] += 1;
+        }
+        assert!(degree.iter().all(|&d| d <= 2));
+    }
+
+    #[test]
+    fn test_mst_clusters() {
+        // two triangles joined by a heavy edge 2-3
+        let mut g = Graph::new(6);
+        for (u, v, w) in [
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 2.0),
+            (3, 4, 1.0),
+            (4, 5, 1.0),
+            (5, 3, 2.0),
+            (2, 3, 9.0),
+        ] {
+            g.add_edge(Edge {
+                u: NodeId(u),
+                v: NodeId(v),
+                weight: w,
+            });
+        }
+
+        let c = mst_clusters(&g, 2);
+        assert_eq!(c.cluster, vec![0, 0, 0, 1, 1, 1]);
+        assert_eq!(c.intra_weight, vec![4.0, 4.0]);
+        assert_eq!(c.inter_weight, 9.0);
+
+        assert_eq!(mst_clusters(&g, 1).members.len(), 1);
+        assert_eq!(mst_clusters(&g, 6).members.len(), 6);
+    }
+
+    #[test]
+    fn test_verify_mst() {
+        let mut g = Graph::new(3);
+        let edge = |u, v, weight| Edge {
+            u: NodeId(u),
+            v: NodeId(v),
+            weight,
+        };
+        for e in [edge(0, 1, 1.0), edge(1, 2, 2.0), edge(2, 0, 3.0)] {
+            g.add_edge(e);
+        }
+
+        assert_eq!(verify_mst(&g, &[edge(1, 0, 1.0), edge(1, 2, 2.0)]), Ok(()));
+        assert_eq!(
+            verify_mst(&g, &[edge(0, 1, 1.0), edge(1, 2, 9.0)]),
+            Err(MstViolation::UnknownEdge(edge(1, 2, 9.0)))
+        );
+        assert_eq!(
+            verify_mst(&g, &[edge(0, 1, 1.0)]),
+            Err(MstViolation::Disconnected(edge(1, 2, 2.0)))
+        );
+        assert_eq!(
+            verify_mst(&g, &[edge(0, 1, 1.0), edge(1, 2, 2.0), edge(2, 0, 3.0)]),
+            Err(MstViolation::Cycle(edge(2, 0, 3.0)))
+        );
+        assert_eq!(
+            verify_mst(&g, &[edge(0, 1, 1.0), edge(2, 0, 3.0)]),
+            Err(MstViolation::NotMinimal {
+                edge: edge(1, 2, 2.0),
+                heaviest: edge(2, 0, 3.0),
+            })
+        );
+    }
 }
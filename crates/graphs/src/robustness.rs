@@ -0,0 +1,79 @@
+use crate::dsu::DisjointSet;
+use crate::graph::Graph;
+
+/// What's left of a graph once some of its nodes and edges fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fragmentation {
+    /// Nodes that didn't fail
+    pub surviving: usize,
+    /// Nodes in the largest connected component of the surviving graph
+    pub largest_component: usize,
+    /// Pairs of surviving nodes with no path between them
+    pub disconnected_pairs: u64,
+}
+
+/// Measures the connectivity of `g` without the nodes marked in
+/// `failed_nodes` and the edges, in `g.edges()` order, marked in
+/// `failed_edges`. Edges at a failed node fail with it.
+///
+/// Pairs already disconnected in `g` are counted too, so compare against
+/// the result with nothing failed.
+pub fn fragmentation(g: &Graph, failed_nodes: &[bool], failed_edges: &[bool]) -> Fragmentation {
+    let failed = |v: usize| failed_nodes.get(v).copied().unwrap_or(false);
+    let mut ds = DisjointSet::new(g.size());
+    for (i, e) in g.edges().iter().enumerate() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if !failed_edges.get(i).copied().unwrap_or(false) && !failed(u) && !failed(v) {
+            ds.union(u, v);
+        }
+    }
+
+    let mut sizes = vec![0u64; g.size()];
+    for v in (0..g.size()).filter(|&v| !failed(v)) {
+        sizes[ds.find(v)] += 1;
+    }
+    let surviving: u64 = sizes.iter().sum();
+    let connected_pairs: u64 = sizes.iter().map(|&s| s * s.saturating_sub(1) / 2).sum();
+    Fragmentation {
+        surviving: surviving as usize,
+        largest_component: sizes.iter().copied().max().unwrap_or(0) as usize,
+        disconnected_pairs: surviving * surviving.saturating_sub(1) / 2 - connected_pairs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A triangle 0-1-2 with a tail 2-3-4.
+    fn graph() -> Graph {
+        Graph::from_unit_edges(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)])
+    }
+
+    #[test]
+    fn test_nothing_failed() {
+        let f = fragmentation(&graph(), &[], &[]);
+        assert_eq!(f.surviving, 5);
+        assert_eq!(f.largest_component, 5);
+        assert_eq!(f.disconnected_pairs, 0);
+    }
+
+    #[test]
+    fn test_failed_edges() {
+        // a triangle edge changes nothing; the tail's bridge cuts off 4
+        let f = fragmentation(&graph(), &[], &[true, false, false, false, false]);
+        assert_eq!(f.largest_component, 5);
+        let f = fragmentation(&graph(), &[], &[false, false, false, false, true]);
+        assert_eq!(f.largest_component, 4);
+        assert_eq!(f.disconnected_pairs, 4);
+    }
+
+    #[test]
+    fn test_failed_nodes() {
+        // node 2 takes three edges with it and splits {0, 1} from {3, 4}
+        let f = fragmentation(&graph(), &[false, false, true, false, false], &[]);
+        assert_eq!(f.surviving, 4);
+        assert_eq!(f.largest_component, 2);
+        assert_eq!(f.disconnected_pairs, 4);
+    }
+}
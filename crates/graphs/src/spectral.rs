@@ -0,0 +1,236 @@
+use crate::graph::Graph;
+
+/// Largest Krylov subspace built by `laplacian_spectrum`. Graphs with more
+/// nodes than this get approximate eigenvalues.
+const MAX_LANCZOS_STEPS: usize = 150;
+
+/// The smallest eigenvalues of a graph's Laplacian matrix.
+pub struct Spectrum {
+    /// Eigenvalues in ascending order; the first is always 0
+    pub eigenvalues: Vec<f64>,
+    /// True if the Krylov subspace covered the whole space, so the values
+    /// are exact up to rounding
+    pub exact: bool,
+}
+
+impl Spectrum {
+    /// The second-smallest eigenvalue (Fiedler value). It is 0 exactly when
+    /// the graph is disconnected, and larger values mean more edges must be
+    /// cut to split the graph. None for graphs with fewer than two nodes.
+    pub fn algebraic_connectivity(&self) -> Option<f64> {
+        self.eigenvalues.get(1).copied()
+    }
+}
+
+/// Computes the `k` smallest eigenvalues of the Laplacian `L = D - A`, where
+/// `A` is the adjacency matrix with parallel edges counted once, self loops
+/// ignored, and weights not considered.
+///
+/// Uses the Lanczos method with full reorthogonalization on the sparse
+/// matrix, restricted to vectors orthogonal to the all-ones vector (which
+/// always has eigenvalue 0). Eigenvalues of the resulting tridiagonal matrix
+/// are found by bisection.
+pub fn laplacian_spectrum(g: &Graph, k: usize) -> Spectrum {
+    let n = g.size();
+    let mut adj = vec![Vec::new(); n];
+    for e in g.edges() {
+        let (u, v) = (e.u.0 as usize, e.v.0 as usize);
+        if u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for list in &mut adj {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    if n == 0 || k == 0 {
+        return Spectrum {
+            eigenvalues: Vec::new(),
+            exact: true,
+        };
+    }
+
+    let steps = (n - 1).min(MAX_LANCZOS_STEPS);
+    let laplacian = |x: &[f64], y: &mut [f64]| {
+        for v in 0..n {
+            y[v] = adj[v].len() as f64 * x[v] - adj[v].iter().map(|&u| x[u]).sum::<f64>();
+        }
+    };
+
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(steps);
+    let mut alpha = Vec::with_capacity(steps);
+    let mut beta: Vec<f64> = Vec::with_capacity(steps);
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut w = vec![0.0; n];
+
+    let mut q = start_vector(n, &basis, &mut seed);
+    while let Some(current) = q {
+        laplacian(&current, &mut w);
+        let a = dot(&w, &current);
+        basis.push(current);
+        alpha.push(a);
+        if basis.len() == steps {
+            break;
+        }
+
+        // full reorthogonalization against the ones vector and the basis
+        deflate(&mut w);
+        for b in &basis {
+            let c = dot(&w, b);
+            axpy(-c, b, &mut w);
+        }
+        let norm = dot(&w, &w).sqrt();
+        if norm > 1e-10 {
+            beta.push(norm);
+            q = Some(w.iter().map(|x| x / norm).collect());
+        } else {
+            // invariant subspace found; continue from a fresh direction
+            beta.push(0.0);
+            q = start_vector(n, &basis, &mut seed);
+            if q.is_none() {
+                beta.pop();
+            }
+        }
+    }
+
+    let mut eigenvalues = vec![0.0];
+    for j in 0..(k - 1).min(alpha.len()) {
+        eigenvalues.push(tridiagonal_eigenvalue(&alpha, &beta, j).max(0.0));
+    }
+
+    Spectrum {
+        eigenvalues,
+        exact: basis.len() == n - 1,
+    }
+}
+
+/// A pseudo-random unit vector orthogonal to the ones vector and `basis`,
+/// or None if they already span the space.
+fn start_vector(n: usize, basis: &[Vec<f64>], seed: &mut u64) -> Option<Vec<f64>> {
+    if basis.len() + 1 >= n {
+        return None;
+    }
+    let mut x: Vec<f64> = (0..n)
+        .map(|_| {
+            *seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (*seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        })
+        .collect();
+    deflate(&mut x);
+    for b in basis {
+        let c = dot(&x, b);
+        axpy(-c, b, &mut x);
+    }
+    let norm = dot(&x, &x).sqrt();
+    (norm > 1e-10).then(|| x.iter().map(|v| v / norm).collect())
+}
+
+/// Removes the component along the all-ones vector.
+fn deflate(x: &mut [f64]) {
+    let mean = x.iter().sum::<f64>() / x.len() as f64;
+    for v in x.iter_mut() {
+        *v -= mean;
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn axpy(a: f64, x: &[f64], y: &mut [f64]) {
+    for (yi, xi) in y.iter_mut().zip(x) {
+        *yi += a * xi;
+    }
+}
+
+/// The `j`-th smallest eigenvalue (from 0) of the symmetric tridiagonal
+/// matrix with diagonal `alpha` and off-diagonal `beta`, by bisection on the
+/// Sturm sequence count.
+fn tridiagonal_eigenvalue(alpha: &[f64], beta: &[f64], j: usize) -> f64 {
+    // Gershgorin bounds
+    let radius = |i: usize| {
+        let left = if i > 0 { beta[i - 1].abs() } else { 0.0 };
+        let right = beta.get(i).map_or(0.0, |b| b.abs());
+        left + right
+    };
+    let mut lo = (0..alpha.len())
+        .map(|i| alpha[i] - radius(i))
+        .fold(f64::INFINITY, f64::min);
+    let mut hi = (0..alpha.len())
+        .map(|i| alpha[i] + radius(i))
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if count_below(alpha, beta, mid) > j {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+/// Number of eigenvalues less than `x`: the negative pivots of `T - xI`.
+fn count_below(alpha: &[f64], beta: &[f64], x: f64) -> usize {
+    let mut count = 0;
+    let mut d = 1.0;
+    for i in 0..alpha.len() {
+        let off = if i > 0 {
+            beta[i - 1] * beta[i - 1] / d
+        } else {
+            0.0
+        };
+        d = alpha[i] - x - off;
+        if d == 0.0 {
+            d = f64::EPSILON;
+        }
+        if d < 0.0 {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_spectra() {
+        // the 4-cycle has Laplacian eigenvalues 0, 2, 2, 4
+        let cycle = laplacian_spectrum(
+            &Graph::from_unit_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]),
+            4,
+        );
+        assert!(cycle.exact);
+        for (got, want) in cycle.eigenvalues.iter().zip([0.0, 2.0, 2.0, 4.0]) {
+            assert!((got - want).abs() < 1e-9, "{:?}", cycle.eigenvalues);
+        }
+
+        // the complete graph K5 has 0 and then 5 four times
+        let mut edges = Vec::new();
+        for u in 0..5 {
+            for v in u + 1..5 {
+                edges.push((u, v));
+            }
+        }
+        let complete = laplacian_spectrum(&Graph::from_unit_edges(5, &edges), 2);
+        assert!((complete.algebraic_connectivity().unwrap() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_disconnected_has_zero_fiedler_value() {
+        let g = Graph::from_unit_edges(4, &[(0, 1), (2, 3)]);
+        let spectrum = laplacian_spectrum(&g, 3);
+        assert!(spectrum.algebraic_connectivity().unwrap().abs() < 1e-9);
+        assert!((spectrum.eigenvalues[2] - 2.0).abs() < 1e-9);
+    }
+}
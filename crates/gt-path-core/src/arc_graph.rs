@@ -0,0 +1,206 @@
+use crate::cache::{CacheKey, CacheStats, PathCache};
+use crate::constraint::Constraints;
+use crate::error::PathError;
+use crate::graph::Graph;
+use crate::io::PathOutput;
+use std::sync::{Arc, RwLock};
+
+/// A shared, hot-swappable graph handle for concurrent servers.
+///
+/// Cloning an `ArcGraph` is cheap and every clone sees the same graph.
+/// Queries run against an immutable snapshot: the lock is held only long
+/// enough to clone an `Arc`, so a reload never blocks behind a long query
+/// and in-flight queries keep using the graph they started with.
+///
+/// # Example
+///
+/// ```ignore
+/// let shared = ArcGraph::load("graph.json")?;
+/// let handle = shared.clone();
+/// tokio::spawn(async move { handle.shortest_path("api", "db", &Constraints::default()) });
+/// shared.reload("graph.json")?; // atomically swap in the new topology
+/// ```
+#[derive(Clone)]
+pub struct ArcGraph {
+    current: Arc<RwLock<Snapshot>>,
+    cache: Option<Arc<PathCache>>,
+}
+
+#[derive(Clone)]
+struct Snapshot {
+    graph: Arc<Graph>,
+    generation: u64,
+}
+
+impl ArcGraph {
+    /// Wraps an already-built graph.
+    pub fn new(graph: Graph) -> ArcGraph {
+        ArcGraph {
+            current: Arc::new(RwLock::new(Snapshot {
+                graph: Arc::new(graph),
+                generation: 0,
+            })),
+            cache: None,
+        }
+    }
+
+    /// Caches up to `capacity` query results, shared by all clones.
+    ///
+    /// Results are keyed by the graph's content hash, so a swap never serves
+    /// stale paths, while reloading an unchanged file keeps the cache warm.
+    pub fn with_cache(mut self, capacity: usize) -> ArcGraph {
+        self.cache = Some(Arc::new(PathCache::new(capacity)));
+        self
+    }
+
+    /// Cache hit/miss counters, if caching is enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
+
+    /// Loads a graph from a JSON file.
+    pub fn load(path: &str) -> anyhow::Result<ArcGraph> {
+        Ok(ArcGraph::new(Graph::load_json(path)?))
+    }
+
+    fn read(&self) -> Snapshot {
+        // a panic while holding the lock can't leave a half-swapped snapshot
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Returns the current graph. Later swaps don't affect the returned snapshot.
+    pub fn snapshot(&self) -> Arc<Graph> {
+        self.read().graph
+    }
+
+    /// Number of times the graph has been swapped since creation.
+    pub fn generation(&self) -> u64 {
+        self.read().generation
+    }
+
+    /// Atomically replaces the graph, returning the previous one.
+    pub fn swap(&self, graph: Graph) -> Arc<Graph> {
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        let previous = std::mem::replace(&mut current.graph, Arc::new(graph));
+        current.generation += 1;
+        previous
+    }
+
+    /// Loads `path` and swaps it in. On error the current graph is kept.
+    pub fn reload(&self, path: &str) -> anyhow::Result<()> {
+        let graph = Graph::load_json(path)?;
+        self.swap(graph);
+        Ok(())
+    }
+
+    /// Finds the shortest allowed path in the current graph.
+    ///
+    /// Returns an owned, name-resolved result so it stays valid after the
+    /// graph is swapped.
+    pub fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        constraints: &Constraints,
+    ) -> Result<PathOutput, PathError> {
+        let graph = self.snapshot();
+        let query = || {
+            let path = graph.shortest_path_with(from, to, constraints)?;
+            Ok(graph.path_output(&path))
+        };
+
+        match &self.cache {
+            Some(cache) => cache.get_or_compute(
+                CacheKey {
+                    fingerprint: fingerprint(&graph),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    constraints: constraints.clone(),
+                },
+                query,
+            ),
+            None => query(),
+        }
+    }
+}
+
+/// Cache key form of the graph's content hash.
+fn fingerprint(graph: &Graph) -> u64 {
+    u64::from_str_radix(&graph.content_hash()[..16], 16).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_concurrent_queries_and_swap() {
+        assert_send_sync::<ArcGraph>();
+
+        let shared = ArcGraph::load("src/testdata/simple_graph.json").unwrap();
+        let before = shared.snapshot();
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = shared.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        handle
+                            .shortest_path("a", "c", &Constraints::default())
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        // same topology, so readers succeed on either side of the swap
+        shared.reload("src/testdata/simple_graph.json").unwrap();
+        for r in readers {
+            r.join().unwrap();
+        }
+        assert_eq!(shared.generation(), 1);
+
+        shared.reload("src/testdata/sample_graph.json").unwrap();
+        let out = shared
+            .shortest_path("api", "db", &Constraints::default())
+            .unwrap();
+        assert_eq!(out.total_latency_ms, 8);
+        // the old snapshot is unaffected by the swaps
+        assert!(before.to_id.contains_key("a"));
+    }
+
+    #[test]
+    fn test_cache_follows_graph_content() {
+        let shared = ArcGraph::load("src/testdata/sample_graph.json")
+            .unwrap()
+            .with_cache(16);
+        let none = Constraints::default();
+
+        shared.shortest_path("api", "db", &none).unwrap();
+        shared.shortest_path("api", "db", &none).unwrap();
+        assert_eq!(shared.cache_stats().unwrap().hits, 1);
+
+        // an identical reload keeps cached results
+        shared.reload("src/testdata/sample_graph.json").unwrap();
+        shared.shortest_path("api", "db", &none).unwrap();
+        assert_eq!(shared.cache_stats().unwrap().hits, 2);
+
+        shared.swap(Graph::load_json("src/testdata/simple_graph.json").unwrap());
+        assert!(shared.shortest_path("api", "db", &none).is_err());
+        assert_eq!(shared.cache_stats().unwrap().misses, 2);
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_graph() {
+        let shared = ArcGraph::load("src/testdata/simple_graph.json").unwrap();
+        assert!(shared.reload("src/testdata/invalid_graph.json").is_err());
+        assert_eq!(shared.generation(), 0);
+        assert!(shared.snapshot().to_id.contains_key("a"));
+    }
+}
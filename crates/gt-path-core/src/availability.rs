@@ -0,0 +1,222 @@
+//! End-to-end availability from per-node and per-edge `availability`
+//! attributes.
+//!
+//! Nodes and edges may declare an `availability` between 0 and 1 (e.g.
+//! `0.999`); anything without one is treated as always available. Failures
+//! are assumed to be independent.
+
+use crate::constraint::Constraints;
+use crate::error::PathError;
+use crate::graph::{Graph, NodeId};
+use crate::io::Attributes;
+use crate::path::Path;
+
+/// Attribute holding a node or edge availability.
+pub const ATTRIBUTE: &str = "availability";
+
+/// Reads the availability from node or edge attributes, defaulting to 1.
+pub fn of(attrs: &Attributes) -> f64 {
+    attrs.get(ATTRIBUTE).and_then(|v| v.as_f64()).unwrap_or(1.0)
+}
+
+/// Checks that an `availability` attribute, if present, is a number in
+/// `[0, 1]`.
+pub(crate) fn validate(attrs: &Attributes) -> Result<(), String> {
+    match attrs.get(ATTRIBUTE) {
+        None => Ok(()),
+        Some(v) => match v.as_f64() {
+            Some(a) if (0.0..=1.0).contains(&a) => Ok(()),
+            _ => Err(v.to_string()),
+        },
+    }
+}
+
+/// Availability of a route served by one or more node-disjoint paths.
+pub struct RouteAvailability {
+    /// Node-disjoint paths found, shortest first
+    pub paths: Vec<Path>,
+    /// Availability of each path on its own
+    pub path_availability: Vec<f64>,
+    /// Probability that at least one of `paths` is up; the route is at
+    /// least this available
+    pub lower_bound: f64,
+    /// The route can be no more available than this: it needs both
+    /// endpoints and at least one edge out of the source and into the
+    /// destination
+    pub upper_bound: f64,
+}
+
+impl Graph {
+    /// Availability of a single path: the product of the availabilities of
+    /// every node and edge on it.
+    pub fn path_availability(&self, path: &Path) -> f64 {
+        let nodes: f64 = path
+            .path
+            .iter()
+            .map(|id| of(&self.node_attrs[id.0 as usize]))
+            .product();
+        let edges: f64 = path
+            .edges
+            .iter()
+            .map(|e| of(&self.edge_attrs[e.index]))
+            .product();
+        nodes * edges
+    }
+
+    /// Finds up to `max_paths` node-disjoint paths from `from` to `to`,
+    /// taking the shortest remaining path each time, and bounds the
+    /// availability of the route they provide.
+    ///
+    /// With one path both bounds describe that path only: the lower bound
+    /// is its availability. The greedy search may find fewer disjoint paths
+    /// than exist, which only loosens the lower bound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RouteAvailability)` - The paths found and the bounds
+    /// * `Err(PathError)` - If either node doesn't exist or no path exists
+    pub fn route_availability(
+        &self,
+        from: &str,
+        to: &str,
+        max_paths: usize,
+        constraints: &Constraints,
+    ) -> Result<RouteAvailability, PathError> {
+        let first = self.shortest_path_with(from, to, constraints)?;
+        let (source, target) = (first.from, first.to);
+
+        let mut paths = vec![first];
+        let mut remaining = self.clone();
+        while paths.len() < max_paths {
+            // drop every edge into the last path's intermediate nodes, and
+            // the direct edge if it used one
+            let last = paths.last().expect("at least one path");
+            let mut drop = Vec::new();
+            for &v in &last.path[1..last.path.len() - 1] {
+                for (u, links) in remaining.adj.iter().enumerate() {
+                    if links.iter().any(|l| l.to == v) {
+                        drop.push((self.name(NodeId(u as u32)), self.name(v)));
+                    }
+                }
+            }
+            if last.path.len() == 2 {
+                drop.push((from.to_string(), to.to_string()));
+            }
+            remaining = remaining.with_modifications(&[], &drop)?;
+
+            match remaining.shortest_path_with(from, to, constraints) {
+                Ok(path) => paths.push(path),
+                Err(PathError::PathNotFound { .. }) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let endpoints =
+            of(&self.node_attrs[source.0 as usize]) * of(&self.node_attrs[target.0 as usize]);
+        let path_availability: Vec<f64> = paths.iter().map(|p| self.path_availability(p)).collect();
+
+        // the paths share only their endpoints, so their interiors fail
+        // independently
+        let all_down: f64 = path_availability
+            .iter()
+            .map(|&a| {
+                if endpoints > 0.0 {
+                    1.0 - a / endpoints
+                } else {
+                    1.0
+                }
+            })
+            .product();
+        let lower_bound = endpoints * (1.0 - all_down);
+
+        // any working route uses one edge out of the source and one into
+        // the target, so each edge set is a cut
+        let allowed =
+            |edge: usize| constraints.allows_edge(&self.edge_ids[edge], &self.edge_attrs[edge]);
+        let out_up = 1.0
+            - self.adj[source.0 as usize]
+                .iter()
+                .filter(|l| allowed(l.edge))
+                .map(|l| 1.0 - of(&self.edge_attrs[l.edge]))
+                .product::<f64>();
+        let in_up = 1.0
+            - self
+                .adj
+                .iter()
+                .flatten()
+                .filter(|l| l.to == target && allowed(l.edge))
+                .map(|l| 1.0 - of(&self.edge_attrs[l.edge]))
+                .product::<f64>();
+        let upper_bound = if max_paths <= 1 {
+            lower_bound
+        } else {
+            endpoints * out_up.min(in_up)
+        };
+
+        Ok(RouteAvailability {
+            paths,
+            path_availability,
+            lower_bound,
+            upper_bound,
+        })
+    }
+
+    fn name(&self, id: NodeId) -> String {
+        self.to_name[id.0 as usize].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GraphInput;
+
+    fn graph() -> Graph {
+        let input: GraphInput = serde_json::from_str(
+            r#"{
+                "nodes": ["api", {"name": "auth", "availability": 0.99}, "cache", "db"],
+                "edges": [
+                    {"from": "api", "to": "auth", "latency_ms": 5},
+                    {"from": "auth", "to": "db", "latency_ms": 3, "availability": 0.99},
+                    {"from": "api", "to": "cache", "latency_ms": 7, "availability": 0.9},
+                    {"from": "cache", "to": "db", "latency_ms": 2}
+                ]
+            }"#,
+        )
+        .unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    #[test]
+    fn test_single_path() {
+        let g = graph();
+        let route = g
+            .route_availability("api", "db", 1, &Constraints::default())
+            .unwrap();
+        assert_eq!(route.paths.len(), 1);
+        assert!((route.lower_bound - 0.99 * 0.99).abs() < 1e-12);
+        assert_eq!(route.lower_bound, route.upper_bound);
+    }
+
+    #[test]
+    fn test_redundant_paths() {
+        let g = graph();
+        let route = g
+            .route_availability("api", "db", 3, &Constraints::default())
+            .unwrap();
+        assert_eq!(route.paths.len(), 2);
+        let expected = 1.0 - (1.0 - 0.99 * 0.99) * (1.0 - 0.9);
+        assert!((route.lower_bound - expected).abs() < 1e-12);
+        assert!(route.upper_bound >= route.lower_bound);
+        assert!(route.upper_bound <= 1.0);
+    }
+
+    #[test]
+    fn test_rejects_out_of_range() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"nodes": [{"name": "a", "availability": 1.5}, "b"], "edges": []}"#,
+        )
+        .unwrap();
+        assert!(Graph::try_from(input).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Restricting searches to a subset of edges, such as a planned backbone
+//! tree, and comparing candidate trees.
+//!
+//! Subsets are masks indexed by `Link::edge`, as taken by
+//! `Graph::shortest_path_without` once negated. Pairs of nodes are joined
+//! with edge direction ignored, the way `gt-connect` sees a graph, and
+//! every link between a chosen pair is kept, so traffic can cross the pair
+//! either way the graph allows.
+
+use crate::error::PathError;
+use crate::graph::{Graph, NodeId};
+use crate::io::{BackboneOutput, StretchOutput, TreeOutput};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+
+/// Every linked pair of nodes, lower ID first, with its lowest latency.
+fn pairs(graph: &Graph) -> BTreeMap<(u32, u32), u32> {
+    let mut pairs = BTreeMap::new();
+    for (u, links) in graph.adj.iter().enumerate() {
+        for link in links {
+            let (u, v) = (u as u32, link.to.0);
+            if u != v {
+                let latency = pairs.entry((u.min(v), u.max(v))).or_insert(u32::MAX);
+                *latency = (*latency).min(link.latency_ms);
+            }
+        }
+    }
+    pairs
+}
+
+/// Marks the edges of a minimum spanning tree of `graph` with edge
+/// direction ignored. Each linked pair of nodes is weighted by its lowest
+/// latency, and ties go to the pair with the lower node IDs, so the tree
+/// is the one `gt-connect analyze` reports for the same graph.
+pub fn minimum_spanning_tree(graph: &Graph) -> Vec<bool> {
+    edges_joining(graph, &minimum_spanning_pairs(graph))
+}
+
+/// The pairs of nodes `minimum_spanning_tree` joins.
+pub fn minimum_spanning_pairs(graph: &Graph) -> Vec<(NodeId, NodeId)> {
+    let mut pairs: Vec<_> = pairs(graph).into_iter().collect();
+    pairs.sort_by_key(|&(pair, latency)| (latency, pair));
+
+    // Kruskal's algorithm, with path halving
+    fn root(parent: &mut [u32], mut n: u32) -> u32 {
+        while parent[n as usize] != n {
+            parent[n as usize] = parent[parent[n as usize] as usize];
+            n = parent[n as usize];
+        }
+        n
+    }
+    let mut parent: Vec<u32> = (0..graph.to_name.len() as u32).collect();
+    let mut tree = Vec::new();
+    for ((u, v), _) in pairs {
+        let (ru, rv) = (root(&mut parent, u), root(&mut parent, v));
+        if ru != rv {
+            parent[ru as usize] = rv;
+            tree.push((NodeId(u), NodeId(v)));
+        }
+    }
+    tree
+}
+
+/// The pairs of nodes joined by a shortest-path tree rooted at `root`,
+/// with edge direction ignored and pairs weighted as for the spanning
+/// tree. Of equally short ways to reach a node, the one through the
+/// lower parent ID wins.
+pub fn shortest_path_pairs(graph: &Graph, root: NodeId) -> Vec<(NodeId, NodeId)> {
+    let pairs: Vec<_> = pairs(graph).into_iter().collect();
+    let (_, parents) = distances(graph.to_name.len(), &pairs, root);
+    parents
+        .iter()
+        .enumerate()
+        .filter_map(|(v, parent)| {
+            parent.map(|u| (NodeId(u.min(v as u32)), NodeId(u.max(v as u32))))
+        })
+        .collect()
+}
+
+/// Parses a CSV edge list of `from,to` node names, one pair per line.
+/// Further columns, such as the weight of a `gt-connect` CSV graph, are
+/// ignored. Blank lines and lines starting with `#` are skipped, as is a
+/// first line of `from,to` or `u,v` headers.
+pub fn parse_edge_pairs(contents: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(str::trim);
+        let (Some(from), Some(to)) = (fields.next(), fields.next()) else {
+            anyhow::bail!("line {}: expected from,to", i + 1);
+        };
+        if i == 0 && matches!((from, to), ("from", "to") | ("u", "v")) {
+            continue;
+        }
+        if from.is_empty() || to.is_empty() {
+            anyhow::bail!("line {}: expected from,to", i + 1);
+        }
+        pairs.push((from.to_string(), to.to_string()));
+    }
+    Ok(pairs)
+}
+
+/// Latency from `root` to each node using only the joined `tree` pairs,
+/// None where the tree doesn't reach.
+pub fn tree_distances(graph: &Graph, tree: &[(NodeId, NodeId)], root: NodeId) -> Vec<Option<u32>> {
+    let all = pairs(graph);
+    let pairs: Vec<_> = tree
+        .iter()
+        .filter_map(|&(a, b)| {
+            let key = (a.0.min(b.0), a.0.max(b.0));
+            all.get(&key).map(|&latency| (key, latency))
+        })
+        .collect();
+    distances(graph.to_name.len(), &pairs, root).0
+}
+
+/// Dijkstra over undirected weighted pairs, returning each node's distance
+/// from `root` and its parent on the way there.
+fn distances(
+    n: usize,
+    pairs: &[((u32, u32), u32)],
+    root: NodeId,
+) -> (Vec<Option<u32>>, Vec<Option<u32>>) {
+    let mut adj = vec![Vec::new(); n];
+    for &((u, v), latency) in pairs {
+        adj[u as usize].push((v, latency));
+        adj[v as usize].push((u, latency));
+    }
+
+    let mut dist: Vec<Option<u32>> = vec![None; n];
+    let mut parents: Vec<Option<u32>> = vec![None; n];
+    dist[root.0 as usize] = Some(0);
+    let mut heap = BinaryHeap::from([Reverse((0u32, root.0))]);
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if dist[u as usize].is_some_and(|best| d > best) {
+            continue;
+        }
+        for &(v, latency) in &adj[u as usize] {
+            let through = d.saturating_add(latency);
+            let better = match dist[v as usize] {
+                None => true,
+                Some(best) => {
+                    through < best
+                        || (through == best && parents[v as usize].is_some_and(|p| u < p))
+                }
+            };
+            if better && v != root.0 {
+                if dist[v as usize] != Some(through) {
+                    heap.push(Reverse((through, v)));
+                }
+                dist[v as usize] = Some(through);
+                parents[v as usize] = Some(u);
+            }
+        }
+    }
+    (dist, parents)
+}
+
+/// Compares the minimum spanning tree of `graph` with its shortest-path
+/// tree from `root`: what each costs in total latency, how much longer
+/// each destination is along the spanning tree, and which pairs only one
+/// of them joins.
+pub fn compare(graph: &Graph, root: &str) -> Result<BackboneOutput, PathError> {
+    let root_id = *graph
+        .to_id
+        .get(root)
+        .ok_or_else(|| PathError::NodeNotFound(root.to_string()))?;
+    let mst = minimum_spanning_pairs(graph);
+    let spt = shortest_path_pairs(graph, root_id);
+    let weights = pairs(graph);
+    let tree = |pairs: &[(NodeId, NodeId)]| TreeOutput {
+        total_latency_ms: pairs.iter().map(|(a, b)| weights[&(a.0, b.0)] as u64).sum(),
+        links: pairs.len(),
+    };
+
+    let along_mst = tree_distances(graph, &mst, root_id);
+    let along_spt = tree_distances(graph, &spt, root_id);
+    let mut destinations: Vec<StretchOutput> = along_spt
+        .iter()
+        .zip(&along_mst)
+        .enumerate()
+        .filter(|&(n, _)| n != root_id.0 as usize)
+        .filter_map(|(n, (&spt, &mst))| {
+            let (spt, mst) = (spt?, mst?);
+            Some(StretchOutput {
+                node: graph.to_name[n].to_string(),
+                mst_latency_ms: mst,
+                spt_latency_ms: spt,
+                stretch: if spt == 0 {
+                    1.0
+                } else {
+                    mst as f64 / spt as f64
+                },
+            })
+        })
+        .collect();
+    destinations.sort_by(|a, b| b.stretch.total_cmp(&a.stretch).then(a.node.cmp(&b.node)));
+
+    let name = |&(a, b): &(u32, u32)| {
+        (
+            graph.to_name[a as usize].to_string(),
+            graph.to_name[b as usize].to_string(),
+        )
+    };
+    let set = |pairs: &[(NodeId, NodeId)]| -> BTreeSet<_> {
+        pairs.iter().map(|(a, b)| (a.0, b.0)).collect()
+    };
+    let (mst_set, spt_set) = (set(&mst), set(&spt));
+    Ok(BackboneOutput {
+        root: root.to_string(),
+        mst: tree(&mst),
+        spt: tree(&spt),
+        destinations,
+        mst_only: mst_set.difference(&spt_set).map(name).collect(),
+        spt_only: spt_set.difference(&mst_set).map(name).collect(),
+        graph_hash: graph.content_hash().to_string(),
+    })
+}
+
+/// Marks the edges joining any of `pairs`, in either direction.
+pub fn edges_joining(graph: &Graph, pairs: &[(NodeId, NodeId)]) -> Vec<bool> {
+    let key = |a: NodeId, b: NodeId| (a.0.min(b.0), a.0.max(b.0));
+    let pairs: std::collections::HashSet<_> = pairs.iter().map(|&(a, b)| key(a, b)).collect();
+    let mut joined = vec![false; graph.edge_ids.len()];
+    for (u, links) in graph.adj.iter().enumerate() {
+        for link in links {
+            if pairs.contains(&key(NodeId(u as u32), link.to)) {
+                joined[link.edge] = true;
+            }
+        }
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constraints;
+    use crate::io::GraphInput;
+
+    fn graph(json: &str) -> Graph {
+        let input: GraphInput = serde_json::from_str(json).unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let graph = graph(
+            r#"{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5},
+                {"from": "auth", "to": "db", "latency_ms": 3},
+                {"from": "db", "to": "auth", "latency_ms": 9},
+                {"from": "api", "to": "cache", "latency_ms": 7},
+                {"from": "cache", "to": "db", "latency_ms": 2}
+            ]}"#,
+        );
+        let tree = minimum_spanning_tree(&graph);
+        // both links between auth and db, but not api → cache
+        assert_eq!(tree, vec![true, true, true, false, true]);
+
+        let down: Vec<bool> = tree.iter().map(|&t| !t).collect();
+        let path = graph
+            .shortest_path_without("api", "db", &Constraints::default(), &down)
+            .unwrap();
+        assert_eq!(path.cost, 8);
+        // the tree is crossed only the way its links go
+        assert!(matches!(
+            graph.shortest_path_without("api", "cache", &Constraints::default(), &down),
+            Err(PathError::PathNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_compare() {
+        // a ring where the spanning tree drops the long direct link the
+        // shortest-path tree needs
+        let graph = graph(
+            r#"{"nodes": ["hub", "a", "b", "c"], "edges": [
+                {"from": "hub", "to": "a", "latency_ms": 2},
+                {"from": "a", "to": "b", "latency_ms": 2},
+                {"from": "b", "to": "c", "latency_ms": 2},
+                {"from": "c", "to": "hub", "latency_ms": 5}
+            ]}"#,
+        );
+        let output = compare(&graph, "hub").unwrap();
+        assert_eq!((output.mst.total_latency_ms, output.mst.links), (6, 3));
+        assert_eq!((output.spt.total_latency_ms, output.spt.links), (9, 3));
+
+        let c = &output.destinations[0];
+        assert_eq!(c.node, "c");
+        assert_eq!((c.mst_latency_ms, c.spt_latency_ms), (6, 5));
+        assert!((c.stretch - 1.2).abs() < 1e-9);
+        assert!(output.destinations[1..].iter().all(|d| d.stretch == 1.0));
+
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(output.mst_only, vec![pair("b", "c")]);
+        assert_eq!(output.spt_only, vec![pair("hub", "c")]);
+
+        assert!(matches!(
+            compare(&graph, "nowhere"),
+            Err(PathError::NodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_edge_pairs() {
+        let pairs = parse_edge_pairs("u,v,weight\n# backbone\napi, auth,5\n\nauth,db\n").unwrap();
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(pairs, vec![pair("api", "auth"), pair("auth", "db")]);
+
+        for bad in ["api", "api,", ",db"] {
+            assert!(parse_edge_pairs(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_edges_joining() {
+        let graph = graph(
+            r#"{"nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 1},
+                {"from": "b", "to": "a", "latency_ms": 1},
+                {"from": "b", "to": "c", "latency_ms": 1}
+            ]}"#,
+        );
+        let joined = edges_joining(&graph, &[(NodeId(1), NodeId(0))]);
+        assert_eq!(joined, vec![true, true, false]);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::constraint::Constraints;
+use crate::error::PathError;
+use crate::io::PathOutput;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Identifies a query against a specific version of a graph.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Identity of the graph version the query ran against (e.g., derived
+    /// from `Graph::content_hash`)
+    pub fingerprint: u64,
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Path search restrictions
+    pub constraints: Constraints,
+}
+
+/// Hit/miss counters for a `PathCache`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to run the query, including expired entries
+    pub misses: u64,
+    /// Lookups that found an entry older than the TTL
+    pub expired: u64,
+    /// Entries currently cached
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache (0.0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Bounded LRU cache of shortest path results.
+///
+/// Both found paths and "no path" errors are cached, since repeated failing
+/// queries are as common as succeeding ones. Entries for an old graph
+/// version are never returned for a new one because the fingerprint is
+/// part of the key; they simply age out.
+pub struct PathCache {
+    entries: Mutex<LruCache<CacheKey, Cached>>,
+    ttl: Option<Duration>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    expired: AtomicU64,
+}
+
+/// A cached result and when it was computed.
+type Cached = (Instant, Result<PathOutput, PathError>);
+
+impl PathCache {
+    /// Creates a cache holding at most `capacity` results (minimum 1).
+    pub fn new(capacity: usize) -> PathCache {
+        PathCache {
+            entries: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+            ttl: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+        }
+    }
+
+    /// Recomputes results older than `ttl` instead of returning them. Keys
+    /// that change with the graph already keep results current; a TTL also
+    /// bounds how long a result can outlive a change the key doesn't see.
+    pub fn with_ttl(mut self, ttl: Duration) -> PathCache {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Returns the cached result for `key`, or runs `query` and caches it.
+    ///
+    /// The lock is not held while `query` runs, so concurrent misses for
+    /// the same key may each compute the result.
+    pub fn get_or_compute(
+        &self,
+        key: CacheKey,
+        query: impl FnOnce() -> Result<PathOutput, PathError>,
+    ) -> Result<PathOutput, PathError> {
+        if let Some((at, cached)) = self.lock().get(&key) {
+            if self.ttl.is_none_or(|ttl| at.elapsed() < ttl) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return cached.clone();
+            }
+            self.expired.fetch_add(1, Ordering::Relaxed);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = query();
+        self.lock().put(key, (Instant::now(), result.clone()));
+        result
+    }
+
+    /// Returns the current hit/miss counters.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            entries: self.lock().len(),
+        }
+    }
+
+    /// Removes every cached result. Counters are kept.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<CacheKey, Cached>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(fingerprint: u64, from: &str) -> CacheKey {
+        CacheKey {
+            fingerprint,
+            from: from.to_string(),
+            to: "z".to_string(),
+            constraints: Constraints::default(),
+        }
+    }
+
+    fn not_found(from: &str) -> Result<PathOutput, PathError> {
+        Err(PathError::NodeNotFound(from.to_string()))
+    }
+
+    #[test]
+    fn test_hits_misses_and_eviction() {
+        let cache = PathCache::new(2);
+
+        cache.get_or_compute(key(1, "a"), || not_found("a")).ok();
+        cache.get_or_compute(key(1, "a"), || unreachable!()).ok();
+        cache.get_or_compute(key(1, "b"), || not_found("b")).ok();
+        // different graph version is a different entry; evicts "a"
+        cache.get_or_compute(key(2, "b"), || not_found("b")).ok();
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.hit_rate(), 0.25);
+
+        let mut recomputed = false;
+        cache
+            .get_or_compute(key(1, "a"), || {
+                recomputed = true;
+                not_found("a")
+            })
+            .ok();
+        assert!(recomputed);
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cache = PathCache::new(4).with_ttl(Duration::from_millis(20));
+        cache.get_or_compute(key(1, "a"), || not_found("a")).ok();
+        cache.get_or_compute(key(1, "a"), || unreachable!()).ok();
+
+        std::thread::sleep(Duration::from_millis(30));
+        let mut recomputed = false;
+        cache
+            .get_or_compute(key(1, "a"), || {
+                recomputed = true;
+                not_found("a")
+            })
+            .ok();
+        assert!(recomputed);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.expired), (1, 2, 1));
+        assert_eq!(stats.entries, 1);
+    }
+}
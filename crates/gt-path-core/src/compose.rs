@@ -0,0 +1,289 @@
+//! Composing one graph from several JSON files with `"includes"`.
+//!
+//! A file lists other graph files, relative to its own directory:
+//!
+//! ```json
+//! {
+//!   "includes": ["core.json", "region-eu.json"],
+//!   "nodes": ["lb"],
+//!   "edges": [{ "from": "lb", "to": "api", "latency_ms": 1 }]
+//! }
+//! ```
+//!
+//! Each file's `unit`, `default_latency_ms`, and `directed` settings apply
+//! to its own edges only. The merged graph has every file's nodes and
+//! edges, with these rules for overlaps:
+//!
+//! * A node may be declared in several files. Declarations with attributes
+//!   must agree; a bare name just refers to the node.
+//! * An edge `from → to` declared in several files must be identical
+//!   (latency, timeline, and attributes), and is then kept once.
+//! * A file included more than once, e.g. by two fragments, is merged once.
+//!   A file that includes itself, directly or not, is an error.
+
+use crate::io::{EdgeInput, GraphInput, NodeInput};
+use anyhow::{Context, Result, bail};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Reads the graph file at `path` and merges in every file it includes.
+pub fn read(path: &Path) -> Result<GraphInput> {
+    let contents = std::fs::read_to_string(path)
+        .context(format!("Failed to read file: {}", path.display()))?;
+    let input: GraphInput = serde_json::from_str(&contents)
+        .context(format!("Failed to parse JSON in {}", path.display()))?;
+    resolve(input, path)
+}
+
+/// Merges the files `input` includes into it. `path` is the file `input`
+/// was read from; includes are relative to its directory.
+///
+/// Input without includes is returned unchanged; otherwise the merged graph
+/// is normalized (see `GraphInput::normalize`), since each file's settings
+/// only apply to its own edges.
+pub fn resolve(input: GraphInput, path: &Path) -> Result<GraphInput> {
+    if input.includes.is_empty() {
+        return Ok(input);
+    }
+
+    let mut fragments = Vec::new();
+    let mut stack = vec![canonical(path)?];
+    let mut merged = HashSet::new();
+    collect(input, path, &mut stack, &mut merged, &mut fragments)?;
+    merge(fragments)
+}
+
+/// The file at `path` and every file it includes, directly or not, each
+/// once. Used to tell when a composed graph is out of date.
+pub fn files(path: &Path) -> Result<Vec<PathBuf>> {
+    #[derive(serde::Deserialize)]
+    struct Includes {
+        #[serde(default)]
+        includes: Vec<String>,
+    }
+
+    let mut files = vec![canonical(path)?];
+    let mut i = 0;
+    while i < files.len() {
+        let file = files[i].clone();
+        let contents = std::fs::read_to_string(&file)
+            .context(format!("Failed to read file: {}", file.display()))?;
+        let parsed: Includes = serde_json::from_str(&contents)
+            .context(format!("Failed to parse JSON in {}", file.display()))?;
+        for include in parsed.includes {
+            let included = canonical(&include_path(&file, &include))?;
+            if !files.contains(&included) {
+                files.push(included);
+            }
+        }
+        i += 1;
+    }
+    Ok(files)
+}
+
+fn canonical(path: &Path) -> Result<PathBuf> {
+    std::fs::canonicalize(path).context(format!("Failed to read file: {}", path.display()))
+}
+
+fn include_path(from: &Path, include: &str) -> PathBuf {
+    from.parent().unwrap_or(Path::new(".")).join(include)
+}
+
+/// Normalizes `input` and the files it includes into `fragments`, included
+/// files first. `stack` holds the files being read, outermost first.
+fn collect(
+    mut input: GraphInput,
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    merged: &mut HashSet<PathBuf>,
+    fragments: &mut Vec<(PathBuf, GraphInput)>,
+) -> Result<()> {
+    for include in std::mem::take(&mut input.includes) {
+        let file = include_path(path, &include);
+        let canonical = canonical(&file).context(format!(
+            "Failed to include {} from {}",
+            include,
+            path.display()
+        ))?;
+        if stack.contains(&canonical) {
+            let cycle: Vec<String> = stack
+                .iter()
+                .chain([&canonical])
+                .map(|p| p.display().to_string())
+                .collect();
+            bail!("include cycle: {}", cycle.join(" -> "));
+        }
+        if !merged.insert(canonical.clone()) {
+            continue;
+        }
+
+        let contents = std::fs::read_to_string(&file)
+            .context(format!("Failed to read file: {}", file.display()))?;
+        let included: GraphInput = serde_json::from_str(&contents)
+            .context(format!("Failed to parse JSON in {}", file.display()))?;
+        stack.push(canonical);
+        collect(included, &file, stack, merged, fragments)?;
+        stack.pop();
+    }
+    fragments.push((path.to_path_buf(), input.normalize()));
+    Ok(())
+}
+
+fn merge(fragments: Vec<(PathBuf, GraphInput)>) -> Result<GraphInput> {
+    let mut nodes: Vec<NodeInput> = Vec::new();
+    let mut node_index: HashMap<String, (usize, PathBuf)> = HashMap::new();
+    let mut edges: Vec<EdgeInput> = Vec::new();
+    // the file that first declared each from → to, and its edges' positions
+    let mut edge_index: HashMap<(String, String), (PathBuf, Vec<usize>)> = HashMap::new();
+
+    for (file, fragment) in fragments {
+        for node in fragment.nodes {
+            let Some((i, first)) = node_index.get(node.name()) else {
+                node_index.insert(node.name().to_string(), (nodes.len(), file.clone()));
+                nodes.push(node);
+                continue;
+            };
+            match (&nodes[*i], &node) {
+                (_, NodeInput::Name(_)) => {}
+                (NodeInput::Name(_), _) => nodes[*i] = node,
+                (existing, _) if *existing == node => {}
+                _ => bail!(
+                    "node {} is declared with different attributes in {} and {}",
+                    node.name(),
+                    first.display(),
+                    file.display()
+                ),
+            }
+        }
+
+        let mut declared: HashMap<(String, String), Vec<EdgeInput>> = HashMap::new();
+        let mut order = Vec::new();
+        for edge in fragment.edges {
+            let key = (edge.from.clone(), edge.to.clone());
+            if !declared.contains_key(&key) {
+                order.push(key.clone());
+            }
+            declared.entry(key).or_default().push(edge);
+        }
+        for key in order {
+            let group = declared.remove(&key).unwrap_or_default();
+            if let Some((first, positions)) = edge_index.get(&key) {
+                let existing: Vec<&EdgeInput> = positions.iter().map(|&i| &edges[i]).collect();
+                if existing.len() != group.len()
+                    || existing.iter().zip(&group).any(|(a, b)| *a != b)
+                {
+                    bail!(
+                        "edge {} → {} is declared differently in {} and {}",
+                        key.0,
+                        key.1,
+                        first.display(),
+                        file.display()
+                    );
+                }
+                continue;
+            }
+            let positions = (edges.len()..edges.len() + group.len()).collect();
+            edges.extend(group);
+            edge_index.insert(key, (file.clone(), positions));
+        }
+    }
+
+    Ok(GraphInput::new(nodes, edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("gt-path-compose-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("eu")).unwrap();
+        for (file, contents) in files {
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_merges_fragments() {
+        let dir = dir(
+            "merge",
+            &[
+                (
+                    "root.json",
+                    r#"{"includes": ["core.json", "eu/region.json"], "nodes": ["lb", "api"],
+                        "edges": [{"from": "lb", "to": "api", "latency_ms": 1}]}"#,
+                ),
+                (
+                    "core.json",
+                    r#"{"nodes": [{"name": "api", "tier": 1}, "db"],
+                        "edges": [{"from": "api", "to": "db", "latency_ms": 4}]}"#,
+                ),
+                (
+                    "eu/region.json",
+                    r#"{"includes": ["../core.json"], "unit": "us", "nodes": ["db", "eu-db"],
+                        "edges": [{"from": "db", "to": "eu-db", "latency_ms": 30000}]}"#,
+                ),
+            ],
+        );
+
+        let input = read(&dir.join("root.json")).unwrap();
+        let names: Vec<&str> = input.nodes.iter().map(|n| n.name()).collect();
+        assert_eq!(names, vec!["api", "db", "eu-db", "lb"]);
+        assert_eq!(
+            input.nodes[0],
+            NodeInput::new("api".into(), [("tier".to_string(), 1.into())].into())
+        );
+        let graph = crate::Graph::try_from(input).unwrap();
+        assert_eq!(graph.shortest_path("lb", "eu-db").unwrap().cost, 35);
+        assert_eq!(files(&dir.join("root.json")).unwrap().len(), 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rejects_cycles_and_conflicts() {
+        let dir = dir(
+            "conflict",
+            &[
+                (
+                    "a.json",
+                    r#"{"includes": ["b.json"], "nodes": [], "edges": []}"#,
+                ),
+                (
+                    "b.json",
+                    r#"{"includes": ["a.json"], "nodes": [], "edges": []}"#,
+                ),
+                (
+                    "c.json",
+                    r#"{"includes": ["d.json"], "nodes": [{"name": "x", "zone": 1}, "y"],
+                        "edges": [{"from": "x", "to": "y", "latency_ms": 1}]}"#,
+                ),
+                (
+                    "d.json",
+                    r#"{"nodes": [{"name": "x", "zone": 2}], "edges": []}"#,
+                ),
+                (
+                    "e.json",
+                    r#"{"includes": ["f.json"], "nodes": ["x", "y"],
+                        "edges": [{"from": "x", "to": "y", "latency_ms": 1}]}"#,
+                ),
+                (
+                    "f.json",
+                    r#"{"nodes": ["x", "y"], "edges": [{"from": "x", "to": "y", "latency_ms": 2}]}"#,
+                ),
+            ],
+        );
+
+        let err = read(&dir.join("a.json")).unwrap_err();
+        assert!(format!("{:#}", err).contains("include cycle"));
+        let err = read(&dir.join("c.json")).unwrap_err();
+        assert!(format!("{:#}", err).contains("node x is declared with different attributes"));
+        let err = read(&dir.join("e.json")).unwrap_err();
+        assert!(format!("{:#}", err).contains("edge x → y is declared differently"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::io::Attributes;
+use crate::predicate::{Predicate, Scope};
+
+/// A `key=value` test against node or edge attributes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttrFilter {
+    /// Attribute name
+    pub key: String,
+    /// Expected attribute value, compared as a string
+    pub value: String,
+}
+
+impl AttrFilter {
+    /// Parses a filter of the form `key=value`.
+    pub fn parse(s: &str) -> anyhow::Result<AttrFilter> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok(AttrFilter {
+                key: key.to_string(),
+                value: value.to_string(),
+            }),
+            _ => anyhow::bail!("Invalid attribute filter '{}'. Expected 'key=value'", s),
+        }
+    }
+
+    /// Returns true if the attributes contain `key` with a matching value.
+    ///
+    /// String attributes are compared directly; numbers and booleans are
+    /// compared by their JSON representation (e.g., `tier=2`).
+    pub fn matches(&self, attrs: &Attributes) -> bool {
+        match attrs.get(&self.key) {
+            Some(serde_json::Value::String(s)) => *s == self.value,
+            Some(v) => v.to_string().eq(&self.value),
+            None => false,
+        }
+    }
+}
+
+/// Restrictions applied to a path search.
+///
+/// The default value imposes no restrictions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Constraints {
+    /// Nodes matching any of these filters are never visited
+    pub avoid_nodes: Vec<AttrFilter>,
+    /// Edges matching any of these filters are never traversed
+    pub avoid_edges: Vec<AttrFilter>,
+    /// Edges with any of these IDs are never traversed
+    pub avoid_edge_ids: Vec<String>,
+    /// Nodes and edges this filter is false for are never used
+    pub filter: Option<Predicate>,
+}
+
+impl Constraints {
+    /// Returns true if a node with these attributes may appear on a path.
+    pub fn allows_node(&self, attrs: &Attributes) -> bool {
+        !self.avoid_nodes.iter().any(|f| f.matches(attrs))
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|p| p.allows(Scope::Node, attrs))
+    }
+
+    /// Returns true if an edge with this ID and these attributes may be
+    /// traversed.
+    pub fn allows_edge(&self, id: &str, attrs: &Attributes) -> bool {
+        !self.avoid_edge_ids.iter().any(|avoided| avoided == id)
+            && !self.avoid_edges.iter().any(|f| f.matches(attrs))
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|p| p.allows(Scope::Edge, attrs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let f = AttrFilter::parse("region=eu-west").unwrap();
+        assert_eq!(f.key, "region");
+        assert_eq!(f.value, "eu-west");
+
+        assert!(AttrFilter::parse("region").is_err());
+        assert!(AttrFilter::parse("=eu-west").is_err());
+    }
+
+    #[test]
+    fn test_matches_strings_and_numbers() {
+        let mut attrs = Attributes::new();
+        attrs.insert("region".to_string(), json!("eu-west"));
+        attrs.insert("tier".to_string(), json!(2));
+
+        assert!(AttrFilter::parse("region=eu-west").unwrap().matches(&attrs));
+        assert!(AttrFilter::parse("tier=2").unwrap().matches(&attrs));
+        assert!(!AttrFilter::parse("region=us-east").unwrap().matches(&attrs));
+        assert!(!AttrFilter::parse("zone=a").unwrap().matches(&attrs));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::io::{Attributes, GraphInput};
+use anyhow::Context;
+use std::collections::BTreeSet;
+
+/// A per-edge cost formula such as `latency_ms + 10*hops + 0.01*cost_usd`.
+///
+/// Expressions combine numbers and variables with `+`, `-`, `*`, `/`,
+/// unary minus, and parentheses. Variables are:
+///
+/// * `latency_ms` - the edge's latency
+/// * `hops` - always 1, so `10*hops` adds 10 per edge traversed
+/// * any other name - the edge attribute of that name; numbers are used
+///   as-is, `true`/`false` as 1/0, and an edge without it counts as 0
+///
+/// # Example
+///
+/// ```ignore
+/// let expr = CostExpr::parse("latency_ms + 10*hops")?;
+/// expr.apply(&mut input)?;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostExpr {
+    root: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f64),
+    Var(String),
+    Neg(Box<Node>),
+    Binary(Box<Node>, char, Box<Node>),
+}
+
+impl CostExpr {
+    /// Parses an expression, failing on a syntax error with its position.
+    pub fn parse(s: &str) -> anyhow::Result<CostExpr> {
+        let mut parser = Parser {
+            chars: s.char_indices().peekable(),
+            len: s.len(),
+        };
+        let root = parser.expr()?;
+        parser.skip_space();
+        if let Some((at, c)) = parser.chars.next() {
+            anyhow::bail!("unexpected '{}' at position {}", c, at + 1);
+        }
+        Ok(CostExpr { root })
+    }
+
+    /// Names of the variables the expression reads.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        fn walk<'a>(node: &'a Node, vars: &mut BTreeSet<&'a str>) {
+            match node {
+                Node::Number(_) => {}
+                Node::Var(name) => {
+                    vars.insert(name);
+                }
+                Node::Neg(inner) => walk(inner, vars),
+                Node::Binary(lhs, _, rhs) => {
+                    walk(lhs, vars);
+                    walk(rhs, vars);
+                }
+            }
+        }
+        let mut vars = BTreeSet::new();
+        walk(&self.root, &mut vars);
+        vars
+    }
+
+    /// Evaluates the expression for an edge of `latency_ms` with `attrs`.
+    pub fn eval(&self, latency_ms: f64, attrs: &Attributes) -> f64 {
+        fn eval(node: &Node, latency_ms: f64, attrs: &Attributes) -> f64 {
+            match node {
+                Node::Number(n) => *n,
+                Node::Var(name) => match name.as_str() {
+                    "latency_ms" => latency_ms,
+                    "hops" => 1.0,
+                    _ => attribute(attrs, name).unwrap_or(0.0),
+                },
+                Node::Neg(inner) => -eval(inner, latency_ms, attrs),
+                Node::Binary(lhs, op, rhs) => {
+                    let (a, b) = (eval(lhs, latency_ms, attrs), eval(rhs, latency_ms, attrs));
+                    match op {
+                        '+' => a + b,
+                        '-' => a - b,
+                        '*' => a * b,
+                        _ => a / b,
+                    }
+                }
+            }
+        }
+        eval(&self.root, latency_ms, attrs)
+    }
+
+    /// Replaces every edge latency, timeline samples included, with its
+    /// cost. Expects normalized input, so latencies are in milliseconds.
+    ///
+    /// Fails if the expression reads an attribute no edge has, which is
+    /// most likely a typo, or if an edge's cost is negative or not a
+    /// number.
+    pub fn apply(&self, input: &mut GraphInput) -> anyhow::Result<()> {
+        for var in self.variables() {
+            let known = matches!(var, "latency_ms" | "hops")
+                || input
+                    .edges
+                    .iter()
+                    .any(|e| attribute(&e.attrs, var).is_some());
+            if !known {
+                anyhow::bail!("unknown variable '{}': no edge has that attribute", var);
+            }
+        }
+
+        for edge in &mut input.edges {
+            let cost = |latency_ms: f32| {
+                let cost = self.eval(latency_ms as f64, &edge.attrs);
+                if cost.is_finite() && cost >= 0.0 {
+                    Ok(cost as f32)
+                } else {
+                    Err(anyhow::anyhow!("cost {}", cost))
+                }
+            };
+            let context = || format!("Invalid cost for edge {} → {}", edge.from, edge.to);
+            let latency_ms = edge
+                .latency_ms
+                .map(cost)
+                .transpose()
+                .with_context(context)?;
+            let timeline = edge
+                .timeline
+                .iter()
+                .map(|s| cost(s.latency_ms))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .with_context(context)?;
+
+            edge.latency_ms = latency_ms;
+            for (sample, cost) in edge.timeline.iter_mut().zip(timeline) {
+                sample.latency_ms = cost;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a numeric or boolean attribute.
+fn attribute(attrs: &Attributes, name: &str) -> Option<f64> {
+    match attrs.get(name)? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::Bool(b) => Some(*b as u8 as f64),
+        _ => None,
+    }
+}
+
+/// Recursive descent parser:
+///
+/// ```text
+/// expr   = term (('+' | '-') term)*
+/// term   = factor (('*' | '/') factor)*
+/// factor = '-' factor | number | name | '(' expr ')'
+/// ```
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    len: usize,
+}
+
+impl Parser<'_> {
+    fn skip_space(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_space();
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |&(at, _)| at) + 1
+    }
+
+    fn expr(&mut self) -> anyhow::Result<Node> {
+        let mut node = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.chars.next();
+            node = Node::Binary(Box::new(node), op, Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> anyhow::Result<Node> {
+        let mut node = self.factor()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.chars.next();
+            node = Node::Binary(Box::new(node), op, Box::new(self.factor()?));
+        }
+        Ok(node)
+    }
+
+    fn factor(&mut self) -> anyhow::Result<Node> {
+        match self.peek() {
+            Some('-') => {
+                self.chars.next();
+                Ok(Node::Neg(Box::new(self.factor()?)))
+            }
+            Some('(') => {
+                self.chars.next();
+                let node = self.expr()?;
+                if self.peek() != Some(')') {
+                    anyhow::bail!("expected ')' at position {}", self.position());
+                }
+                self.chars.next();
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let at = self.position();
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                number
+                    .parse()
+                    .map(Node::Number)
+                    .map_err(|_| anyhow::anyhow!("invalid number '{}' at position {}", number, at))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => Ok(Node::Var(
+                self.take_while(|c| c.is_alphanumeric() || c == '_'),
+            )),
+            Some(c) => anyhow::bail!("unexpected '{}' at position {}", c, self.position()),
+            None => anyhow::bail!("unexpected end of expression"),
+        }
+    }
+
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| keep(c)) {
+            taken.push(c);
+        }
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_eval() {
+        let expr = CostExpr::parse("latency_ms + 10*hops + 0.01*cost_usd").unwrap();
+        assert_eq!(
+            expr.variables().into_iter().collect::<Vec<_>>(),
+            vec!["cost_usd", "hops", "latency_ms"]
+        );
+        let mut attrs = Attributes::new();
+        attrs.insert("cost_usd".to_string(), json!(200));
+        assert_eq!(expr.eval(5.0, &attrs), 17.0);
+        // a missing attribute counts as 0
+        assert_eq!(expr.eval(5.0, &Attributes::new()), 15.0);
+
+        let expr = CostExpr::parse("-(latency_ms - 8) / 2 * 3").unwrap();
+        assert_eq!(expr.eval(4.0, &Attributes::new()), 6.0);
+        attrs.insert("metered".to_string(), json!(true));
+        assert_eq!(CostExpr::parse("metered").unwrap().eval(0.0, &attrs), 1.0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in ["", "latency_ms +", "(1 + 2", "1 2", "2 ** 3", "1.2.3", "a$"] {
+            assert!(CostExpr::parse(bad).is_err(), "{}", bad);
+        }
+        let err = CostExpr::parse("latency_ms # 2").unwrap_err();
+        assert_eq!(err.to_string(), "unexpected '#' at position 12");
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut input: GraphInput = serde_json::from_str(
+            r#"{"nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 4, "cost_usd": 100},
+                {"from": "b", "to": "c", "latency_ms": 2,
+                 "timeline": [{"at": "2024-06-01T00:00Z", "latency_ms": 6}]}
+            ]}"#,
+        )
+        .unwrap();
+        CostExpr::parse("latency_ms + 0.1*cost_usd")
+            .unwrap()
+            .apply(&mut input)
+            .unwrap();
+        assert_eq!(input.edges[0].latency_ms, Some(14.0));
+        assert_eq!(input.edges[1].timeline[0].latency_ms, 6.0);
+
+        let unknown = CostExpr::parse("latency_ms + cost_eur").unwrap();
+        assert!(unknown.apply(&mut input).is_err());
+        let negative = CostExpr::parse("latency_ms - 10").unwrap();
+        assert!(negative.apply(&mut input).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Context;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// Bumped whenever the layout of a cached type changes, so entries written
+/// by an older build are ignored instead of misread.
+const FORMAT_VERSION: u32 = 2;
+
+/// Directory of preprocessed data (parsed graphs, landmark tables) kept
+/// across runs.
+///
+/// Entries are MessagePack files named after a kind and a caller-supplied
+/// key, such as the SHA-256 of the input file, so a changed input simply
+/// misses. Nothing is ever evicted; delete the directory to clear it.
+///
+/// # Example
+///
+/// ```no_run
+/// use gt_path_core::{DiskCache, Graph};
+///
+/// let cache = DiskCache::new(".gt-cache");
+/// let key = DiskCache::key(&std::fs::read("graph.json")?);
+/// let graph = match cache.get::<Graph>("graph", &key) {
+///     Some(graph) => graph,
+///     None => {
+///         let graph = Graph::load_json("graph.json")?;
+///         cache.put("graph", &key, &graph)?;
+///         graph
+///     }
+/// };
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Uses `dir` for cache entries; it is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> DiskCache {
+        DiskCache { dir: dir.into() }
+    }
+
+    /// SHA-256 of `data` as hex, for keying entries by content.
+    pub fn key(data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Reads the `kind` entry for `key`. Returns None if it is missing,
+    /// or unreadable, such as one left by an interrupted write.
+    pub fn get<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Option<T> {
+        let data = std::fs::read(self.entry(kind, key)).ok()?;
+        rmp_serde::from_slice(&data).ok()
+    }
+
+    /// Writes `value` as the `kind` entry for `key`. The entry is written to
+    /// a temporary file and renamed into place, so concurrent runs never
+    /// see a partial entry.
+    pub fn put<T: Serialize>(&self, kind: &str, key: &str, value: &T) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.dir).context(format!(
+            "Failed to create cache directory: {}",
+            self.dir.display()
+        ))?;
+        let data = rmp_serde::to_vec(value).context("Failed to serialize cache entry")?;
+
+        let path = self.entry(kind, key);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, data)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .context(format!("Failed to write cache entry: {}", path.display()))
+    }
+
+    fn entry(&self, kind: &str, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}-v{}-{}.msgpack", kind, FORMAT_VERSION, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constraints;
+    use crate::graph::Graph;
+    use crate::oracle::DistanceOracle;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("gt-path-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_graph_round_trip() {
+        let dir = temp_dir("cache-graph");
+        let cache = DiskCache::new(&dir);
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+
+        assert!(cache.get::<Graph>("graph", "abc").is_none());
+        cache.put("graph", "abc", &graph).unwrap();
+        let cached: Graph = cache.get("graph", "abc").unwrap();
+
+        assert_eq!(cached.content_hash(), graph.content_hash());
+        assert_eq!(
+            cached.shortest_path("api", "db").unwrap().cost,
+            graph.shortest_path("api", "db").unwrap().cost
+        );
+        assert!(cache.get::<Graph>("graph", "other").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_oracle_round_trip() {
+        let dir = temp_dir("cache-oracle");
+        let cache = DiskCache::new(&dir);
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let oracle = DistanceOracle::build(&graph, 2, &Constraints::default());
+
+        cache.put("oracle", "k", &oracle).unwrap();
+        let cached: DistanceOracle = cache.get("oracle", "k").unwrap();
+        assert_eq!(cached.landmarks, oracle.landmarks);
+        let (api, db) = (graph.to_id["api"], graph.to_id["db"]);
+        assert_eq!(cached.bounds(api, db), oracle.bounds(api, db));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_entry_misses() {
+        let dir = temp_dir("cache-corrupt");
+        let cache = DiskCache::new(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(cache.entry("graph", "bad"), b"not msgpack").unwrap();
+        assert!(cache.get::<Graph>("graph", "bad").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+/// Errors that can occur when building a graph from input.
+#[derive(thiserror::Error, Debug)]
+pub enum GraphBuildError {
+    /// A node name appears more than once in the node list
+    #[error("duplicate node name: {0}")]
+    DuplicateNode(String),
+    /// An edge references a non-existent source node
+    #[error("unknown node in edge 'from': {0}")]
+    UnknownFrom(String),
+    /// An edge references a non-existent destination node
+    #[error("unknown node in edge 'to': {0}")]
+    UnknownTo(String),
+    /// An edge has a negative latency value
+    #[error("negative latency on edge {from}->{to}: {latency_ms}")]
+    NegativeLatency {
+        from: String,
+        to: String,
+        latency_ms: f32,
+    },
+    /// An edge has no latency and the graph no default latency
+    #[error("missing latency on edge {from}->{to} (and no default_latency_ms)")]
+    MissingLatency { from: String, to: String },
+    /// The input lists files to include that haven't been merged in
+    #[error("graph includes other files, which must be read with compose::read")]
+    UnresolvedIncludes,
+    /// A self-loop was detected (node pointing to itself)
+    #[error("self loop detected on node {node}")]
+    SelfLoop { node: String },
+    /// Two edges declare the same `id`
+    #[error("duplicate edge id: {0}")]
+    DuplicateEdgeId(String),
+    /// An edge timeline entry has an unparseable timestamp
+    #[error("invalid timeline on edge {from}->{to}: {reason}")]
+    InvalidTimeline {
+        from: String,
+        to: String,
+        reason: String,
+    },
+    /// A node or edge declares an availability outside [0, 1]
+    #[error("invalid availability on {element}: {value} (expected a number between 0 and 1)")]
+    InvalidAvailability { element: String, value: String },
+}
+
+/// Errors that can occur when finding a path through the graph.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum PathError {
+    /// The specified node does not exist in the graph
+    #[error("node not found: {0}")]
+    NodeNotFound(String),
+    /// No path exists between the source and destination nodes
+    #[error("path not found {from}->{to}")]
+    PathNotFound { from: String, to: String },
+    /// A modification names an edge the graph doesn't have
+    #[error("edge not found {from}->{to}")]
+    EdgeNotFound { from: String, to: String },
+    /// A modification names an edge ID the graph doesn't have
+    #[error("edge not found: {0}")]
+    EdgeIdNotFound(String),
+}
+
+/// Errors that can occur when running a what-if simulation.
+#[derive(thiserror::Error, Debug)]
+pub enum SimulationError {
+    /// No path exists in the original graph
+    #[error("Failed to find path from {from} to {to}")]
+    OriginalPath {
+        from: String,
+        to: String,
+        #[source]
+        source: PathError,
+    },
+    /// A modification references a non-existent node or edge
+    #[error("Failed to apply modifications to graph")]
+    Modification(#[source] PathError),
+    /// No path exists once the modifications are applied
+    #[error("Failed to find path from {from} to {to} in modified graph")]
+    ModifiedPath {
+        from: String,
+        to: String,
+        #[source]
+        source: PathError,
+    },
+}
@@ -0,0 +1,1446 @@
+use crate::availability;
+use crate::constraint::Constraints;
+use crate::error::{GraphBuildError, PathError};
+use crate::io::{Attributes, EdgeInput, GraphInput, LatencySample, NodeInput, edge_id};
+use crate::path::{Edge, Path};
+use crate::stats::SearchStats;
+use crate::time::{format_timestamp, parse_timestamp};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+
+/// Internal node identifier
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize, Deserialize)]
+pub struct NodeId(pub u32);
+
+/// An outgoing edge in the adjacency list.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Link {
+    /// Destination node
+    pub to: NodeId,
+    /// Edge latency in milliseconds
+    pub latency_ms: u32,
+    /// Index of the edge in the graph's edge attribute table
+    pub edge: usize,
+}
+
+/// Outgoing links of one node. Most nodes have a handful of links, so up to
+/// four are stored inline and only busier nodes allocate, which keeps
+/// Dijkstra's neighbor scans cache-friendly on large graphs.
+pub type Links = SmallVec<[Link; 4]>;
+
+/// A directed weighted graph optimized for shortest path queries.
+/// The graph stores nodes as string names with integer-based internal
+/// representation. Edges are stored in adjacency lists with latency weights
+/// in milliseconds (as u32), and each edge keeps an index into the edge
+/// attribute table.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Graph {
+    /// Maps NodeId to node name. Names are interned: this table, `to_id`,
+    /// and every output built from the graph share one allocation per name.
+    pub to_name: Vec<Arc<str>>,
+    /// Maps node name to NodeId
+    pub to_id: HashMap<Arc<str>, NodeId>,
+    /// Attributes declared on each node, indexed by NodeId
+    pub node_attrs: Vec<Attributes>,
+    /// Adjacency list: for each node, stores its outgoing links
+    pub adj: Vec<Links>,
+    /// Stable ID of each edge, declared or derived, indexed by `Link::edge`
+    pub edge_ids: Vec<Arc<str>>,
+    /// Attributes declared on each edge, indexed by `Link::edge`
+    pub edge_attrs: Vec<Attributes>,
+    /// Latency timeline of each edge as (unix seconds, latency_ms), sorted by time
+    pub edge_timelines: Vec<Vec<(i64, u32)>>,
+    /// Timestamp the latencies were resolved at, if this is a point-in-time view
+    pub at: Option<i64>,
+    /// Lazily computed `content_hash`; reset whenever the graph is modified
+    #[serde(skip)]
+    hash: OnceLock<String>,
+}
+
+impl Graph {
+    /// Loads a graph from a JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the JSON file containing graph data
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Graph)` - Successfully loaded and validated graph
+    /// * `Err` - If file cannot be read, JSON is invalid, or graph validation fails
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let graph = Graph::load_json("graph.json")?;
+    /// ```
+    pub fn load_json(path: &str) -> anyhow::Result<Graph> {
+        use anyhow::Context;
+
+        let input = crate::compose::read(std::path::Path::new(path))?;
+
+        let graph = Graph::try_from(input).context("Failed to build graph from input")?;
+
+        Ok(graph)
+    }
+
+    /// Finds the shortest path between two nodes using Dijkstra's algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Source node name
+    /// * `to` - Destination node name
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Path)` - The shortest path with cost and node sequence
+    /// * `Err(PathError::NodeNotFound)` - If either node doesn't exist
+    /// * `Err(PathError::PathNotFound)` - If no path exists between the nodes
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let path = graph.shortest_path("api", "db")?;
+    /// println!("Cost: {}, Path: {:?}", path.cost, path.path);
+    /// ```
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Path, PathError> {
+        self.shortest_path_with(from, to, &Constraints::default())
+    }
+
+    /// Finds the shortest path between two nodes that satisfies the constraints.
+    ///
+    /// Nodes rejected by the constraints are never visited; if the source or
+    /// destination itself is rejected, no path exists.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Path)` - The shortest path with cost and node sequence
+    /// * `Err(PathError::NodeNotFound)` - If either node doesn't exist
+    /// * `Err(PathError::PathNotFound)` - If no allowed path exists between the nodes
+    pub fn shortest_path_with(
+        &self,
+        from: &str,
+        to: &str,
+        constraints: &Constraints,
+    ) -> Result<Path, PathError> {
+        self.shortest_path_without(from, to, constraints, &[])
+    }
+
+    /// Like `shortest_path_with`, but also skips every edge whose entry in
+    /// `down` (indexed by `Link::edge`) is true. Edges past the end of
+    /// `down` are usable, so an empty slice excludes nothing.
+    ///
+    /// Cheaper than `with_modifications` for evaluating many failure
+    /// scenarios, since the graph isn't copied.
+    pub fn shortest_path_without(
+        &self,
+        from: &str,
+        to: &str,
+        constraints: &Constraints,
+        down: &[bool],
+    ) -> Result<Path, PathError> {
+        let from_id = self
+            .to_id
+            .get(from)
+            .ok_or_else(|| PathError::NodeNotFound(from.to_string()))?;
+        let to_id = self
+            .to_id
+            .get(to)
+            .ok_or_else(|| PathError::NodeNotFound(to.to_string()))?;
+
+        let n = self.to_name.len();
+        let allowed: Vec<bool> = self
+            .node_attrs
+            .iter()
+            .map(|attrs| constraints.allows_node(attrs))
+            .collect();
+        if !allowed[from_id.0 as usize] || !allowed[to_id.0 as usize] {
+            return Err(PathError::PathNotFound {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+
+        let mut distances = vec![u32::MAX; n];
+        let mut parents: Vec<Option<Edge>> = vec![None; n];
+        distances[from_id.0 as usize] = 0;
+
+        let mut h = BinaryHeap::new();
+        h.push(Reverse(State {
+            cost: 0,
+            node: *from_id,
+        }));
+        let mut stats = SearchStats {
+            heap_pushes: 1,
+            ..SearchStats::default()
+        };
+
+        while let Some(Reverse(State { cost, node })) = h.pop() {
+            if node == *to_id {
+                stats.nodes_visited += 1;
+                stats.record();
+                let (path, edges) = self.path(*to_id, &parents);
+                let cost = distances[node.0 as usize];
+                let bottleneck = self.bottleneck(&edges);
+
+                return Ok(Path {
+                    from: *from_id,
+                    to: *to_id,
+                    path,
+                    edges,
+                    cost,
+                    bottleneck,
+                });
+            }
+
+            if cost > distances[node.0 as usize] {
+                continue;
+            }
+            stats.nodes_visited += 1;
+
+            for link in &self.adj[node.0 as usize] {
+                stats.edges_relaxed += 1;
+                let neighbor = link.to;
+                if !allowed[neighbor.0 as usize]
+                    || down.get(link.edge).copied().unwrap_or(false)
+                    || !constraints
+                        .allows_edge(&self.edge_ids[link.edge], &self.edge_attrs[link.edge])
+                {
+                    continue;
+                }
+
+                let new_cost = cost + link.latency_ms;
+
+                if new_cost < distances[neighbor.0 as usize] {
+                    distances[neighbor.0 as usize] = new_cost;
+                    parents[neighbor.0 as usize] = Some(Edge {
+                        from: node,
+                        to: neighbor,
+                        latency_ms: link.latency_ms,
+                        index: link.edge,
+                    });
+
+                    h.push(Reverse(State {
+                        cost: new_cost,
+                        node: neighbor,
+                    }));
+                    stats.heap_pushes += 1;
+                }
+            }
+        }
+
+        stats.record();
+        Err(PathError::PathNotFound {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+
+    /// Reconstructs the path from source to destination by walking backwards through parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The destination NodeId
+    /// * `parents` - Edge used to reach each node, from Dijkstra's algorithm
+    ///
+    /// # Returns
+    ///
+    /// The NodeIds and the edges traversed, in order from source to destination
+    pub(crate) fn path(&self, start: NodeId, parents: &[Option<Edge>]) -> (Vec<NodeId>, Vec<Edge>) {
+        let mut path = vec![start];
+        let mut edges = Vec::new();
+
+        while let Some(e) = &parents[path[path.len() - 1].0 as usize] {
+            path.push(e.from);
+            edges.push(e.clone());
+        }
+
+        path.reverse();
+        edges.reverse();
+
+        (path, edges)
+    }
+
+    /// Identifies the bottleneck edge (highest latency) on a given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - Edges traversed by a path through the graph
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Edge)` - The edge with maximum latency on the path
+    /// * `None` - If the path has no edges with non-zero latency
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // For path api → auth → db with edges (5ms, 3ms)
+    /// // Returns Edge { from: "api", to: "auth", latency_ms: 5 }
+    /// ```
+    pub(crate) fn bottleneck(&self, edges: &[Edge]) -> Option<Edge> {
+        let mut max: u32 = 0;
+        let mut e = None;
+
+        for edge in edges {
+            if edge.latency_ms > max {
+                max = edge.latency_ms;
+                e = Some(edge.clone());
+            }
+        }
+
+        e
+    }
+
+    /// Resolves time-varying latencies at a point in time.
+    /// Returns a new Graph where every edge with a timeline uses its latest
+    /// sample at or before `at`. Edges without a timeline, or whose timeline
+    /// starts after `at`, keep their base latency.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - Seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let peak = graph.at(time::parse_timestamp("2024-06-01T14:00Z")?);
+    /// let path = peak.shortest_path("api", "db")?;
+    /// ```
+    pub fn at(&self, at: i64) -> Graph {
+        let mut resolved = self.clone();
+        resolved.at = Some(at);
+        resolved.hash = OnceLock::new();
+
+        for links in resolved.adj.iter_mut() {
+            for link in links.iter_mut() {
+                let timeline = &self.edge_timelines[link.edge];
+                let i = timeline.partition_point(|(t, _)| *t <= at);
+                if i > 0 {
+                    link.latency_ms = timeline[i - 1].1;
+                }
+            }
+        }
+
+        resolved
+    }
+
+    /// Forgets the cached `content_hash` after an in-place change.
+    pub(crate) fn invalidate_hash(&mut self) {
+        self.hash = OnceLock::new();
+    }
+
+    /// Returns a stable SHA-256 hex digest of the graph's content.
+    ///
+    /// Covers node names and attributes, and every edge's endpoints,
+    /// latency, attributes, and timeline. Declaration order in the input
+    /// file doesn't matter, so two files describing the same topology hash
+    /// the same. Latencies are hashed as the engine sees them (whole
+    /// milliseconds, resolved at `at` for point-in-time views).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let a = Graph::load_json("graph.json")?;
+    /// let b = Graph::load_json("graph-copy.json")?;
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> &str {
+        self.hash.get_or_init(|| {
+            use sha2::{Digest, Sha256};
+
+            let mut nodes: Vec<(&str, &Attributes)> = self
+                .to_name
+                .iter()
+                .zip(&self.node_attrs)
+                .map(|(name, attrs)| (&**name, attrs))
+                .collect();
+            nodes.sort_by(|a, b| a.0.cmp(b.0));
+
+            let mut edges = Vec::new();
+            for (from, links) in self.adj.iter().enumerate() {
+                for link in links {
+                    edges.push((
+                        &self.to_name[from],
+                        &self.to_name[link.to.0 as usize],
+                        link.latency_ms,
+                        serde_json::to_string(&self.edge_attrs[link.edge]).unwrap_or_default(),
+                        &self.edge_timelines[link.edge],
+                    ));
+                }
+            }
+            edges.sort();
+
+            let canonical = serde_json::to_vec(&(nodes, edges)).unwrap_or_default();
+            Sha256::digest(canonical)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect()
+        })
+    }
+
+    /// Formats a path as a human-readable string with arrow separators.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to format
+    ///
+    /// # Returns
+    ///
+    /// A string like "api → auth → db"
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let path = graph.shortest_path("api", "db")?;
+    /// println!("{}", graph.format_path(&path));
+    /// // Output: "api → auth → db"
+    /// ```
+    pub fn format_path(&self, path: &Path) -> String {
+        path.path
+            .iter()
+            .map(|node_id| &*self.to_name[node_id.0 as usize])
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    /// Apply modifications to create a simulation graph.
+    /// Returns a new Graph with modified/dropped edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - Edges to modify with new weights: (from, to, new_weight)
+    /// * `drop` - Edges to remove: (from, to)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Graph)` - Modified graph with changes applied
+    /// * `Err(PathError::NodeNotFound)` - If any node in overrides/drops doesn't exist
+    /// * `Err(PathError::EdgeNotFound)` - If an override names an edge the
+    ///   graph doesn't have (after drops are applied)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let modified = graph.with_modifications(
+    ///     &[("auth".to_string(), "db".to_string(), 200)],
+    ///     &[("api".to_string(), "cache".to_string())]
+    /// )?;
+    /// ```
+    pub fn with_modifications(
+        &self,
+        overrides: &[(String, String, u32)],
+        drop: &[(String, String)],
+    ) -> Result<Graph, PathError> {
+        self.modified(overrides, drop, false)
+    }
+
+    /// Like `with_modifications`, but an override naming an edge the graph
+    /// doesn't have adds that edge, with no attributes, instead of failing.
+    pub fn with_modifications_creating(
+        &self,
+        overrides: &[(String, String, u32)],
+        drop: &[(String, String)],
+    ) -> Result<Graph, PathError> {
+        self.modified(overrides, drop, true)
+    }
+
+    fn modified(
+        &self,
+        overrides: &[(String, String, u32)],
+        drop: &[(String, String)],
+        create_missing: bool,
+    ) -> Result<Graph, PathError> {
+        let mut modified = self.clone();
+        modified.hash = OnceLock::new();
+
+        // apply drops
+        for (from_name, to_name) in drop {
+            let from_id = self
+                .to_id
+                .get(from_name.as_str())
+                .ok_or_else(|| PathError::NodeNotFound(from_name.clone()))?;
+            let to_id = self
+                .to_id
+                .get(to_name.as_str())
+                .ok_or_else(|| PathError::NodeNotFound(to_name.clone()))?;
+
+            modified.adj[from_id.0 as usize].retain(|link| link.to != *to_id);
+        }
+
+        // apply weight overrides
+        for (from_name, to_name, new_weight) in overrides {
+            let from_id = self
+                .to_id
+                .get(from_name.as_str())
+                .ok_or_else(|| PathError::NodeNotFound(from_name.clone()))?;
+            let to_id = self
+                .to_id
+                .get(to_name.as_str())
+                .ok_or_else(|| PathError::NodeNotFound(to_name.clone()))?;
+
+            let adj_list = &mut modified.adj[from_id.0 as usize];
+            if let Some(link) = adj_list.iter_mut().find(|link| link.to == *to_id) {
+                link.latency_ms = *new_weight;
+            } else if create_missing {
+                adj_list.push(Link {
+                    to: *to_id,
+                    latency_ms: *new_weight,
+                    edge: modified.edge_attrs.len(),
+                });
+                let id = (0..)
+                    .map(|ordinal| edge_id(from_name, to_name, ordinal))
+                    .find(|id| !modified.edge_ids.iter().any(|e| **e == **id))
+                    .expect("some ordinal is free");
+                modified.edge_ids.push(id.into());
+                modified.edge_attrs.push(Attributes::default());
+                modified.edge_timelines.push(Vec::new());
+            } else {
+                return Err(PathError::EdgeNotFound {
+                    from: from_name.clone(),
+                    to: to_name.clone(),
+                });
+            }
+        }
+
+        Ok(modified)
+    }
+
+    /// Returns a copy of the graph with edges dropped and re-weighted by
+    /// ID: `drops` first, then `overrides` as (ID, new latency in ms).
+    ///
+    /// # Errors
+    ///
+    /// * `PathError::EdgeIdNotFound` - an ID the graph doesn't have, or an
+    ///   override of an edge that was dropped
+    pub fn with_edge_modifications(
+        &self,
+        overrides: &[(String, u32)],
+        drops: &[String],
+    ) -> Result<Graph, PathError> {
+        let mut modified = self.clone();
+        modified.hash = OnceLock::new();
+
+        for id in drops {
+            let edge = self
+                .edge_index(id)
+                .ok_or_else(|| PathError::EdgeIdNotFound(id.clone()))?;
+            if let Some((u, i)) = modified.find_link(edge) {
+                modified.adj[u].remove(i);
+            }
+        }
+
+        for (id, new_weight) in overrides {
+            let (u, i) = self
+                .edge_index(id)
+                .and_then(|edge| modified.find_link(edge))
+                .ok_or_else(|| PathError::EdgeIdNotFound(id.clone()))?;
+            modified.adj[u][i].latency_ms = *new_weight;
+        }
+
+        Ok(modified)
+    }
+
+    /// Index of the edge with this ID in the edge tables, if any.
+    pub fn edge_index(&self, id: &str) -> Option<usize> {
+        self.edge_ids.iter().position(|e| **e == *id)
+    }
+
+    /// Where the edge at `edge` in the edge tables sits in the adjacency
+    /// list, as (source node, position among its links). None if the edge
+    /// was dropped.
+    pub fn find_link(&self, edge: usize) -> Option<(usize, usize)> {
+        self.adj.iter().enumerate().find_map(|(u, links)| {
+            links
+                .iter()
+                .position(|link| link.edge == edge)
+                .map(|i| (u, i))
+        })
+    }
+
+    /// Checks for input that builds a valid graph but is likely a mistake:
+    ///
+    /// * `isolated_nodes` - nodes with no edges in or out, reported together
+    /// * `duplicate_edge` - the same `from → to` declared more than once;
+    ///   routing uses the fastest, and overrides only change the first
+    /// * `invalid_utilization` - an edge utilization outside [0, 1), which
+    ///   `simulate --demand` can't model
+    pub fn lint(&self) -> Vec<crate::io::WarningOutput> {
+        use crate::io::WarningOutput;
+
+        let mut warnings = Vec::new();
+
+        let mut linked = vec![false; self.to_name.len()];
+        for (from, links) in self.adj.iter().enumerate() {
+            for link in links {
+                linked[from] = true;
+                linked[link.to.0 as usize] = true;
+            }
+        }
+        let isolated: Vec<&str> = linked
+            .iter()
+            .zip(&self.to_name)
+            .filter(|(linked, _)| !**linked)
+            .map(|(_, name)| &**name)
+            .collect();
+        if !isolated.is_empty() {
+            warnings.push(WarningOutput::new(
+                "isolated_nodes",
+                format!(
+                    "{} node(s) have no edges: {}",
+                    isolated.len(),
+                    isolated.join(", ")
+                ),
+            ));
+        }
+
+        for (from, links) in self.adj.iter().enumerate() {
+            let mut seen: Vec<(NodeId, usize)> = Vec::new();
+            for link in links {
+                match seen.iter_mut().find(|(to, _)| *to == link.to) {
+                    Some((_, count)) => *count += 1,
+                    None => seen.push((link.to, 1)),
+                }
+            }
+            for (to, count) in seen.into_iter().filter(|(_, count)| *count > 1) {
+                warnings.push(WarningOutput::new(
+                    "duplicate_edge",
+                    format!(
+                        "edge {} → {} is declared {} times",
+                        self.to_name[from], self.to_name[to.0 as usize], count
+                    ),
+                ));
+            }
+        }
+
+        for (from, links) in self.adj.iter().enumerate() {
+            for link in links {
+                let utilization = crate::simulation::utilization(&self.edge_attrs[link.edge]);
+                if let Some(u) = utilization.filter(|u| !(0.0..1.0).contains(u)) {
+                    warnings.push(WarningOutput::new(
+                        "invalid_utilization",
+                        format!(
+                            "edge {} → {} has utilization {}, outside [0, 1); \
+                             changes in demand won't affect it",
+                            self.to_name[from], self.to_name[link.to.0 as usize], u
+                        ),
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Converts an internal Path to PathOutput with human-readable node names.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to convert
+    ///
+    /// # Returns
+    ///
+    /// PathOutput with node names instead of NodeIds, suitable for JSON serialization
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let path = graph.shortest_path("api", "db")?;
+    /// let output = graph.path_output(&path);
+    /// println!("{}", serde_json::to_string_pretty(&output)?);
+    /// ```
+    pub fn path_output(&self, path: &Path) -> crate::io::PathOutput {
+        use crate::io::PathOutput;
+
+        PathOutput {
+            from: self.to_name[path.from.0 as usize].clone(),
+            to: self.to_name[path.to.0 as usize].clone(),
+            path: path
+                .path
+                .iter()
+                .map(|id| self.to_name[id.0 as usize].clone())
+                .collect(),
+            total_latency_ms: path.cost,
+            edge_ids: path
+                .edges
+                .iter()
+                .map(|e| self.edge_ids[e.index].clone())
+                .collect(),
+            bottleneck: path.bottleneck.as_ref().map(|b| self.edge_output(b)),
+            node_attributes: path
+                .path
+                .iter()
+                .filter(|id| !self.node_attrs[id.0 as usize].is_empty())
+                .map(|id| {
+                    (
+                        self.to_name[id.0 as usize].clone(),
+                        self.node_attrs[id.0 as usize].clone(),
+                    )
+                })
+                .collect(),
+            edge_attributes: path
+                .edges
+                .iter()
+                .filter(|e| !self.edge_attrs[e.index].is_empty())
+                .map(|e| self.edge_output(e))
+                .collect(),
+            at: self.at.map(format_timestamp),
+            graph_hash: self.content_hash().to_string(),
+        }
+    }
+
+    /// Converts an internal Edge to EdgeOutput with human-readable node names.
+    fn edge_output(&self, edge: &Edge) -> crate::io::EdgeOutput {
+        crate::io::EdgeOutput {
+            id: self.edge_ids[edge.index].clone(),
+            from: self.to_name[edge.from.0 as usize].clone(),
+            to: self.to_name[edge.to.0 as usize].clone(),
+            latency_ms: edge.latency_ms,
+            attributes: self.edge_attrs[edge.index].clone(),
+        }
+    }
+}
+
+impl TryFrom<GraphInput> for Graph {
+    type Error = GraphBuildError;
+    fn try_from(src: GraphInput) -> Result<Self, Self::Error> {
+        if !src.includes.is_empty() {
+            return Err(GraphBuildError::UnresolvedIncludes);
+        }
+        let src = src.normalize();
+        let mut to_name: Vec<Arc<str>> = Vec::new();
+        let mut to_id: HashMap<Arc<str>, NodeId> = HashMap::new();
+        let mut node_attrs: Vec<Attributes> = Vec::new();
+
+        for node in src.nodes.into_iter() {
+            let (n, attrs) = node.into_parts();
+            if to_id.contains_key(n.as_str()) {
+                return Err(GraphBuildError::DuplicateNode(n));
+            }
+            availability::validate(&attrs).map_err(|value| {
+                GraphBuildError::InvalidAvailability {
+                    element: format!("node {}", n),
+                    value,
+                }
+            })?;
+
+            let name: Arc<str> = n.into();
+            to_id.insert(name.clone(), NodeId(to_name.len() as u32));
+            to_name.push(name);
+            node_attrs.push(attrs);
+        }
+
+        let mut adj: Vec<Links> = vec![Links::new(); to_name.len()];
+        let mut edge_ids: Vec<Arc<str>> = Vec::new();
+        let mut seen_ids: HashSet<Arc<str>> = HashSet::new();
+        let mut ordinals: HashMap<(String, String), usize> = HashMap::new();
+        let mut edge_attrs: Vec<Attributes> = Vec::new();
+        let mut edge_timelines: Vec<Vec<(i64, u32)>> = Vec::new();
+        for edge in src.edges.into_iter() {
+            if !to_id.contains_key(edge.from.as_str()) {
+                return Err(GraphBuildError::UnknownFrom(edge.from));
+            }
+
+            if !to_id.contains_key(edge.to.as_str()) {
+                return Err(GraphBuildError::UnknownTo(edge.to));
+            }
+
+            let Some(latency_ms) = edge.latency_ms else {
+                return Err(GraphBuildError::MissingLatency {
+                    from: edge.from,
+                    to: edge.to,
+                });
+            };
+            if latency_ms < 0.0 {
+                return Err(GraphBuildError::NegativeLatency {
+                    from: edge.from,
+                    to: edge.to,
+                    latency_ms,
+                });
+            }
+
+            if edge.from == edge.to {
+                return Err(GraphBuildError::SelfLoop { node: edge.from });
+            }
+
+            availability::validate(&edge.attrs).map_err(|value| {
+                GraphBuildError::InvalidAvailability {
+                    element: format!("edge {}->{}", edge.from, edge.to),
+                    value,
+                }
+            })?;
+
+            let mut timeline = Vec::with_capacity(edge.timeline.len());
+            for sample in &edge.timeline {
+                let invalid = |reason: String| GraphBuildError::InvalidTimeline {
+                    from: edge.from.clone(),
+                    to: edge.to.clone(),
+                    reason,
+                };
+
+                let at = parse_timestamp(&sample.at).map_err(invalid)?;
+                if sample.latency_ms < 0.0 {
+                    return Err(invalid(format!(
+                        "negative latency {} at {}",
+                        sample.latency_ms, sample.at
+                    )));
+                }
+                timeline.push((at, sample.latency_ms as u32));
+            }
+            timeline.sort_by_key(|(at, _)| *at);
+
+            let ordinal = ordinals
+                .entry((edge.from.clone(), edge.to.clone()))
+                .or_default();
+            let id: Arc<str> = match edge.id {
+                Some(id) => id.into(),
+                None => edge_id(&edge.from, &edge.to, *ordinal).into(),
+            };
+            *ordinal += 1;
+            if !seen_ids.insert(id.clone()) {
+                return Err(GraphBuildError::DuplicateEdgeId(id.to_string()));
+            }
+
+            let from = to_id
+                .get(edge.from.as_str())
+                .expect("from node must exist: validated above");
+            let to = to_id
+                .get(edge.to.as_str())
+                .expect("to node must exist: validated above");
+
+            adj[from.0 as usize].push(Link {
+                to: *to,
+                latency_ms: latency_ms as u32,
+                edge: edge_attrs.len(),
+            });
+            edge_ids.push(id);
+            edge_attrs.push(edge.attrs);
+            edge_timelines.push(timeline);
+        }
+
+        Ok(Graph {
+            adj,
+            to_name,
+            to_id,
+            node_attrs,
+            edge_ids,
+            edge_attrs,
+            edge_timelines,
+            at: None,
+            hash: OnceLock::new(),
+        })
+    }
+}
+
+impl From<&Graph> for GraphInput {
+    /// Converts a graph back into the JSON input schema.
+    ///
+    /// Latencies are written as the whole milliseconds stored in the graph,
+    /// so fractional input latencies are not preserved. Edge IDs are
+    /// written only where they differ from the derived ones.
+    fn from(graph: &Graph) -> Self {
+        let mut ordinals: HashMap<(usize, u32), usize> = HashMap::new();
+        let edges = graph
+            .adj
+            .iter()
+            .enumerate()
+            .flat_map(|(from, neighbors)| neighbors.iter().map(move |link| (from, link)))
+            .map(|(from, link)| {
+                let (from_name, to_name) =
+                    (&graph.to_name[from], &graph.to_name[link.to.0 as usize]);
+                let ordinal = ordinals.entry((from, link.to.0)).or_default();
+                let derived = edge_id(from_name, to_name, *ordinal);
+                *ordinal += 1;
+                let id = &graph.edge_ids[link.edge];
+                EdgeInput {
+                    from: from_name.to_string(),
+                    to: to_name.to_string(),
+                    id: (**id != *derived).then(|| id.to_string()),
+                    latency_ms: Some(link.latency_ms as f32),
+                    timeline: graph.edge_timelines[link.edge]
+                        .iter()
+                        .map(|(at, latency_ms)| LatencySample {
+                            at: format_timestamp(*at),
+                            latency_ms: *latency_ms as f32,
+                        })
+                        .collect(),
+                    bidirectional: false,
+                    attrs: graph.edge_attrs[link.edge].clone(),
+                }
+            })
+            .collect();
+
+        let nodes = graph
+            .to_name
+            .iter()
+            .zip(graph.node_attrs.iter())
+            .map(|(name, attrs)| NodeInput::new(name.to_string(), attrs.clone()))
+            .collect();
+
+        GraphInput::new(nodes, edges)
+    }
+}
+
+/// Priority queue state for Dijkstra's algorithm.
+///
+/// Wraps a node and its current best known distance from the source.
+/// Used with `Reverse` to create a min-heap from BinaryHeap's max-heap.
+#[derive(PartialEq, Eq, Debug)]
+struct State {
+    node: NodeId,
+    cost: u32,
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost.cmp(&other.cost)
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::AttrFilter;
+
+    fn edge(from: &str, to: &str, latency_ms: f32) -> EdgeInput {
+        EdgeInput {
+            from: from.to_string(),
+            to: to.to_string(),
+            id: None,
+            latency_ms: Some(latency_ms),
+            timeline: Vec::new(),
+            bidirectional: false,
+            attrs: Attributes::new(),
+        }
+    }
+
+    fn create_test_graph() -> Graph {
+        let input = GraphInput::new(
+            vec!["api".into(), "auth".into(), "db".into()],
+            vec![edge("api", "auth", 5.2), edge("auth", "db", 3.1)],
+        );
+        Graph::try_from(input).unwrap()
+    }
+
+    #[test]
+    fn test_shortest_path_simple() {
+        let graph = create_test_graph();
+        let path = graph.shortest_path("api", "db").unwrap();
+
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.path.len(), 3);
+        assert_eq!(graph.format_path(&path), "api → auth → db");
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(create_test_graph().lint().is_empty());
+
+        let input = GraphInput::new(
+            vec!["api".into(), "db".into(), "orphan".into()],
+            vec![edge("api", "db", 5.0), edge("api", "db", 7.0)],
+        );
+        let warnings = Graph::try_from(input).unwrap().lint();
+        let kinds: Vec<&str> = warnings.iter().map(|w| w.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["isolated_nodes", "duplicate_edge"]);
+        assert_eq!(warnings[0].message, "1 node(s) have no edges: orphan");
+        assert_eq!(warnings[1].message, "edge api → db is declared 2 times");
+    }
+
+    #[test]
+    fn test_node_not_found() {
+        let graph = create_test_graph();
+        let result = graph.shortest_path("api", "nonexistent");
+
+        assert!(result.is_err());
+        match result {
+            Err(PathError::NodeNotFound(node)) => {
+                assert_eq!(node, "nonexistent");
+            }
+            _ => panic!("Expected NodeNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_path_not_found() {
+        let input = GraphInput::new(vec!["a".into(), "b".into()], vec![]);
+        let graph = Graph::try_from(input).unwrap();
+
+        let result = graph.shortest_path("a", "b");
+        assert!(result.is_err());
+        match result {
+            Err(PathError::PathNotFound { from, to }) => {
+                assert_eq!(from, "a");
+                assert_eq!(to, "b");
+            }
+            _ => panic!("Expected PathNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_bottleneck_identification() {
+        let graph = create_test_graph();
+        let path = graph.shortest_path("api", "db").unwrap();
+
+        assert!(path.bottleneck.is_some());
+        let bottleneck = path.bottleneck.unwrap();
+
+        let from_name = &*graph.to_name[bottleneck.from.0 as usize];
+        let to_name = &*graph.to_name[bottleneck.to.0 as usize];
+
+        assert_eq!(from_name, "api");
+        assert_eq!(to_name, "auth");
+        assert_eq!(bottleneck.latency_ms, 5);
+    }
+
+    #[test]
+    fn test_bottleneck_with_larger_graph() {
+        let input = GraphInput::new(
+            vec!["a".into(), "b".into(), "c".into(), "d".into()],
+            vec![
+                edge("a", "b", 2.0),
+                edge("b", "c", 10.0),
+                edge("c", "d", 3.0),
+            ],
+        );
+        let graph = Graph::try_from(input).unwrap();
+        let path = graph.shortest_path("a", "d").unwrap();
+
+        assert!(path.bottleneck.is_some());
+        let bottleneck = path.bottleneck.unwrap();
+
+        let from_name = &*graph.to_name[bottleneck.from.0 as usize];
+        let to_name = &*graph.to_name[bottleneck.to.0 as usize];
+
+        assert_eq!(from_name, "b");
+        assert_eq!(to_name, "c");
+        assert_eq!(bottleneck.latency_ms, 10);
+    }
+
+    #[test]
+    fn test_load_json_from_embedded_data() {
+        let json = include_str!("testdata/simple_graph.json");
+        let input: GraphInput = serde_json::from_str(json).unwrap();
+        let graph = Graph::try_from(input).unwrap();
+
+        assert_eq!(graph.to_name.len(), 3);
+        assert!(graph.to_id.contains_key("a"));
+        assert!(graph.to_id.contains_key("b"));
+        assert!(graph.to_id.contains_key("c"));
+    }
+
+    #[test]
+    fn test_load_json_file() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+
+        assert_eq!(graph.to_name.len(), 4);
+        assert!(graph.to_id.contains_key("api"));
+        assert!(graph.to_id.contains_key("auth"));
+        assert!(graph.to_id.contains_key("db"));
+        assert!(graph.to_id.contains_key("cache"));
+
+        let path = graph.shortest_path("api", "db").unwrap();
+        assert!(path.cost > 0);
+    }
+
+    #[test]
+    fn test_load_json_invalid_graph() {
+        let result = Graph::load_json("src/testdata/invalid_graph.json");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_json_nonexistent_file() {
+        let result = Graph::load_json("nonexistent_file.json");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_self_loop_detection() {
+        // Self-loop!
+        let input = GraphInput::new(vec!["a".into(), "b".into()], vec![edge("a", "a", 5.0)]);
+        let result = Graph::try_from(input);
+        assert!(result.is_err());
+        match result {
+            Err(GraphBuildError::SelfLoop { node }) => {
+                assert_eq!(node, "a");
+            }
+            _ => panic!("Expected SelfLoop error"),
+        }
+    }
+
+    #[test]
+    fn test_with_modifications_override() {
+        let graph = create_test_graph();
+
+        let original_path = graph.shortest_path("api", "db").unwrap();
+        assert_eq!(original_path.cost, 8);
+        assert_eq!(graph.format_path(&original_path), "api → auth → db");
+
+        let modified = graph
+            .with_modifications(&[("auth".to_string(), "db".to_string(), 100)], &[])
+            .unwrap();
+
+        let new_path = modified.shortest_path("api", "db").unwrap();
+        assert_eq!(new_path.cost, 105); // api→auth (5) + auth→db (100)
+    }
+
+    #[test]
+    fn test_with_modifications_drop() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+
+        // Original shortest path should be api → auth → db
+        let original_path = graph.shortest_path("api", "db").unwrap();
+        assert_eq!(graph.format_path(&original_path), "api → auth → db");
+
+        // Drop auth→db edge
+        let modified = graph
+            .with_modifications(&[], &[("auth".to_string(), "db".to_string())])
+            .unwrap();
+
+        // Path should change to go through cache
+        let new_path = modified.shortest_path("api", "db").unwrap();
+        assert_eq!(graph.format_path(&new_path), "api → cache → db");
+    }
+
+    #[test]
+    fn test_with_modifications_combined() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+
+        let modified = graph
+            .with_modifications(
+                &[("api".to_string(), "cache".to_string(), 1)], // Make cache path faster
+                &[("auth".to_string(), "db".to_string())],      // Drop auth→db
+            )
+            .unwrap();
+
+        let new_path = modified.shortest_path("api", "db").unwrap();
+        assert_eq!(graph.format_path(&new_path), "api → cache → db");
+        assert!(new_path.cost < 5); // Should be much faster now
+    }
+
+    #[test]
+    fn test_graph_input_round_trip() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let modified = graph
+            .with_modifications(&[], &[("auth".to_string(), "db".to_string())])
+            .unwrap();
+
+        let input = GraphInput::from(&modified);
+        let names: Vec<Arc<str>> = input.nodes.iter().map(|n| n.name().into()).collect();
+        assert_eq!(names, modified.to_name);
+        assert_eq!(input.edges.len(), 3);
+
+        let rebuilt = Graph::try_from(input).unwrap();
+        let path = rebuilt.shortest_path("api", "db").unwrap();
+        assert_eq!(rebuilt.format_path(&path), "api → cache → db");
+        assert_eq!(path.cost, 9);
+    }
+
+    #[test]
+    fn test_node_attributes_and_avoid_filter() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let path = graph.shortest_path("api", "db").unwrap();
+        assert_eq!(graph.format_path(&path), "api → auth → db");
+
+        let output = graph.path_output(&path);
+        assert_eq!(output.node_attributes["auth"]["region"], "eu-west");
+        assert!(!output.node_attributes.contains_key("api"));
+
+        let constraints = Constraints {
+            avoid_nodes: vec![AttrFilter::parse("region=eu-west").unwrap()],
+            ..Default::default()
+        };
+        let path = graph.shortest_path_with("api", "db", &constraints).unwrap();
+        assert_eq!(graph.format_path(&path), "api → cache → db");
+
+        let constraints = Constraints {
+            avoid_nodes: vec![AttrFilter::parse("tier=1").unwrap()],
+            ..Default::default()
+        };
+        let result = graph.shortest_path_with("api", "db", &constraints);
+        assert!(matches!(result, Err(PathError::PathNotFound { .. })));
+    }
+
+    #[test]
+    fn test_edge_attributes_and_avoid_filter() {
+        let graph = Graph::load_json("src/testdata/attributed_graph.json").unwrap();
+        let path = graph.shortest_path("api", "db").unwrap();
+
+        let output = graph.path_output(&path);
+        assert_eq!(output.edge_attributes.len(), 1);
+        assert_eq!(&*output.edge_attributes[0].from, "api");
+        assert_eq!(output.edge_attributes[0].attributes["provider"], "acme");
+        assert_eq!(output.bottleneck.unwrap().attributes["link_type"], "fiber");
+
+        let constraints = Constraints {
+            avoid_edges: vec![AttrFilter::parse("provider=acme").unwrap()],
+            ..Default::default()
+        };
+        let path = graph.shortest_path_with("api", "db", &constraints).unwrap();
+        assert_eq!(graph.format_path(&path), "api → cache → db");
+
+        let constraints = Constraints {
+            avoid_edge_ids: vec![output.edge_attributes[0].id.to_string()],
+            ..Default::default()
+        };
+        let path = graph.shortest_path_with("api", "db", &constraints).unwrap();
+        assert_eq!(graph.format_path(&path), "api → cache → db");
+
+        // attributes survive the round trip through the input schema
+        let input = GraphInput::from(&graph);
+        assert_eq!(input.edges[0].attrs["capacity"], 1000);
+    }
+
+    #[test]
+    fn test_at_resolves_timeline() {
+        let graph = Graph::load_json("src/testdata/timeline_graph.json").unwrap();
+
+        // before the first sample the base latency applies
+        let early = graph.at(parse_timestamp("2024-05-31T00:00Z").unwrap());
+        let path = early.shortest_path("api", "db").unwrap();
+        assert_eq!(early.format_path(&path), "api → auth → db");
+        assert_eq!(path.cost, 8);
+
+        // during the peak auth→db is slow and traffic shifts to the cache
+        let peak = graph.at(parse_timestamp("2024-06-01T14:30Z").unwrap());
+        let path = peak.shortest_path("api", "db").unwrap();
+        assert_eq!(peak.format_path(&path), "api → cache → db");
+        assert_eq!(
+            peak.path_output(&path).at.as_deref(),
+            Some("2024-06-01T14:30:00Z")
+        );
+
+        // after the peak the latest sample is back to normal
+        let late = graph.at(parse_timestamp("2024-06-01T16:00Z").unwrap());
+        let path = late.shortest_path("api", "db").unwrap();
+        assert_eq!(path.cost, 9);
+    }
+
+    #[test]
+    fn test_invalid_timeline() {
+        let mut bad = edge("a", "b", 1.0);
+        bad.timeline.push(LatencySample {
+            at: "not-a-time".to_string(),
+            latency_ms: 1.0,
+        });
+        let input = GraphInput::new(vec!["a".into(), "b".into()], vec![bad]);
+
+        let result = Graph::try_from(input);
+        assert!(matches!(
+            result,
+            Err(GraphBuildError::InvalidTimeline { .. })
+        ));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_declaration_order() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+
+        let mut input = GraphInput::from(&graph);
+        input.nodes.reverse();
+        input.edges.reverse();
+        let reordered = Graph::try_from(input).unwrap();
+        assert_eq!(graph.content_hash(), reordered.content_hash());
+        assert_eq!(graph.content_hash().len(), 64);
+
+        let modified = graph
+            .with_modifications(&[("auth".to_string(), "db".to_string(), 50)], &[])
+            .unwrap();
+        assert_ne!(graph.content_hash(), modified.content_hash());
+    }
+
+    #[test]
+    fn test_with_modifications_invalid_node() {
+        let graph = create_test_graph();
+
+        // Try to override edge with non-existent node
+        let result =
+            graph.with_modifications(&[("api".to_string(), "nonexistent".to_string(), 100)], &[]);
+
+        assert!(result.is_err());
+        match result {
+            Err(PathError::NodeNotFound(node)) => {
+                assert_eq!(node, "nonexistent");
+            }
+            _ => panic!("Expected NodeNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_with_modifications_missing_edge() {
+        let graph = create_test_graph();
+        let overrides = [("db".to_string(), "api".to_string(), 3)];
+
+        match graph.with_modifications(&overrides, &[]) {
+            Err(PathError::EdgeNotFound { from, to }) => {
+                assert_eq!((from.as_str(), to.as_str()), ("db", "api"));
+            }
+            _ => panic!("Expected EdgeNotFound error"),
+        }
+
+        let modified = graph.with_modifications_creating(&overrides, &[]).unwrap();
+        assert_eq!(modified.shortest_path("db", "api").unwrap().cost, 3);
+        assert_eq!(modified.edge_attrs.len(), graph.edge_attrs.len() + 1);
+    }
+
+    #[test]
+    fn test_bidirectional_edges() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 2, "bidirectional": true, "provider": "x"},
+                {"from": "b", "to": "c", "latency_ms": 3}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(!input.edges[0].attrs.contains_key("bidirectional"));
+        let graph = Graph::try_from(input.clone()).unwrap();
+        assert_eq!(graph.shortest_path("b", "a").unwrap().cost, 2);
+        assert_eq!(graph.edge_attrs[1]["provider"], "x");
+        assert!(graph.shortest_path("c", "b").is_err());
+
+        let mut undirected = input;
+        undirected.directed = false;
+        let graph = Graph::try_from(undirected).unwrap();
+        assert_eq!(graph.shortest_path("c", "a").unwrap().cost, 5);
+        assert_eq!(graph.edge_attrs.len(), 4);
+    }
+
+    #[test]
+    fn test_latency_unit_and_default() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"unit": "us", "default_latency_ms": 2000, "nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 1500,
+                 "timeline": [{"at": "2024-06-01T00:00Z", "latency_ms": 4000}]},
+                {"from": "b", "to": "c"}
+            ]}"#,
+        )
+        .unwrap();
+        let normalized = input.clone().normalize();
+        assert_eq!(normalized.unit, crate::io::LatencyUnit::Ms);
+        assert_eq!(normalized.edges[0].latency_ms, Some(1.5));
+        assert_eq!(normalized.edges[0].timeline[0].latency_ms, 4.0);
+        assert_eq!(normalized.edges[1].latency_ms, Some(2.0));
+        assert_eq!(
+            Graph::try_from(input)
+                .unwrap()
+                .shortest_path("a", "c")
+                .unwrap()
+                .cost,
+            3
+        );
+
+        let input: GraphInput =
+            serde_json::from_str(r#"{"nodes": ["a", "b"], "edges": [{"from": "a", "to": "b"}]}"#)
+                .unwrap();
+        assert!(matches!(
+            Graph::try_from(input),
+            Err(GraphBuildError::MissingLatency { .. })
+        ));
+    }
+
+    #[test]
+    fn test_retain_latency() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"unit": "us", "nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 500},
+                {"from": "b", "to": "c", "latency_ms": 3000},
+                {"from": "a", "to": "c", "latency_ms": 90000}
+            ]}"#,
+        )
+        .unwrap();
+        let mut input = input.normalize();
+        assert_eq!(input.retain_latency(Some(1.0), Some(50.0)), 2);
+        assert_eq!(input.edges.len(), 1);
+        assert_eq!(input.edges[0].from, "b");
+        assert_eq!(input.retain_latency(None, None), 0);
+
+        // a and c lose their edges but stay in the graph
+        let graph = Graph::try_from(input).unwrap();
+        assert_eq!(graph.to_name.len(), 3);
+    }
+
+    #[test]
+    fn test_rename_nodes() {
+        let map = crate::io::parse_node_map(
+            "old,new\n# load balancers\n10.0.0.1, lb\n\n10.0.0.2,api\n10.0.0.2,api\n",
+        )
+        .unwrap();
+        assert_eq!(map.len(), 2);
+        assert!(crate::io::parse_node_map("a,b\na,c\n").is_err());
+        assert!(crate::io::parse_node_map("a\n").is_err());
+
+        let mut input: GraphInput = serde_json::from_str(
+            r#"{"nodes": ["10.0.0.1", {"name": "10.0.0.2", "tier": 1}, "db"], "edges": [
+                {"from": "10.0.0.1", "to": "10.0.0.2", "latency_ms": 1},
+                {"from": "10.0.0.2", "to": "db", "latency_ms": 2}
+            ]}"#,
+        )
+        .unwrap();
+        input.rename_nodes(&map);
+        assert_eq!(input.nodes[1].name(), "api");
+        let graph = Graph::try_from(input).unwrap();
+        assert_eq!(graph.shortest_path("lb", "db").unwrap().cost, 3);
+        // IDs are derived from the names before renaming
+        assert_eq!(&*graph.edge_ids[1], edge_id("10.0.0.2", "db", 0));
+    }
+
+    #[test]
+    fn test_edge_ids() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"nodes": ["a", "b", "c"], "edges": [
+                {"from": "a", "to": "b", "latency_ms": 1},
+                {"from": "a", "to": "b", "latency_ms": 2},
+                {"from": "b", "to": "c", "latency_ms": 3, "id": "b-c", "bidirectional": true}
+            ]}"#,
+        )
+        .unwrap();
+        let graph = Graph::try_from(input).unwrap();
+        assert_eq!(&*graph.edge_ids[0], edge_id("a", "b", 0));
+        assert_eq!(&*graph.edge_ids[1], edge_id("a", "b", 1));
+        assert_ne!(graph.edge_ids[0], graph.edge_ids[1]);
+        assert_eq!(&*graph.edge_ids[2], "b-c");
+        assert_eq!(&*graph.edge_ids[3], "b-c-r");
+        assert_eq!(graph.edge_index("b-c-r"), Some(3));
+
+        // only declared IDs are written back, and the round trip keeps all
+        let input = GraphInput::from(&graph);
+        assert_eq!(input.edges[0].id, None);
+        assert_eq!(input.edges[2].id.as_deref(), Some("b-c"));
+        assert_eq!(Graph::try_from(input).unwrap().edge_ids, graph.edge_ids);
+
+        // dropping the first parallel edge keeps the second one's ID
+        let dropped = graph
+            .with_edge_modifications(&[], &[graph.edge_ids[0].to_string()])
+            .unwrap();
+        let input = GraphInput::from(&dropped);
+        assert_eq!(input.edges[0].id.as_deref(), Some(&*graph.edge_ids[1]));
+
+        let duplicate = GraphInput::new(
+            vec!["a".into(), "b".into()],
+            vec![
+                EdgeInput {
+                    id: Some("x".to_string()),
+                    ..edge("a", "b", 1.0)
+                },
+                EdgeInput {
+                    id: Some("x".to_string()),
+                    ..edge("b", "a", 1.0)
+                },
+            ],
+        );
+        assert!(matches!(
+            Graph::try_from(duplicate),
+            Err(GraphBuildError::DuplicateEdgeId(id)) if id == "x"
+        ));
+    }
+}
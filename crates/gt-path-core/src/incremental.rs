@@ -0,0 +1,471 @@
+use crate::constraint::Constraints;
+use crate::error::PathError;
+use crate::graph::{Graph, Link, NodeId};
+use crate::path::{Edge, Path};
+use crate::simulation::Modifications;
+use crate::stats::SearchStats;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const UNREACHABLE: u32 = u32::MAX;
+
+/// Shortest paths from one source to every node, kept up to date as edges
+/// are re-weighted or dropped.
+///
+/// Updates follow Ramalingam and Reps: only nodes whose distance can change
+/// are revisited. A cheaper edge relaxes outward from its head; a dearer or
+/// dropped edge matters only if it is in the shortest path tree, and then
+/// just the subtree below it is recomputed from its unaffected in-links.
+/// On large graphs a single change usually touches a small fraction of the
+/// nodes, so answering what-if queries doesn't need a full Dijkstra run.
+///
+/// # Example
+///
+/// ```no_run
+/// use gt_path_core::{Constraints, Graph, ShortestPathTree};
+///
+/// let graph = Graph::load_json("graph.json")?;
+/// let mut tree = ShortestPathTree::new(graph, "api", &Constraints::default())?;
+/// tree.set_latency("auth", "db", 50)?;
+/// let path = tree.path_to("db")?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ShortestPathTree {
+    graph: Graph,
+    source: NodeId,
+    allowed_nodes: Vec<bool>,
+    allowed_edges: Vec<bool>,
+    /// In-links of each node as (tail node, edge index)
+    incoming: Vec<Vec<(NodeId, usize)>>,
+    dist: Vec<u32>,
+    /// Tree edge into each reached node
+    parents: Vec<Option<Edge>>,
+}
+
+impl ShortestPathTree {
+    /// Takes ownership of `graph` and computes shortest paths from `from`
+    /// to every node, honoring `constraints` like `shortest_path_with`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ShortestPathTree)` - The tree, with unreachable nodes left out
+    /// * `Err(PathError::NodeNotFound)` - If `from` doesn't exist
+    pub fn new(
+        graph: Graph,
+        from: &str,
+        constraints: &Constraints,
+    ) -> Result<ShortestPathTree, PathError> {
+        let source = *graph
+            .to_id
+            .get(from)
+            .ok_or_else(|| PathError::NodeNotFound(from.to_string()))?;
+        let n = graph.to_name.len();
+
+        let mut incoming = vec![Vec::new(); n];
+        for (u, links) in graph.adj.iter().enumerate() {
+            for link in links {
+                incoming[link.to.0 as usize].push((NodeId(u as u32), link.edge));
+            }
+        }
+
+        let mut tree = ShortestPathTree {
+            allowed_nodes: graph
+                .node_attrs
+                .iter()
+                .map(|attrs| constraints.allows_node(attrs))
+                .collect(),
+            allowed_edges: graph
+                .edge_ids
+                .iter()
+                .zip(&graph.edge_attrs)
+                .map(|(id, attrs)| constraints.allows_edge(id, attrs))
+                .collect(),
+            graph,
+            source,
+            incoming,
+            dist: vec![UNREACHABLE; n],
+            parents: vec![None; n],
+        };
+        if tree.allowed_nodes[source.0 as usize] {
+            tree.dist[source.0 as usize] = 0;
+            tree.settle(vec![source.0 as usize]);
+        }
+        Ok(tree)
+    }
+
+    /// The graph with every update so far applied.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Gives back the updated graph.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    /// Returns the current shortest path from the source to `to`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Path)` - The shortest path with cost and node sequence
+    /// * `Err(PathError::NodeNotFound)` - If `to` doesn't exist
+    /// * `Err(PathError::PathNotFound)` - If no allowed path reaches `to`
+    pub fn path_to(&self, to: &str) -> Result<Path, PathError> {
+        let to_id = *self
+            .graph
+            .to_id
+            .get(to)
+            .ok_or_else(|| PathError::NodeNotFound(to.to_string()))?;
+        if self.dist[to_id.0 as usize] == UNREACHABLE {
+            return Err(PathError::PathNotFound {
+                from: self.graph.to_name[self.source.0 as usize].to_string(),
+                to: to.to_string(),
+            });
+        }
+
+        let (path, edges) = self.graph.path(to_id, &self.parents);
+        let bottleneck = self.graph.bottleneck(&edges);
+        Ok(Path {
+            from: self.source,
+            to: to_id,
+            path,
+            edges,
+            cost: self.dist[to_id.0 as usize],
+            bottleneck,
+        })
+    }
+
+    /// Applies drops and then overrides, with the same meaning as
+    /// `Graph::with_modifications`: an override naming an edge the graph
+    /// doesn't have fails with `PathError::EdgeNotFound`, and one naming an
+    /// unknown or dropped edge ID with `PathError::EdgeIdNotFound`. Overrides
+    /// applied before the failing one stay applied. `create_missing` isn't
+    /// supported.
+    /// Pattern and relative overrides are resolved against the graph as it
+    /// was before any of `mods` applied.
+    pub fn apply(&mut self, mods: &Modifications) -> Result<(), PathError> {
+        let mods = &mods.resolve(&self.graph)?;
+        for (from, to) in &mods.drops {
+            self.drop_edge(from, to)?;
+        }
+        for (from, to, latency_ms) in &mods.overrides {
+            let (u, v) = self.ids(from, to)?;
+            if !self.graph.adj[u].iter().any(|l| l.to.0 as usize == v) {
+                return Err(PathError::EdgeNotFound {
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+            self.set_latency(from, to, *latency_ms)?;
+        }
+        for id in &mods.edge_drops {
+            let edge = self
+                .graph
+                .edge_index(id)
+                .ok_or_else(|| PathError::EdgeIdNotFound(id.clone()))?;
+            if let Some((u, i)) = self.graph.find_link(edge) {
+                let v = self.graph.adj[u][i].to.0 as usize;
+                self.drop_links(u, v, |l| l.edge == edge);
+            }
+        }
+        for (id, latency_ms) in &mods.edge_overrides {
+            let (u, i) = self
+                .graph
+                .edge_index(id)
+                .and_then(|edge| self.graph.find_link(edge))
+                .ok_or_else(|| PathError::EdgeIdNotFound(id.clone()))?;
+            self.set_link_latency(u, i, *latency_ms);
+        }
+        Ok(())
+    }
+
+    /// Changes the latency of the first edge `from → to` and updates the
+    /// affected distances. Does nothing if there is no such edge.
+    pub fn set_latency(&mut self, from: &str, to: &str, latency_ms: u32) -> Result<(), PathError> {
+        let (u, v) = self.ids(from, to)?;
+        if let Some(i) = self.graph.adj[u].iter().position(|l| l.to.0 as usize == v) {
+            self.set_link_latency(u, i, latency_ms);
+        }
+        Ok(())
+    }
+
+    /// Changes the latency of the `i`th link out of `u`.
+    fn set_link_latency(&mut self, u: usize, i: usize, latency_ms: u32) {
+        let link = &mut self.graph.adj[u][i];
+        let (v, old, edge) = (link.to.0 as usize, link.latency_ms, link.edge);
+        link.latency_ms = latency_ms;
+        self.graph.invalidate_hash();
+
+        if latency_ms < old {
+            if self.usable(u, v, edge) && self.dist[u] != UNREACHABLE {
+                let candidate = self.dist[u].saturating_add(latency_ms);
+                if candidate < self.dist[v] {
+                    self.reach(u, v, edge, latency_ms, candidate);
+                    self.settle(vec![v]);
+                }
+            }
+        } else if latency_ms > old && self.is_tree_edge(u, v, edge) {
+            self.recompute_subtree(v);
+        }
+    }
+
+    /// Removes every edge `from → to` and updates the affected distances.
+    pub fn drop_edge(&mut self, from: &str, to: &str) -> Result<(), PathError> {
+        let (u, v) = self.ids(from, to)?;
+        self.drop_links(u, v, |l| l.to.0 as usize == v);
+        Ok(())
+    }
+
+    /// Removes the links `u → v` that `matches` selects.
+    fn drop_links(&mut self, u: usize, v: usize, matches: impl Fn(&Link) -> bool) {
+        let dropped: Vec<usize> = self.graph.adj[u]
+            .iter()
+            .filter(|l| matches(l))
+            .map(|l| l.edge)
+            .collect();
+        if dropped.is_empty() {
+            return;
+        }
+        self.graph.adj[u].retain(|l| !matches(l));
+        self.incoming[v].retain(|&(_, edge)| !dropped.contains(&edge));
+        self.graph.invalidate_hash();
+
+        if dropped.iter().any(|&edge| self.is_tree_edge(u, v, edge)) {
+            self.recompute_subtree(v);
+        }
+    }
+
+    fn ids(&self, from: &str, to: &str) -> Result<(usize, usize), PathError> {
+        let id = |name: &str| {
+            self.graph
+                .to_id
+                .get(name)
+                .map(|id| id.0 as usize)
+                .ok_or_else(|| PathError::NodeNotFound(name.to_string()))
+        };
+        Ok((id(from)?, id(to)?))
+    }
+
+    fn usable(&self, u: usize, v: usize, edge: usize) -> bool {
+        self.allowed_nodes[u] && self.allowed_nodes[v] && self.allowed_edges[edge]
+    }
+
+    fn is_tree_edge(&self, u: usize, v: usize, edge: usize) -> bool {
+        matches!(&self.parents[v], Some(e) if e.from.0 as usize == u && e.index == edge)
+    }
+
+    fn reach(&mut self, u: usize, v: usize, edge: usize, latency_ms: u32, dist: u32) {
+        self.dist[v] = dist;
+        self.parents[v] = Some(Edge {
+            from: NodeId(u as u32),
+            to: NodeId(v as u32),
+            latency_ms,
+            index: edge,
+        });
+    }
+
+    /// Distances below `root` in the tree may have grown: forget them,
+    /// seed each such node with its best in-link from outside the subtree,
+    /// and let Dijkstra settle the rest.
+    fn recompute_subtree(&mut self, root: usize) {
+        let mut affected = vec![root];
+        let mut i = 0;
+        while i < affected.len() {
+            let x = affected[i];
+            for link in &self.graph.adj[x] {
+                let y = link.to.0 as usize;
+                if self.is_tree_edge(x, y, link.edge) {
+                    affected.push(y);
+                }
+            }
+            i += 1;
+        }
+        for &x in &affected {
+            self.dist[x] = UNREACHABLE;
+            self.parents[x] = None;
+        }
+
+        let mut seeds = Vec::new();
+        for &x in &affected {
+            let mut best: Option<(u32, usize, usize, u32)> = None;
+            for &(p, edge) in &self.incoming[x] {
+                let p = p.0 as usize;
+                if self.dist[p] == UNREACHABLE || !self.usable(p, x, edge) {
+                    continue;
+                }
+                let Some(link) = self.graph.adj[p].iter().find(|l| l.edge == edge) else {
+                    continue;
+                };
+                let candidate = self.dist[p].saturating_add(link.latency_ms);
+                if best.is_none_or(|(d, ..)| candidate < d) {
+                    best = Some((candidate, p, edge, link.latency_ms));
+                }
+            }
+            if let Some((dist, p, edge, latency_ms)) = best {
+                self.reach(p, x, edge, latency_ms, dist);
+                seeds.push(x);
+            }
+        }
+        self.settle(seeds);
+    }
+
+    /// Dijkstra from nodes whose distances were just lowered.
+    fn settle(&mut self, start: Vec<usize>) {
+        let mut heap: BinaryHeap<Reverse<(u32, usize)>> = start
+            .into_iter()
+            .map(|v| Reverse((self.dist[v], v)))
+            .collect();
+        let mut stats = SearchStats {
+            heap_pushes: heap.len() as u64,
+            ..SearchStats::default()
+        };
+
+        while let Some(Reverse((cost, u))) = heap.pop() {
+            if cost > self.dist[u] {
+                continue;
+            }
+            stats.nodes_visited += 1;
+            for i in 0..self.graph.adj[u].len() {
+                stats.edges_relaxed += 1;
+                let link = &self.graph.adj[u][i];
+                let (v, edge, latency_ms) = (link.to.0 as usize, link.edge, link.latency_ms);
+                if !self.usable(u, v, edge) {
+                    continue;
+                }
+                let candidate = cost.saturating_add(latency_ms);
+                if candidate < self.dist[v] {
+                    self.reach(u, v, edge, latency_ms, candidate);
+                    heap.push(Reverse((candidate, v)));
+                    stats.heap_pushes += 1;
+                }
+            }
+        }
+        stats.record();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GraphInput;
+
+    /// 6x6 grid with edges both ways; latencies vary so paths are unique-ish
+    fn grid() -> Graph {
+        let name = |r: usize, c: usize| format!("n{}_{}", r, c);
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for r in 0..6 {
+            for c in 0..6 {
+                nodes.push(format!("\"{}\"", name(r, c)));
+                let w = (r * 7 + c * 3) % 5 + 1;
+                if c + 1 < 6 {
+                    edges.push(format!(
+                        r#"{{"from": "{}", "to": "{}", "latency_ms": {}}}"#,
+                        name(r, c),
+                        name(r, c + 1),
+                        w
+                    ));
+                    edges.push(format!(
+                        r#"{{"from": "{}", "to": "{}", "latency_ms": {}}}"#,
+                        name(r, c + 1),
+                        name(r, c),
+                        w + 1
+                    ));
+                }
+                if r + 1 < 6 {
+                    edges.push(format!(
+                        r#"{{"from": "{}", "to": "{}", "latency_ms": {}}}"#,
+                        name(r, c),
+                        name(r + 1, c),
+                        w + 2
+                    ));
+                }
+            }
+        }
+        let json = format!(
+            r#"{{"nodes": [{}], "edges": [{}]}}"#,
+            nodes.join(","),
+            edges.join(",")
+        );
+        let input: GraphInput = serde_json::from_str(&json).unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    fn assert_matches_dijkstra(tree: &ShortestPathTree) {
+        let graph = tree.graph();
+        for name in &graph.to_name {
+            let expected = graph.shortest_path("n0_0", name).map(|p| p.cost).ok();
+            let actual = tree.path_to(name).ok();
+            assert_eq!(
+                actual.as_ref().map(|p| p.cost),
+                expected,
+                "distance to {}",
+                name
+            );
+            if let Some(path) = actual {
+                let sum: u32 = path.edges.iter().map(|e| e.latency_ms).sum();
+                assert_eq!(sum, path.cost, "path to {} is consistent", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_updates_match_full_recompute() {
+        let graph = grid();
+        let links: Vec<(String, String)> = graph
+            .adj
+            .iter()
+            .enumerate()
+            .flat_map(|(u, links)| {
+                links
+                    .iter()
+                    .map(move |l| (u, l.to.0 as usize))
+                    .collect::<Vec<_>>()
+            })
+            .map(|(u, v)| (graph.to_name[u].to_string(), graph.to_name[v].to_string()))
+            .collect();
+
+        let mut tree = ShortestPathTree::new(graph, "n0_0", &Constraints::default()).unwrap();
+        assert_matches_dijkstra(&tree);
+
+        // deterministic pseudo-random mix of increases, decreases and drops
+        let mut state: u64 = 42;
+        for step in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let (from, to) = &links[(state >> 33) as usize % links.len()];
+            if step % 7 == 0 {
+                tree.drop_edge(from, to).unwrap();
+            } else {
+                tree.set_latency(from, to, (state >> 50) as u32 % 12)
+                    .unwrap();
+            }
+            assert_matches_dijkstra(&tree);
+        }
+    }
+
+    #[test]
+    fn test_apply_matches_simulation() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let mods = Modifications::parse(&["auth:db:50".to_string()], &[]).unwrap();
+        let expected = graph
+            .with_modifications(&mods.overrides, &mods.drops)
+            .unwrap();
+
+        let mut tree = ShortestPathTree::new(graph, "api", &Constraints::default()).unwrap();
+        assert_eq!(tree.path_to("db").unwrap().cost, 8);
+        tree.apply(&mods).unwrap();
+        assert_eq!(tree.path_to("db").unwrap().cost, 9);
+        assert_eq!(tree.graph().content_hash(), expected.content_hash());
+
+        tree.drop_edge("cache", "db").unwrap();
+        assert_eq!(tree.path_to("db").unwrap().cost, 55);
+        tree.drop_edge("auth", "db").unwrap();
+        assert!(matches!(
+            tree.path_to("db"),
+            Err(PathError::PathNotFound { .. })
+        ));
+        assert!(tree.set_latency("api", "nowhere", 1).is_err());
+    }
+}
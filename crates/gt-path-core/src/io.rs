@@ -0,0 +1,1216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Arbitrary key/value attributes attached to a node or edge.
+pub type Attributes = BTreeMap<String, serde_json::Value>;
+
+/// JSON input format for a graph.
+///
+/// Expected format:
+/// ```json
+/// {
+///   "nodes": ["api", "auth", { "name": "db", "region": "us-east" }],
+///   "edges": [
+///     { "from": "api", "to": "auth", "latency_ms": 5.2 },
+///     { "from": "auth", "to": "db", "latency_ms": 3, "bidirectional": true }
+///   ]
+/// }
+/// ```
+///
+/// A top-level `"directed": false` makes every edge bidirectional.
+/// Latencies are in milliseconds unless a top-level `"unit"` (`"us"`, `"ms"`
+/// or `"s"`) says otherwise, and an edge without `latency_ms` takes the
+/// top-level `"default_latency_ms"`. `normalize` resolves all three. Files
+/// listed in `"includes"` are merged in by `compose::read`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GraphInput {
+    /// Other graph files to merge in, relative to this one; see `compose`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    /// False if every edge links its nodes both ways
+    #[serde(default = "directed_default", skip_serializing_if = "is_true")]
+    pub directed: bool,
+    /// Unit of every latency in the file, despite the `_ms` field names
+    #[serde(default, skip_serializing_if = "LatencyUnit::is_ms")]
+    pub unit: LatencyUnit,
+    /// Latency of edges that don't declare one, in `unit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_latency_ms: Option<f32>,
+    /// List of nodes, as bare names or objects with attributes
+    pub nodes: Vec<NodeInput>,
+    /// List of directed edges with latencies
+    pub edges: Vec<EdgeInput>,
+}
+
+fn directed_default() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/// Unit the latencies of a graph file are written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyUnit {
+    /// Microseconds
+    Us,
+    /// Milliseconds
+    #[default]
+    Ms,
+    /// Seconds
+    S,
+}
+
+impl LatencyUnit {
+    /// Converts a latency in this unit to milliseconds.
+    pub fn to_ms(self, latency: f32) -> f32 {
+        match self {
+            LatencyUnit::Us => latency / 1000.0,
+            LatencyUnit::Ms => latency,
+            LatencyUnit::S => latency * 1000.0,
+        }
+    }
+
+    /// Converts a latency in milliseconds to this unit.
+    pub fn from_ms(self, latency_ms: f32) -> f32 {
+        match self {
+            LatencyUnit::Us => latency_ms * 1000.0,
+            LatencyUnit::Ms => latency_ms,
+            LatencyUnit::S => latency_ms / 1000.0,
+        }
+    }
+
+    fn is_ms(&self) -> bool {
+        *self == LatencyUnit::Ms
+    }
+}
+
+impl GraphInput {
+    /// Creates a directed graph input with latencies in milliseconds.
+    pub fn new(nodes: Vec<NodeInput>, edges: Vec<EdgeInput>) -> GraphInput {
+        GraphInput {
+            includes: Vec::new(),
+            directed: true,
+            unit: LatencyUnit::Ms,
+            default_latency_ms: None,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Returns the same graph in canonical form: latencies in milliseconds,
+    /// every edge carrying its own latency, and only directed edges.
+    ///
+    /// Edges without a latency take `default_latency_ms`; with no default
+    /// they keep none, which building the graph rejects. Each bidirectional
+    /// edge, or every edge if the graph is undirected, becomes one edge per
+    /// direction with the same latency, timeline, and attributes; the
+    /// reverse edge directly follows the declared one.
+    pub fn normalize(self) -> GraphInput {
+        let unit = self.unit;
+        let mut edges = Vec::with_capacity(self.edges.len());
+        for mut edge in self.edges {
+            edge.latency_ms = edge
+                .latency_ms
+                .or(self.default_latency_ms)
+                .map(|l| unit.to_ms(l));
+            for sample in &mut edge.timeline {
+                sample.latency_ms = unit.to_ms(sample.latency_ms);
+            }
+
+            let both = edge.bidirectional || !self.directed;
+            edge.bidirectional = false;
+            if both {
+                let mut reverse = edge.clone();
+                std::mem::swap(&mut reverse.from, &mut reverse.to);
+                reverse.id = edge.id.as_ref().map(|id| format!("{}-r", id));
+                edges.push(edge);
+                edges.push(reverse);
+            } else {
+                edges.push(edge);
+            }
+        }
+        GraphInput::new(self.nodes, edges)
+    }
+
+    /// Drops edges whose latency is below `min_ms` or above `max_ms` and
+    /// returns how many were dropped. Latencies are read as milliseconds,
+    /// so this expects normalized input; edges without a latency are kept.
+    /// Nodes are kept even if they lose all their edges.
+    pub fn retain_latency(&mut self, min_ms: Option<f32>, max_ms: Option<f32>) -> usize {
+        let before = self.edges.len();
+        self.edges.retain(|e| match e.latency_ms {
+            Some(l) => min_ms.is_none_or(|min| l >= min) && max_ms.is_none_or(|max| l <= max),
+            None => true,
+        });
+        before - self.edges.len()
+    }
+
+    /// Sets the `id` of every edge that has none to the one derived from
+    /// its endpoints, so it survives later renames. Expects normalized
+    /// input, as derived IDs count parallel edges per direction.
+    pub fn pin_edge_ids(&mut self) {
+        let mut seen: HashMap<(String, String), usize> = HashMap::new();
+        for edge in &mut self.edges {
+            let ordinal = seen
+                .entry((edge.from.clone(), edge.to.clone()))
+                .or_default();
+            if edge.id.is_none() {
+                edge.id = Some(edge_id(&edge.from, &edge.to, *ordinal));
+            }
+            *ordinal += 1;
+        }
+    }
+
+    /// Renames nodes, in node declarations and edge endpoints alike, by
+    /// `map` from old to new name. Names the map doesn't mention are kept.
+    /// The input comes back normalized, with edge IDs pinned to the ones
+    /// derived from the old names.
+    pub fn rename_nodes(&mut self, map: &HashMap<String, String>) {
+        let input = std::mem::replace(self, GraphInput::new(Vec::new(), Vec::new()));
+        *self = input.normalize();
+        self.pin_edge_ids();
+
+        let rename = |name: &mut String| {
+            if let Some(new) = map.get(name.as_str()) {
+                name.clone_from(new);
+            }
+        };
+        for node in &mut self.nodes {
+            rename(node.name_mut());
+        }
+        for edge in &mut self.edges {
+            rename(&mut edge.from);
+            rename(&mut edge.to);
+        }
+    }
+
+    /// Reads and parses a graph input file without building the graph.
+    /// Files it includes aren't merged in; `compose::read` does that.
+    pub fn read(path: &str) -> anyhow::Result<GraphInput> {
+        use anyhow::Context;
+
+        let contents =
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+
+        serde_json::from_str(&contents).context("Failed to parse JSON")
+    }
+
+    /// Parses a graph input from a reader, such as a file wrapped for
+    /// progress reporting.
+    pub fn from_reader<R: std::io::Read>(reader: R) -> anyhow::Result<GraphInput> {
+        use anyhow::Context;
+
+        serde_json::from_reader(std::io::BufReader::new(reader)).context("Failed to parse JSON")
+    }
+
+    /// Writes the graph input as pretty JSON to `path`, or stdout if `None`.
+    pub fn write(&self, path: Option<&str>) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize graph to JSON")?;
+        match path {
+            Some(path) => std::fs::write(path, json + "\n")
+                .context(format!("Failed to write file: {}", path))?,
+            None => println!("{}", json),
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a node mapping file: one `old,new` pair per line, renaming node
+/// `old` to `new`. Blank lines and lines starting with `#` are skipped, as
+/// is a first line of `old,new`.
+pub fn read_node_map(path: &str) -> anyhow::Result<HashMap<String, String>> {
+    use anyhow::Context;
+
+    let contents =
+        std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+    parse_node_map(&contents).context(format!("Invalid node map {}", path))
+}
+
+/// Parses the contents of a node mapping file; see `read_node_map`.
+pub fn parse_node_map(contents: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || (i == 0 && line == "old,new") {
+            continue;
+        }
+        let Some((old, new)) = line.split_once(',') else {
+            anyhow::bail!("line {}: expected old,new", i + 1);
+        };
+        let (old, new) = (old.trim(), new.trim());
+        if old.is_empty() || new.is_empty() || new.contains(',') {
+            anyhow::bail!("line {}: expected old,new", i + 1);
+        }
+        if let Some(previous) = map.insert(old.to_string(), new.to_string())
+            && previous != new
+        {
+            anyhow::bail!(
+                "line {}: {} is mapped to both {} and {}",
+                i + 1,
+                old,
+                previous,
+                new
+            );
+        }
+    }
+    Ok(map)
+}
+
+/// A node in the input graph.
+///
+/// Either a bare name (`"api"`) or an object with a `name` and arbitrary
+/// attributes (`{"name": "api", "region": "us-east", "tier": "edge"}`).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum NodeInput {
+    /// Node declared by name only
+    Name(String),
+    /// Node declared with attributes
+    Detailed {
+        /// Node name
+        name: String,
+        /// Remaining fields of the node object
+        #[serde(flatten)]
+        attrs: Attributes,
+    },
+}
+
+impl NodeInput {
+    /// Returns the node name.
+    pub fn name(&self) -> &str {
+        match self {
+            NodeInput::Name(name) => name,
+            NodeInput::Detailed { name, .. } => name,
+        }
+    }
+
+    /// Returns a mutable reference to the node name.
+    pub fn name_mut(&mut self) -> &mut String {
+        match self {
+            NodeInput::Name(name) => name,
+            NodeInput::Detailed { name, .. } => name,
+        }
+    }
+
+    /// Consumes the node, returning its name and attributes.
+    pub fn into_parts(self) -> (String, Attributes) {
+        match self {
+            NodeInput::Name(name) => (name, Attributes::new()),
+            NodeInput::Detailed { name, attrs } => (name, attrs),
+        }
+    }
+
+    /// Builds a node, using the bare-name form when there are no attributes.
+    pub fn new(name: String, attrs: Attributes) -> NodeInput {
+        if attrs.is_empty() {
+            NodeInput::Name(name)
+        } else {
+            NodeInput::Detailed { name, attrs }
+        }
+    }
+}
+
+impl From<&str> for NodeInput {
+    fn from(name: &str) -> Self {
+        NodeInput::Name(name.to_string())
+    }
+}
+
+/// Represents a directed edge in the input graph.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EdgeInput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Stable identifier; None to derive one from the endpoints (see
+    /// `edge_id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Edge weight/latency in milliseconds (or the graph's `unit`); None
+    /// to use the graph's `default_latency_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f32>,
+    /// Latency measurements over time, used for point-in-time queries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<LatencySample>,
+    /// Also link `to` back to `from`, with the same latency and attributes
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bidirectional: bool,
+    /// Any additional fields (provider, link type, capacity, ...)
+    #[serde(flatten)]
+    pub attrs: Attributes,
+}
+
+/// Derives the ID of an edge without an explicit one: a hash of its
+/// endpoints and `ordinal`, its position among the edges `from → to` in
+/// declaration order (counting from 0), so parallel edges get distinct IDs.
+pub fn edge_id(from: &str, to: &str, ordinal: usize) -> String {
+    use sha2::{Digest, Sha256};
+
+    let key = format!("{}\0{}\0{}", from, to, ordinal);
+    Sha256::digest(key.as_bytes())[..6]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A latency measurement taking effect at a point in time.
+///
+/// Expected format:
+/// ```json
+/// { "at": "2024-06-01T14:00Z", "latency_ms": 12.5 }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LatencySample {
+    /// Timestamp the measurement takes effect (RFC 3339, UTC if no offset)
+    pub at: String,
+    /// Edge latency in milliseconds (or the graph's `unit`) from this
+    /// timestamp onward
+    pub latency_ms: f32,
+}
+
+/// JSON input format for a set of traffic demands.
+///
+/// Expected format:
+/// ```json
+/// {
+///   "demands": [
+///     { "from": "api", "to": "db", "rps": 120.0 }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct DemandsInput {
+    /// List of demands between node pairs
+    pub demands: Vec<DemandInput>,
+}
+
+impl DemandsInput {
+    /// Reads and parses a demands file.
+    pub fn read(path: &str) -> anyhow::Result<DemandsInput> {
+        use anyhow::Context;
+
+        let contents =
+            std::fs::read_to_string(path).context(format!("Failed to read file: {}", path))?;
+
+        let input: DemandsInput =
+            serde_json::from_str(&contents).context("Failed to parse JSON")?;
+        if let Some(d) = input.demands.iter().find(|d| d.rps.is_nan() || d.rps < 0.0) {
+            anyhow::bail!("invalid rate on demand {}->{}: {}", d.from, d.to, d.rps);
+        }
+
+        Ok(input)
+    }
+}
+
+/// Traffic between a source and a destination.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DemandInput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Requests per second sent from source to destination
+    pub rps: f64,
+}
+
+/// Version of the JSON output format, reported as `schema_version` in
+/// every output object. Bumped whenever a field is renamed, removed, or
+/// changes meaning; adding a field doesn't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON-serializable path output with human-readable node names.
+///
+/// Suitable for CLI output and API responses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PathOutput {
+    /// Source node name
+    pub from: Arc<str>,
+    /// Destination node name
+    pub to: Arc<str>,
+    /// Sequence of node names from source to destination
+    pub path: Vec<Arc<str>>,
+    /// Total latency in milliseconds
+    pub total_latency_ms: u32,
+    /// IDs of the edges traversed, in path order
+    #[serde(default)]
+    pub edge_ids: Vec<Arc<str>>,
+    /// Edge with the highest latency (bottleneck)
+    pub bottleneck: Option<EdgeOutput>,
+    /// Attributes of the nodes on the path, keyed by node name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_attributes: BTreeMap<Arc<str>, Attributes>,
+    /// Edges on the path that carry attributes, in path order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edge_attributes: Vec<EdgeOutput>,
+    /// Timestamp the edge latencies were evaluated at, for point-in-time queries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+    /// Content hash of the graph the path was found in
+    pub graph_hash: String,
+}
+
+/// JSON-serializable edge with human-readable node names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EdgeOutput {
+    /// Stable edge ID
+    #[serde(default)]
+    pub id: Arc<str>,
+    /// Source node name
+    pub from: Arc<str>,
+    /// Destination node name
+    pub to: Arc<str>,
+    /// Edge latency in milliseconds
+    pub latency_ms: u32,
+    /// Additional edge attributes
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: Attributes,
+}
+
+/// JSON-serializable result of routing traffic demands.
+#[derive(Debug, Serialize)]
+pub struct LoadOutput {
+    /// Total requests per second of all routed demands
+    pub routed_rps: f64,
+    /// Number of edges whose load exceeds their declared capacity
+    pub over_capacity: usize,
+    /// Per-edge load, heaviest first
+    pub edges: Vec<EdgeLoadOutput>,
+    /// Demands that could not be routed
+    pub unrouted: Vec<UnroutedDemandOutput>,
+    /// Content hash of the graph the demands were routed through
+    pub graph_hash: String,
+    /// How the equilibrium assignment converged, if one was run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignment: Option<AssignmentOutput>,
+}
+
+/// JSON-serializable convergence of an equilibrium traffic assignment.
+#[derive(Debug, Serialize)]
+pub struct AssignmentOutput {
+    /// Rounds of routing run
+    pub iterations: u32,
+    /// Total latency the traffic would save on the final shortest paths,
+    /// as a fraction of what it sees
+    pub relative_gap: f64,
+    /// Whether the gap fell below the target before the last round
+    pub converged: bool,
+}
+
+/// JSON-serializable aggregate load on one edge.
+#[derive(Debug, Serialize)]
+pub struct EdgeLoadOutput {
+    /// Stable edge ID
+    pub id: Arc<str>,
+    /// Source node name
+    pub from: Arc<str>,
+    /// Destination node name
+    pub to: Arc<str>,
+    /// Total requests per second routed over the edge
+    pub load_rps: f64,
+    /// Declared capacity in requests per second
+    pub capacity_rps: Option<f64>,
+    /// Load as a fraction of capacity
+    pub utilization: Option<f64>,
+    /// Whether the load exceeds the declared capacity
+    pub over_capacity: bool,
+    /// Latency of the edge under this load, for equilibrium assignment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded_latency_ms: Option<f64>,
+}
+
+/// JSON-serializable demand that could not be routed.
+#[derive(Debug, Serialize)]
+pub struct UnroutedDemandOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Requests per second of the demand
+    pub rps: f64,
+    /// Why the demand could not be routed
+    pub reason: String,
+}
+
+/// JSON-serializable ranking of edges by the demand routes that share
+/// them.
+#[derive(Debug, Serialize)]
+pub struct SharedFateOutput {
+    /// Number of demands routed
+    pub routed: usize,
+    /// Edges on at least one route, most shared first
+    pub edges: Vec<SharedEdgeOutput>,
+    /// Demands that could not be routed
+    pub unrouted: Vec<UnroutedDemandOutput>,
+    /// Content hash of the graph the demands were routed through
+    pub graph_hash: String,
+}
+
+/// JSON-serializable count of the demand routes crossing one edge.
+#[derive(Debug, Serialize)]
+pub struct SharedEdgeOutput {
+    /// Stable edge ID
+    pub id: Arc<str>,
+    /// Source node name
+    pub from: Arc<str>,
+    /// Destination node name
+    pub to: Arc<str>,
+    /// Number of routed demands whose shortest path crosses the edge
+    pub routes: usize,
+    /// `routes` as a fraction of all routed demands
+    pub share: f64,
+    /// Number of those demands left with no path if the edge fails
+    pub stranded: usize,
+    /// Total requests per second routed over the edge
+    pub load_rps: f64,
+    /// Source and destination of each demand crossing the edge
+    pub demands: Vec<(String, String)>,
+}
+
+/// JSON-serializable comparison of the minimum spanning tree with the
+/// shortest-path tree from a root.
+#[derive(Debug, Serialize)]
+pub struct BackboneOutput {
+    /// Node the shortest-path tree is rooted at
+    pub root: String,
+    /// The minimum spanning tree
+    pub mst: TreeOutput,
+    /// The shortest-path tree from `root`
+    pub spt: TreeOutput,
+    /// Latency from `root` along each tree to every node both reach,
+    /// largest stretch first
+    pub destinations: Vec<StretchOutput>,
+    /// Node pairs joined by the minimum spanning tree only
+    pub mst_only: Vec<(String, String)>,
+    /// Node pairs joined by the shortest-path tree only
+    pub spt_only: Vec<(String, String)>,
+    /// Content hash of the compared graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable size of a tree of node pairs.
+#[derive(Debug, Serialize)]
+pub struct TreeOutput {
+    /// Sum of the latencies of the joined pairs
+    pub total_latency_ms: u64,
+    /// Number of node pairs joined
+    pub links: usize,
+}
+
+/// JSON-serializable latency to one destination along each tree.
+#[derive(Debug, Serialize)]
+pub struct StretchOutput {
+    /// Destination node name
+    pub node: String,
+    /// Latency along the minimum spanning tree
+    pub mst_latency_ms: u32,
+    /// Latency along the shortest-path tree, the shortest there is
+    pub spt_latency_ms: u32,
+    /// `mst_latency_ms` over `spt_latency_ms`, 1 when both are 0
+    pub stretch: f64,
+}
+
+/// JSON-serializable stretch of latencies over a subset of edges.
+#[derive(Debug, Serialize)]
+pub struct SpannerOutput {
+    /// Number of edges in the subset
+    pub kept_edges: usize,
+    /// Number of edges in the graph
+    pub edges: usize,
+    /// Number of nodes measured from
+    pub sources: usize,
+    /// Whether the sources were a random sample of the nodes
+    pub sampled: bool,
+    /// Number of pairs the whole graph connects, at nonzero latency
+    pub pairs: usize,
+    /// Number of those pairs the subset disconnects
+    pub disconnected: usize,
+    /// Largest stretch of a pair the subset connects
+    pub max_stretch: Option<f64>,
+    /// Mean stretch of the pairs the subset connects
+    pub mean_stretch: Option<f64>,
+    /// Largest stretch allowed, if checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_allowed: Option<f64>,
+    /// Whether the subset connects every pair within the allowed stretch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spanner: Option<bool>,
+    /// Pairs with the largest stretch, disconnected ones first
+    pub worst: Vec<PairStretchOutput>,
+    /// Content hash of the measured graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable stretch of one pair of nodes.
+#[derive(Debug, Serialize)]
+pub struct PairStretchOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Shortest path latency in the whole graph
+    pub latency_ms: u32,
+    /// Shortest path latency using only the subset, None if disconnected
+    pub subset_latency_ms: Option<u32>,
+    /// `subset_latency_ms` over `latency_ms`, None if disconnected
+    pub stretch: Option<f64>,
+}
+
+/// JSON-serializable per-hop latency budget report.
+#[derive(Debug, Serialize)]
+pub struct BudgetOutput {
+    /// Policy used to split the budget
+    pub policy: String,
+    /// End-to-end latency budget in milliseconds
+    pub max_latency_ms: u32,
+    /// Current end-to-end latency in milliseconds
+    pub actual_latency_ms: u32,
+    /// Whether the current path meets the end-to-end budget
+    pub slo_met: bool,
+    /// Budget for each hop, in path order
+    pub hops: Vec<HopBudgetOutput>,
+    /// The path the budget was allocated over
+    pub path: PathOutput,
+}
+
+/// JSON-serializable occurrences of a pattern in a graph.
+#[derive(Debug, Serialize)]
+pub struct PatternMatchOutput {
+    /// Pattern node names, in the pattern file's order
+    pub pattern_nodes: Vec<String>,
+    /// Number of matches reported
+    pub count: usize,
+    /// Whether the search stopped at the match limit
+    pub truncated: bool,
+    /// Graph node bound to each pattern node, per match
+    pub matches: Vec<BTreeMap<String, String>>,
+    /// Content hash of the searched graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable structural comparison of two graphs.
+#[derive(Debug, Serialize)]
+pub struct CompareOutput {
+    /// Content hash of the first graph
+    pub graph_hash_a: String,
+    /// Content hash of the second graph
+    pub graph_hash_b: String,
+    /// Shared nodes over all nodes, matched by name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_jaccard: Option<f64>,
+    /// Shared edges over all edges, matched by `(from, to)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge_jaccard: Option<f64>,
+    /// Total variation distance between degree distributions (0 to 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degree_distance: Option<f64>,
+    /// Edits turning the first graph into the second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_distance: Option<EditDistanceOutput>,
+}
+
+/// JSON-serializable edit counts between two graphs with nodes matched by
+/// name.
+#[derive(Debug, Serialize)]
+pub struct EditDistanceOutput {
+    /// Sum of all edits; an upper bound on the graph edit distance
+    pub total: usize,
+    pub nodes_added: usize,
+    pub nodes_removed: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+    /// Edges present in both graphs with a different latency
+    pub latency_changed: usize,
+    /// `1 - total / (nodes + edges of both graphs)`, from 0 to 1
+    pub similarity: f64,
+}
+
+/// JSON-serializable availability of a route.
+#[derive(Debug, Serialize)]
+pub struct AvailabilityOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// The route is at least this available
+    pub lower_bound: f64,
+    /// The route is at most this available
+    pub upper_bound: f64,
+    /// Required availability, if checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_availability: Option<f64>,
+    /// Whether the lower bound meets `min_availability`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slo_met: Option<bool>,
+    /// Node-disjoint paths serving the route, shortest first
+    pub paths: Vec<PathAvailabilityOutput>,
+    /// Content hash of the graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable path with its own availability.
+#[derive(Debug, Serialize)]
+pub struct PathAvailabilityOutput {
+    /// Product of the node and edge availabilities on the path
+    pub availability: f64,
+    /// The path itself
+    #[serde(flatten)]
+    pub path: PathOutput,
+}
+
+/// JSON-serializable latency matrix between a set of nodes.
+#[derive(Debug, Serialize)]
+pub struct MatrixOutput {
+    /// Row and column order
+    pub nodes: Vec<String>,
+    /// `latency_ms[i][j]` is the latency from `nodes[i]` to `nodes[j]`
+    /// (None if unreachable)
+    pub latency_ms: Vec<Vec<Option<u32>>>,
+    /// True if entries may be landmark estimates rather than exact
+    pub approximate: bool,
+    /// Landmarks the estimates were computed from
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub landmarks: Vec<String>,
+    /// Largest relative error allowed for an estimate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_error: Option<f64>,
+    /// Rows computed exactly because an estimate was too loose
+    pub exact_rows: usize,
+    /// Content hash of the graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable result of a Monte Carlo failure simulation.
+#[derive(Debug, Serialize)]
+pub struct ChaosOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Maximum allowed latency in milliseconds
+    pub max_latency_ms: u32,
+    /// Route latency with every edge working
+    pub baseline_latency_ms: u32,
+    /// Number of simulated periods
+    pub trials: u32,
+    /// Seed of the random failure draws
+    pub seed: u64,
+    /// True if `--verify` re-ran the analysis and got the same result
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub verified: bool,
+    /// Trials with no path or a path over the SLO
+    pub violations: u32,
+    /// Trials with no path at all
+    pub disconnected: u32,
+    /// Fraction of trials that violated the SLO
+    pub violation_probability: f64,
+    /// 95% confidence interval for `violation_probability`
+    pub confidence_interval: (f64, f64),
+    /// Content hash of the graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable comparison of several simulated scenarios over
+/// several routes.
+#[derive(Debug, Serialize)]
+pub struct ScenarioMatrixOutput {
+    /// Scenario names in column order, `baseline` first if requested
+    pub scenarios: Vec<String>,
+    /// Maximum allowed latency in milliseconds, if an SLO was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_latency_ms: Option<u32>,
+    /// One row per route
+    pub routes: Vec<ScenarioRouteOutput>,
+    /// Content hash of the unmodified graph
+    pub graph_hash: String,
+}
+
+/// One route's row in a `ScenarioMatrixOutput`.
+#[derive(Debug, Serialize)]
+pub struct ScenarioRouteOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// One result per scenario, in the order of `scenarios`
+    pub results: Vec<ScenarioResultOutput>,
+}
+
+/// One route under one scenario.
+#[derive(Debug, Serialize)]
+pub struct ScenarioResultOutput {
+    /// Scenario name
+    pub scenario: String,
+    /// Shortest path latency, absent if there is no path
+    pub latency_ms: Option<u32>,
+    /// Whether the route meets the SLO; false without a path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slo_met: Option<bool>,
+    /// Latency minus the baseline's, when both have a path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_ms: Option<i64>,
+}
+
+/// JSON-serializable SLO evaluation across a series of graph snapshots.
+#[derive(Debug, Serialize)]
+pub struct SloHistoryOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Maximum allowed latency in milliseconds
+    pub max_latency_ms: u32,
+    /// Snapshots in time order
+    pub snapshots: Vec<SnapshotSloOutput>,
+    /// Snapshots with no path or a path over the SLO
+    pub violations: usize,
+    /// Fraction of snapshots that violated the SLO
+    pub violation_rate: f64,
+    /// Least-squares change in latency per snapshot, over snapshots with a
+    /// path
+    pub latency_slope_ms: f64,
+    /// `improving`, `degrading`, or `stable`
+    pub trend: String,
+}
+
+/// One snapshot in an `SloHistoryOutput`.
+#[derive(Debug, Serialize)]
+pub struct SnapshotSloOutput {
+    /// Graph file of the snapshot
+    pub file: String,
+    /// Shortest path latency, absent if there is no path
+    pub latency_ms: Option<u32>,
+    pub slo_met: bool,
+    /// Content hash of the snapshot's graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable counters of a running `gt-path daemon`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DaemonStatsOutput {
+    /// Graph files held in memory
+    pub graphs: usize,
+    /// Whether query results are cached
+    pub cache_enabled: bool,
+    /// Queries answered from the cache
+    pub hits: u64,
+    /// Queries that had to run, including expired entries
+    pub misses: u64,
+    /// Queries whose cached answer was older than the TTL
+    pub expired: u64,
+    /// Answers currently cached
+    pub entries: usize,
+    /// Fraction of queries answered from the cache
+    pub hit_rate: f64,
+    /// Graphs loaded again because their file changed, or replaced by an
+    /// upload
+    #[serde(default)]
+    pub reloads: u64,
+    /// Time taken to load and validate each graph, in seconds
+    #[serde(default)]
+    pub load_seconds: HistogramOutput,
+    /// Time taken to answer each query, in seconds
+    #[serde(default)]
+    pub query_seconds: HistogramOutput,
+}
+
+/// JSON-serializable histogram, bucketed like a Prometheus histogram.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HistogramOutput {
+    /// Upper bound of each bucket, ascending
+    pub bounds: Vec<f64>,
+    /// Observations at or below each bound
+    pub counts: Vec<u64>,
+    /// Sum of all observations
+    pub sum: f64,
+    /// Number of observations, including those above every bound
+    pub count: u64,
+}
+
+impl HistogramOutput {
+    /// Creates an empty histogram with buckets up to each of `bounds`.
+    pub fn new(bounds: &[f64]) -> HistogramOutput {
+        HistogramOutput {
+            bounds: bounds.to_vec(),
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Adds one observation.
+    pub fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Mean of the observations, or 0 without any.
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+}
+
+/// JSON-serializable health of a running `gt-path daemon`. A daemon that
+/// answers at all is live.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DaemonHealthOutput {
+    /// Whether the daemon should be sent queries
+    pub ready: bool,
+    /// `loading`, `ready`, `failed` (a graph failed to preload), or
+    /// `stopping`
+    pub status: String,
+    /// Graphs still preloading
+    pub loading: usize,
+    /// Why graphs failed to preload
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// JSON-serializable result of uploading a graph to a `gt-path daemon`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DaemonUploadOutput {
+    /// Name the graph is now served under
+    pub name: String,
+    /// Content hash of the uploaded graph
+    pub graph_hash: String,
+    /// Number of nodes
+    pub nodes: usize,
+    /// Number of edges
+    pub edges: usize,
+}
+
+/// JSON-serializable result of replaying a recorded daemon session against
+/// a graph.
+#[derive(Debug, Serialize)]
+pub struct ReplayOutput {
+    /// Session file the queries were read from
+    pub session: String,
+    /// Content hash of the graph the queries were replayed against
+    pub graph_hash: String,
+    /// Number of queries replayed
+    pub queries: usize,
+    /// Queries answered differently than recorded
+    pub changed: usize,
+    /// Every query, in session order
+    pub results: Vec<ReplayQueryOutput>,
+}
+
+/// One query in a `ReplayOutput`.
+#[derive(Debug, Serialize)]
+pub struct ReplayQueryOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Point in time the query was made for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+    /// True if the answers differ
+    pub changed: bool,
+    /// Answer from the session
+    pub recorded: ReplayAnswerOutput,
+    /// Answer from the replay graph
+    pub replayed: ReplayAnswerOutput,
+}
+
+/// A query's answer, reduced to what a replay compares.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReplayAnswerOutput {
+    /// Node names from source to destination, absent on error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<Arc<str>>>,
+    /// Total latency in milliseconds, absent on error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u32>,
+    /// Why there is no path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// JSON-serializable result of a weight-noise simulation.
+#[derive(Debug, Serialize)]
+pub struct StabilityOutput {
+    /// Source node name
+    pub from: String,
+    /// Destination node name
+    pub to: String,
+    /// Largest change to any edge latency, in percent
+    pub noise_percent: f64,
+    /// Number of perturbed graphs
+    pub trials: u32,
+    /// Seed of the random perturbations
+    pub seed: u64,
+    /// True if `--verify` re-ran the analysis and got the same result
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub verified: bool,
+    /// Shortest path on the unperturbed graph
+    pub baseline_path: Vec<Arc<str>>,
+    /// Latency of the baseline path on the unperturbed graph
+    pub baseline_latency_ms: u32,
+    /// Trials whose shortest path differs from the baseline
+    pub route_changes: u32,
+    /// Fraction of trials whose shortest path differs from the baseline
+    pub route_change_probability: f64,
+    /// Distribution of the shortest path latency over all trials
+    pub latency: LatencyDistributionOutput,
+    /// Every route chosen at least once, most frequent first
+    pub routes: Vec<RouteFrequencyOutput>,
+    /// Content hash of the graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable summary of a latency sample in milliseconds.
+#[derive(Debug, Serialize)]
+pub struct LatencyDistributionOutput {
+    pub min: u32,
+    pub p50: u32,
+    pub p95: u32,
+    pub p99: u32,
+    pub max: u32,
+    pub mean: f64,
+}
+
+/// JSON-serializable route with the number of trials that chose it.
+#[derive(Debug, Serialize)]
+pub struct RouteFrequencyOutput {
+    pub path: Vec<Arc<str>>,
+    pub count: u32,
+}
+
+/// JSON-serializable topology change set proposed by the optimizer.
+#[derive(Debug, Serialize)]
+pub struct OptimizeOutput {
+    /// `max-latency` or `bridges`
+    pub objective: String,
+    /// Objective value before the changes
+    pub before: f64,
+    /// Objective value after the changes
+    pub after: f64,
+    /// Most the changes were allowed to cost
+    pub budget: f64,
+    /// What the proposed changes cost
+    pub total_cost: f64,
+    /// Edges to add
+    pub changes: Vec<ProposedEdgeOutput>,
+    /// Latency of each key route before and after (None if unreachable)
+    pub routes: Vec<RouteChangeOutput>,
+    /// Annealing steps run
+    pub iterations: u32,
+    /// Seed of the random search
+    pub seed: u64,
+    /// True if `--verify` re-ran the analysis and got the same result
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub verified: bool,
+    /// Content hash of the original graph
+    pub graph_hash: String,
+}
+
+/// JSON-serializable edge proposed for addition.
+#[derive(Debug, Serialize)]
+pub struct ProposedEdgeOutput {
+    pub from: String,
+    pub to: String,
+    pub latency_ms: f32,
+    pub cost: f64,
+}
+
+/// JSON-serializable latency of a route before and after a change.
+#[derive(Debug, Serialize)]
+pub struct RouteChangeOutput {
+    pub from: String,
+    pub to: String,
+    pub before_ms: Option<u32>,
+    pub after_ms: Option<u32>,
+}
+
+/// JSON-serializable tour through a set of nodes.
+#[derive(Debug, Serialize)]
+pub struct TourOutput {
+    /// Nodes in visiting order, starting with the first requested node
+    pub order: Vec<String>,
+    /// Whether the tour returns to its starting node
+    pub round_trip: bool,
+    /// Sum of all leg latencies in milliseconds
+    pub total_latency_ms: u64,
+    /// Shortest path for each leg, in visiting order
+    pub legs: Vec<PathOutput>,
+    /// Content hash of the graph the tour was planned on
+    pub graph_hash: String,
+}
+
+/// JSON-serializable latency budget for one hop.
+#[derive(Debug, Serialize)]
+pub struct HopBudgetOutput {
+    /// Stable edge ID
+    pub id: Arc<str>,
+    /// Source node name
+    pub from: Arc<str>,
+    /// Destination node name
+    pub to: Arc<str>,
+    /// Current hop latency in milliseconds
+    pub latency_ms: u32,
+    /// Allocated budget in milliseconds
+    pub budget_ms: f64,
+    /// Budget minus current latency (negative if over budget)
+    pub headroom_ms: f64,
+}
+
+/// JSON-serializable cost of running a command, added to JSON output as
+/// `stats` when requested.
+#[derive(Debug, Serialize)]
+pub struct StatsOutput {
+    /// Shortest path searches and the work they did
+    #[serde(flatten)]
+    pub search: crate::stats::SearchStats,
+    /// Wall-clock time from start to output, in milliseconds
+    pub wall_time_ms: f64,
+    /// Peak resident memory of the process in bytes, where the platform
+    /// reports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// JSON-serializable warning about input that was accepted but is likely a
+/// mistake.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WarningOutput {
+    /// Machine-readable category, e.g. `isolated_nodes`
+    pub kind: String,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl WarningOutput {
+    /// Creates a warning of the given kind.
+    pub fn new(kind: &str, message: impl Into<String>) -> WarningOutput {
+        WarningOutput {
+            kind: kind.to_string(),
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Named-node shortest path engine.
+//!
+//! Graphs are directed, with string node names and per-edge latencies in
+//! milliseconds. The engine finds lowest-latency paths with Dijkstra's
+//! algorithm, checks them against SLOs, and simulates edge changes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use gt_path_core::{Constraints, Graph, SloCheck};
+//!
+//! let graph = Graph::load_json("graph.json")?;
+//! let path = graph.shortest_path("api", "db")?;
+//! println!("{} ({}ms)", graph.format_path(&path), path.cost);
+//!
+//! let check = SloCheck::run(&graph, "api", "db", 100, &Constraints::default())?;
+//! assert!(check.met());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Used by the `gt-path` CLI and the `gtools-ffi` C bindings.
+
+pub mod arc_graph;
+pub mod availability;
+pub mod backbone;
+pub mod cache;
+pub mod compose;
+pub mod constraint;
+pub mod cost;
+pub mod disk_cache;
+pub mod error;
+pub mod graph;
+pub mod incremental;
+pub mod io;
+pub mod oracle;
+pub mod path;
+pub mod predicate;
+pub mod simulation;
+pub mod slo;
+pub mod stats;
+pub mod time;
+pub mod watch;
+
+pub use arc_graph::ArcGraph;
+pub use availability::RouteAvailability;
+pub use cache::{CacheStats, PathCache};
+pub use constraint::{AttrFilter, Constraints};
+pub use cost::CostExpr;
+pub use disk_cache::DiskCache;
+pub use error::{GraphBuildError, PathError, SimulationError};
+pub use graph::{Graph, NodeId};
+pub use incremental::ShortestPathTree;
+pub use io::GraphInput;
+pub use oracle::DistanceOracle;
+pub use path::{Edge, Path};
+pub use simulation::{Modifications, Simulation};
+pub use slo::SloCheck;
+pub use stats::SearchStats;
+pub use watch::GraphWatcher;
@@ -0,0 +1,293 @@
+use crate::constraint::Constraints;
+use crate::graph::{Graph, NodeId};
+use crate::stats::SearchStats;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Distance reported for unreachable nodes.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+/// Approximate shortest path latencies from a landmark embedding.
+///
+/// Each landmark stores its distance to and from every node, so by the
+/// triangle inequality any pair's latency lies between
+/// `max |d(L, v) - d(L, u)|, |d(u, L) - d(v, L)|` and
+/// `min d(u, L) + d(L, v)` over the landmarks. Building takes two Dijkstra
+/// runs per landmark; each query afterwards is O(landmarks), which makes
+/// estimates across millions of pairs affordable when exact all-pairs
+/// isn't.
+///
+/// # Example
+///
+/// ```no_run
+/// use gt_path_core::{Constraints, DistanceOracle, Graph};
+///
+/// let graph = Graph::load_json("graph.json")?;
+/// let oracle = DistanceOracle::build(&graph, 16, &Constraints::default());
+/// let (api, db) = (graph.to_id["api"], graph.to_id["db"]);
+/// let (lower, upper) = oracle.bounds(api, db);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Serialize, Deserialize)]
+pub struct DistanceOracle {
+    /// Landmark nodes, in the order they were picked
+    pub landmarks: Vec<NodeId>,
+    /// `from_landmark[i][v]` is the latency from landmark `i` to `v`
+    from_landmark: Vec<Vec<u32>>,
+    /// `to_landmark[i][v]` is the latency from `v` to landmark `i`
+    to_landmark: Vec<Vec<u32>>,
+}
+
+impl DistanceOracle {
+    /// Picks up to `k` landmarks by farthest-point selection and computes
+    /// their distances. The first landmark is the node with the most
+    /// links; each next one is the node farthest (in either direction)
+    /// from every landmark picked so far, so nodes in parts of the graph
+    /// no landmark reaches are picked first.
+    pub fn build(graph: &Graph, k: usize, constraints: &Constraints) -> DistanceOracle {
+        let n = graph.to_name.len();
+        let reverse = reverse_adjacency(graph);
+        let mut oracle = DistanceOracle {
+            landmarks: Vec::new(),
+            from_landmark: Vec::new(),
+            to_landmark: Vec::new(),
+        };
+        if n == 0 {
+            return oracle;
+        }
+
+        let mut degree = vec![0usize; n];
+        for (u, links) in graph.adj.iter().enumerate() {
+            degree[u] += links.len();
+            for link in links {
+                degree[link.to.0 as usize] += 1;
+            }
+        }
+        let mut next = (0..n).max_by_key(|&v| (degree[v], Reverse(v))).unwrap_or(0);
+        // distance from each node to its nearest landmark, either direction
+        let mut nearest = vec![UNREACHABLE; n];
+
+        for _ in 0..k.min(n) {
+            let from = distances(graph, NodeId(next as u32), constraints);
+            let to = dijkstra(&reverse, graph, next, constraints);
+            for ((near, &f), &t) in nearest.iter_mut().zip(&from).zip(&to) {
+                *near = (*near).min(f.min(t));
+            }
+            oracle.landmarks.push(NodeId(next as u32));
+            oracle.from_landmark.push(from);
+            oracle.to_landmark.push(to);
+
+            match (0..n)
+                .filter(|&v| nearest[v] > 0)
+                .max_by_key(|&v| (nearest[v], Reverse(v)))
+            {
+                Some(v) => next = v,
+                None => break,
+            }
+        }
+        oracle
+    }
+
+    /// Lower and upper bounds on the latency from `u` to `v`. The upper
+    /// bound is `UNREACHABLE` if no landmark lies on a route between them,
+    /// which includes pairs that aren't connected at all.
+    pub fn bounds(&self, u: NodeId, v: NodeId) -> (u32, u32) {
+        let (u, v) = (u.0 as usize, v.0 as usize);
+        if u == v {
+            return (0, 0);
+        }
+        let mut lower = 0;
+        let mut upper = UNREACHABLE;
+        for (from, to) in self.from_landmark.iter().zip(&self.to_landmark) {
+            if to[u] != UNREACHABLE && from[v] != UNREACHABLE {
+                upper = upper.min(to[u].saturating_add(from[v]));
+            }
+            // d(L, v) <= d(L, u) + d(u, v)
+            if from[v] != UNREACHABLE && from[u] != UNREACHABLE {
+                lower = lower.max(from[v].saturating_sub(from[u]));
+            }
+            // d(u, L) <= d(u, v) + d(v, L)
+            if to[u] != UNREACHABLE && to[v] != UNREACHABLE {
+                lower = lower.max(to[u].saturating_sub(to[v]));
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Estimates the latency from `u` to `v` as the upper bound (the
+    /// latency of a real route through a landmark), if it is within
+    /// `max_error` of the true latency: `upper <= lower * (1 + max_error)`.
+    /// Returns None when the bounds are too loose to guarantee that.
+    pub fn estimate(&self, u: NodeId, v: NodeId, max_error: f64) -> Option<u32> {
+        let (lower, upper) = self.bounds(u, v);
+        (upper != UNREACHABLE && upper as f64 <= lower as f64 * (1.0 + max_error)).then_some(upper)
+    }
+}
+
+/// Latency from `source` to every node honoring `constraints`, with
+/// `UNREACHABLE` for nodes no allowed path reaches.
+pub fn distances(graph: &Graph, source: NodeId, constraints: &Constraints) -> Vec<u32> {
+    distances_without(graph, source, constraints, &[])
+}
+
+/// Like `distances`, without the edges marked in `down`, indexed by
+/// `Link::edge`; edges past its end are up.
+pub fn distances_without(
+    graph: &Graph,
+    source: NodeId,
+    constraints: &Constraints,
+    down: &[bool],
+) -> Vec<u32> {
+    let forward: Vec<Vec<(usize, u32, usize)>> = graph
+        .adj
+        .iter()
+        .map(|links| {
+            links
+                .iter()
+                .filter(|l| !down.get(l.edge).copied().unwrap_or(false))
+                .map(|l| (l.to.0 as usize, l.latency_ms, l.edge))
+                .collect()
+        })
+        .collect();
+    dijkstra(&forward, graph, source.0 as usize, constraints)
+}
+
+/// In-links of each node as (tail, latency, edge index).
+fn reverse_adjacency(graph: &Graph) -> Vec<Vec<(usize, u32, usize)>> {
+    let mut reverse = vec![Vec::new(); graph.to_name.len()];
+    for (u, links) in graph.adj.iter().enumerate() {
+        for l in links {
+            reverse[l.to.0 as usize].push((u, l.latency_ms, l.edge));
+        }
+    }
+    reverse
+}
+
+fn dijkstra(
+    adj: &[Vec<(usize, u32, usize)>],
+    graph: &Graph,
+    source: usize,
+    constraints: &Constraints,
+) -> Vec<u32> {
+    let allowed: Vec<bool> = graph
+        .node_attrs
+        .iter()
+        .map(|attrs| constraints.allows_node(attrs))
+        .collect();
+    let mut dist = vec![UNREACHABLE; adj.len()];
+    if !allowed[source] {
+        return dist;
+    }
+    dist[source] = 0;
+    let mut heap = BinaryHeap::from([Reverse((0u32, source))]);
+    let mut stats = SearchStats {
+        heap_pushes: 1,
+        ..SearchStats::default()
+    };
+    while let Some(Reverse((cost, u))) = heap.pop() {
+        if cost > dist[u] {
+            continue;
+        }
+        stats.nodes_visited += 1;
+        for &(v, latency_ms, edge) in &adj[u] {
+            stats.edges_relaxed += 1;
+            if !allowed[v]
+                || !constraints.allows_edge(&graph.edge_ids[edge], &graph.edge_attrs[edge])
+            {
+                continue;
+            }
+            let candidate = cost.saturating_add(latency_ms);
+            if candidate < dist[v] {
+                dist[v] = candidate;
+                heap.push(Reverse((candidate, v)));
+                stats.heap_pushes += 1;
+            }
+        }
+    }
+    stats.record();
+    dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GraphInput;
+
+    /// Ring of `n` nodes with links both ways, plus a chord every 5 nodes.
+    fn ring(n: usize) -> Graph {
+        let mut edges = Vec::new();
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let w = i % 4 + 1;
+            edges.push(format!(
+                r#"{{"from": "n{i}", "to": "n{j}", "latency_ms": {w}}}"#
+            ));
+            edges.push(format!(
+                r#"{{"from": "n{j}", "to": "n{i}", "latency_ms": {}}}"#,
+                w + 1
+            ));
+            if i % 5 == 0 {
+                let k = (i + n / 2) % n;
+                edges.push(format!(
+                    r#"{{"from": "n{i}", "to": "n{k}", "latency_ms": 9}}"#
+                ));
+            }
+        }
+        let nodes: Vec<String> = (0..n).map(|i| format!("\"n{i}\"")).collect();
+        let json = format!(
+            r#"{{"nodes": [{}], "edges": [{}]}}"#,
+            nodes.join(","),
+            edges.join(",")
+        );
+        let input: GraphInput = serde_json::from_str(&json).unwrap();
+        Graph::try_from(input).unwrap()
+    }
+
+    #[test]
+    fn test_bounds_contain_exact_distance() {
+        let g = ring(40);
+        let c = Constraints::default();
+        let oracle = DistanceOracle::build(&g, 4, &c);
+        assert_eq!(oracle.landmarks.len(), 4);
+
+        for u in 0..40 {
+            let exact = distances(&g, NodeId(u), &c);
+            for (v, &d) in exact.iter().enumerate() {
+                let (lower, upper) = oracle.bounds(NodeId(u), NodeId(v as u32));
+                assert!(lower <= d && d <= upper, "{u} -> {v}");
+                if let Some(estimate) = oracle.estimate(NodeId(u), NodeId(v as u32), 0.25) {
+                    assert!(estimate as f64 <= d as f64 * 1.25);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_landmark_pairs_are_exact() {
+        let g = ring(12);
+        let c = Constraints::default();
+        let oracle = DistanceOracle::build(&g, 2, &c);
+        let l = oracle.landmarks[0];
+        let exact = distances(&g, l, &c);
+        for v in 0..12 {
+            assert_eq!(oracle.estimate(l, NodeId(v), 0.0), Some(exact[v as usize]));
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let input: GraphInput = serde_json::from_str(
+            r#"{"nodes": ["a", "b", "c"], "edges": [{"from": "a", "to": "b", "latency_ms": 1}]}"#,
+        )
+        .unwrap();
+        let g = Graph::try_from(input).unwrap();
+        let oracle = DistanceOracle::build(&g, 3, &Constraints::default());
+        assert_eq!(oracle.bounds(NodeId(1), NodeId(0)).1, UNREACHABLE);
+        assert_eq!(oracle.estimate(NodeId(0), NodeId(2), 1.0), None);
+        assert_eq!(
+            distances(&g, NodeId(0), &Constraints::default()),
+            vec![0, 1, UNREACHABLE]
+        );
+    }
+}
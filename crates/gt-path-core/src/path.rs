@@ -4,25 +4,31 @@ use crate::graph::NodeId;
 ///
 /// Returned by `Graph::shortest_path()` to indicate the sequence of nodes
 /// and the total latency in milliseconds.
-pub(crate) struct Path {
+#[derive(PartialEq)]
+pub struct Path {
     /// Source node
-    pub(crate) from: NodeId,
+    pub from: NodeId,
     /// Destination node
-    pub(crate) to: NodeId,
+    pub to: NodeId,
     /// Sequence of nodes from source to destination
-    pub(crate) path: Vec<NodeId>,
+    pub path: Vec<NodeId>,
+    /// Edges traversed from source to destination
+    pub edges: Vec<Edge>,
     /// Total latency in milliseconds
-    pub(crate) cost: u32,
+    pub cost: u32,
     /// Edge with the highest latency along the path
-    pub(crate) bottleneck: Option<Edge>,
+    pub bottleneck: Option<Edge>,
 }
 
 /// Represents a directed edge in the graph with its latency.
-pub(crate) struct Edge {
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge {
     /// Source node
-    pub(crate) from: NodeId,
+    pub from: NodeId,
     /// Destination node
-    pub(crate) to: NodeId,
+    pub to: NodeId,
     /// Edge latency/weight in milliseconds
-    pub(crate) latency_ms: u32,
+    pub latency_ms: u32,
+    /// Index of the edge in the graph's edge attribute table
+    pub index: usize,
 }
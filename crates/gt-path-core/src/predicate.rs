@@ -0,0 +1,358 @@
+use crate::io::Attributes;
+use std::hash::{Hash, Hasher};
+
+/// A `--where` filter such as `region != "eu-west" && tier >= 2`, tested
+/// against the attributes of each node and edge a search may use.
+///
+/// Comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`) take an attribute name
+/// on the left and a string, number, or `true`/`false` on the right, and
+/// combine with `&&`, `||`, `!`, and parentheses. A bare name reads the
+/// attribute of whichever node or edge is tested; `node.` and `edge.`
+/// restrict it to one kind.
+///
+/// A comparison on an attribute the element doesn't have is unknown, and
+/// an element is excluded only when the whole filter is definitely false
+/// for it. So `tier >= 2` excludes nodes with a lower tier but not edges,
+/// which have no tier.
+///
+/// # Example
+///
+/// ```ignore
+/// let filter = Predicate::parse(r#"region != "eu-west" && edge.provider == "acme""#)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Predicate {
+    source: String,
+    root: Expr,
+}
+
+/// Which kind of element a predicate is tested against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// A node, with its attributes
+    Node,
+    /// An edge, with its attributes
+    Edge,
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Compare {
+        scope: Option<Scope>,
+        key: String,
+        op: &'static str,
+        value: Value,
+    },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Name(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+}
+
+const OPS: [&str; 11] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")"];
+
+impl Predicate {
+    /// Parses a filter expression.
+    pub fn parse(s: &str) -> anyhow::Result<Predicate> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        let root = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            anyhow::bail!("unexpected {}", describe(token));
+        }
+        Ok(Predicate {
+            source: s.to_string(),
+            root,
+        })
+    }
+
+    /// Returns false if the filter is definitely false for an element of
+    /// `scope` with these attributes.
+    pub fn allows(&self, scope: Scope, attrs: &Attributes) -> bool {
+        eval(&self.root, scope, attrs) != Some(false)
+    }
+}
+
+impl std::fmt::Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+// filters are compared by their text, so they can key result caches
+impl PartialEq for Predicate {
+    fn eq(&self, other: &Predicate) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Predicate {}
+
+impl Hash for Predicate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+/// Evaluates with three-valued logic: None is unknown.
+fn eval(expr: &Expr, scope: Scope, attrs: &Attributes) -> Option<bool> {
+    match expr {
+        Expr::Compare {
+            scope: only,
+            key,
+            op,
+            value,
+        } => {
+            if only.is_some_and(|only| only != scope) {
+                return None;
+            }
+            compare(attrs.get(key)?, op, value)
+        }
+        Expr::Not(inner) => eval(inner, scope, attrs).map(|b| !b),
+        Expr::And(lhs, rhs) => match (eval(lhs, scope, attrs), eval(rhs, scope, attrs)) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        Expr::Or(lhs, rhs) => match (eval(lhs, scope, attrs), eval(rhs, scope, attrs)) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+    }
+}
+
+/// Compares an attribute with a literal. Numbers compare numerically and
+/// strings lexically; other combinations are only ever unequal.
+fn compare(attr: &serde_json::Value, op: &str, value: &Value) -> Option<bool> {
+    use std::cmp::Ordering;
+
+    let ordering = match (attr, value) {
+        (serde_json::Value::Number(a), Value::Num(b)) => a.as_f64()?.partial_cmp(b),
+        (serde_json::Value::String(a), Value::Str(b)) => Some(a.as_str().cmp(b)),
+        (serde_json::Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    Some(match (op, ordering) {
+        ("==", ordering) => ordering == Some(Ordering::Equal),
+        ("!=", ordering) => ordering != Some(Ordering::Equal),
+        (_, None) => false,
+        ("<", Some(o)) => o.is_lt(),
+        ("<=", Some(o)) => o.is_le(),
+        (">", Some(o)) => o.is_gt(),
+        (_, Some(o)) => o.is_ge(),
+    })
+}
+
+fn tokenize(s: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            op.len()
+        } else if c == '"' || c == '\'' {
+            let Some(end) = rest[1..].find(c) else {
+                anyhow::bail!("unterminated string {}", rest);
+            };
+            tokens.push(Token::Str(rest[1..end + 1].to_string()));
+            end + 2
+        } else if c.is_ascii_digit() || c == '-' || c == '.' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '-' || c == '.'))
+                .unwrap_or(rest.len());
+            let number = &rest[..len];
+            let value = number
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid number '{}'", number))?;
+            tokens.push(Token::Num(value));
+            len
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '-'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..len].to_string()));
+            len
+        } else {
+            anyhow::bail!("unexpected '{}'", c);
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Name(name) => format!("'{}'", name),
+        Token::Str(s) => format!("\"{}\"", s),
+        Token::Num(n) => n.to_string(),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+/// Recursive descent parser:
+///
+/// ```text
+/// or      = and ('||' and)*
+/// and     = unary ('&&' unary)*
+/// unary   = '!' unary | '(' or ')' | compare
+/// compare = name ('==' | '!=' | '<' | '<=' | '>' | '>=') literal
+/// ```
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> anyhow::Result<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("unexpected end of expression"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some(Token::Op(o)) if *o == op);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.and()?;
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> anyhow::Result<Expr> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.or()?;
+            if !self.eat(")") {
+                anyhow::bail!("expected ')'");
+            }
+            return Ok(expr);
+        }
+
+        let name = match self.next()? {
+            Token::Name(name) => name,
+            token => anyhow::bail!("expected an attribute name, found {}", describe(&token)),
+        };
+        let (scope, key) = match name.split_once('.') {
+            Some(("node", key)) => (Some(Scope::Node), key.to_string()),
+            Some(("edge", key)) => (Some(Scope::Edge), key.to_string()),
+            _ => (None, name),
+        };
+        let op = match self.next()? {
+            Token::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">=")) => op,
+            token => anyhow::bail!(
+                "expected a comparison after '{}', found {}",
+                key,
+                describe(&token)
+            ),
+        };
+        let value = match self.next()? {
+            Token::Str(s) => Value::Str(s),
+            Token::Num(n) => Value::Num(n),
+            Token::Name(b) if b == "true" || b == "false" => Value::Bool(b == "true"),
+            token => anyhow::bail!(
+                "expected a value after '{}', found {}",
+                op,
+                describe(&token)
+            ),
+        };
+        Ok(Expr::Compare {
+            scope,
+            key,
+            op,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn attrs(value: serde_json::Value) -> Attributes {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_allows() {
+        let filter = Predicate::parse(r#"region != "eu-west" && tier >= 2"#).unwrap();
+        assert!(filter.allows(Scope::Node, &attrs(json!({"region": "us-east", "tier": 2}))));
+        assert!(!filter.allows(Scope::Node, &attrs(json!({"region": "eu-west", "tier": 3}))));
+        assert!(!filter.allows(Scope::Node, &attrs(json!({"tier": 1}))));
+        // unknown is not false: edges have neither attribute
+        assert!(filter.allows(Scope::Edge, &attrs(json!({"provider": "acme"}))));
+
+        let filter = Predicate::parse("edge.metered == true || !(edge.capacity < 100)").unwrap();
+        assert!(filter.allows(Scope::Node, &attrs(json!({"capacity": 10}))));
+        assert!(!filter.allows(
+            Scope::Edge,
+            &attrs(json!({"capacity": 10, "metered": false}))
+        ));
+        assert!(filter.allows(
+            Scope::Edge,
+            &attrs(json!({"capacity": 10, "metered": true}))
+        ));
+        // unknown || false is still unknown
+        assert!(filter.allows(Scope::Edge, &attrs(json!({"capacity": 10}))));
+
+        // mismatched types are unequal
+        let filter = Predicate::parse("tier == '2'").unwrap();
+        assert!(!filter.allows(Scope::Node, &attrs(json!({"tier": 2}))));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "",
+            "tier",
+            "tier >=",
+            "tier >= 2 &&",
+            "(tier >= 2",
+            "tier >= 2)",
+            "2 >= tier",
+            "region == \"eu",
+            "tier = 2",
+            "tier >= 2 region",
+        ] {
+            assert!(Predicate::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
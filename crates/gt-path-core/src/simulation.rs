@@ -0,0 +1,620 @@
+use crate::constraint::Constraints;
+use crate::error::{PathError, SimulationError};
+use crate::graph::Graph;
+use crate::io::Attributes;
+use crate::path::Path;
+use std::collections::BTreeMap;
+
+/// Edge changes applied in a what-if simulation.
+#[derive(Clone, Debug, Default)]
+pub struct Modifications {
+    /// Edges to re-weight: (from, to, new latency in ms)
+    pub overrides: Vec<(String, String, u32)>,
+    /// Overrides with glob endpoints or relative latencies, applied after
+    /// `overrides` once `resolve` expands them
+    pub rules: Vec<OverrideRule>,
+    /// Edges to remove: (from, to)
+    pub drops: Vec<(String, String)>,
+    /// Edges to re-weight by ID: (edge ID, new latency in ms), applied
+    /// after `overrides`
+    pub edge_overrides: Vec<(String, u32)>,
+    /// ID overrides with relative latencies, applied after `edge_overrides`
+    /// once `resolve` expands them
+    pub edge_rules: Vec<(String, Adjustment)>,
+    /// Edges to remove by ID, applied after `drops`
+    pub edge_drops: Vec<String>,
+    /// Add the edge for an override that names one the graph doesn't have,
+    /// instead of failing
+    pub create_missing: bool,
+    /// Scale the traffic on every edge by this factor, inflating latencies
+    /// as `congestion` models; applied after everything else
+    pub demand: Option<f64>,
+}
+
+impl Modifications {
+    /// Parses `from:to:weight` overrides and `from:to` drops. Either may
+    /// name a single edge by its ID instead: `ID:weight` and `ID`.
+    ///
+    /// An override's endpoints may be glob patterns (`*` matches any run of
+    /// characters, `?` any one), and its weight may be relative to the
+    /// edge's latency: `+30` or `-5` adds, `+20%` or `-10%` changes it by a
+    /// percentage, and `*1.5` multiplies. These become
+    /// `rules`; plain overrides stay in `overrides`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mods = Modifications::parse(&["auth:db:50".into()], &["api:cache".into()])?;
+    /// let mods = Modifications::parse(&["eu-*:us-*:+30".into()], &[])?;
+    /// let mods = Modifications::parse(&["3f9a0c12d4e5:+10".into()], &["a81b7e04c3d2".into()])?;
+    /// ```
+    pub fn parse(overrides: &[String], drops: &[String]) -> anyhow::Result<Modifications> {
+        use anyhow::Context;
+
+        let mut mods = Modifications::default();
+
+        for override_str in overrides {
+            let parts: Vec<&str> = override_str.split(':').collect();
+            if !(2..=3).contains(&parts.len()) {
+                anyhow::bail!(
+                    "Invalid override format '{}'. Expected 'from:to:weight' or 'id:weight'",
+                    override_str
+                );
+            }
+            let weight = parts[parts.len() - 1];
+            let adjustment = Adjustment::parse(weight).context(format!(
+                "Invalid weight '{}' in override '{}'",
+                weight, override_str
+            ))?;
+            if let [id, _] = parts[..] {
+                match adjustment {
+                    Adjustment::Set(weight) => mods.edge_overrides.push((id.to_string(), weight)),
+                    adjustment => mods.edge_rules.push((id.to_string(), adjustment)),
+                }
+                continue;
+            }
+            let (from, to) = (parts[0].to_string(), parts[1].to_string());
+            match adjustment {
+                Adjustment::Set(weight) if !is_pattern(&from) && !is_pattern(&to) => {
+                    mods.overrides.push((from, to, weight))
+                }
+                adjustment => mods.rules.push(OverrideRule {
+                    from,
+                    to,
+                    adjustment,
+                }),
+            }
+        }
+
+        for drop_str in drops {
+            match drop_str.split(':').collect::<Vec<_>>()[..] {
+                [id] => mods.edge_drops.push(id.to_string()),
+                [from, to] => mods.drops.push((from.to_string(), to.to_string())),
+                _ => anyhow::bail!(
+                    "Invalid drop format '{}'. Expected 'from:to' or 'id'",
+                    drop_str
+                ),
+            }
+        }
+
+        Ok(mods)
+    }
+
+    /// Parses a `--demand` change: a percentage (`+20%`, `-10%`) or a
+    /// factor (`*1.5`), returned as the factor to scale traffic by.
+    pub fn parse_demand(s: &str) -> anyhow::Result<f64> {
+        match Adjustment::parse(s) {
+            Ok(Adjustment::Scale(factor)) => Ok(factor),
+            _ => anyhow::bail!(
+                "Invalid demand change '{}'. Expected a percentage like +20% or a factor like *1.2",
+                s
+            ),
+        }
+    }
+
+    /// Returns these modifications with every rule expanded into plain
+    /// overrides of the matching edges in `graph`. Relative latencies are
+    /// applied to the graph's latencies; edges that `drops` removes aren't
+    /// matched. A rule matching no edge fails with `PathError::EdgeNotFound`,
+    /// naming its patterns, and an edge ID the graph doesn't have with
+    /// `PathError::EdgeIdNotFound`.
+    pub fn resolve(&self, graph: &Graph) -> Result<Modifications, PathError> {
+        let mut resolved = Modifications {
+            rules: Vec::new(),
+            edge_rules: Vec::new(),
+            ..self.clone()
+        };
+        let ids = self.edge_overrides.iter().map(|(id, _)| id);
+        for id in ids.chain(&self.edge_drops) {
+            if graph.edge_index(id).is_none() {
+                return Err(PathError::EdgeIdNotFound(id.clone()));
+            }
+        }
+        for (id, adjustment) in &self.edge_rules {
+            let (u, i) = graph
+                .edge_index(id)
+                .and_then(|edge| graph.find_link(edge))
+                .ok_or_else(|| PathError::EdgeIdNotFound(id.clone()))?;
+            resolved
+                .edge_overrides
+                .push((id.clone(), adjustment.apply(graph.adj[u][i].latency_ms)));
+        }
+        for rule in &self.rules {
+            // the fastest of parallel edges is the one routing uses
+            let mut matched: BTreeMap<(usize, usize), u32> = BTreeMap::new();
+            for (u, links) in graph.adj.iter().enumerate() {
+                let from = &graph.to_name[u];
+                if !glob_match(&rule.from, from) {
+                    continue;
+                }
+                for link in links {
+                    let to = &graph.to_name[link.to.0 as usize];
+                    let dropped = self.drops.iter().any(|(f, t)| **f == **from && **t == **to);
+                    if glob_match(&rule.to, to) && !dropped {
+                        let latency = matched.entry((u, link.to.0 as usize)).or_insert(u32::MAX);
+                        *latency = (*latency).min(link.latency_ms);
+                    }
+                }
+            }
+            if matched.is_empty() {
+                return Err(PathError::EdgeNotFound {
+                    from: rule.from.clone(),
+                    to: rule.to.clone(),
+                });
+            }
+            for ((u, v), latency_ms) in matched {
+                resolved.overrides.push((
+                    graph.to_name[u].to_string(),
+                    graph.to_name[v].to_string(),
+                    rule.adjustment.apply(latency_ms),
+                ));
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Returns a copy of `graph` with these modifications applied: rules
+    /// resolved, then drops, then overrides, then the same by edge ID, and
+    /// finally the change in demand.
+    pub fn apply(&self, graph: &Graph) -> Result<Graph, PathError> {
+        self.apply_congested(graph).map(|(graph, _)| graph)
+    }
+
+    /// Like `apply`, also returning the edges the change in demand affects.
+    fn apply_congested(&self, graph: &Graph) -> Result<(Graph, Vec<CongestedEdge>), PathError> {
+        let mods = self.resolve(graph)?;
+        let mut modified = if mods.create_missing {
+            graph.with_modifications_creating(&mods.overrides, &mods.drops)?
+        } else {
+            graph.with_modifications(&mods.overrides, &mods.drops)?
+        };
+        if !mods.edge_overrides.is_empty() || !mods.edge_drops.is_empty() {
+            modified = modified.with_edge_modifications(&mods.edge_overrides, &mods.edge_drops)?;
+        }
+        let Some(scale) = self.demand else {
+            return Ok((modified, Vec::new()));
+        };
+
+        let congested = congestion(&modified, scale);
+        let (mut overrides, mut drops) = (Vec::new(), Vec::new());
+        for c in &congested {
+            let id = modified.edge_ids[c.edge].to_string();
+            match c.congested_latency_ms {
+                Some(latency_ms) => overrides.push((id, latency_ms)),
+                None => drops.push(id),
+            }
+        }
+        let modified = modified.with_edge_modifications(&overrides, &drops)?;
+        Ok((modified, congested))
+    }
+
+    /// Drops naming an edge the graph doesn't have, as (from, to).
+    /// `Graph::with_modifications` ignores these.
+    pub fn unmatched_drops(&self, graph: &Graph) -> Vec<(String, String)> {
+        let exists = |from: &str, to: &str| match (graph.to_id.get(from), graph.to_id.get(to)) {
+            (Some(from), Some(to)) => graph.adj[from.0 as usize].iter().any(|l| l.to == *to),
+            _ => false,
+        };
+        self.drops
+            .iter()
+            .filter(|(from, to)| !exists(from, to))
+            .cloned()
+            .collect()
+    }
+}
+
+/// An edge whose latency depends on its traffic; see `congestion`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CongestedEdge {
+    /// Index of the edge, as in `Link::edge`
+    pub edge: usize,
+    /// Utilization the edge's latency was measured at
+    pub utilization: f64,
+    /// Utilization once traffic is scaled
+    pub scaled_utilization: f64,
+    /// Latency as measured
+    pub latency_ms: u32,
+    /// Latency once traffic is scaled, None if the edge saturates
+    pub congested_latency_ms: Option<u32>,
+}
+
+/// Utilization an edge's latency was measured at, from its attributes:
+/// `utilization` if declared, otherwise `load_rps` over `capacity`.
+pub fn utilization(attrs: &Attributes) -> Option<f64> {
+    let number = |key: &str| attrs.get(key).and_then(serde_json::Value::as_f64);
+    number("utilization").or_else(|| match (number("load_rps"), number("capacity")) {
+        (Some(load), Some(capacity)) if capacity > 0.0 => Some(load / capacity),
+        _ => None,
+    })
+}
+
+/// Latency of an M/M/1 queue measured at `latency_ms` and `utilization`
+/// once its utilization becomes `new_utilization`: its idle latency,
+/// `latency_ms (1 - utilization)`, over `1 - new_utilization`. None once
+/// `new_utilization` reaches 1 and the queue grows without bound.
+pub fn queue_latency(latency_ms: f64, utilization: f64, new_utilization: f64) -> Option<f64> {
+    (new_utilization < 1.0).then(|| latency_ms * (1.0 - utilization) / (1.0 - new_utilization))
+}
+
+/// What scaling the traffic on every edge by `scale` does to the latency
+/// of the edges with a known `utilization` in [0, 1).
+///
+/// Each edge is modelled as an M/M/1 queue, whose latency is its idle
+/// latency over `1 - utilization`. So an edge measured at latency `L` and
+/// utilization `u` has latency `L (1 - u) / (1 - u × scale)` under the new
+/// traffic, rounded to whole milliseconds, and saturates once
+/// `u × scale` reaches 1. Edges are returned in index order.
+pub fn congestion(graph: &Graph, scale: f64) -> Vec<CongestedEdge> {
+    let mut congested = Vec::new();
+    for links in &graph.adj {
+        for link in links {
+            let Some(u) = utilization(&graph.edge_attrs[link.edge]) else {
+                continue;
+            };
+            if !(0.0..1.0).contains(&u) {
+                continue;
+            }
+            let scaled = u * scale;
+            congested.push(CongestedEdge {
+                edge: link.edge,
+                utilization: u,
+                scaled_utilization: scaled,
+                latency_ms: link.latency_ms,
+                congested_latency_ms: queue_latency(link.latency_ms as f64, u, scaled)
+                    .map(|latency| latency.round().min(u32::MAX as f64) as u32),
+            });
+        }
+    }
+    congested.sort_by_key(|c| c.edge);
+    congested
+}
+
+/// An override given by patterns or a relative latency, e.g. `eu-*:us-*:+30`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverrideRule {
+    /// Glob pattern for the source node
+    pub from: String,
+    /// Glob pattern for the destination node
+    pub to: String,
+    /// New latency of each matching edge
+    pub adjustment: Adjustment,
+}
+
+/// How an override changes an edge's latency.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Adjustment {
+    /// Replace it, in milliseconds (`100`)
+    Set(u32),
+    /// Add milliseconds to it, or subtract if negative (`+30`, `-5`)
+    Add(i64),
+    /// Multiply it (`*1.5`, or `+20%` as 1.2)
+    Scale(f64),
+}
+
+impl Adjustment {
+    fn parse(s: &str) -> anyhow::Result<Adjustment> {
+        Ok(if let Some(percent) = s.strip_suffix('%') {
+            let (sign, percent) = match percent.split_at_checked(1) {
+                Some(("+", p)) => (1.0, p),
+                Some(("-", p)) => (-1.0, p),
+                _ => anyhow::bail!("percentages must start with + or -"),
+            };
+            let percent = percent.parse::<f64>()?;
+            if !(percent.is_finite() && percent >= 0.0) {
+                anyhow::bail!("percentage must be a non-negative number");
+            }
+            Adjustment::Scale((1.0 + sign * percent / 100.0).max(0.0))
+        } else if let Some(delta) = s.strip_prefix('+') {
+            Adjustment::Add(delta.parse::<u32>()? as i64)
+        } else if let Some(delta) = s.strip_prefix('-') {
+            Adjustment::Add(-(delta.parse::<u32>()? as i64))
+        } else if let Some(factor) = s.strip_prefix('*') {
+            let factor = factor.parse::<f64>()?;
+            if !(factor.is_finite() && factor >= 0.0) {
+                anyhow::bail!("factor must be a non-negative number");
+            }
+            Adjustment::Scale(factor)
+        } else {
+            Adjustment::Set(s.parse()?)
+        })
+    }
+
+    /// The new latency for an edge of `latency_ms`, rounded to whole
+    /// milliseconds and never below 0.
+    pub fn apply(&self, latency_ms: u32) -> u32 {
+        match *self {
+            Adjustment::Set(latency) => latency,
+            Adjustment::Add(delta) => (latency_ms as i64 + delta).clamp(0, u32::MAX as i64) as u32,
+            Adjustment::Scale(factor) => {
+                (latency_ms as f64 * factor).round().min(u32::MAX as f64) as u32
+            }
+        }
+    }
+}
+
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+/// Whether `name` matches the glob `pattern`: `*` matches any run of
+/// characters, `?` exactly one, and anything else itself.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut i, mut j) = (0, 0);
+    // position of the last `*`, and where in `name` it started matching
+    let mut star: Option<(usize, usize)> = None;
+    while j < n.len() {
+        if i < p.len() && (p[i] == '?' || p[i] == n[j]) {
+            i += 1;
+            j += 1;
+        } else if i < p.len() && p[i] == '*' {
+            star = Some((i, j));
+            i += 1;
+        } else if let Some((s, m)) = star {
+            // let the `*` take one more character
+            i = s + 1;
+            j = m + 1;
+            star = Some((s, m + 1));
+        } else {
+            return false;
+        }
+    }
+    p[i..].iter().all(|&c| c == '*')
+}
+
+/// Shortest paths before and after applying modifications to a graph.
+///
+/// # Example
+///
+/// ```ignore
+/// let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default())?;
+/// println!("impact: {}ms", sim.latency_change_ms());
+/// ```
+pub struct Simulation {
+    /// The graph with modifications applied
+    pub graph: Graph,
+    /// Shortest path in the original graph
+    pub original: Path,
+    /// Shortest path in the modified graph
+    pub modified: Path,
+    /// Edges whose latency the change in demand affected, by their index
+    /// in the modified graph
+    pub congestion: Vec<CongestedEdge>,
+}
+
+impl Simulation {
+    /// Applies `mods` to a copy of `graph` and finds the shortest path in both.
+    pub fn run(
+        graph: &Graph,
+        from: &str,
+        to: &str,
+        mods: &Modifications,
+        constraints: &Constraints,
+    ) -> Result<Simulation, SimulationError> {
+        let original = graph
+            .shortest_path_with(from, to, constraints)
+            .map_err(|source| SimulationError::OriginalPath {
+                from: from.to_string(),
+                to: to.to_string(),
+                source,
+            })?;
+
+        let (modified_graph, congestion) = mods
+            .apply_congested(graph)
+            .map_err(SimulationError::Modification)?;
+
+        let modified = modified_graph
+            .shortest_path_with(from, to, constraints)
+            .map_err(|source| SimulationError::ModifiedPath {
+                from: from.to_string(),
+                to: to.to_string(),
+                source,
+            })?;
+
+        Ok(Simulation {
+            graph: modified_graph,
+            original,
+            modified,
+            congestion,
+        })
+    }
+
+    /// Modified path latency minus original path latency in milliseconds.
+    pub fn latency_change_ms(&self) -> i64 {
+        self.modified.cost as i64 - self.original.cost as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PathError;
+
+    #[test]
+    fn test_simulation() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let mods = Modifications::parse(&["auth:db:50".to_string()], &[]).unwrap();
+
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        assert_eq!(sim.original.cost, 8);
+        assert_eq!(sim.modified.cost, 9);
+        assert_eq!(sim.latency_change_ms(), 1);
+    }
+
+    #[test]
+    fn test_demand_congestion() {
+        let input: crate::io::GraphInput = serde_json::from_str(
+            r#"{"nodes": ["api", "auth", "db", "cache"], "edges": [
+                {"from": "api", "to": "auth", "latency_ms": 5, "utilization": 0.5},
+                {"from": "auth", "to": "db", "latency_ms": 3, "load_rps": 300, "capacity": 1000},
+                {"from": "api", "to": "cache", "latency_ms": 7},
+                {"from": "cache", "to": "db", "latency_ms": 2, "utilization": 0.9}
+            ]}"#,
+        )
+        .unwrap();
+        let graph = Graph::try_from(input).unwrap();
+        let mods = Modifications {
+            demand: Some(Modifications::parse_demand("+20%").unwrap()),
+            ..Modifications::default()
+        };
+
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        let latencies: Vec<_> = sim
+            .congestion
+            .iter()
+            .map(|c| (c.edge, c.congested_latency_ms))
+            .collect();
+        // 5 × 0.5 / 0.4 and 3 × 0.7 / 0.64; cache → db saturates
+        assert_eq!(latencies, vec![(0, Some(6)), (1, Some(3)), (3, None)]);
+        assert_eq!(sim.original.cost, 8);
+        assert_eq!(sim.modified.cost, 9);
+
+        assert_eq!(Modifications::parse_demand("*1.5").unwrap(), 1.5);
+        assert!(Modifications::parse_demand("+20").is_err());
+    }
+
+    #[test]
+    fn test_unmatched_drops() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let mods = Modifications::parse(
+            &["db:auth:5".to_string()],
+            &["api:cache".to_string(), "api:db".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            mods.unmatched_drops(&graph),
+            vec![("api".to_string(), "db".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_missing_override_edge() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let mut mods = Modifications::parse(&["api:db:1".to_string()], &[]).unwrap();
+        assert!(matches!(
+            Simulation::run(&graph, "api", "db", &mods, &Constraints::default()),
+            Err(SimulationError::Modification(
+                PathError::EdgeNotFound { .. }
+            ))
+        ));
+
+        mods.create_missing = true;
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        assert_eq!(sim.modified.cost, 1);
+        assert_eq!(sim.latency_change_ms(), -7);
+    }
+
+    #[test]
+    fn test_pattern_overrides() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        // api → auth 5, auth → db 3, api → cache 7, cache → db 2
+        let mods =
+            Modifications::parse(&["*:db:*2".to_string(), "api:a*:+10".to_string()], &[]).unwrap();
+        assert!(mods.overrides.is_empty());
+        assert_eq!(mods.rules.len(), 2);
+
+        let resolved = mods.resolve(&graph).unwrap();
+        assert_eq!(
+            resolved.overrides,
+            vec![
+                ("auth".to_string(), "db".to_string(), 6),
+                ("cache".to_string(), "db".to_string(), 4),
+                ("api".to_string(), "auth".to_string(), 15),
+            ]
+        );
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        assert_eq!(sim.modified.cost, 11);
+
+        let mods = Modifications::parse(&["eu-*:us-*:+30".to_string()], &[]).unwrap();
+        assert!(matches!(
+            mods.resolve(&graph),
+            Err(PathError::EdgeNotFound { .. })
+        ));
+        assert!(glob_match("eu-*-db", "eu-west-db"));
+        assert!(glob_match("?pi", "api"));
+        assert!(!glob_match("eu-*", "us-east"));
+        assert_eq!(Adjustment::Add(-10).apply(4), 0);
+    }
+
+    #[test]
+    fn test_relative_overrides() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let mods = Modifications::parse(
+            &[
+                "api:auth:+20%".to_string(),
+                "auth:db:-5".to_string(),
+                "cache:db:-50%".to_string(),
+            ],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(mods.rules[0].adjustment, Adjustment::Scale(1.2));
+        let resolved = mods.resolve(&graph).unwrap();
+        assert_eq!(
+            resolved.overrides,
+            vec![
+                ("api".to_string(), "auth".to_string(), 6),
+                ("auth".to_string(), "db".to_string(), 0),
+                ("cache".to_string(), "db".to_string(), 1),
+            ]
+        );
+        assert_eq!(Adjustment::parse("-150%").unwrap().apply(10), 0);
+        assert!(Adjustment::parse("20%").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(Modifications::parse(&["a:b".to_string()], &[]).is_err());
+        assert!(Modifications::parse(&["a:b:c:1".to_string()], &[]).is_err());
+        assert!(Modifications::parse(&["a:b:x".to_string()], &[]).is_err());
+        assert!(Modifications::parse(&["a:b:*-1".to_string()], &[]).is_err());
+        assert!(Modifications::parse(&[], &["a:b:c".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_edge_id_modifications() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let path = graph.shortest_path("api", "db").unwrap();
+        let ids = graph.path_output(&path).edge_ids;
+        assert_eq!(ids.len(), 2);
+
+        // auth → db by ID, relative to its 3ms
+        let mods = Modifications::parse(&[format!("{}:+10", ids[1])], &[]).unwrap();
+        assert_eq!(mods.edge_rules.len(), 1);
+        let resolved = mods.resolve(&graph).unwrap();
+        assert_eq!(resolved.edge_overrides, vec![(ids[1].to_string(), 13)]);
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        assert_eq!(sim.modified.cost, 9);
+
+        let mods = Modifications::parse(&[], &[ids[0].to_string()]).unwrap();
+        assert_eq!(mods.edge_drops, vec![ids[0].to_string()]);
+        let sim = Simulation::run(&graph, "api", "db", &mods, &Constraints::default()).unwrap();
+        assert_eq!(sim.modified.cost, 9);
+
+        let mods = Modifications::parse(&["0123456789ab:5".to_string()], &[]).unwrap();
+        assert!(matches!(
+            mods.apply(&graph),
+            Err(PathError::EdgeIdNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::constraint::Constraints;
+use crate::error::PathError;
+use crate::graph::Graph;
+use crate::path::Path;
+
+/// Result of checking a route's latency against an SLO.
+///
+/// # Example
+///
+/// ```ignore
+/// let check = SloCheck::run(&graph, "api", "db", 100, &Constraints::default())?;
+/// if !check.met() {
+///     println!("over budget by {}ms", -check.headroom_ms());
+/// }
+/// ```
+pub struct SloCheck {
+    /// The shortest path that was checked
+    pub path: Path,
+    /// Maximum allowed end-to-end latency in milliseconds
+    pub max_latency_ms: u32,
+}
+
+impl SloCheck {
+    /// Finds the shortest allowed path and checks it against `max_latency_ms`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SloCheck)` - The path and limit, whether or not the SLO is met
+    /// * `Err(PathError)` - If either node doesn't exist or no path exists
+    pub fn run(
+        graph: &Graph,
+        from: &str,
+        to: &str,
+        max_latency_ms: u32,
+        constraints: &Constraints,
+    ) -> Result<SloCheck, PathError> {
+        let path = graph.shortest_path_with(from, to, constraints)?;
+
+        Ok(SloCheck {
+            path,
+            max_latency_ms,
+        })
+    }
+
+    /// Returns true if the path latency is within the limit.
+    pub fn met(&self) -> bool {
+        self.path.cost <= self.max_latency_ms
+    }
+
+    /// Limit minus path latency in milliseconds (negative if violated).
+    pub fn headroom_ms(&self) -> i64 {
+        self.max_latency_ms as i64 - self.path.cost as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slo_check() {
+        let graph = Graph::load_json("src/testdata/simple_graph.json").unwrap();
+
+        let check = SloCheck::run(&graph, "a", "c", 30, &Constraints::default()).unwrap();
+        assert!(check.met());
+        assert_eq!(check.headroom_ms(), 0);
+
+        let check = SloCheck::run(&graph, "a", "c", 25, &Constraints::default()).unwrap();
+        assert!(!check.met());
+        assert_eq!(check.headroom_ms(), -5);
+    }
+}
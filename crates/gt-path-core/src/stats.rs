@@ -0,0 +1,83 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static SEARCHES: AtomicU64 = AtomicU64::new(0);
+static NODES_VISITED: AtomicU64 = AtomicU64::new(0);
+static EDGES_RELAXED: AtomicU64 = AtomicU64::new(0);
+static HEAP_PUSHES: AtomicU64 = AtomicU64::new(0);
+
+/// Work done by Dijkstra searches, for tracking algorithmic cost as graphs
+/// grow.
+///
+/// Every search in the process adds to running totals, so the work behind
+/// any computation is the difference of two `total` snapshots:
+///
+/// ```
+/// use gt_path_core::SearchStats;
+///
+/// let before = SearchStats::total();
+/// // ... run queries ...
+/// let work = SearchStats::total().since(&before);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SearchStats {
+    /// Searches run
+    pub searches: u64,
+    /// Nodes settled (popped from the heap at their final distance)
+    pub nodes_visited: u64,
+    /// Outgoing links examined from settled nodes
+    pub edges_relaxed: u64,
+    /// Entries pushed onto the priority queue
+    pub heap_pushes: u64,
+}
+
+impl SearchStats {
+    /// Totals over every search in this process so far.
+    pub fn total() -> SearchStats {
+        SearchStats {
+            searches: SEARCHES.load(Ordering::Relaxed),
+            nodes_visited: NODES_VISITED.load(Ordering::Relaxed),
+            edges_relaxed: EDGES_RELAXED.load(Ordering::Relaxed),
+            heap_pushes: HEAP_PUSHES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Work done between an `earlier` snapshot and this one.
+    pub fn since(&self, earlier: &SearchStats) -> SearchStats {
+        SearchStats {
+            searches: self.searches.saturating_sub(earlier.searches),
+            nodes_visited: self.nodes_visited.saturating_sub(earlier.nodes_visited),
+            edges_relaxed: self.edges_relaxed.saturating_sub(earlier.edges_relaxed),
+            heap_pushes: self.heap_pushes.saturating_sub(earlier.heap_pushes),
+        }
+    }
+
+    /// Adds one finished search's counts to the process totals. Searches
+    /// count locally and record once, keeping atomics out of the hot loop.
+    pub(crate) fn record(&self) {
+        SEARCHES.fetch_add(1, Ordering::Relaxed);
+        NODES_VISITED.fetch_add(self.nodes_visited, Ordering::Relaxed);
+        EDGES_RELAXED.fetch_add(self.edges_relaxed, Ordering::Relaxed);
+        HEAP_PUSHES.fetch_add(self.heap_pushes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_counts_search_work() {
+        let graph = Graph::load_json("src/testdata/sample_graph.json").unwrap();
+        let before = SearchStats::total();
+        graph.shortest_path("api", "db").unwrap();
+        let work = SearchStats::total().since(&before);
+
+        // other tests may search concurrently, so only lower bounds hold
+        assert!(work.searches >= 1);
+        assert!(work.nodes_visited >= 2);
+        assert!(work.heap_pushes >= work.nodes_visited);
+        assert!(work.edges_relaxed >= 1);
+    }
+}
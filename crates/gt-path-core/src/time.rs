@@ -0,0 +1,166 @@
+/// Parses an RFC 3339-style UTC timestamp into seconds since the Unix epoch.
+///
+/// Accepts `YYYY-MM-DD`, `YYYY-MM-DDTHH:MM`, and `YYYY-MM-DDTHH:MM:SS`, each
+/// optionally followed by fractional seconds (ignored) and a `Z` or `±HH:MM`
+/// offset. Timestamps without an offset are treated as UTC.
+///
+/// # Example
+///
+/// ```ignore
+/// assert_eq!(parse_timestamp("1970-01-02T00:00Z")?, 86_400);
+/// ```
+pub fn parse_timestamp(s: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid timestamp '{}': expected YYYY-MM-DDTHH:MM[:SS]Z", s);
+
+    let (date, rest) = match s.split_once(['T', 't', ' ']) {
+        Some((date, rest)) => (date, Some(rest)),
+        None => (s, None),
+    };
+
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = number(parts.next(), 4).ok_or_else(invalid)?;
+    let month: i64 = number(parts.next(), 2).ok_or_else(invalid)?;
+    let day: i64 = number(parts.next(), 2).ok_or_else(invalid)?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    let mut seconds = days_from_civil(year, month, day) * 86_400;
+
+    if let Some(rest) = rest {
+        let (clock, offset) = split_offset(rest).ok_or_else(invalid)?;
+
+        let mut fields = clock.splitn(3, ':');
+        let hour: i64 = number(fields.next(), 2).ok_or_else(invalid)?;
+        let minute: i64 = number(fields.next(), 2).ok_or_else(invalid)?;
+        let second: i64 = match fields.next() {
+            // fractional seconds are accepted but don't affect the result
+            Some(sec) => {
+                number(Some(sec.split('.').next().unwrap_or(sec)), 2).ok_or_else(invalid)?
+            }
+            None => 0,
+        };
+        if hour > 23 || minute > 59 || second > 60 {
+            return Err(invalid());
+        }
+
+        seconds += hour * 3600 + minute * 60 + second - offset;
+    }
+
+    Ok(seconds)
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let secs = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Parses a fixed-width run of ASCII digits.
+fn number(s: Option<&str>, width: usize) -> Option<i64> {
+    let s = s?;
+    if s.len() != width || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Splits `HH:MM[:SS][Z|±HH:MM]` into the clock part and the offset in seconds.
+fn split_offset(s: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = s.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0));
+    }
+
+    match s.rfind(['+', '-']) {
+        Some(i) => {
+            let sign = if s.as_bytes()[i] == b'+' { 1 } else { -1 };
+            let (hours, minutes) = s[i + 1..].split_once(':')?;
+            let offset = number(Some(hours), 2)? * 3600 + number(Some(minutes), 2)? * 60;
+            Some((&s[..i], sign * offset))
+        }
+        None => Some((s, 0)),
+    }
+}
+
+fn is_leap(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(parse_timestamp("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_timestamp("1970-01-02T00:00Z").unwrap(), 86_400);
+        assert_eq!(
+            parse_timestamp("2024-06-01T14:00Z").unwrap(),
+            parse_timestamp("2024-06-01T14:00:00.250Z").unwrap()
+        );
+        assert_eq!(
+            parse_timestamp("2024-06-01T16:00+02:00").unwrap(),
+            parse_timestamp("2024-06-01T14:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let ts = parse_timestamp("2024-02-29T23:59:59Z").unwrap();
+        assert_eq!(format_timestamp(ts), "2024-02-29T23:59:59Z");
+        assert_eq!(format_timestamp(-1), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        assert!(parse_timestamp("2023-02-29").is_err());
+        assert!(parse_timestamp("2024-13-01").is_err());
+        assert!(parse_timestamp("2024-06-01T25:00Z").is_err());
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::arc_graph::ArcGraph;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Background watcher that reloads an `ArcGraph` when its file changes.
+///
+/// The modification times of the file and any files it includes are polled
+/// every `interval`; polling works the same on every platform and on
+/// network filesystems. A reload that fails (e.g., a half-written or
+/// invalid file) keeps the current graph and is retried on the next change.
+/// Dropping the watcher stops it.
+///
+/// # Example
+///
+/// ```ignore
+/// let shared = ArcGraph::load("graph.json")?;
+/// let _watcher = GraphWatcher::spawn(shared.clone(), "graph.json", Duration::from_secs(1));
+/// ```
+pub struct GraphWatcher {
+    stop: Arc<AtomicBool>,
+    last_error: Arc<Mutex<Option<String>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl GraphWatcher {
+    /// Starts watching `path` and reloading `graph` on change.
+    pub fn spawn(graph: ArcGraph, path: impl Into<PathBuf>, interval: Duration) -> GraphWatcher {
+        let path = path.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let last_error = Arc::new(Mutex::new(None));
+        // taken before the thread starts so no change in between is missed
+        let mut seen = modified(&path);
+
+        let handle = {
+            let stop = Arc::clone(&stop);
+            let last_error = Arc::clone(&last_error);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+
+                    let current = modified(&path);
+                    if current.is_none() || current == seen {
+                        continue;
+                    }
+                    seen = current;
+
+                    let result = graph.reload(&path.to_string_lossy());
+                    *last_error.lock().unwrap_or_else(|e| e.into_inner()) =
+                        result.err().map(|e| format!("{:#}", e));
+                }
+            })
+        };
+
+        GraphWatcher {
+            stop,
+            last_error,
+            handle: Some(handle),
+        }
+    }
+
+    /// Error from the most recent reload attempt, if it failed.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for GraphWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Latest modification time of the file and the files it includes.
+fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    let mtime = |file: &std::path::Path| std::fs::metadata(file).and_then(|m| m.modified()).ok();
+    match crate::compose::files(path) {
+        Ok(files) => files.iter().filter_map(|f| mtime(f)).max(),
+        // e.g. half-written; the next change retries
+        Err(_) => mtime(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constraints;
+
+    #[test]
+    fn test_reloads_on_change() {
+        let dir = std::env::temp_dir().join(format!("gt-path-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("graph.json");
+        std::fs::copy("src/testdata/simple_graph.json", &file).unwrap();
+
+        let shared = ArcGraph::load(file.to_str().unwrap()).unwrap();
+        let watcher = GraphWatcher::spawn(shared.clone(), &file, Duration::from_millis(10));
+
+        std::fs::copy("src/testdata/sample_graph.json", &file).unwrap();
+        // make sure the new mtime differs even on coarse-grained filesystems
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+
+        let deadline = SystemTime::now() + Duration::from_secs(5);
+        while shared.generation() == 0 && SystemTime::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(shared.generation(), 1);
+        assert!(watcher.last_error().is_none());
+        assert!(
+            shared
+                .shortest_path("api", "db", &Constraints::default())
+                .is_ok()
+        );
+
+        drop(watcher);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
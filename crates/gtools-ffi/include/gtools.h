@@ -0,0 +1,55 @@
+/*
+ * gtools.h - C interface to the gt-path shortest path engine.
+ *
+ * Link against libgtools_ffi (cdylib or staticlib). Every function that can
+ * fail returns a GtStatus; call gt_last_error() for a description.
+ * Handles are not thread-safe.
+ */
+#ifndef GTOOLS_H
+#define GTOOLS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum GtStatus {
+    GT_OK = 0,
+    GT_NULL_POINTER = 1,
+    GT_INVALID_UTF8 = 2,
+    GT_INVALID_GRAPH = 3,
+    GT_NODE_NOT_FOUND = 4,
+    GT_PATH_NOT_FOUND = 5,
+    GT_OUT_OF_RANGE = 6,
+} GtStatus;
+
+typedef struct GtGraph GtGraph;
+typedef struct GtPath GtPath;
+
+/* Message for the last failure on this thread, or NULL. */
+const char *gt_last_error(void);
+
+GtGraph *gt_graph_new(void);
+void gt_graph_free(GtGraph *graph);
+
+/* Adds a directed edge; endpoints are created on first use. */
+GtStatus gt_graph_add_edge(GtGraph *graph, const char *from, const char *to,
+                           float latency_ms);
+
+/* On success, *out must be released with gt_path_free. */
+GtStatus gt_shortest_path(GtGraph *graph, const char *from, const char *to,
+                          GtPath **out);
+
+uint32_t gt_path_cost(const GtPath *path);
+size_t gt_path_len(const GtPath *path);
+/* Valid until gt_path_free; NULL if index is out of range. */
+const char *gt_path_node(const GtPath *path, size_t index);
+void gt_path_free(GtPath *path);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GTOOLS_H */
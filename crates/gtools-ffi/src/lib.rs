@@ -0,0 +1,338 @@
+//! C ABI for the `gt-path-core` shortest path engine.
+//!
+//! See `include/gtools.h` for the C declarations. All functions return a
+//! `GtStatus`; on failure, `gt_last_error()` describes what went wrong.
+//!
+//! Handles are not thread-safe: a `GtGraph` must not be used from several
+//! threads at once without external locking.
+
+use gt_path_core::error::PathError;
+use gt_path_core::graph::Graph;
+use gt_path_core::io::{Attributes, EdgeInput, GraphInput, NodeInput};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+/// Result codes returned by every FFI function.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GtStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The graph is invalid (e.g., negative latency or self loop)
+    InvalidGraph = 3,
+    /// A node name does not exist in the graph
+    NodeNotFound = 4,
+    /// No path exists between the nodes
+    PathNotFound = 5,
+    /// An index argument was out of range
+    OutOfRange = 6,
+}
+
+/// Opaque graph handle built up edge by edge.
+pub struct GtGraph {
+    input: GraphInput,
+    names: HashSet<String>,
+    // rebuilt lazily after edges are added
+    built: Option<Graph>,
+}
+
+/// Opaque shortest path result.
+pub struct GtPath {
+    nodes: Vec<CString>,
+    cost: u32,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(status: GtStatus, message: impl ToString) -> GtStatus {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+    status
+}
+
+/// Reads a C string argument as UTF-8.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char, arg: &str) -> Result<&'a str, GtStatus> {
+    if s.is_null() {
+        return Err(fail(GtStatus::NullPointer, format!("{} is null", arg)));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| fail(GtStatus::InvalidUtf8, format!("{} is not valid UTF-8", arg)))
+}
+
+/// Returns the message for the most recent failure on this thread, or null.
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn gt_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Creates an empty graph. Free it with `gt_graph_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn gt_graph_new() -> *mut GtGraph {
+    Box::into_raw(Box::new(GtGraph {
+        input: GraphInput::new(Vec::new(), Vec::new()),
+        names: HashSet::new(),
+        built: None,
+    }))
+}
+
+/// Frees a graph created by `gt_graph_new`. Null is ignored.
+///
+/// # Safety
+///
+/// `graph` must be null or a pointer returned by `gt_graph_new` that has not
+/// already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_graph_free(graph: *mut GtGraph) {
+    if !graph.is_null() {
+        drop(unsafe { Box::from_raw(graph) });
+    }
+}
+
+/// Adds a directed edge, creating either endpoint if it doesn't exist yet.
+///
+/// # Safety
+///
+/// `graph` must be a live pointer from `gt_graph_new`; `from` and `to` must
+/// be NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_graph_add_edge(
+    graph: *mut GtGraph,
+    from: *const c_char,
+    to: *const c_char,
+    latency_ms: f32,
+) -> GtStatus {
+    let Some(graph) = (unsafe { graph.as_mut() }) else {
+        return fail(GtStatus::NullPointer, "graph is null");
+    };
+    let (from, to) = match unsafe { (read_str(from, "from"), read_str(to, "to")) } {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(status), _) | (_, Err(status)) => return status,
+    };
+
+    if from == to {
+        return fail(
+            GtStatus::InvalidGraph,
+            format!("self loop detected on node {}", from),
+        );
+    }
+    if latency_ms.is_nan() || latency_ms < 0.0 {
+        return fail(
+            GtStatus::InvalidGraph,
+            format!("negative latency on edge {}->{}: {}", from, to, latency_ms),
+        );
+    }
+
+    for name in [from, to] {
+        if graph.names.insert(name.to_string()) {
+            graph.input.nodes.push(NodeInput::from(name));
+        }
+    }
+    graph.input.edges.push(EdgeInput {
+        from: from.to_string(),
+        to: to.to_string(),
+        id: None,
+        latency_ms: Some(latency_ms),
+        timeline: Vec::new(),
+        bidirectional: false,
+        attrs: Attributes::new(),
+    });
+    graph.built = None;
+
+    GtStatus::Ok
+}
+
+/// Finds the lowest-latency path from `from` to `to`.
+///
+/// On success, `*out` receives a path to free with `gt_path_free`.
+///
+/// # Safety
+///
+/// `graph` must be a live pointer from `gt_graph_new`; `from` and `to` must
+/// be NUL-terminated strings; `out` must be a valid pointer to write to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_shortest_path(
+    graph: *mut GtGraph,
+    from: *const c_char,
+    to: *const c_char,
+    out: *mut *mut GtPath,
+) -> GtStatus {
+    let Some(graph) = (unsafe { graph.as_mut() }) else {
+        return fail(GtStatus::NullPointer, "graph is null");
+    };
+    if out.is_null() {
+        return fail(GtStatus::NullPointer, "out is null");
+    }
+    let (from, to) = match unsafe { (read_str(from, "from"), read_str(to, "to")) } {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(status), _) | (_, Err(status)) => return status,
+    };
+
+    if graph.built.is_none() {
+        match Graph::try_from(graph.input.clone()) {
+            Ok(built) => graph.built = Some(built),
+            Err(e) => return fail(GtStatus::InvalidGraph, e),
+        }
+    }
+    let built = graph.built.as_ref().expect("graph built above");
+
+    let path = match built.shortest_path(from, to) {
+        Ok(path) => path,
+        Err(e @ PathError::NodeNotFound(_)) => return fail(GtStatus::NodeNotFound, e),
+        Err(
+            e @ (PathError::PathNotFound { .. }
+            | PathError::EdgeNotFound { .. }
+            | PathError::EdgeIdNotFound(_)),
+        ) => {
+            return fail(GtStatus::PathNotFound, e);
+        }
+    };
+
+    let nodes = path
+        .path
+        .iter()
+        .map(|id| CString::new(&*built.to_name[id.0 as usize]).unwrap_or_default())
+        .collect();
+    unsafe {
+        *out = Box::into_raw(Box::new(GtPath {
+            nodes,
+            cost: path.cost,
+        }));
+    }
+
+    GtStatus::Ok
+}
+
+/// Returns the total latency of the path in milliseconds (0 for null).
+///
+/// # Safety
+///
+/// `path` must be null or a live pointer from `gt_shortest_path`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_path_cost(path: *const GtPath) -> u32 {
+    unsafe { path.as_ref() }.map_or(0, |p| p.cost)
+}
+
+/// Returns the number of nodes on the path, including both endpoints (0 for null).
+///
+/// # Safety
+///
+/// `path` must be null or a live pointer from `gt_shortest_path`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_path_len(path: *const GtPath) -> usize {
+    unsafe { path.as_ref() }.map_or(0, |p| p.nodes.len())
+}
+
+/// Returns the name of the node at `index`, or null if out of range.
+///
+/// The string is owned by the path and valid until `gt_path_free`.
+///
+/// # Safety
+///
+/// `path` must be null or a live pointer from `gt_shortest_path`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_path_node(path: *const GtPath, index: usize) -> *const c_char {
+    let Some(path) = (unsafe { path.as_ref() }) else {
+        fail(GtStatus::NullPointer, "path is null");
+        return ptr::null();
+    };
+    match path.nodes.get(index) {
+        Some(name) => name.as_ptr(),
+        None => {
+            fail(
+                GtStatus::OutOfRange,
+                format!(
+                    "index {} out of range for path of {}",
+                    index,
+                    path.nodes.len()
+                ),
+            );
+            ptr::null()
+        }
+    }
+}
+
+/// Frees a path returned by `gt_shortest_path`. Null is ignored.
+///
+/// # Safety
+///
+/// `path` must be null or a pointer from `gt_shortest_path` that has not
+/// already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gt_path_free(path: *mut GtPath) {
+    if !path.is_null() {
+        drop(unsafe { Box::from_raw(path) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn test_shortest_path_round_trip() {
+        let graph = gt_graph_new();
+        unsafe {
+            for (from, to, latency) in [
+                ("api", "auth", 5.0),
+                ("auth", "db", 3.0),
+                ("api", "db", 20.0),
+            ] {
+                let status = gt_graph_add_edge(graph, c(from).as_ptr(), c(to).as_ptr(), latency);
+                assert_eq!(status, GtStatus::Ok);
+            }
+
+            let mut path = ptr::null_mut();
+            let status = gt_shortest_path(graph, c("api").as_ptr(), c("db").as_ptr(), &mut path);
+            assert_eq!(status, GtStatus::Ok);
+            assert_eq!(gt_path_cost(path), 8);
+            assert_eq!(gt_path_len(path), 3);
+            assert_eq!(
+                CStr::from_ptr(gt_path_node(path, 1)).to_str().unwrap(),
+                "auth"
+            );
+            assert!(gt_path_node(path, 3).is_null());
+
+            gt_path_free(path);
+            gt_graph_free(graph);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let graph = gt_graph_new();
+        unsafe {
+            let status = gt_graph_add_edge(graph, c("a").as_ptr(), c("a").as_ptr(), 1.0);
+            assert_eq!(status, GtStatus::InvalidGraph);
+            assert!(!gt_last_error().is_null());
+
+            gt_graph_add_edge(graph, c("a").as_ptr(), c("b").as_ptr(), 1.0);
+            let mut path = ptr::null_mut();
+            let status = gt_shortest_path(graph, c("b").as_ptr(), c("a").as_ptr(), &mut path);
+            assert_eq!(status, GtStatus::PathNotFound);
+            let status = gt_shortest_path(graph, c("a").as_ptr(), c("z").as_ptr(), &mut path);
+            assert_eq!(status, GtStatus::NodeNotFound);
+            assert!(path.is_null());
+
+            gt_graph_free(graph);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Loads an integer-ID edge list with both the streaming and the parallel
+// (`--fast-load`) loader, which must agree.
+fuzz_target!(|data: &[u8]| {
+    let streamed = graphs::io::load_csv_from_reader_with_report(data);
+    let parallel = graphs::io::load_csv_parallel_with_report(data);
+    if let (Ok((a, _)), Ok((b, _))) = (&streamed, &parallel) {
+        assert_eq!(a.size(), b.size());
+        assert_eq!(a.edges().len(), b.edges().len());
+    }
+    if let Ok((graph, _)) = streamed {
+        let _ = graph.critical_components();
+    }
+});
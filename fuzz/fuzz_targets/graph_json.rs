@@ -0,0 +1,17 @@
+#![no_main]
+
+use gt_path_core::graph::Graph;
+use gt_path_core::io::GraphInput;
+use libfuzzer_sys::fuzz_target;
+
+// Parses a gt-path graph file and builds the graph, as `load_graph` does.
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = GraphInput::from_reader(data) else {
+        return;
+    };
+    let Ok(graph) = Graph::try_from(input) else {
+        return;
+    };
+    let _ = graph.lint();
+    let _ = graph.content_hash();
+});
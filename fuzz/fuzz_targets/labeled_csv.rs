@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Loads an edge list whose nodes are labels.
+fuzz_target!(|data: &[u8]| {
+    if let Ok((graph, labels)) = graphs::io::load_labeled_csv_from_reader(data) {
+        assert_eq!(graph.size(), labels.len());
+    }
+});